@@ -0,0 +1,446 @@
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use clap::Args;
+use eyre::{Result, bail};
+use keystore::Keystore;
+use rpc::{
+    RpcConfig,
+    coins::{CoinInfo, parse_amount},
+    json_u128, rpc_request,
+};
+use serde_json::json;
+use sui_types::{
+    base_types::{ObjectRef, SuiAddress},
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::TransactionData,
+};
+
+use crate::{
+    coin_select::{self, Strategy},
+    context::Context,
+    tx::{
+        SUI_COIN_TYPE, dry_run, execute, executed_effects, gas_cost, object_ref, owned_coins,
+        reference_gas_price, select_gas_coins,
+    },
+};
+
+/// Transfers per PTB stay well below the protocol's command and argument limits
+const MAX_BATCH_SIZE: usize = 500;
+
+#[derive(Args)]
+pub struct AirdropArgs {
+    /// CSV file with `address` and `amount` columns (amounts in whole coin units)
+    #[arg(long, value_name = "PATH")]
+    csv: PathBuf,
+
+    /// Coin type to distribute
+    #[arg(long, value_name = "TYPE", default_value = SUI_COIN_TYPE)]
+    coin_type: String,
+
+    /// Address or keystore alias paying for the airdrop
+    #[arg(long, value_name = "ADDRESS|ALIAS")]
+    key: String,
+
+    /// Treat amounts as raw base units instead of whole coins
+    #[arg(long)]
+    raw: bool,
+
+    /// Recipients per transaction
+    #[arg(long, value_name = "COUNT", default_value = "200")]
+    batch_size: usize,
+
+    /// Gas budget per transaction in MIST
+    #[arg(long, value_name = "MIST", default_value = "500000000")]
+    gas_budget: u64,
+
+    /// Attempts per batch before it is recorded as failed
+    #[arg(long, value_name = "COUNT", default_value = "3")]
+    attempts: u32,
+
+    /// Per-row result file [default: <CSV>.results.csv]; rows already sent are skipped
+    #[arg(long, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    /// Send the transfers (without this only the preview is shown)
+    #[arg(long)]
+    execute: bool,
+
+    /// Keystore holding the key
+    #[arg(long, value_name = "PATH")]
+    keystore: Option<PathBuf>,
+
+    /// RPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+}
+
+/// One validated row of the recipients file
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Recipient {
+    /// 1-based data row number (the header is not counted)
+    row: usize,
+    address: SuiAddress,
+    amount: u64,
+}
+
+/// Outcome of one row, as written to the result file
+#[derive(Debug, Clone)]
+struct RowResult {
+    status: &'static str,
+    digest: String,
+    error: String,
+}
+
+pub async fn run(args: AirdropArgs, ctx: &Context) -> Result<()> {
+    if args.batch_size == 0 || args.batch_size > MAX_BATCH_SIZE {
+        bail!("--batch-size must be between 1 and {}", MAX_BATCH_SIZE);
+    }
+    let config = ctx.rpc_config(args.url.clone(), false);
+    let keystore = ctx.keystore(args.keystore.clone())?;
+    let sender = keystore.get(&args.key)?.address;
+    let registry = ctx.coin_registry(&config);
+    let coin = registry.get_or_raw(&args.coin_type).await;
+
+    let decimals = if args.raw { 0 } else { coin.decimals };
+    let recipients = parse_recipients(File::open(&args.csv)?, decimals)?;
+    let out = args
+        .out
+        .clone()
+        .unwrap_or_else(|| args.csv.with_extension("results.csv"));
+    let done = completed_rows(&out)?;
+    let pending: Vec<Recipient> = recipients
+        .iter()
+        .filter(|r| !done.contains(&r.row))
+        .cloned()
+        .collect();
+
+    let mut seen = BTreeSet::new();
+    for recipient in &recipients {
+        if !seen.insert(recipient.address) {
+            eprintln!(
+                "Warning: row {} repeats recipient {}",
+                recipient.row, recipient.address
+            );
+        }
+    }
+
+    let total: u128 = pending.iter().map(|r| u128::from(r.amount)).sum();
+    let batches: Vec<&[Recipient]> = pending.chunks(args.batch_size).collect();
+    let gas_price = reference_gas_price(&config).await?;
+
+    println!(
+        "Recipients: {} ({} already sent)",
+        pending.len(),
+        done.len()
+    );
+    println!("Total:      {}", coin.format(total));
+    println!(
+        "Batches:    {} of up to {} transfers",
+        batches.len(),
+        args.batch_size
+    );
+    if let Some(first) = batches.first() {
+        let data = build_batch(&config, sender, &coin, first, args.gas_budget, gas_price).await?;
+        let effects = dry_run(&config, &data).await?;
+        if effects["status"]["status"].as_str() != Some("success") {
+            bail!("Dry run of the first batch failed: {}", effects["status"]);
+        }
+        let per_batch = gas_cost(&effects);
+        let sui = registry.get_or_raw(SUI_COIN_TYPE).await;
+        println!(
+            "Fees:       ~{} ({} per batch, estimated from a dry run)",
+            sui.format_signed(per_batch * batches.len() as i128),
+            sui.format_signed(per_batch)
+        );
+    }
+
+    let balance = rpc_request(
+        &config,
+        "suix_getBalance",
+        json!([sender.to_string(), coin.coin_type]),
+    )
+    .await?;
+    let balance = json_u128(&balance["totalBalance"]).unwrap_or_default();
+    if balance < total {
+        bail!(
+            "{} holds only {}, the airdrop needs {}",
+            sender,
+            coin.format(balance),
+            coin.format(total)
+        );
+    }
+
+    if !args.execute {
+        println!("Preview only; re-run with --execute to send");
+        return Ok(());
+    }
+
+    let mut results = open_results(&out)?;
+    let mut failed = 0;
+    for (i, batch) in batches.iter().enumerate() {
+        let result = send_batch(&config, &keystore, sender, &coin, batch, &args, gas_price).await;
+        if result.status != "success" {
+            failed += batch.len();
+        }
+        eprintln!(
+            "Batch {}/{}: {} {}",
+            i + 1,
+            batches.len(),
+            result.status,
+            if result.digest.is_empty() {
+                &result.error
+            } else {
+                &result.digest
+            }
+        );
+        for recipient in batch.iter() {
+            results.write_record([
+                recipient.row.to_string(),
+                recipient.address.to_string(),
+                recipient.amount.to_string(),
+                result.status.to_string(),
+                result.digest.clone(),
+                result.error.clone(),
+            ])?;
+        }
+        results.flush()?;
+    }
+
+    println!("Results written to {}", out.display());
+    if failed > 0 {
+        bail!("{} of {} transfers failed", failed, pending.len());
+    }
+    Ok(())
+}
+
+/// Validate the recipients file, reporting every bad row at once
+fn parse_recipients(reader: impl Read, decimals: u8) -> Result<Vec<Recipient>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| eyre::eyre!("CSV has no `{}` column", name))
+    };
+    let (address_col, amount_col) = (column("address")?, column("amount")?);
+
+    let mut recipients = Vec::new();
+    let mut errors = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let row = i + 1;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(format!("row {row}: {e}"));
+                continue;
+            }
+        };
+        let address = record.get(address_col).unwrap_or_default().trim();
+        let amount = record.get(amount_col).unwrap_or_default();
+
+        let address = match SuiAddress::from_str(address) {
+            Ok(address) => address,
+            Err(e) => {
+                errors.push(format!("row {row}: invalid address `{address}`: {e}"));
+                continue;
+            }
+        };
+        match parse_amount(amount, decimals).map(u64::try_from) {
+            Ok(Ok(0)) => errors.push(format!("row {row}: amount must be positive")),
+            Ok(Ok(amount)) => recipients.push(Recipient {
+                row,
+                address,
+                amount,
+            }),
+            Ok(Err(_)) => errors.push(format!("row {row}: amount `{amount}` is too large")),
+            Err(e) => errors.push(format!("row {row}: {e}")),
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!("Invalid recipients file:\n  {}", errors.join("\n  "));
+    }
+    if recipients.is_empty() {
+        bail!("Recipients file has no rows");
+    }
+    Ok(recipients)
+}
+
+/// Rows recorded as sent in an existing result file
+fn completed_rows(path: &Path) -> Result<BTreeSet<usize>> {
+    if !path.exists() {
+        return Ok(BTreeSet::new());
+    }
+    let mut reader = csv::Reader::from_path(path)?;
+    let mut rows = BTreeSet::new();
+    for record in reader.records() {
+        let record = record?;
+        if record.get(3) == Some("success")
+            && let Some(row) = record.get(0).and_then(|r| r.parse().ok())
+        {
+            rows.insert(row);
+        }
+    }
+    Ok(rows)
+}
+
+/// Append to the result file, writing the header when it is new
+fn open_results(path: &Path) -> Result<csv::Writer<File>> {
+    let exists = path.exists();
+    let file = File::options().create(true).append(true).open(path)?;
+    let mut writer = csv::Writer::from_writer(file);
+    if !exists {
+        writer.write_record(["row", "address", "amount", "status", "digest", "error"])?;
+    }
+    Ok(writer)
+}
+
+/// Build one PTB paying every recipient of the batch
+async fn build_batch(
+    config: &RpcConfig,
+    sender: SuiAddress,
+    coin: &CoinInfo,
+    batch: &[Recipient],
+    gas_budget: u64,
+    gas_price: u64,
+) -> Result<TransactionData> {
+    let recipients: Vec<SuiAddress> = batch.iter().map(|r| r.address).collect();
+    let amounts: Vec<u64> = batch.iter().map(|r| r.amount).collect();
+    let mut builder = ProgrammableTransactionBuilder::new();
+
+    let gas = if coin.coin_type == SUI_COIN_TYPE {
+        // Transfers are split off the gas coins, so they must cover both
+        let total: u64 = amounts.iter().sum();
+        builder
+            .pay_sui(recipients, amounts)
+            .map_err(|e| eyre::eyre!("Failed to build transfers: {}", e))?;
+        select_gas_coins(config, sender, gas_budget.saturating_add(total)).await?
+    } else {
+        let total: u128 = amounts.iter().map(|&a| u128::from(a)).sum();
+        let coins = owned_coins(config, sender, &coin.coin_type).await?;
+        let coins = coin_select::select(&coins, total, Strategy::LargestFirst, None)?
+            .coins
+            .iter()
+            .map(object_ref)
+            .collect::<Result<Vec<ObjectRef>>>()?;
+        builder
+            .pay(coins, recipients, amounts)
+            .map_err(|e| eyre::eyre!("Failed to build transfers: {}", e))?;
+        select_gas_coins(config, sender, gas_budget).await?
+    };
+
+    Ok(TransactionData::new_programmable(
+        sender,
+        gas,
+        builder.finish(),
+        gas_budget,
+        gas_price,
+    ))
+}
+
+/// Sign and execute a batch, retrying with fresh object versions on errors.
+///
+/// Before a retry the previous attempt's digest is looked up, so a transaction
+/// whose response was lost is never sent twice.
+async fn send_batch(
+    config: &RpcConfig,
+    keystore: &Keystore,
+    sender: SuiAddress,
+    coin: &CoinInfo,
+    batch: &[Recipient],
+    args: &AirdropArgs,
+    gas_price: u64,
+) -> RowResult {
+    let mut last_error = String::new();
+    for attempt in 0..args.attempts.max(1) {
+        if attempt > 0 {
+            tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+        }
+        let data = match build_batch(config, sender, coin, batch, args.gas_budget, gas_price).await
+        {
+            Ok(data) => data,
+            Err(e) => {
+                last_error = e.to_string();
+                continue;
+            }
+        };
+        let digest = data.digest().to_string();
+        let signature = match keystore.sign_transaction(&sender.to_string(), &data) {
+            Ok(signature) => signature,
+            Err(e) => return failure(e.to_string()),
+        };
+
+        let effects = match execute(config, &data, &[signature], "WaitForLocalExecution").await {
+            Ok(response) => response["effects"].clone(),
+            Err(e) => match executed_effects(config, &digest).await {
+                Some(effects) => effects,
+                None => {
+                    last_error = e.to_string();
+                    continue;
+                }
+            },
+        };
+        return match effects["status"]["status"].as_str() {
+            Some("success") => RowResult {
+                status: "success",
+                digest,
+                error: String::new(),
+            },
+            // Executed but aborted: gas was charged and retrying would fail the same way
+            _ => RowResult {
+                status: "failed",
+                digest,
+                error: effects["status"]["error"]
+                    .as_str()
+                    .unwrap_or("execution failed")
+                    .to_string(),
+            },
+        };
+    }
+    failure(last_error)
+}
+
+fn failure(error: String) -> RowResult {
+    RowResult {
+        status: "failed",
+        digest: String::new(),
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "0x000000000000000000000000000000000000000000000000000000000000000a";
+
+    #[test]
+    fn test_parse_recipients() {
+        let csv = format!("Address,Amount\n{A},1.5\n{A},2\n");
+        let recipients = parse_recipients(csv.as_bytes(), 9).unwrap();
+        assert_eq!(recipients.len(), 2);
+        assert_eq!(recipients[0].row, 1);
+        assert_eq!(recipients[0].amount, 1_500_000_000);
+    }
+
+    #[test]
+    fn test_parse_recipients_reports_all_errors() {
+        let csv = format!("address,amount\nnot-an-address,1\n{A},0\n{A},abc\n");
+        let error = parse_recipients(csv.as_bytes(), 9).unwrap_err().to_string();
+        assert!(error.contains("row 1"));
+        assert!(error.contains("row 2"));
+        assert!(error.contains("row 3"));
+    }
+}
@@ -0,0 +1,383 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use clap::Args;
+use eyre::{Result, bail};
+use rpc::{RpcConfig, coins::parse_amount, json_u64, json_u128, latest_checkpoint, rpc_request};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::{
+    context::Context,
+    filter::Filter,
+    hooks::{Webhook, run_command},
+    tx::{SUI_COIN_TYPE, reference_gas_price},
+};
+
+/// Page size used when polling `suix_queryEvents`
+const EVENTS_PAGE_SIZE: u64 = 50;
+
+#[derive(Args)]
+pub struct AlertArgs {
+    /// YAML rules file
+    #[arg(long, value_name = "PATH")]
+    rules: PathBuf,
+
+    /// Evaluate every rule once and exit non-zero if any fired
+    #[arg(long)]
+    once: bool,
+
+    /// Seconds between evaluations (overrides `interval` in the rules file)
+    #[arg(long, value_name = "SECONDS")]
+    interval: Option<u64>,
+
+    /// RPC endpoint URL (overrides `url` in the rules file)
+    #[arg(long, value_name = "URL")]
+    url: Option<String>,
+}
+
+/// Top level of the rules file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    interval: Option<u64>,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Rule {
+    name: String,
+    #[serde(flatten)]
+    condition: Condition,
+    #[serde(default)]
+    actions: Vec<Action>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Condition {
+    /// Balance of `coin_type` held by `address` below `amount` whole units
+    BalanceBelow {
+        address: String,
+        #[serde(default = "sui_coin_type")]
+        coin_type: String,
+        amount: String,
+    },
+    /// Latest checkpoint older than this many seconds
+    NoCheckpointFor(u64),
+    /// An event matching this filter expression was emitted
+    Event(String),
+    /// Reference gas price above this many MIST
+    GasPriceAbove(u64),
+}
+
+/// A rule action; one list entry may combine several
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Action {
+    /// POST the alert JSON to this URL
+    webhook: Option<String>,
+    /// Run this shell command with the alert JSON on stdin
+    exec: Option<String>,
+    /// Exit with this status code
+    exit: Option<i32>,
+}
+
+fn sui_coin_type() -> String {
+    SUI_COIN_TYPE.to_string()
+}
+
+/// Per-rule evaluation state
+enum State {
+    /// Threshold rules fire when their condition starts to hold
+    Threshold { firing: bool },
+    /// Event rules fire once per new matching event
+    Events {
+        filter: Box<Filter>,
+        server_filter: Value,
+        cursor: Value,
+    },
+}
+
+pub async fn run(args: AlertArgs, ctx: &Context) -> Result<()> {
+    let content = std::fs::read_to_string(&args.rules)
+        .map_err(|e| eyre::eyre!("Failed to read {}: {}", args.rules.display(), e))?;
+    let rules = parse_rules(&content)?;
+    let url = args
+        .url
+        .or(rules.url)
+        .unwrap_or_else(|| "https://fullnode.mainnet.sui.io:443".to_string());
+    let interval = Duration::from_secs(args.interval.or(rules.interval).unwrap_or(10));
+    let config = ctx.rpc_config(url, false);
+
+    let mut states = Vec::new();
+    for rule in &rules.rules {
+        states.push(match rule.condition {
+            Condition::Event(ref expression) => {
+                let filter = Filter::parse(expression)
+                    .map_err(|e| eyre::eyre!("Rule {}: {}", rule.name, e))?;
+                let (server_filter, _) = filter.event_filter();
+                // Only events emitted after startup count
+                let cursor = newest_event_id(&config, &server_filter).await?;
+                State::Events {
+                    filter: Box::new(filter),
+                    server_filter,
+                    cursor,
+                }
+            }
+            _ => State::Threshold { firing: false },
+        });
+    }
+    eprintln!(
+        "Evaluating {} rules every {}s",
+        rules.rules.len(),
+        interval.as_secs()
+    );
+
+    loop {
+        let mut fired = 0;
+        for (rule, state) in rules.rules.iter().zip(&mut states) {
+            let alerts = match evaluate(ctx, &config, rule, state).await {
+                Ok(alerts) => alerts,
+                Err(e) => {
+                    eprintln!("Rule {}: evaluation failed: {e}", rule.name);
+                    continue;
+                }
+            };
+            for alert in alerts {
+                fired += 1;
+                fire(rule, &alert).await;
+            }
+        }
+        if args.once {
+            if fired > 0 {
+                bail!("{} alerts fired", fired);
+            }
+            return Ok(());
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+fn parse_rules(content: &str) -> Result<RulesFile> {
+    let rules: RulesFile =
+        serde_yaml::from_str(content).map_err(|e| eyre::eyre!("Invalid rules file: {}", e))?;
+    if rules.rules.is_empty() {
+        bail!("The rules file has no rules");
+    }
+    for rule in &rules.rules {
+        if let Condition::Event(ref expression) = rule.condition {
+            Filter::parse(expression).map_err(|e| eyre::eyre!("Rule {}: {}", rule.name, e))?;
+        }
+    }
+    Ok(rules)
+}
+
+/// Alert payloads produced by one evaluation of `rule`
+async fn evaluate(
+    ctx: &Context,
+    config: &RpcConfig,
+    rule: &Rule,
+    state: &mut State,
+) -> Result<Vec<Value>> {
+    match state {
+        State::Threshold { firing } => {
+            let reading = threshold_reading(ctx, config, &rule.condition).await?;
+            let was_firing = std::mem::replace(firing, reading.is_some());
+            match reading {
+                Some(message) if !was_firing => Ok(vec![json!({
+                    "type": "alert",
+                    "rule": rule.name,
+                    "message": message,
+                })]),
+                None if was_firing => {
+                    eprintln!("✅ {}: resolved", rule.name);
+                    Ok(Vec::new())
+                }
+                _ => Ok(Vec::new()),
+            }
+        }
+        State::Events {
+            filter,
+            server_filter,
+            cursor,
+        } => {
+            // Nothing matched at startup: read newest first so history is skipped
+            let descending = cursor.is_null();
+            let page = rpc_request(
+                config,
+                "suix_queryEvents",
+                json!([server_filter, cursor, EVENTS_PAGE_SIZE, descending]),
+            )
+            .await?;
+            if descending {
+                if let Some(newest) = page["data"].as_array().and_then(|events| events.first()) {
+                    *cursor = newest["id"].clone();
+                }
+            } else if !page["nextCursor"].is_null() {
+                *cursor = page["nextCursor"].clone();
+            }
+            Ok(page["data"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|event| filter.matches_event(event))
+                .map(|event| {
+                    json!({
+                        "type": "alert",
+                        "rule": rule.name,
+                        "message": format!("event {}", event["type"].as_str().unwrap_or_default()),
+                        "event": event,
+                    })
+                })
+                .collect())
+        }
+    }
+}
+
+/// Description of the breach if the threshold condition currently holds
+async fn threshold_reading(
+    ctx: &Context,
+    config: &RpcConfig,
+    condition: &Condition,
+) -> Result<Option<String>> {
+    match condition {
+        Condition::BalanceBelow {
+            address,
+            coin_type,
+            amount,
+        } => {
+            let coin = ctx.coin_registry(config).get_or_raw(coin_type).await;
+            let threshold = parse_amount(amount, coin.decimals)?;
+            let balance =
+                rpc_request(config, "suix_getBalance", json!([address, coin_type])).await?;
+            let total = json_u128(&balance["totalBalance"]).unwrap_or_default();
+            Ok((total < threshold).then(|| {
+                format!(
+                    "balance of {address} is {} (below {})",
+                    coin.format(total),
+                    coin.format(threshold)
+                )
+            }))
+        }
+        Condition::NoCheckpointFor(seconds) => {
+            let latest = latest_checkpoint(config).await?;
+            let checkpoint =
+                rpc_request(config, "sui_getCheckpoint", json!([latest.to_string()])).await?;
+            let timestamp_ms = json_u64(&checkpoint["timestampMs"]).unwrap_or_default();
+            let age = now_ms().saturating_sub(timestamp_ms) / 1000;
+            Ok((age > *seconds).then(|| format!("no new checkpoint for {age}s (latest {latest})")))
+        }
+        Condition::GasPriceAbove(limit) => {
+            let price = reference_gas_price(config).await?;
+            Ok((price > *limit)
+                .then(|| format!("reference gas price {price} MIST (above {limit})")))
+        }
+        Condition::Event(_) => Ok(None),
+    }
+}
+
+/// Report an alert and run the rule's actions
+async fn fire(rule: &Rule, alert: &Value) {
+    eprintln!(
+        "🚨 {}: {}",
+        rule.name,
+        alert["message"].as_str().unwrap_or_default()
+    );
+    let mut exit_code = None;
+    for action in &rule.actions {
+        if let Some(ref url) = action.webhook
+            && let Err(e) = Webhook::new(url).deliver(alert).await
+        {
+            eprintln!("Failed to deliver webhook: {e}");
+        }
+        if let Some(ref command) = action.exec
+            && let Err(e) = run_command(command, alert).await
+        {
+            eprintln!("Hook command failed: {e}");
+        }
+        exit_code = exit_code.or(action.exit);
+    }
+    // Exit last so the rule's other actions still run
+    if let Some(code) = exit_code {
+        std::process::exit(code);
+    }
+}
+
+/// ID of the newest event matching `filter`, or null if there is none
+async fn newest_event_id(config: &RpcConfig, filter: &Value) -> Result<Value> {
+    let newest = rpc_request(config, "suix_queryEvents", json!([filter, null, 1, true])).await?;
+    Ok(newest["data"]
+        .as_array()
+        .and_then(|events| events.first())
+        .map(|event| event["id"].clone())
+        .unwrap_or(Value::Null))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = parse_rules(
+            r#"
+interval: 30
+rules:
+  - name: treasury low
+    balance_below: { address: "0xa", amount: "100.5" }
+    actions:
+      - webhook: https://hooks.example.com/alert
+      - exit: 2
+  - name: stalled
+    no_checkpoint_for: 60
+  - name: swaps
+    event: 'type =~ "::swap::"'
+    actions: [{ exec: "notify-send swap" }]
+  - name: gas spike
+    gas_price_above: 2000
+"#,
+        )
+        .unwrap();
+        assert_eq!(rules.interval, Some(30));
+        assert_eq!(rules.rules.len(), 4);
+        assert!(matches!(
+            rules.rules[0].condition,
+            Condition::BalanceBelow { ref coin_type, .. } if coin_type == SUI_COIN_TYPE
+        ));
+        assert_eq!(rules.rules[0].actions[1].exit, Some(2));
+        assert!(matches!(
+            rules.rules[1].condition,
+            Condition::NoCheckpointFor(60)
+        ));
+        assert!(matches!(
+            rules.rules[3].condition,
+            Condition::GasPriceAbove(2000)
+        ));
+    }
+
+    #[test]
+    fn test_parse_rules_errors() {
+        assert!(parse_rules("rules: []").is_err());
+        assert!(parse_rules("rules:\n  - name: x\n    unknown_condition: 1\n").is_err());
+        assert!(
+            parse_rules(
+                "rules:\n  - name: x\n    gas_price_above: 1\n    actions: [{ page: x }]\n"
+            )
+            .is_err()
+        );
+        assert!(parse_rules("rules:\n  - name: x\n    event: 'owner == 0x1'\n").is_err());
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use clap::Subcommand;
+use eyre::Result;
+use rpc::{coins::format_signed_amount, json_u64, json_u128, latest_checkpoint, rpc_request};
+use serde_json::{Value, json};
+
+use crate::{
+    context::Context,
+    filter::normalize_address,
+    output::{OutputFormat, Table},
+};
+
+/// Page size used when paging `suix_queryTransactionBlocks`
+const TX_PAGE_SIZE: u64 = 50;
+
+#[derive(Subcommand)]
+pub enum AnalyzeCommands {
+    /// Gas usage per entry function of a package, most expensive first.
+    ///
+    /// A transaction calling several of the package's functions has its gas
+    /// split evenly between them.
+    Gas {
+        /// Package ID
+        #[arg(long, value_name = "ID")]
+        package: String,
+        /// Number of most recent checkpoints to scan
+        #[arg(long, value_name = "COUNT", default_value = "1000")]
+        last_checkpoints: u64,
+        /// Show only the N most expensive functions
+        #[arg(long, value_name = "N")]
+        top: Option<usize>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+/// Gas charged to one entry function, in MIST
+#[derive(Debug, Default, Clone, PartialEq)]
+struct FunctionGas {
+    transactions: u64,
+    failed: u64,
+    computation: u128,
+    storage: u128,
+    rebate: u128,
+}
+
+impl FunctionGas {
+    /// Computation plus storage minus rebates
+    fn net(&self) -> i128 {
+        (self.computation + self.storage) as i128 - self.rebate as i128
+    }
+
+    fn average(&self) -> i128 {
+        self.net() / i128::from(self.transactions.max(1))
+    }
+}
+
+/// Gas usage of a package's entry functions
+#[derive(Debug, Default)]
+struct GasUsage {
+    functions: BTreeMap<String, FunctionGas>,
+    transactions: u64,
+}
+
+impl GasUsage {
+    fn add(&mut self, package: &str, tx: &Value) {
+        let commands = tx["transaction"]["data"]["transaction"]["transactions"].as_array();
+        let functions: BTreeSet<String> = commands
+            .into_iter()
+            .flatten()
+            .filter_map(|command| command.get("MoveCall"))
+            .filter(|call| {
+                call["package"]
+                    .as_str()
+                    .is_some_and(|p| normalize_address(p) == package)
+            })
+            .map(|call| {
+                format!(
+                    "{}::{}",
+                    call["module"].as_str().unwrap_or_default(),
+                    call["function"].as_str().unwrap_or_default()
+                )
+            })
+            .collect();
+        if functions.is_empty() {
+            return;
+        }
+        self.transactions += 1;
+
+        let effects = &tx["effects"];
+        let failed = effects["status"]["status"].as_str() == Some("failure");
+        let used = &effects["gasUsed"];
+        let share = functions.len() as u128;
+        let field = |name: &str| json_u128(&used[name]).unwrap_or_default() / share;
+        for function in functions {
+            let gas = self.functions.entry(function).or_default();
+            gas.transactions += 1;
+            gas.failed += u64::from(failed);
+            gas.computation += field("computationCost");
+            gas.storage += field("storageCost");
+            gas.rebate += field("storageRebate");
+        }
+    }
+
+    /// Functions ordered by net gas, most expensive first
+    fn ranked(&self) -> Vec<(&String, &FunctionGas)> {
+        let mut ranked: Vec<_> = self.functions.iter().collect();
+        ranked.sort_by_key(|(_, gas)| std::cmp::Reverse(gas.net()));
+        ranked
+    }
+}
+
+pub async fn run(cmd: AnalyzeCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        AnalyzeCommands::Gas {
+            package,
+            last_checkpoints,
+            top,
+            format,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let package = normalize_address(&package);
+            let latest = latest_checkpoint(&config).await?;
+            let first = (latest + 1).saturating_sub(last_checkpoints);
+
+            let query = json!({
+                "filter": { "MoveFunction": { "package": package } },
+                "options": { "showInput": true, "showEffects": true },
+            });
+            let mut usage = GasUsage::default();
+            let mut cursor = Value::Null;
+            'pages: loop {
+                let page = rpc_request(
+                    &config,
+                    "suix_queryTransactionBlocks",
+                    json!([query, cursor, TX_PAGE_SIZE, true]),
+                )
+                .await?;
+                for tx in page["data"].as_array().into_iter().flatten() {
+                    if json_u64(&tx["checkpoint"]).is_some_and(|seq| seq < first) {
+                        break 'pages;
+                    }
+                    usage.add(&package, tx);
+                }
+                if !page["hasNextPage"].as_bool().unwrap_or(false) {
+                    break;
+                }
+                cursor = page["nextCursor"].clone();
+                eprint!("\r{} transactions scanned", usage.transactions);
+            }
+            eprintln!(
+                "\r{} transactions in checkpoints {}..={}",
+                usage.transactions, first, latest
+            );
+
+            let ranked: Vec<_> = usage
+                .ranked()
+                .into_iter()
+                .take(top.unwrap_or(usize::MAX))
+                .collect();
+            if format == OutputFormat::Json {
+                let functions: Vec<Value> = ranked
+                    .iter()
+                    .map(|(function, gas)| {
+                        json!({
+                            "function": function,
+                            "transactions": gas.transactions,
+                            "failed": gas.failed,
+                            "computation_cost": gas.computation.to_string(),
+                            "storage_cost": gas.storage.to_string(),
+                            "storage_rebate": gas.rebate.to_string(),
+                            "net_gas": gas.net().to_string(),
+                            "average_net_gas": gas.average().to_string(),
+                        })
+                    })
+                    .collect();
+                let report = json!({
+                    "package": package,
+                    "first_checkpoint": first,
+                    "last_checkpoint": latest,
+                    "transactions": usage.transactions,
+                    "functions": functions,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+
+            let sui = |mist: i128| format_signed_amount(mist, 9);
+            let mut table = Table::new([
+                "function",
+                "txs",
+                "failed",
+                "computation",
+                "storage",
+                "rebate",
+                "net_sui",
+                "avg_sui",
+            ]);
+            for (function, gas) in ranked {
+                table.push_row([
+                    function.clone(),
+                    gas.transactions.to_string(),
+                    gas.failed.to_string(),
+                    sui(gas.computation as i128),
+                    sui(gas.storage as i128),
+                    sui(gas.rebate as i128),
+                    sui(gas.net()),
+                    sui(gas.average()),
+                ]);
+            }
+            table.print(format)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(calls: &[(&str, &str)], status: &str, computation: u64, storage: u64) -> Value {
+        let commands: Vec<Value> = calls
+            .iter()
+            .map(|(package, function)| {
+                json!({ "MoveCall": { "package": package, "module": "pool", "function": function } })
+            })
+            .collect();
+        json!({
+            "transaction": { "data": { "transaction": { "transactions": commands } } },
+            "effects": {
+                "status": { "status": status },
+                "gasUsed": {
+                    "computationCost": computation.to_string(),
+                    "storageCost": storage.to_string(),
+                    "storageRebate": "100",
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn test_gas_usage_ranks_functions() {
+        let package = normalize_address("0xa");
+        let mut usage = GasUsage::default();
+        usage.add(&package, &tx(&[("0xa", "swap")], "success", 1000, 500));
+        usage.add(&package, &tx(&[("0xa", "swap")], "failure", 1000, 0));
+        // Split evenly; the call into another package is ignored
+        usage.add(
+            &package,
+            &tx(
+                &[("0xa", "deposit"), ("0xa", "withdraw"), ("0x2", "split")],
+                "success",
+                400,
+                200,
+            ),
+        );
+        usage.add(&package, &tx(&[("0x2", "split")], "success", 1, 1));
+
+        assert_eq!(usage.transactions, 3);
+        let ranked = usage.ranked();
+        assert_eq!(ranked[0].0, "pool::swap");
+        assert_eq!(ranked[0].1.transactions, 2);
+        assert_eq!(ranked[0].1.failed, 1);
+        assert_eq!(ranked[0].1.net(), 2300);
+        assert_eq!(ranked[0].1.average(), 1150);
+        assert_eq!(ranked[1].1.net(), 250);
+        assert_eq!(ranked.len(), 3);
+    }
+}
@@ -0,0 +1,140 @@
+use std::{fs, path::PathBuf};
+
+use clap::{Args, ValueEnum};
+use eyre::{Result, bail};
+use futures::{StreamExt, stream};
+use grpc::SuiGrpcClient;
+
+use crate::{
+    context::Context,
+    sink::{Cursor, write_atomic},
+};
+
+/// Checkpoints between progress lines on stderr
+const REPORT_EVERY: u64 = 100;
+
+#[derive(Args)]
+pub struct ArchiveArgs {
+    /// Directory receiving one file per checkpoint and a `progress.json` watermark
+    #[arg(long, value_name = "DIR")]
+    dir: PathBuf,
+
+    /// First checkpoint to archive; a saved watermark past it takes precedence
+    #[arg(long, value_name = "SEQ", default_value = "0")]
+    from: u64,
+
+    /// Last checkpoint to archive (a sequence number or `latest`)
+    #[arg(long, value_name = "SEQ", default_value = "latest")]
+    to: String,
+
+    /// File format of the archived checkpoints
+    #[arg(long, value_enum, default_value = "bcs")]
+    format: Format,
+
+    /// Maximum number of checkpoints fetched concurrently
+    #[arg(short = 'j', long, value_name = "COUNT", default_value = "8")]
+    concurrency: usize,
+
+    /// gRPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+
+    /// Request timeout in seconds
+    #[arg(long, value_name = "SECONDS", default_value = "30")]
+    timeout: u64,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// BCS-encoded `CheckpointData` (`<SEQ>.bcs`)
+    Bcs,
+    /// `CheckpointData` as a single JSON line (`<SEQ>.ndjson`)
+    Ndjson,
+    /// One flat JSON line per transaction: digest, sender, gas, status and
+    /// changed objects (`<SEQ>.jsonl`)
+    Transactions,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Bcs => "bcs",
+            Format::Ndjson => "ndjson",
+            Format::Transactions => "jsonl",
+        }
+    }
+}
+
+pub async fn run(args: ArchiveArgs, ctx: &Context) -> Result<()> {
+    if args.concurrency == 0 {
+        bail!("--concurrency must be greater than 0");
+    }
+
+    let config = ctx.grpc_config(args.url, false, true, args.timeout);
+    let client = SuiGrpcClient::new(config)
+        .await
+        .map_err(|e| eyre::eyre!(e))?;
+    let to = if args.to == "latest" {
+        client
+            .latest_checkpoint_sequence()
+            .await
+            .map_err(|e| eyre::eyre!(e))?
+    } else {
+        args.to
+            .parse()
+            .map_err(|_| eyre::eyre!("--to must be a sequence number or `latest`"))?
+    };
+
+    fs::create_dir_all(&args.dir)?;
+    let cursor = Cursor {
+        path: args.dir.join("progress.json"),
+    };
+    let from = match cursor.load()? {
+        Some(next) if next > args.from => {
+            eprintln!("Resuming archive at checkpoint {next}");
+            next
+        }
+        _ => args.from,
+    };
+
+    // Checkpoints arrive in order, so the watermark never skips a missing file
+    let client = &client;
+    let mut checkpoints = stream::iter(from..=to)
+        .map(|seq| async move { (seq, client.fetch_full_checkpoint(seq).await) })
+        .buffered(args.concurrency);
+    while let Some((seq, checkpoint)) = checkpoints.next().await {
+        let checkpoint = checkpoint.map_err(|e| eyre::eyre!("Checkpoint {}: {}", seq, e))?;
+        let content = match args.format {
+            Format::Bcs => bcs::to_bytes(&checkpoint)?,
+            Format::Ndjson => {
+                let mut line = serde_json::to_vec(&checkpoint)?;
+                line.push(b'\n');
+                line
+            }
+            Format::Transactions => {
+                let mut lines = Vec::new();
+                for tx in grpc::flat_transactions(&checkpoint) {
+                    serde_json::to_writer(&mut lines, &tx)?;
+                    lines.push(b'\n');
+                }
+                lines
+            }
+        };
+        let path = args.dir.join(format!("{seq}.{}", args.format.extension()));
+        write_atomic(&path, content)?;
+        cursor.save(seq + 1)?;
+        if (seq + 1 - from) % REPORT_EVERY == 0 {
+            eprintln!("Archived checkpoints {from}..={seq}");
+        }
+    }
+
+    eprintln!(
+        "✅ Archived checkpoints up to {to} in {}",
+        args.dir.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat};
+use clap::Args;
+use eyre::{Result, bail};
+use rpc::{RpcConfig, json_u64, latest_checkpoint, rpc_request};
+use serde_json::{Value, json};
+
+use crate::context::Context;
+
+#[derive(Args)]
+pub struct AtTimeArgs {
+    /// RFC 3339 timestamp, YYYY-MM-DD (midnight UTC) or Unix time in seconds or milliseconds
+    #[arg(value_name = "TIME")]
+    time: String,
+
+    /// Also list the digests of the checkpoint's transactions
+    #[arg(long)]
+    txs: bool,
+
+    /// Print the result as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// RPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+}
+
+pub async fn run(args: AtTimeArgs, ctx: &Context) -> Result<()> {
+    let target = parse_timestamp_ms(&args.time)?;
+    let config = ctx.rpc_config(args.url, false);
+
+    let latest = latest_checkpoint(&config).await?;
+
+    let mut low = lowest_checkpoint(&config, latest).await?;
+    if checkpoint_timestamp(&low.1) > target {
+        bail!(
+            "{} is before checkpoint {} ({}), the oldest available; set --archive-url for older data",
+            format_ms(target),
+            low.0,
+            format_ms(checkpoint_timestamp(&low.1))
+        );
+    }
+
+    // Invariant: `low` started at or before the target, `high` after it
+    let mut high = latest + 1;
+    while high - low.0 > 1 {
+        let mid = low.0 + (high - low.0) / 2;
+        let checkpoint = fetch_checkpoint(&config, mid).await?;
+        if checkpoint_timestamp(&checkpoint) <= target {
+            low = (mid, checkpoint);
+        } else {
+            high = mid;
+        }
+    }
+    let (seq, checkpoint) = low;
+    let next = if seq < latest {
+        Some(fetch_checkpoint(&config, seq + 1).await?)
+    } else {
+        None
+    };
+
+    let timestamp = checkpoint_timestamp(&checkpoint);
+    let digests: Vec<&str> = checkpoint["transactions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+
+    if args.json {
+        let mut report = json!({
+            "time": format_ms(target),
+            "timestamp_ms": target,
+            "checkpoint": seq,
+            "checkpoint_timestamp_ms": timestamp,
+            "epoch": json_u64(&checkpoint["epoch"]),
+            "digest": checkpoint["digest"],
+            "next_checkpoint_timestamp_ms": next.as_ref().map(checkpoint_timestamp),
+            "transaction_count": digests.len(),
+        });
+        if args.txs {
+            report["transactions"] = json!(digests);
+        }
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("Time:         {} ({target})", format_ms(target));
+    println!(
+        "Checkpoint:   {seq} at {} ({:.3}s earlier)",
+        format_ms(timestamp),
+        (target - timestamp) as f64 / 1000.0
+    );
+    println!(
+        "Epoch:        {}",
+        json_u64(&checkpoint["epoch"]).unwrap_or_default()
+    );
+    match next {
+        Some(ref next) => println!(
+            "Next:         {} at {}",
+            seq + 1,
+            format_ms(checkpoint_timestamp(next))
+        ),
+        None => println!("Next:         none yet (this is the latest checkpoint)"),
+    }
+    println!("Transactions: {}", digests.len());
+    if args.txs {
+        for digest in digests {
+            println!("  {digest}");
+        }
+    }
+    Ok(())
+}
+
+/// Oldest checkpoint the node still serves: genesis, or the first one after its pruning point
+async fn lowest_checkpoint(config: &RpcConfig, latest: u64) -> Result<(u64, Value)> {
+    if let Ok(genesis) = fetch_checkpoint(config, 0).await {
+        return Ok((0, genesis));
+    }
+    // Checkpoints are pruned from the bottom: find the first one still available
+    let (mut missing, mut available) = (0, latest);
+    while available - missing > 1 {
+        let mid = missing + (available - missing) / 2;
+        if fetch_checkpoint(config, mid).await.is_ok() {
+            available = mid;
+        } else {
+            missing = mid;
+        }
+    }
+    Ok((available, fetch_checkpoint(config, available).await?))
+}
+
+async fn fetch_checkpoint(config: &RpcConfig, seq: u64) -> Result<Value> {
+    rpc_request(config, "sui_getCheckpoint", json!([seq.to_string()]))
+        .await
+        .map_err(|e| eyre::eyre!("Checkpoint {}: {}", seq, e))
+}
+
+fn checkpoint_timestamp(checkpoint: &Value) -> u64 {
+    json_u64(&checkpoint["timestampMs"]).unwrap_or_default()
+}
+
+/// Milliseconds since the Unix epoch for an RFC 3339 timestamp, a date or a Unix time
+pub(crate) fn parse_timestamp_ms(value: &str) -> Result<u64> {
+    if let Ok(number) = value.parse::<u64>() {
+        // Second-resolution Unix times stay below 10^11 until the year 5138
+        return Ok(if number < 100_000_000_000 {
+            number * 1000
+        } else {
+            number
+        });
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return u64::try_from(datetime.timestamp_millis())
+            .map_err(|_| eyre::eyre!("{} is before 1970", value));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        eyre::eyre!("Time must be RFC 3339, YYYY-MM-DD or a Unix timestamp, got {value}")
+    })?;
+    u64::try_from(NaiveDateTime::from(date).and_utc().timestamp_millis())
+        .map_err(|_| eyre::eyre!("{} is before 1970", value))
+}
+
+pub(crate) fn format_ms(ms: u64) -> String {
+    i64::try_from(ms)
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|dt| dt.to_rfc3339_opts(SecondsFormat::Millis, true))
+        .unwrap_or_else(|| ms.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp_ms() {
+        assert_eq!(
+            parse_timestamp_ms("2024-06-01T12:00:00Z").unwrap(),
+            1_717_243_200_000
+        );
+        assert_eq!(
+            parse_timestamp_ms("2024-06-01T14:00:00+02:00").unwrap(),
+            1_717_243_200_000
+        );
+        assert_eq!(parse_timestamp_ms("2024-06-01").unwrap(), 1_717_200_000_000);
+        assert_eq!(parse_timestamp_ms("1717243200").unwrap(), 1_717_243_200_000);
+        assert_eq!(
+            parse_timestamp_ms("1717243200123").unwrap(),
+            1_717_243_200_123
+        );
+        assert!(parse_timestamp_ms("June 1st").is_err());
+        assert_eq!(format_ms(1_717_243_200_000), "2024-06-01T12:00:00.000Z");
+    }
+}
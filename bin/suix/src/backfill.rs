@@ -0,0 +1,158 @@
+use clap::{Args, ValueEnum};
+use eyre::{Result, bail};
+use futures::{StreamExt, TryStreamExt, stream};
+use rpc::{MAX_DIGESTS_PER_CALL, RpcConfig, latest_checkpoint, rpc_request};
+use serde_json::{Value, json};
+
+use crate::{context::Context, sink::open_sink};
+
+#[derive(Args)]
+pub struct BackfillArgs {
+    /// First checkpoint to backfill
+    #[arg(long, value_name = "SEQ", default_value = "0")]
+    from: u64,
+
+    /// Last checkpoint to backfill (a sequence number or `latest`)
+    #[arg(long, value_name = "SEQ", default_value = "latest")]
+    to: String,
+
+    /// Kind of data to backfill
+    #[arg(long, value_enum, default_value = "checkpoints")]
+    what: Kind,
+
+    /// Output location: a directory of NDJSON files, `sled:<PATH>` for an embedded
+    /// database, or a `kafka://...` / `nats://...` stream (see `suix sink`)
+    #[arg(long, value_name = "SINK")]
+    sink: String,
+
+    /// Checkpoints per batch; progress is recorded after every batch
+    #[arg(long, value_name = "COUNT", default_value = "100")]
+    batch_size: u64,
+
+    /// Maximum number of checkpoints fetched concurrently
+    #[arg(short = 'j', long, value_name = "COUNT", default_value = "8")]
+    concurrency: usize,
+
+    /// RPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum Kind {
+    Checkpoints,
+    Events,
+    Txs,
+}
+
+impl Kind {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Kind::Checkpoints => "checkpoints",
+            Kind::Events => "events",
+            Kind::Txs => "txs",
+        }
+    }
+}
+
+pub async fn run(args: BackfillArgs, ctx: &Context) -> Result<()> {
+    if args.batch_size == 0 || args.concurrency == 0 {
+        bail!("--batch-size and --concurrency must be greater than 0");
+    }
+
+    // Pruned ranges are fetched from the global --archive-url, if set
+    let config = ctx.rpc_config(args.url, false);
+
+    let to = if args.to == "latest" {
+        latest_checkpoint(&config).await?
+    } else {
+        args.to
+            .parse()
+            .map_err(|_| eyre::eyre!("--to must be a sequence number or `latest`"))?
+    };
+
+    let mut sink = open_sink(&args.sink, args.what).await?;
+    let mut next = match sink.load_progress()? {
+        Some(next) if next > args.from => {
+            eprintln!(
+                "Resuming {} backfill at checkpoint {next}",
+                args.what.name()
+            );
+            next
+        }
+        _ => args.from,
+    };
+
+    while next <= to {
+        let end = (next + args.batch_size - 1).min(to);
+        let config = &config;
+        let per_checkpoint: Vec<Vec<Value>> = stream::iter(next..=end)
+            .map(|seq| fetch_items(config, seq, args.what))
+            .buffered(args.concurrency)
+            .try_collect()
+            .await?;
+        let items: Vec<Value> = per_checkpoint.into_iter().flatten().collect();
+
+        sink.write_batch(next, end, &items).await?;
+        sink.save_progress(end + 1)?;
+        eprintln!(
+            "Backfilled {} for checkpoints {next}..={end} ({} items)",
+            args.what.name(),
+            items.len()
+        );
+        next = end + 1;
+    }
+
+    eprintln!("✅ Backfill complete up to checkpoint {to}");
+    Ok(())
+}
+
+/// Fetch everything of the requested kind contained in one checkpoint
+pub(crate) async fn fetch_items(config: &RpcConfig, seq: u64, kind: Kind) -> Result<Vec<Value>> {
+    let checkpoint = rpc_request(config, "sui_getCheckpoint", json!([seq.to_string()]))
+        .await
+        .map_err(|e| eyre::eyre!("Checkpoint {}: {}", seq, e))?;
+
+    if let Kind::Checkpoints = kind {
+        return Ok(vec![json!({ "checkpoint": seq, "data": checkpoint })]);
+    }
+
+    let digests: Vec<Value> = checkpoint["transactions"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let mut items = Vec::new();
+    for chunk in digests.chunks(MAX_DIGESTS_PER_CALL) {
+        let options = json!({
+            "showInput": true,
+            "showEffects": true,
+            "showEvents": true,
+            "showObjectChanges": true,
+            "showBalanceChanges": true,
+        });
+        let txs = rpc_request(
+            config,
+            "sui_multiGetTransactionBlocks",
+            json!([chunk, options]),
+        )
+        .await?;
+        for tx in txs.as_array().into_iter().flatten() {
+            match kind {
+                Kind::Txs => items.push(json!({ "checkpoint": seq, "data": tx })),
+                Kind::Events => items.extend(
+                    tx["events"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .map(|event| json!({ "checkpoint": seq, "data": event })),
+                ),
+                Kind::Checkpoints => {}
+            }
+        }
+    }
+    Ok(items)
+}
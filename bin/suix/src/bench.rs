@@ -0,0 +1,315 @@
+use std::time::Duration;
+
+use clap::Args;
+use eyre::{Result, bail};
+use grpc::{BenchRun, SuiGrpcClient};
+use serde_json::{Value, json};
+
+use crate::{
+    context::Context,
+    gas::percentile,
+    output::{OutputFormat, Table},
+};
+
+/// Upper bounds of the histogram buckets in milliseconds; slower calls share a last bucket
+const BUCKETS_MS: [f64; 16] = [
+    0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0,
+    20000.0, 50000.0,
+];
+
+/// Width of the longest histogram bar
+const BAR_WIDTH: usize = 40;
+
+/// Columns of the latency table, in milliseconds after the endpoint
+const LATENCY_COLUMNS: [&str; 9] = [
+    "Endpoint", "Requests", "Errors", "Req/s", "Min", "P50", "P95", "P99", "Max",
+];
+
+#[derive(Args)]
+pub struct BenchArgs {
+    /// Method to call; `GetLatestCheckpoint` needs no reflection
+    #[arg(long, default_value = "GetLatestCheckpoint")]
+    method: String,
+
+    /// Service of --method
+    #[arg(long, default_value = "sui.rpc.v2.LedgerService")]
+    service: String,
+
+    /// Proto3 JSON request sent with every call
+    #[arg(long, value_name = "JSON", default_value = "{}")]
+    request: String,
+
+    /// Number of timed calls
+    #[arg(long, value_name = "COUNT", default_value = "1000")]
+    requests: usize,
+
+    /// Maximum number of calls in flight
+    #[arg(long, value_name = "COUNT", default_value = "16")]
+    concurrency: usize,
+
+    /// Untimed calls made first, to open connections
+    #[arg(long, value_name = "COUNT", default_value = "1")]
+    warmup: usize,
+
+    /// Output format of the report
+    #[arg(long, value_enum, default_value = "table")]
+    format: OutputFormat,
+
+    /// gRPC endpoint URL (repeat to compare providers)
+    #[arg(
+        long = "url",
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    urls: Vec<String>,
+
+    /// Request timeout in seconds
+    #[arg(long, value_name = "SECONDS", default_value = "30")]
+    timeout: u64,
+}
+
+pub async fn run(args: BenchArgs, ctx: &Context) -> Result<()> {
+    if args.requests == 0 || args.concurrency == 0 {
+        bail!("--requests and --concurrency must be greater than 0");
+    }
+    let request: Value = serde_json::from_str(&args.request)
+        .map_err(|e| eyre::eyre!("Invalid --request JSON: {}", e))?;
+
+    // Endpoints are benchmarked one after another so they do not compete for bandwidth
+    let mut runs = Vec::with_capacity(args.urls.len());
+    for url in &args.urls {
+        let config = ctx.grpc_config(url.clone(), false, true, args.timeout);
+        let client = SuiGrpcClient::new(config)
+            .await
+            .map_err(|e| eyre::eyre!(e))?;
+        if args.format == OutputFormat::Table {
+            eprintln!(
+                "Sending {} {} calls to {} ({} in flight)...",
+                args.requests, args.method, url, args.concurrency
+            );
+        }
+        let run = client
+            .bench(
+                &args.service,
+                &args.method,
+                request.clone(),
+                args.requests,
+                args.concurrency,
+                args.warmup,
+            )
+            .await
+            .map_err(|e| eyre::eyre!("{}: {}", url, e))?;
+        runs.push((url, run));
+    }
+
+    match args.format {
+        OutputFormat::Json => {
+            for (url, run) in &runs {
+                let mut json = Report::new(run).to_json();
+                json["url"] = json!(url);
+                json["method"] = json!(args.method);
+                json["concurrency"] = json!(args.concurrency);
+                println!("{json}");
+            }
+        }
+        OutputFormat::Csv => {
+            let mut table = Table::new(LATENCY_COLUMNS);
+            for (url, run) in &runs {
+                table.push_row(Report::new(run).row(url));
+            }
+            table.print(args.format)?;
+        }
+        OutputFormat::Table => {
+            for (i, (url, run)) in runs.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                let report = Report::new(run);
+                println!(
+                    "{url}: {} calls in {:.2}s, {:.1} req/s, {:.2}% errors (latency in ms)",
+                    run.requests(),
+                    run.elapsed.as_secs_f64(),
+                    run.throughput(),
+                    run.error_rate() * 100.0
+                );
+                let mut table = Table::new(LATENCY_COLUMNS.into_iter().skip(1));
+                table.push_row(report.row(url).into_iter().skip(1));
+                table.print(args.format)?;
+                println!();
+                for line in report.histogram_lines() {
+                    println!("{line}");
+                }
+                for (error, count) in &run.errors {
+                    println!("{count:>6} × {error}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Latency distribution of a run, in microseconds
+struct Report<'a> {
+    run: &'a BenchRun,
+    sorted: Vec<u64>,
+}
+
+impl<'a> Report<'a> {
+    fn new(run: &'a BenchRun) -> Self {
+        let mut sorted: Vec<u64> = run.latencies.iter().map(|l| l.as_micros() as u64).collect();
+        sorted.sort_unstable();
+        Self { run, sorted }
+    }
+
+    fn stats(&self) -> [(&'static str, u64); 6] {
+        let p = |p| percentile(&self.sorted, p).unwrap_or_default();
+        let mean = match self.sorted.len() as u64 {
+            0 => 0,
+            n => self.sorted.iter().sum::<u64>() / n,
+        };
+        [
+            ("min", p(0.0)),
+            ("p50", p(50.0)),
+            ("p95", p(95.0)),
+            ("p99", p(99.0)),
+            ("max", p(100.0)),
+            ("mean", mean),
+        ]
+    }
+
+    /// Row of [`LATENCY_COLUMNS`]
+    fn row(&self, url: &str) -> Vec<String> {
+        let [min, p50, p95, p99, max, _] = self.stats().map(|(_, v)| format_ms(v));
+        vec![
+            url.to_string(),
+            self.run.requests().to_string(),
+            self.run.errors.values().sum::<usize>().to_string(),
+            format!("{:.1}", self.run.throughput()),
+            min,
+            p50,
+            p95,
+            p99,
+            max,
+        ]
+    }
+
+    /// Calls per bucket of [`BUCKETS_MS`], plus one for anything slower
+    fn histogram(&self) -> Vec<usize> {
+        let mut counts = vec![0; BUCKETS_MS.len() + 1];
+        for micros in &self.sorted {
+            let ms = *micros as f64 / 1000.0;
+            let bucket = BUCKETS_MS
+                .iter()
+                .position(|bound| ms <= *bound)
+                .unwrap_or(BUCKETS_MS.len());
+            counts[bucket] += 1;
+        }
+        counts
+    }
+
+    /// Bar chart of the occupied range of buckets
+    fn histogram_lines(&self) -> Vec<String> {
+        let counts = self.histogram();
+        let Some(first) = counts.iter().position(|c| *c > 0) else {
+            return Vec::new();
+        };
+        let last = counts.iter().rposition(|c| *c > 0).unwrap_or(first);
+        let peak = counts.iter().copied().max().unwrap_or(1);
+        (first..=last)
+            .map(|bucket| {
+                let label = match BUCKETS_MS.get(bucket) {
+                    Some(bound) => format!("≤ {bound} ms"),
+                    None => format!("> {} ms", BUCKETS_MS[BUCKETS_MS.len() - 1]),
+                };
+                let count = counts[bucket];
+                let bar = "█".repeat((count * BAR_WIDTH).div_ceil(peak));
+                format!("{label:>11} {count:>7} {bar}")
+            })
+            .collect()
+    }
+
+    fn to_json(&self) -> Value {
+        let latency: serde_json::Map<_, _> = self
+            .stats()
+            .iter()
+            .map(|(name, v)| (name.to_string(), json!(*v as f64 / 1000.0)))
+            .collect();
+        let histogram: Vec<Value> = self
+            .histogram()
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(bucket, count)| json!({ "le_ms": BUCKETS_MS.get(bucket), "count": count }))
+            .collect();
+        json!({
+            "requests": self.run.requests(),
+            "succeeded": self.run.latencies.len(),
+            "error_rate": self.run.error_rate(),
+            "errors": self.run.errors,
+            "elapsed_ms": duration_ms(self.run.elapsed),
+            "throughput_rps": self.run.throughput(),
+            "latency_ms": latency,
+            "histogram": histogram,
+        })
+    }
+}
+
+fn format_ms(micros: u64) -> String {
+    format!("{:.2}", micros as f64 / 1000.0)
+}
+
+fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut run = BenchRun {
+            latencies: [800, 1500, 1800, 4000, 70_000]
+                .into_iter()
+                .map(Duration::from_micros)
+                .collect(),
+            elapsed: Duration::from_secs(2),
+            ..BenchRun::default()
+        };
+        run.errors.insert("deadline exceeded".to_string(), 1);
+        let report = Report::new(&run);
+
+        assert_eq!(report.stats()[1], ("p50", 1800));
+        assert_eq!(report.stats()[4], ("max", 70_000));
+        assert_eq!(
+            report.row("http://node"),
+            [
+                "http://node",
+                "6",
+                "1",
+                "3.0",
+                "0.80",
+                "1.80",
+                "70.00",
+                "70.00",
+                "70.00"
+            ]
+        );
+        let histogram = report.histogram();
+        assert_eq!(&histogram[..4], [0, 1, 2, 1]);
+        assert_eq!(histogram[7], 1);
+
+        let lines = report.histogram_lines();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[0].starts_with("     ≤ 1 ms       1 "));
+        assert!(lines[1].ends_with(&"█".repeat(BAR_WIDTH)));
+
+        let json = report.to_json();
+        assert_eq!(json["requests"], 6);
+        assert_eq!(json["succeeded"], 5);
+        assert_eq!(json["errors"]["deadline exceeded"], 1);
+        assert_eq!(json["latency_ms"]["p50"], 1.8);
+        assert_eq!(json["histogram"][0], json!({ "le_ms": 1.0, "count": 1 }));
+        assert_eq!(json["throughput_rps"], 3.0);
+    }
+}
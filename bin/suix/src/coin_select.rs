@@ -0,0 +1,245 @@
+//! Coin selection strategies over `suix_getCoins` entries
+
+use std::cmp::Reverse;
+
+use clap::ValueEnum;
+use eyre::{Result, bail};
+use rpc::json_u128;
+use serde_json::Value;
+
+/// Move commands take at most 512 arguments; stay below so a selection can always be merged
+pub const MAX_COINS: usize = 500;
+
+/// Branch-and-bound gives up after this many search steps and falls back to largest-first
+const BNB_MAX_STEPS: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Strategy {
+    /// Fewest inputs: take the largest coins until the amount is covered
+    LargestFirst,
+    /// Search for the combination with the least change, ideally an exact match
+    BranchAndBound,
+    /// Spend the smallest coins first so dust gets merged away
+    MinimizeDust,
+    /// Largest-first, but never touch the gas coin (the largest SUI coin unless given)
+    PreserveGasCoin,
+}
+
+impl Strategy {
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::LargestFirst => "largest-first",
+            Strategy::BranchAndBound => "branch-and-bound",
+            Strategy::MinimizeDust => "minimize-dust",
+            Strategy::PreserveGasCoin => "preserve-gas-coin",
+        }
+    }
+}
+
+/// Coins chosen to cover an amount
+#[derive(Debug, Clone)]
+pub struct Selection {
+    pub coins: Vec<Value>,
+    pub total: u128,
+}
+
+impl Selection {
+    /// Amount left over after paying `amount`
+    pub fn change(&self, amount: u128) -> u128 {
+        self.total.saturating_sub(amount)
+    }
+}
+
+/// Pick coins from `coins` covering `amount`.
+///
+/// `gas_coin` is only consulted by [`Strategy::PreserveGasCoin`].
+pub fn select(
+    coins: &[Value],
+    amount: u128,
+    strategy: Strategy,
+    gas_coin: Option<&str>,
+) -> Result<Selection> {
+    let mut candidates: Vec<&Value> = coins.iter().collect();
+    candidates.sort_by_key(|c| Reverse(balance(c)));
+
+    let chosen = match strategy {
+        Strategy::LargestFirst => accumulate(&candidates, amount),
+        Strategy::BranchAndBound => {
+            branch_and_bound(&candidates, amount).or_else(|| accumulate(&candidates, amount))
+        }
+        Strategy::MinimizeDust => {
+            candidates.reverse();
+            accumulate(&candidates, amount)
+        }
+        Strategy::PreserveGasCoin => {
+            let gas = match gas_coin {
+                Some(id) => Some(id.to_string()),
+                None => candidates.first().map(|c| coin_id(c).to_string()),
+            };
+            candidates.retain(|c| Some(coin_id(c)) != gas.as_deref());
+            accumulate(&candidates, amount)
+        }
+    };
+
+    let available: u128 = candidates.iter().map(|c| balance(c)).sum();
+    let Some(chosen) = chosen else {
+        if available >= amount {
+            bail!(
+                "Covering {} needs more than {} coins with {}; merge coins first",
+                amount,
+                MAX_COINS,
+                strategy.name()
+            );
+        }
+        bail!(
+            "Only {} available to {} ({} coins), {} needed",
+            available,
+            strategy.name(),
+            candidates.len(),
+            amount
+        );
+    };
+    let total = chosen.iter().map(|c| balance(c)).sum();
+    Ok(Selection {
+        coins: chosen.into_iter().cloned().collect(),
+        total,
+    })
+}
+
+/// Take coins in order until `amount` is covered
+fn accumulate<'a>(candidates: &[&'a Value], amount: u128) -> Option<Vec<&'a Value>> {
+    let mut chosen = Vec::new();
+    let mut total = 0u128;
+    for coin in candidates {
+        if total >= amount && !chosen.is_empty() {
+            break;
+        }
+        if chosen.len() == MAX_COINS {
+            return None;
+        }
+        total += balance(coin);
+        chosen.push(*coin);
+    }
+    (total >= amount && !chosen.is_empty()).then_some(chosen)
+}
+
+/// Depth-first search over coins sorted largest first for the subset with
+/// the least change, preferring fewer inputs on ties
+fn branch_and_bound<'a>(candidates: &[&'a Value], amount: u128) -> Option<Vec<&'a Value>> {
+    struct Search {
+        balances: Vec<u128>,
+        /// Sum of balances from index i to the end
+        remaining: Vec<u128>,
+        amount: u128,
+        steps: usize,
+        current: Vec<usize>,
+        best: Option<(u128, Vec<usize>)>,
+    }
+
+    impl Search {
+        fn run(&mut self, index: usize, total: u128) {
+            self.steps += 1;
+            if self.steps > BNB_MAX_STEPS || self.current.len() > MAX_COINS {
+                return;
+            }
+            if total >= self.amount {
+                let waste = total - self.amount;
+                let better = match self.best {
+                    Some((best_waste, ref best)) => {
+                        waste < best_waste
+                            || (waste == best_waste && self.current.len() < best.len())
+                    }
+                    None => true,
+                };
+                if better {
+                    self.best = Some((waste, self.current.clone()));
+                }
+                return;
+            }
+            if index == self.balances.len() || total + self.remaining[index] < self.amount {
+                return;
+            }
+            // Including more cannot beat a perfect match
+            if matches!(self.best, Some((0, _))) {
+                return;
+            }
+            self.current.push(index);
+            self.run(index + 1, total + self.balances[index]);
+            self.current.pop();
+            self.run(index + 1, total);
+        }
+    }
+
+    let balances: Vec<u128> = candidates.iter().map(|c| balance(c)).collect();
+    let mut remaining = vec![0u128; balances.len() + 1];
+    for i in (0..balances.len()).rev() {
+        remaining[i] = remaining[i + 1] + balances[i];
+    }
+    let mut search = Search {
+        balances,
+        remaining,
+        amount,
+        steps: 0,
+        current: Vec::new(),
+        best: None,
+    };
+    search.run(0, 0);
+    let (_, indices) = search.best?;
+    Some(indices.into_iter().map(|i| candidates[i]).collect())
+}
+
+pub fn balance(coin: &Value) -> u128 {
+    json_u128(&coin["balance"]).unwrap_or_default()
+}
+
+pub fn coin_id(coin: &Value) -> &str {
+    coin["coinObjectId"].as_str().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn coins(balances: &[u128]) -> Vec<Value> {
+        balances
+            .iter()
+            .enumerate()
+            .map(|(i, b)| json!({ "coinObjectId": format!("0x{i}"), "balance": b.to_string() }))
+            .collect()
+    }
+
+    fn ids(selection: &Selection) -> Vec<&str> {
+        selection.coins.iter().map(coin_id).collect()
+    }
+
+    #[test]
+    fn test_strategies() {
+        let coins = coins(&[1, 50, 30, 20, 2]);
+
+        let largest = select(&coins, 60, Strategy::LargestFirst, None).unwrap();
+        assert_eq!(ids(&largest), ["0x1", "0x2"]);
+        assert_eq!(largest.change(60), 20);
+
+        let exact = select(&coins, 70, Strategy::BranchAndBound, None).unwrap();
+        assert_eq!(exact.total, 70);
+        assert_eq!(exact.coins.len(), 2);
+
+        let dust = select(&coins, 20, Strategy::MinimizeDust, None).unwrap();
+        assert_eq!(ids(&dust), ["0x0", "0x4", "0x3"]);
+
+        let preserve = select(&coins, 40, Strategy::PreserveGasCoin, None).unwrap();
+        assert_eq!(ids(&preserve), ["0x2", "0x3"]);
+        let preserve = select(&coins, 40, Strategy::PreserveGasCoin, Some("0x2")).unwrap();
+        assert_eq!(ids(&preserve), ["0x1"]);
+    }
+
+    #[test]
+    fn test_insufficient_balance() {
+        let coins = coins(&[10, 5]);
+        assert!(select(&coins, 16, Strategy::LargestFirst, None).is_err());
+        assert!(select(&coins, 10, Strategy::PreserveGasCoin, None).is_err());
+        assert!(select(&coins, 15, Strategy::BranchAndBound, None).is_ok());
+    }
+}
@@ -0,0 +1,290 @@
+use std::str::FromStr;
+
+use clap::{Subcommand, ValueEnum};
+use eyre::Result;
+use rpc::{
+    client::{Balance, Coin, CoinMetadata},
+    coins::{CoinRegistry, parse_amount},
+};
+use serde_json::json;
+use sui_types::base_types::SuiAddress;
+
+use crate::{
+    coin_select::{self, Strategy},
+    context::Context,
+    output::{OutputFormat, Table},
+    tx::{SUI_COIN_TYPE, owned_coins},
+};
+
+#[derive(Subcommand)]
+pub enum CoinsCommands {
+    /// Manage the local coin metadata registry
+    #[command(subcommand)]
+    Registry(RegistryCommands),
+    /// Show which coins a transfer of AMOUNT would spend (dry run)
+    Select {
+        /// Owner address
+        address: String,
+        /// Amount in whole coin units (e.g. 1.5)
+        #[arg(long)]
+        amount: String,
+        /// Coin type
+        #[arg(long, value_name = "TYPE", default_value = SUI_COIN_TYPE)]
+        coin_type: String,
+        /// Selection strategy; compares all strategies when omitted
+        #[arg(long, value_enum)]
+        strategy: Option<Strategy>,
+        /// Gas coin kept aside by preserve-gas-coin (defaults to the largest coin)
+        #[arg(long, value_name = "OBJECT_ID")]
+        gas_coin: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RegistryCommands {
+    /// List known coin types with their symbol and decimals
+    List {
+        /// Fetch metadata for these coin types first
+        #[arg(value_name = "COIN_TYPE")]
+        coin_types: Vec<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Re-fetch metadata for every known coin type
+    Refresh {
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+pub async fn run(cmd: CoinsCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        CoinsCommands::Registry(RegistryCommands::List {
+            coin_types,
+            format,
+            url,
+        }) => {
+            let registry = ctx.coin_registry(&ctx.rpc_config(url, false));
+            for coin_type in &coin_types {
+                registry.get(coin_type).await?;
+            }
+            let coins = registry.list();
+
+            if format == OutputFormat::Json {
+                println!("{}", serde_json::to_string_pretty(&coins)?);
+                return Ok(());
+            }
+            let mut table = Table::new(["symbol", "decimals", "name", "coin_type"]);
+            for coin in coins {
+                table.push_row([
+                    coin.symbol,
+                    coin.decimals.to_string(),
+                    coin.name,
+                    coin.coin_type,
+                ]);
+            }
+            table.print(format)
+        }
+        CoinsCommands::Registry(RegistryCommands::Refresh { url }) => {
+            let registry = ctx.coin_registry(&ctx.rpc_config(url, false));
+            let updated = registry.refresh().await?;
+            println!(
+                "Refreshed {} coin types ({updated} changed)",
+                registry.list().len()
+            );
+            Ok(())
+        }
+        CoinsCommands::Select {
+            address,
+            amount,
+            coin_type,
+            strategy,
+            gas_coin,
+            format,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let owner = SuiAddress::from_str(&address)
+                .map_err(|e| eyre::eyre!("Invalid address {}: {}", address, e))?;
+            let coin = ctx.coin_registry(&config).get_or_raw(&coin_type).await;
+            let amount = parse_amount(&amount, coin.decimals)?;
+            let coins = owned_coins(&config, owner, &coin_type).await?;
+
+            let strategies = match strategy {
+                Some(strategy) => vec![strategy],
+                None => Strategy::value_variants().to_vec(),
+            };
+            let mut table = Table::new(["strategy", "coins", "total", "change", "selected"]);
+            for strategy in strategies {
+                match coin_select::select(&coins, amount, strategy, gas_coin.as_deref()) {
+                    Ok(selection) => table.push_row([
+                        strategy.name().to_string(),
+                        selection.coins.len().to_string(),
+                        coin.format(selection.total),
+                        coin.format(selection.change(amount)),
+                        selection
+                            .coins
+                            .iter()
+                            .map(coin_select::coin_id)
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    ]),
+                    Err(e) => table.push_row([
+                        strategy.name().to_string(),
+                        "-".to_string(),
+                        "-".to_string(),
+                        "-".to_string(),
+                        e.to_string(),
+                    ]),
+                }
+            }
+            eprintln!(
+                "{} holds {} {} coins; selecting {}",
+                owner,
+                coins.len(),
+                coin.symbol,
+                coin.format(amount)
+            );
+            table.print(format)
+        }
+    }
+}
+
+/// Print coins as returned by `json-rpc-quick coins`, balances in whole units
+pub async fn print_coins(
+    registry: &CoinRegistry,
+    coins: &[Coin],
+    format: OutputFormat,
+) -> Result<()> {
+    if format == OutputFormat::Json {
+        let coins: Vec<_> = coins
+            .iter()
+            .map(|coin| {
+                json!({
+                    "coinType": coin.coin_type,
+                    "coinObjectId": coin.coin_object_id,
+                    "version": coin.version.to_string(),
+                    "digest": coin.digest,
+                    "balance": coin.balance.to_string(),
+                    "previousTransaction": coin.previous_transaction,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&coins)?);
+        return Ok(());
+    }
+    let mut table = Table::new(["object_id", "balance", "version", "coin_type"]);
+    for coin in coins {
+        let info = registry.get_or_raw(&coin.coin_type).await;
+        table.push_row([
+            coin.coin_object_id.clone(),
+            info.format(coin.balance),
+            coin.version.to_string(),
+            coin.coin_type.clone(),
+        ]);
+    }
+    table.print(format)
+}
+
+/// Print balances as returned by `json-rpc-quick balances`, in whole units
+pub async fn print_balances(
+    registry: &CoinRegistry,
+    balances: &[Balance],
+    format: OutputFormat,
+) -> Result<()> {
+    if format == OutputFormat::Json {
+        let balances: Vec<_> = balances
+            .iter()
+            .map(|balance| {
+                json!({
+                    "coinType": balance.coin_type,
+                    "coinObjectCount": balance.coin_object_count,
+                    "totalBalance": balance.total_balance.to_string(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&balances)?);
+        return Ok(());
+    }
+    let mut table = Table::new(["symbol", "balance", "coins", "coin_type"]);
+    for balance in balances {
+        let info = registry.get_or_raw(&balance.coin_type).await;
+        table.push_row([
+            info.symbol.clone(),
+            info.format(balance.total_balance),
+            balance.coin_object_count.to_string(),
+            balance.coin_type.clone(),
+        ]);
+    }
+    table.print(format)
+}
+
+/// Print the metadata of `coin_type`, or a note that it has none
+pub fn print_metadata(
+    coin_type: &str,
+    metadata: Option<&CoinMetadata>,
+    format: OutputFormat,
+) -> Result<()> {
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&metadata)?);
+        return Ok(());
+    }
+    let Some(metadata) = metadata else {
+        eprintln!("{coin_type} has no coin metadata");
+        return Ok(());
+    };
+    let mut table = Table::new(["symbol", "decimals", "name", "description", "icon_url"]);
+    table.push_row([
+        metadata.symbol.clone(),
+        metadata.decimals.to_string(),
+        metadata.name.clone(),
+        metadata.description.clone(),
+        metadata.icon_url.clone().unwrap_or_default(),
+    ]);
+    table.print(format)
+}
+
+/// Print the total supply of `coin_type`, in whole units and raw
+pub async fn print_supply(
+    registry: &CoinRegistry,
+    coin_type: &str,
+    supply: u128,
+    format: OutputFormat,
+) -> Result<()> {
+    if format == OutputFormat::Json {
+        let supply = json!({ "coinType": coin_type, "totalSupply": supply.to_string() });
+        println!("{}", serde_json::to_string_pretty(&supply)?);
+        return Ok(());
+    }
+    let info = registry.get_or_raw(coin_type).await;
+    let mut table = Table::new(["coin_type", "supply", "raw"]);
+    table.push_row([
+        coin_type.to_string(),
+        info.format(supply),
+        supply.to_string(),
+    ]);
+    table.print(format)
+}
@@ -0,0 +1,404 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use clap::Subcommand;
+use eyre::{Result, bail};
+use rpc::rpc_request;
+use serde_json::{Value, json};
+
+use crate::{
+    context::{Context, Network},
+    diff::{Change, diff_values},
+    package::fetch_package,
+};
+
+/// Placeholder for the package's own address in compared interfaces
+const SELF_ADDRESS: &str = "<self>";
+
+#[derive(Subcommand)]
+pub enum CompareCommands {
+    /// Compare a package's bytecode and interfaces across networks
+    Package {
+        /// Package ID or Move Registry name (e.g. `@org/app`); pass one ID per network
+        /// when the package lives at different addresses
+        #[arg(value_name = "ID_OR_NAME", required = true)]
+        packages: Vec<String>,
+        /// Networks to compare; the first one is the baseline
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_value = "mainnet,testnet"
+        )]
+        networks: Vec<Network>,
+        /// Print the comparison as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// One network's deployment, with its own address masked out
+struct Deployment {
+    network: Network,
+    id: String,
+    version: u64,
+    /// Bytecode with the self address zeroed
+    bytecode: BTreeMap<String, Vec<u8>>,
+    /// Normalized interfaces with the self address replaced by [`SELF_ADDRESS`]
+    interfaces: BTreeMap<String, Value>,
+}
+
+/// How a module on one network compares to the baseline
+#[derive(Debug, PartialEq)]
+enum ModuleStatus {
+    Identical,
+    /// Same public interface, different bytecode (e.g. changed function bodies)
+    BytecodeDiffers,
+    InterfaceDiffers(Vec<Change>),
+    Missing,
+    /// Present here but not on the baseline
+    Extra,
+}
+
+impl ModuleStatus {
+    fn label(&self) -> String {
+        match self {
+            ModuleStatus::Identical => "✅ identical".to_string(),
+            ModuleStatus::BytecodeDiffers => "⚠️ bytecode differs".to_string(),
+            ModuleStatus::InterfaceDiffers(changes) => {
+                format!("❌ interface differs ({})", changes.len())
+            }
+            ModuleStatus::Missing => "❌ missing".to_string(),
+            ModuleStatus::Extra => "❌ not on baseline".to_string(),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ModuleStatus::Identical => "identical",
+            ModuleStatus::BytecodeDiffers => "bytecode_differs",
+            ModuleStatus::InterfaceDiffers(_) => "interface_differs",
+            ModuleStatus::Missing => "missing",
+            ModuleStatus::Extra => "extra",
+        }
+    }
+}
+
+pub async fn run(cmd: CompareCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        CompareCommands::Package {
+            packages,
+            networks,
+            json,
+        } => {
+            if networks.len() < 2 {
+                bail!("--networks needs at least two networks");
+            }
+            if packages.len() != 1 && packages.len() != networks.len() {
+                bail!(
+                    "Pass one package for all networks or one per network ({} given, {} networks)",
+                    packages.len(),
+                    networks.len()
+                );
+            }
+
+            let mut deployments = Vec::new();
+            for (i, network) in networks.iter().enumerate() {
+                let package = &packages[i.min(packages.len() - 1)];
+                deployments.push(fetch_deployment(ctx, *network, package).await?);
+            }
+            let (baseline, others) = deployments
+                .split_first()
+                .ok_or_else(|| eyre::eyre!("No networks to compare"))?;
+            let comparison: BTreeMap<&str, Vec<ModuleStatus>> = module_names(&deployments)
+                .into_iter()
+                .map(|name| {
+                    let statuses = others
+                        .iter()
+                        .map(|other| compare_module(baseline, other, name))
+                        .collect();
+                    (name, statuses)
+                })
+                .collect();
+            let differing = comparison
+                .values()
+                .flatten()
+                .filter(|status| **status != ModuleStatus::Identical)
+                .count();
+
+            if json {
+                let report = json!({
+                    "baseline": baseline.network.name(),
+                    "packages": deployments
+                        .iter()
+                        .map(|d| json!({
+                            "network": d.network.name(),
+                            "id": d.id,
+                            "version": d.version,
+                        }))
+                        .collect::<Vec<_>>(),
+                    "modules": comparison
+                        .iter()
+                        .map(|(name, statuses)| {
+                            let per_network: BTreeMap<&str, Value> = others
+                                .iter()
+                                .zip(statuses)
+                                .map(|(other, status)| {
+                                    let changes = match status {
+                                        ModuleStatus::InterfaceDiffers(changes) => {
+                                            changes.iter().map(Change::to_json).collect()
+                                        }
+                                        _ => Vec::new(),
+                                    };
+                                    (
+                                        other.network.name(),
+                                        json!({ "status": status.name(), "changes": changes }),
+                                    )
+                                })
+                                .collect();
+                            (name.to_string(), json!(per_network))
+                        })
+                        .collect::<BTreeMap<_, _>>(),
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                for deployment in &deployments {
+                    println!(
+                        "{:<9} {} v{}",
+                        deployment.network.name(),
+                        deployment.id,
+                        deployment.version
+                    );
+                }
+                println!();
+                for (name, statuses) in &comparison {
+                    let labels: Vec<String> = others
+                        .iter()
+                        .zip(statuses)
+                        .map(|(other, status)| {
+                            format!("{}: {}", other.network.name(), status.label())
+                        })
+                        .collect();
+                    println!("{name:<24} {}", labels.join("  "));
+                    for (other, status) in others.iter().zip(statuses) {
+                        if let ModuleStatus::InterfaceDiffers(changes) = status {
+                            for change in changes {
+                                println!("    [{}] {change}", other.network.name());
+                            }
+                        }
+                    }
+                }
+                println!();
+            }
+
+            if differing > 0 {
+                bail!(
+                    "Deployments differ from {} ({} module comparisons)",
+                    baseline.network.name(),
+                    differing
+                );
+            }
+            if !json {
+                println!(
+                    "✅ All {} modules match {}",
+                    comparison.len(),
+                    baseline.network.name()
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn fetch_deployment(ctx: &Context, network: Network, package: &str) -> Result<Deployment> {
+    let config = ctx.rpc_config(network.rpc_url().to_string(), false);
+    let id = resolve_package(network, package).await?;
+    let on_chain = fetch_package(&config, &id)
+        .await
+        .map_err(|e| eyre::eyre!("{}: {}", network.name(), e))?;
+    let normalized = rpc_request(
+        &config,
+        "sui_getNormalizedMoveModulesByPackage",
+        json!([id]),
+    )
+    .await?;
+
+    // Modules are compiled against the original package ID, which differs
+    // per network even when the code is identical
+    let self_address = normalized
+        .as_object()
+        .and_then(|modules| modules.values().next())
+        .and_then(|module| module["address"].as_str())
+        .map(normalize_address)
+        .unwrap_or_else(|| normalize_address(&id));
+    let address_bytes = hex::decode(self_address.trim_start_matches("0x"))?;
+
+    let bytecode = on_chain
+        .modules
+        .into_iter()
+        .map(|(name, bytes)| (name, mask_bytes(&bytes, &address_bytes)))
+        .collect();
+    let interfaces = normalized
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(name, module)| (name.clone(), mask_address(module, &self_address)))
+        .collect();
+
+    Ok(Deployment {
+        network,
+        id,
+        version: on_chain.version,
+        bytecode,
+        interfaces,
+    })
+}
+
+/// Package ID, resolving Move Registry names against the network's registry
+async fn resolve_package(network: Network, package: &str) -> Result<String> {
+    if package.starts_with("0x") {
+        return Ok(package.to_string());
+    }
+    let registry = match network {
+        Network::Mainnet => "https://mainnet.mvr.mystenlabs.com",
+        Network::Testnet => "https://testnet.mvr.mystenlabs.com",
+        _ => bail!(
+            "Move Registry names cannot be resolved on {}; pass the package ID",
+            network.name()
+        ),
+    };
+    let url = format!("{registry}/v1/resolution/{package}");
+    let response: Value = reqwest::get(&url)
+        .await
+        .map_err(|e| eyre::eyre!("Move Registry request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| eyre::eyre!("Invalid Move Registry response: {}", e))?;
+    match response["package_id"].as_str() {
+        Some(id) => Ok(id.to_string()),
+        None => bail!(
+            "{} is not registered on {}: {}",
+            package,
+            network.name(),
+            response
+        ),
+    }
+}
+
+fn compare_module(baseline: &Deployment, other: &Deployment, name: &str) -> ModuleStatus {
+    match (baseline.bytecode.get(name), other.bytecode.get(name)) {
+        (Some(a), Some(b)) if a == b => ModuleStatus::Identical,
+        (Some(_), Some(_)) => {
+            let changes = diff_values(
+                baseline.interfaces.get(name).unwrap_or(&Value::Null),
+                other.interfaces.get(name).unwrap_or(&Value::Null),
+            );
+            if changes.is_empty() {
+                ModuleStatus::BytecodeDiffers
+            } else {
+                ModuleStatus::InterfaceDiffers(changes)
+            }
+        }
+        (Some(_), None) => ModuleStatus::Missing,
+        (None, _) => ModuleStatus::Extra,
+    }
+}
+
+fn module_names(deployments: &[Deployment]) -> BTreeSet<&str> {
+    deployments
+        .iter()
+        .flat_map(|d| d.bytecode.keys().map(String::as_str))
+        .collect()
+}
+
+/// Replace every string naming `address` with [`SELF_ADDRESS`]
+fn mask_address(value: &Value, address: &str) -> Value {
+    match value {
+        Value::String(s) if s.starts_with("0x") && normalize_address(s) == address => {
+            json!(SELF_ADDRESS)
+        }
+        Value::Array(items) => items.iter().map(|v| mask_address(v, address)).collect(),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), mask_address(v, address)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Zero every occurrence of `address` in compiled bytecode
+fn mask_bytes(bytes: &[u8], address: &[u8]) -> Vec<u8> {
+    let mut masked = bytes.to_vec();
+    if address.is_empty() || bytes.len() < address.len() {
+        return masked;
+    }
+    let mut i = 0;
+    while i + address.len() <= masked.len() {
+        if masked[i..i + address.len()] == *address {
+            masked[i..i + address.len()].fill(0);
+            i += address.len();
+        } else {
+            i += 1;
+        }
+    }
+    masked
+}
+
+fn normalize_address(address: &str) -> String {
+    let hex = address.trim_start_matches("0x").to_lowercase();
+    format!("0x{hex:0>64}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_address() {
+        let address = normalize_address("0xab");
+        let module = json!({
+            "address": "0xab",
+            "fields": [{ "Struct": { "address": format!("0x{:0>64}", "ab"), "name": "Coin" } }],
+            "other": "0x2",
+        });
+        assert_eq!(
+            mask_address(&module, &address),
+            json!({
+                "address": "<self>",
+                "fields": [{ "Struct": { "address": "<self>", "name": "Coin" } }],
+                "other": "0x2",
+            })
+        );
+    }
+
+    #[test]
+    fn test_compare_module() {
+        let deployment = |code: &[u8], interface: Value| Deployment {
+            network: Network::Mainnet,
+            id: "0x1".to_string(),
+            version: 1,
+            bytecode: BTreeMap::from([("m".to_string(), mask_bytes(code, &[7, 7]))]),
+            interfaces: BTreeMap::from([("m".to_string(), interface)]),
+        };
+        let base = deployment(&[1, 7, 7, 2], json!({ "f": 1 }));
+        assert_eq!(
+            compare_module(&base, &deployment(&[1, 7, 7, 2], json!({ "f": 1 })), "m"),
+            ModuleStatus::Identical
+        );
+        assert_eq!(
+            compare_module(&base, &deployment(&[1, 7, 7, 3], json!({ "f": 1 })), "m"),
+            ModuleStatus::BytecodeDiffers
+        );
+        assert!(matches!(
+            compare_module(&base, &deployment(&[1, 3], json!({ "f": 2 })), "m"),
+            ModuleStatus::InterfaceDiffers(ref changes) if changes.len() == 1
+        ));
+        let empty = Deployment {
+            bytecode: BTreeMap::new(),
+            interfaces: BTreeMap::new(),
+            ..deployment(&[], json!({}))
+        };
+        assert_eq!(compare_module(&base, &empty, "m"), ModuleStatus::Missing);
+        assert_eq!(compare_module(&empty, &base, "m"), ModuleStatus::Extra);
+    }
+}
@@ -0,0 +1,422 @@
+use std::{
+    net::IpAddr,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use cache::ResponseCache;
+use clap::{Args, ValueEnum};
+use eyre::Result;
+use grpc::{
+    ChannelOptions, GrpcConfig, PollPolicy, checkpoint_cache::CheckpointCache, tls::TlsOptions,
+};
+use keystore::{Keystore, vault::Vault};
+use rpc::{RpcConfig, coins::CoinRegistry};
+use store::ChainStore;
+use transport::{RetryPolicy, Transport};
+
+/// Response cache and local store options shared by all commands
+#[derive(Args, Debug, Clone, Default)]
+pub struct CacheArgs {
+    /// Do not read from or write to the on-disk response cache
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Ignore cached responses older than this many seconds
+    #[arg(long, global = true, value_name = "SECONDS")]
+    cache_ttl: Option<u64>,
+
+    /// Directory of the on-disk response cache [default: ~/.cache/suix]
+    #[arg(long, global = true, value_name = "PATH")]
+    cache_dir: Option<PathBuf>,
+
+    /// SQLite file caching checkpoints and object versions fetched over gRPC,
+    /// e.g. ~/.cache/suix/checkpoints.db
+    #[arg(
+        long = "cache",
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "no_cache"
+    )]
+    checkpoint_cache: Option<PathBuf>,
+
+    /// Answer JSON-RPC queries from a store written by `suix store sync`, without network
+    #[arg(long, global = true, value_name = "PATH")]
+    local: Option<PathBuf>,
+}
+
+/// Retry, failover, archival and rate limiting options shared by all commands
+#[derive(Args, Debug, Clone)]
+pub struct TransportArgs {
+    /// Retries per request on transient errors and throttling
+    #[arg(long, global = true, value_name = "COUNT", default_value = "2")]
+    max_retries: u32,
+
+    /// Maximum requests per second across all requests of this run
+    #[arg(long, global = true, value_name = "RPS")]
+    rps: Option<f64>,
+
+    /// Back off automatically when the node throttles (HTTP 429 / RESOURCE_EXHAUSTED)
+    #[arg(long, global = true)]
+    adaptive_rate: bool,
+
+    /// Fallback JSON-RPC endpoint used when the primary keeps failing (repeatable)
+    #[arg(long = "failover", global = true, value_name = "URL")]
+    failover: Vec<String>,
+
+    /// Archival endpoint for historical data the primary endpoint has pruned
+    #[arg(long, global = true, value_name = "URL", env = "SUIX_ARCHIVE_URL")]
+    archive_url: Option<String>,
+
+    /// Print request statistics on exit
+    #[arg(long, global = true)]
+    transport_stats: bool,
+}
+
+impl Default for TransportArgs {
+    fn default() -> Self {
+        Self {
+            max_retries: RetryPolicy::default().max_retries,
+            rps: None,
+            adaptive_rate: false,
+            failover: Vec::new(),
+            archive_url: None,
+            transport_stats: false,
+        }
+    }
+}
+
+/// Where signing commands get their keys from
+#[derive(Args, Debug, Clone, Default)]
+pub struct KeyArgs {
+    /// Sign with keys from this encrypted vault instead of the Sui keystore; also the
+    /// vault the `vault` commands manage [default: ~/.suix/vault.json]
+    #[arg(long, global = true, value_name = "PATH", env = "SUIX_VAULT")]
+    vault: Option<PathBuf>,
+}
+
+/// gRPC options shared by the gRPC commands
+#[derive(Args, Debug, Clone, Default)]
+pub struct GrpcArgs {
+    /// Metadata sent with every gRPC request, e.g. `x-api-key=SECRET` (repeatable)
+    #[arg(long = "header", global = true, value_name = "KEY=VALUE", value_parser = parse_header)]
+    headers: Vec<(String, String)>,
+    /// PEM CA bundle to trust for gRPC endpoints with internal certificates
+    #[arg(long, global = true, value_name = "PATH")]
+    ca_cert: Option<PathBuf>,
+    /// PEM client certificate for mutual TLS (with --client-key)
+    #[arg(long, global = true, value_name = "PATH", requires = "client_key")]
+    client_cert: Option<PathBuf>,
+    /// PEM private key of --client-cert
+    #[arg(long, global = true, value_name = "PATH", requires = "client_cert")]
+    client_key: Option<PathBuf>,
+    /// Skip gRPC server certificate verification (testing only)
+    #[arg(long, global = true)]
+    insecure: bool,
+    /// gRPC connections to spread requests across, for bulk commands
+    #[arg(long, global = true, value_name = "COUNT", default_value = "1")]
+    pool_size: usize,
+    /// Send HTTP/2 pings this often so idle gRPC connections survive NAT timeouts
+    #[arg(long, global = true, value_name = "SECONDS")]
+    keepalive: Option<u64>,
+    /// Drop a gRPC connection whose keepalive ping is not answered in time
+    #[arg(long, global = true, value_name = "SECONDS")]
+    keepalive_timeout: Option<u64>,
+    /// HTTP/2 flow-control window of each gRPC call
+    #[arg(long, global = true, value_name = "BYTES")]
+    window_size: Option<u32>,
+    /// HTTP/2 flow-control window of each gRPC connection
+    #[arg(long, global = true, value_name = "BYTES")]
+    connection_window_size: Option<u32>,
+    /// Largest gRPC response message accepted [default: 4 MiB]
+    #[arg(long, global = true, value_name = "BYTES")]
+    max_message_size: Option<usize>,
+    /// Print gRPC metadata, status codes and timing of each call to stderr
+    #[arg(long, global = true)]
+    verbose: bool,
+    /// Print gRPC responses in the canonical proto3 JSON mapping, as documented by
+    /// the Sui protobuf definitions
+    #[arg(long, global = true)]
+    proto_json: bool,
+    /// Recompute the content, transaction, effects and events digests of each full
+    /// checkpoint fetched and fail on any mismatch with its summary
+    #[arg(long, global = true)]
+    verify_digests: bool,
+}
+
+/// Parse a `KEY=VALUE` header
+fn parse_header(header: &str) -> Result<(String, String), String> {
+    match header.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got `{header}`")),
+    }
+}
+
+/// Well-known Sui networks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Devnet,
+    Localnet,
+}
+
+impl Network {
+    pub fn name(self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Devnet => "devnet",
+            Network::Localnet => "localnet",
+        }
+    }
+
+    /// Public fullnode JSON-RPC endpoint
+    pub fn rpc_url(self) -> &'static str {
+        match self {
+            Network::Mainnet => "https://fullnode.mainnet.sui.io:443",
+            Network::Testnet => "https://fullnode.testnet.sui.io:443",
+            Network::Devnet => "https://fullnode.devnet.sui.io:443",
+            Network::Localnet => "http://127.0.0.1:9000",
+        }
+    }
+}
+
+/// Whether `url` points at this machine
+fn is_loopback(url: &str) -> bool {
+    let Some(host) = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+    else {
+        return false;
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Process-wide state shared by command handlers
+#[derive(Debug, Default)]
+pub struct Context {
+    cache_args: CacheArgs,
+    cache: OnceLock<Option<ResponseCache>>,
+    checkpoint_cache: OnceLock<Option<CheckpointCache>>,
+    transport_args: TransportArgs,
+    transport: OnceLock<Arc<Transport>>,
+    key_args: KeyArgs,
+    grpc_args: GrpcArgs,
+    local: Option<ChainStore>,
+}
+
+impl Context {
+    /// Fails if `--local` names a store that cannot be opened
+    pub fn new(
+        cache_args: CacheArgs,
+        transport_args: TransportArgs,
+        key_args: KeyArgs,
+        grpc_args: GrpcArgs,
+    ) -> Result<Self> {
+        let local = cache_args
+            .local
+            .as_ref()
+            .map(ChainStore::open)
+            .transpose()?;
+        Ok(Self {
+            cache_args,
+            cache: OnceLock::new(),
+            checkpoint_cache: OnceLock::new(),
+            transport_args,
+            transport: OnceLock::new(),
+            key_args,
+            grpc_args,
+            local,
+        })
+    }
+
+    /// Signing keys: the keystore at `path` if given, else the `--vault`, else the Sui keystore.
+    ///
+    /// A vault without an unlock session prompts for its password.
+    pub fn keystore(&self, path: Option<PathBuf>) -> Result<Keystore> {
+        match (path, &self.key_args.vault) {
+            (Some(path), _) => Keystore::load(path),
+            (None, Some(vault)) => {
+                let vault = Vault::open(vault)?;
+                vault.keystore(&crate::vault::unlock_key(&vault)?)
+            }
+            (None, None) => Keystore::load(Keystore::default_path()),
+        }
+    }
+
+    /// Vault file: `--vault` if given, else the default location
+    pub fn vault_path(&self) -> PathBuf {
+        self.key_args
+            .vault
+            .clone()
+            .unwrap_or_else(Vault::default_path)
+    }
+
+    /// The response cache, opened on first use.
+    ///
+    /// Failing to open the cache (e.g. another suix process holds the lock)
+    /// only disables caching for this run.
+    pub fn cache(&self) -> Option<ResponseCache> {
+        self.cache
+            .get_or_init(|| {
+                if self.cache_args.no_cache {
+                    return None;
+                }
+                match ResponseCache::open(self.cache_dir()) {
+                    Ok(cache) => {
+                        Some(cache.with_ttl(self.cache_args.cache_ttl.map(Duration::from_secs)))
+                    }
+                    Err(e) => {
+                        eprintln!("Warning: response cache disabled: {e}");
+                        None
+                    }
+                }
+            })
+            .clone()
+    }
+
+    /// Directory of the response cache, `--cache-dir` or the default location
+    fn cache_dir(&self) -> PathBuf {
+        self.cache_args
+            .cache_dir
+            .clone()
+            .unwrap_or_else(|| ResponseCache::default_dir().join("responses"))
+    }
+
+    /// The response cache for calls to `url`; none for a loopback endpoint, whose
+    /// chain (e.g. `suix localnet`) is thrown away and restarted under the same URL
+    fn cache_for(&self, url: &str) -> Option<ResponseCache> {
+        self.cache().filter(|_| !is_loopback(url))
+    }
+
+    /// The `--cache` database, opened on first use; failing to open it only
+    /// disables it for this run
+    pub fn checkpoint_cache(&self) -> Option<CheckpointCache> {
+        self.checkpoint_cache
+            .get_or_init(|| {
+                let path = self.cache_args.checkpoint_cache.as_ref()?;
+                CheckpointCache::open(path)
+                    .map_err(|e| eprintln!("Warning: checkpoint cache disabled: {e}"))
+                    .ok()
+            })
+            .clone()
+    }
+
+    /// Transport shared by every client of this run
+    pub fn transport(&self) -> Arc<Transport> {
+        self.transport
+            .get_or_init(|| {
+                let args = &self.transport_args;
+                let policy = RetryPolicy {
+                    max_retries: args.max_retries,
+                    ..RetryPolicy::default()
+                };
+                Arc::new(
+                    Transport::new(policy)
+                        .with_rate_limit(args.rps)
+                        .with_adaptive(args.adaptive_rate)
+                        .with_failover(args.failover.clone()),
+                )
+            })
+            .clone()
+    }
+
+    /// Whether `--verbose` asked for per-call gRPC diagnostics
+    pub fn verbose(&self) -> bool {
+        self.grpc_args.verbose
+    }
+
+    /// Print transport statistics if requested and any requests were made
+    pub fn report_transport_stats(&self) {
+        if let Some(transport) = self.transport.get()
+            && self.transport_args.transport_stats
+        {
+            eprintln!("Transport: {}", transport.stats());
+        }
+    }
+
+    /// JSON-RPC configuration for `url` with the shared options applied
+    pub fn rpc_config(&self, url: String, pretty: bool) -> RpcConfig {
+        RpcConfig {
+            cache: self.cache_for(&url),
+            url,
+            pretty,
+            transport: Some(self.transport()),
+            archive_url: self.transport_args.archive_url.clone(),
+            local: self.local.clone(),
+            mock: None,
+        }
+    }
+
+    /// gRPC configuration for `url` with the shared options applied
+    pub fn grpc_config(&self, url: String, pretty: bool, json: bool, timeout: u64) -> GrpcConfig {
+        GrpcConfig {
+            cache: self.cache_for(&url),
+            checkpoint_cache: self.checkpoint_cache().filter(|_| !is_loopback(&url)),
+            url,
+            pretty,
+            json,
+            timeout: Duration::from_secs(timeout),
+            headers: self.grpc_args.headers.clone(),
+            transport: Some(self.transport()),
+            mock: None,
+            tls: TlsOptions {
+                ca_cert: self.grpc_args.ca_cert.clone(),
+                client_cert: self.grpc_args.client_cert.clone(),
+                client_key: self.grpc_args.client_key.clone(),
+                insecure: self.grpc_args.insecure,
+            },
+            verbose: self.grpc_args.verbose,
+            pool_size: self.grpc_args.pool_size,
+            poll: PollPolicy::default(),
+            channel: ChannelOptions {
+                keepalive_interval: self.grpc_args.keepalive.map(Duration::from_secs),
+                keepalive_timeout: self.grpc_args.keepalive_timeout.map(Duration::from_secs),
+                initial_stream_window_size: self.grpc_args.window_size,
+                initial_connection_window_size: self.grpc_args.connection_window_size,
+                max_message_size: self.grpc_args.max_message_size,
+            },
+            middleware: Vec::new(),
+            proto_json: self.grpc_args.proto_json,
+            verify_digests: self.grpc_args.verify_digests,
+        }
+    }
+
+    /// Coin metadata registry for the network behind `config`.
+    ///
+    /// Persisted next to the response cache unless caching is disabled or the
+    /// network is a loopback one.
+    pub fn coin_registry(&self, config: &RpcConfig) -> CoinRegistry {
+        if self.cache_args.no_cache || is_loopback(&config.url) {
+            return CoinRegistry::new(config.clone());
+        }
+        let path = self.cache_dir().join("coins.json");
+        CoinRegistry::open(config.clone(), &path).unwrap_or_else(|e| {
+            eprintln!("Warning: coin registry not persisted: {e}");
+            CoinRegistry::new(config.clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_loopback() {
+        assert!(is_loopback(Network::Localnet.rpc_url()));
+        assert!(is_loopback("http://localhost:9000"));
+        assert!(is_loopback("http://[::1]:9000"));
+        assert!(!is_loopback(Network::Mainnet.rpc_url()));
+        assert!(!is_loopback("unix:///tmp/sui.sock"));
+    }
+}
@@ -0,0 +1,185 @@
+use std::str::FromStr;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use clap::{Subcommand, ValueEnum};
+use eyre::{Result, bail};
+use sui_types::{
+    base_types::SuiAddress,
+    crypto::{EncodeDecodeBase64, PublicKey, SignatureScheme, SuiKeyPair},
+    digests::TransactionDigest,
+    multisig::MultiSigPublicKey,
+};
+
+#[derive(Subcommand)]
+pub enum ConvertCommands {
+    /// Address of a base64 public key
+    PubkeyToAddress {
+        /// Base64 public key, prefixed with its scheme flag unless --scheme is given
+        pubkey: String,
+        /// Scheme of a raw (unflagged) public key
+        #[arg(long, value_enum)]
+        scheme: Option<Scheme>,
+    },
+    /// Convert a private key between bech32 `suiprivkey1...` and base64 `flag || key`
+    Key {
+        /// Private key in either encoding; the other one is printed
+        key: String,
+    },
+    /// Convert a 32-byte digest between hex and base58
+    Digest {
+        /// Hex (with or without 0x) or base58 digest; the other encoding is printed
+        digest: String,
+    },
+    /// Address of a MultiSig public key set
+    MultisigAddress {
+        /// Member public key (base64 with scheme flag) and weight as KEY:WEIGHT
+        #[arg(long = "pubkey", value_name = "KEY:WEIGHT", required = true)]
+        pubkeys: Vec<String>,
+        /// Total weight needed to sign
+        #[arg(long)]
+        threshold: u16,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Scheme {
+    Ed25519,
+    Secp256k1,
+    Secp256r1,
+}
+
+impl From<Scheme> for SignatureScheme {
+    fn from(scheme: Scheme) -> Self {
+        match scheme {
+            Scheme::Ed25519 => SignatureScheme::ED25519,
+            Scheme::Secp256k1 => SignatureScheme::Secp256k1,
+            Scheme::Secp256r1 => SignatureScheme::Secp256r1,
+        }
+    }
+}
+
+pub fn run(cmd: ConvertCommands) -> Result<()> {
+    match cmd {
+        ConvertCommands::PubkeyToAddress { pubkey, scheme } => {
+            let pubkey = parse_pubkey(&pubkey, scheme)?;
+            println!("{}", SuiAddress::from(&pubkey));
+            Ok(())
+        }
+        ConvertCommands::Key { key } => {
+            let key = key.trim();
+            let (keypair, converted) = if key.starts_with("suiprivkey") {
+                let keypair = SuiKeyPair::decode(key)
+                    .map_err(|e| eyre::eyre!("Invalid bech32 private key: {}", e))?;
+                let base64 = keypair.encode_base64();
+                (keypair, base64)
+            } else {
+                let keypair = SuiKeyPair::decode_base64(key)
+                    .map_err(|e| eyre::eyre!("Invalid base64 private key: {}", e))?;
+                let bech32 = keypair
+                    .encode()
+                    .map_err(|e| eyre::eyre!("Failed to encode key: {}", e))?;
+                (keypair, bech32)
+            };
+            println!("{converted}");
+            eprintln!("Address: {}", SuiAddress::from(&keypair.public()));
+            Ok(())
+        }
+        ConvertCommands::Digest { digest } => {
+            println!("{}", convert_digest(&digest)?);
+            Ok(())
+        }
+        ConvertCommands::MultisigAddress { pubkeys, threshold } => {
+            let mut keys = Vec::with_capacity(pubkeys.len());
+            let mut weights = Vec::with_capacity(pubkeys.len());
+            for member in &pubkeys {
+                let (key, weight) = parse_member(member)?;
+                keys.push(parse_pubkey(key, None)?);
+                weights.push(weight);
+            }
+            let multisig = MultiSigPublicKey::new(keys, weights, threshold)
+                .map_err(|e| eyre::eyre!("Invalid MultiSig: {}", e))?;
+            println!("{}", SuiAddress::from(&multisig));
+            Ok(())
+        }
+    }
+}
+
+fn parse_pubkey(pubkey: &str, scheme: Option<Scheme>) -> Result<PublicKey> {
+    let pubkey = pubkey.trim();
+    match scheme {
+        Some(scheme) => {
+            let bytes = base64_decode(pubkey)?;
+            PublicKey::try_from_bytes(scheme.into(), &bytes)
+                .map_err(|e| eyre::eyre!("Invalid {:?} public key: {}", scheme, e))
+        }
+        None => PublicKey::decode_base64(pubkey).map_err(|e| {
+            eyre::eyre!(
+                "Invalid flagged public key {} ({}); pass --scheme for raw keys",
+                pubkey,
+                e
+            )
+        }),
+    }
+}
+
+fn base64_decode(value: &str) -> Result<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|e| eyre::eyre!("Invalid base64 {}: {}", value, e))
+}
+
+/// Split a `KEY:WEIGHT` MultiSig member
+fn parse_member(member: &str) -> Result<(&str, u8)> {
+    let Some((key, weight)) = member.rsplit_once(':') else {
+        bail!("MultiSig member must be KEY:WEIGHT, got {}", member);
+    };
+    let weight = weight
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid weight in {} (expected 1-255)", member))?;
+    if weight == 0 {
+        bail!("Weight of {} must be at least 1", key);
+    }
+    Ok((key, weight))
+}
+
+/// Hex digests become base58 and base58 digests become hex
+fn convert_digest(digest: &str) -> Result<String> {
+    let digest = digest.trim();
+    let hex_digits = digest.strip_prefix("0x").unwrap_or(digest);
+    if hex_digits.len() == 64 && hex_digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        let bytes: [u8; 32] = hex::decode(hex_digits)?
+            .try_into()
+            .map_err(|_| eyre::eyre!("Digest must be 32 bytes"))?;
+        return Ok(TransactionDigest::new(bytes).to_string());
+    }
+    let parsed = TransactionDigest::from_str(digest)
+        .map_err(|e| eyre::eyre!("{} is neither a hex nor a base58 digest: {}", digest, e))?;
+    Ok(format!("0x{}", hex::encode(parsed.into_inner())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_member() {
+        assert_eq!(parse_member("AOy1...:2").unwrap(), ("AOy1...", 2));
+        assert!(parse_member("AOy1...").is_err());
+        assert!(parse_member("AOy1...:0").is_err());
+        assert!(parse_member("AOy1...:256").is_err());
+    }
+
+    #[test]
+    fn test_convert_digest() {
+        let zero = format!("0x{}", "00".repeat(32));
+        assert_eq!(convert_digest(&zero).unwrap(), "1".repeat(32));
+        assert_eq!(convert_digest(&"1".repeat(32)).unwrap(), zero);
+
+        let digest = "4ALHjuwFJmCqW2TPgGwJKnSzxKSPaJk7b8dYZMx3fkcJ";
+        assert_eq!(
+            convert_digest(&convert_digest(digest).unwrap()).unwrap(),
+            digest
+        );
+        assert!(convert_digest("not a digest").is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use clap::Subcommand;
+use eyre::{Result, bail};
+use rpc::{coins::format_signed_amount, graphql::GraphqlClient, json_u64, json_u128, rpc_request};
+use serde_json::{Value, json};
+
+use crate::{
+    context::Context,
+    output::{OutputFormat, Table},
+    tx::reference_gas_price,
+};
+
+const EPOCH_QUERY: &str = "query ($id: UInt53) { epoch(id: $id) { epochId referenceGasPrice } }";
+
+#[derive(Subcommand)]
+pub enum CostCommands {
+    /// Dry-run a transaction once and report its cost under several gas prices.
+    ///
+    /// Computation is charged per gas unit, so it scales with the gas price;
+    /// storage cost and rebates do not depend on it. Without --gas-prices or
+    /// --at-epochs the transaction's own price is compared with the current
+    /// reference gas price.
+    Compare {
+        /// Base64 BCS `TransactionData`, e.g. from `sui client ... --serialize-unsigned-transaction`
+        #[arg(long, value_name = "BASE64")]
+        tx_bytes: String,
+        /// Gas prices in MIST per unit to compare
+        #[arg(long, value_name = "PRICES", value_delimiter = ',')]
+        gas_prices: Vec<u64>,
+        /// Compare at the reference gas prices of these epochs (via GraphQL)
+        #[arg(
+            long,
+            value_name = "EPOCHS",
+            value_delimiter = ',',
+            conflicts_with = "gas_prices"
+        )]
+        at_epochs: Vec<u64>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// GraphQL endpoint URL, used with --at-epochs
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://graphql.mainnet.sui.io/graphql"
+        )]
+        graphql_url: String,
+    },
+}
+
+/// Gas usage of a dry run, independent of the gas price it ran at
+#[derive(Debug, Clone, PartialEq)]
+struct DryRunGas {
+    computation_units: u64,
+    storage_cost: u128,
+    storage_rebate: u128,
+    non_refundable_storage_fee: u128,
+    budget: u64,
+}
+
+impl DryRunGas {
+    /// Split the dry-run charges at `price` into units and price-independent parts
+    fn from_effects(effects: &Value, price: u64, budget: u64) -> Result<Self> {
+        if price == 0 {
+            bail!("The transaction has a gas price of 0");
+        }
+        let used = &effects["gasUsed"];
+        let field = |name: &str| json_u128(&used[name]).unwrap_or_default();
+        Ok(Self {
+            computation_units: u64::try_from(field("computationCost") / u128::from(price))?,
+            storage_cost: field("storageCost"),
+            storage_rebate: field("storageRebate"),
+            non_refundable_storage_fee: field("nonRefundableStorageFee"),
+            budget,
+        })
+    }
+
+    /// Charges had the transaction been submitted at `price`
+    fn at(&self, label: String, price: u64) -> PriceCost {
+        let computation = u128::from(self.computation_units) * u128::from(price);
+        PriceCost {
+            label,
+            price,
+            computation,
+            net: (computation + self.storage_cost) as i128 - self.storage_rebate as i128,
+            // The budget must cover the gross charge; rebates are paid back afterwards
+            fits_budget: computation + self.storage_cost <= u128::from(self.budget),
+        }
+    }
+}
+
+/// One row of the comparison
+#[derive(Debug, PartialEq)]
+struct PriceCost {
+    label: String,
+    price: u64,
+    computation: u128,
+    net: i128,
+    fits_budget: bool,
+}
+
+pub async fn run(cmd: CostCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        CostCommands::Compare {
+            tx_bytes,
+            gas_prices,
+            at_epochs,
+            format,
+            url,
+            graphql_url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let response = rpc_request(
+                &config,
+                "sui_dryRunTransactionBlock",
+                json!([tx_bytes.trim()]),
+            )
+            .await?;
+            let effects = &response["effects"];
+            if effects["status"]["status"].as_str() != Some("success") {
+                eprintln!(
+                    "Warning: the dry run failed ({}); costs are those of the failed execution",
+                    effects["status"]["error"]
+                        .as_str()
+                        .unwrap_or("unknown error")
+                );
+            }
+            let gas_data = &response["input"]["gasData"];
+            let tx_price = json_u64(&gas_data["price"])
+                .ok_or_else(|| eyre::eyre!("Dry run response has no gas price"))?;
+            let budget = json_u64(&gas_data["budget"]).unwrap_or_default();
+            let gas = DryRunGas::from_effects(effects, tx_price, budget)?;
+            let reference = reference_gas_price(&config).await?;
+
+            let prices: Vec<(String, u64)> = if !at_epochs.is_empty() {
+                let client = GraphqlClient::new(graphql_url);
+                let mut prices = Vec::with_capacity(at_epochs.len());
+                for epoch in at_epochs {
+                    let price = epoch_gas_price(&client, epoch).await?;
+                    prices.push((format!("epoch {epoch}"), price));
+                }
+                prices
+            } else if !gas_prices.is_empty() {
+                gas_prices.iter().map(|p| (p.to_string(), *p)).collect()
+            } else {
+                vec![
+                    ("transaction".to_string(), tx_price),
+                    ("reference".to_string(), reference),
+                ]
+            };
+            let rows: Vec<PriceCost> = prices
+                .into_iter()
+                .map(|(label, price)| gas.at(label, price))
+                .collect();
+
+            if format == OutputFormat::Json {
+                let report = json!({
+                    "computation_units": gas.computation_units,
+                    "storage_cost": gas.storage_cost.to_string(),
+                    "storage_rebate": gas.storage_rebate.to_string(),
+                    "non_refundable_storage_fee": gas.non_refundable_storage_fee.to_string(),
+                    "gas_budget": gas.budget,
+                    "reference_gas_price": reference,
+                    "prices": rows.iter().map(|row| json!({
+                        "label": row.label,
+                        "gas_price": row.price,
+                        "computation_cost": row.computation.to_string(),
+                        "net_cost": row.net.to_string(),
+                        "below_reference": row.price < reference,
+                        "fits_budget": row.fits_budget,
+                    })).collect::<Vec<_>>(),
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+
+            let sui = |mist: i128| format_signed_amount(mist, 9);
+            if format == OutputFormat::Table {
+                println!(
+                    "{} computation units; storage {} SUI, rebate {} SUI ({} SUI non-refundable); reference gas price {reference}",
+                    gas.computation_units,
+                    sui(gas.storage_cost as i128),
+                    sui(gas.storage_rebate as i128),
+                    sui(gas.non_refundable_storage_fee as i128),
+                );
+            }
+            let mut table = Table::new([
+                "price_source",
+                "gas_price",
+                "computation_sui",
+                "net_mist",
+                "net_sui",
+                "fits_budget",
+            ]);
+            for row in &rows {
+                let price = if row.price < reference {
+                    format!("{} (below reference)", row.price)
+                } else {
+                    row.price.to_string()
+                };
+                table.push_row([
+                    row.label.clone(),
+                    price,
+                    sui(row.computation as i128),
+                    row.net.to_string(),
+                    sui(row.net),
+                    if row.fits_budget { "yes" } else { "no" }.to_string(),
+                ]);
+            }
+            table.print(format)
+        }
+    }
+}
+
+/// Reference gas price of a past epoch from the GraphQL service
+async fn epoch_gas_price(client: &GraphqlClient, epoch: u64) -> Result<u64> {
+    let data = client.query(EPOCH_QUERY, json!({ "id": epoch })).await?;
+    json_u64(&data["epoch"]["referenceGasPrice"])
+        .ok_or_else(|| eyre::eyre!("Epoch {} not found", epoch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost_at_prices() {
+        let effects = json!({
+            "gasUsed": {
+                "computationCost": "1500000",
+                "storageCost": "2964000",
+                "storageRebate": "978120",
+                "nonRefundableStorageFee": "9880",
+            },
+        });
+        let gas = DryRunGas::from_effects(&effects, 750, 5_000_000).unwrap();
+        assert_eq!(gas.computation_units, 2000);
+
+        let cheap = gas.at("750".to_string(), 750);
+        assert_eq!(cheap.computation, 1_500_000);
+        assert_eq!(cheap.net, 1_500_000 + 2_964_000 - 978_120);
+        assert!(cheap.fits_budget);
+
+        let busy = gas.at("1500".to_string(), 1500);
+        assert_eq!(busy.computation, 3_000_000);
+        assert_eq!(busy.net, 3_000_000 + 2_964_000 - 978_120);
+        assert!(!busy.fits_budget);
+
+        assert!(DryRunGas::from_effects(&effects, 0, 0).is_err());
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::HashSet;
+
+use clap::Subcommand;
+use eyre::Result;
+use rpc::{
+    RpcConfig,
+    dynamic_fields::get_dynamic_fields,
+    objects::{ObjectOptions, multi_get_objects},
+    rpc_request,
+};
+use serde_json::{Value, json};
+
+use crate::context::Context;
+
+/// Fields listed to show how many sit below the depth limit
+const DEPTH_LIMIT_SAMPLE: usize = 50;
+
+/// Longest decoded value printed on a tree line
+const MAX_VALUE_WIDTH: usize = 80;
+
+#[derive(Subcommand)]
+pub enum DfCommands {
+    /// Walk dynamic fields recursively (tables, bags, nested objects) and print them as a tree
+    Tree {
+        /// Parent object ID
+        parent_id: String,
+        /// Maximum nesting depth to expand
+        #[arg(long, value_name = "N", default_value = "3")]
+        depth: usize,
+        /// Stop after visiting this many fields
+        #[arg(long, value_name = "COUNT", default_value = "1000")]
+        max_nodes: usize,
+        /// Show decoded field values
+        #[arg(long)]
+        decode: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+/// A line of the rendered tree and its children
+#[derive(Debug)]
+struct Node {
+    label: String,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn leaf(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            children: Vec::new(),
+        }
+    }
+
+    fn render(&self, prefix: &str, out: &mut Vec<String>) {
+        for (i, child) in self.children.iter().enumerate() {
+            let last = i + 1 == self.children.len();
+            out.push(format!(
+                "{prefix}{}{}",
+                if last { "└── " } else { "├── " },
+                child.label
+            ));
+            let child_prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+            child.render(&child_prefix, out);
+        }
+    }
+}
+
+/// Recursive walk with depth, size and cycle guards
+struct Explorer {
+    config: RpcConfig,
+    max_depth: usize,
+    max_nodes: usize,
+    decode: bool,
+    visited: HashSet<String>,
+    nodes: usize,
+}
+
+impl Explorer {
+    /// Nodes for the dynamic fields of `uid` and everything below them
+    async fn expand(&mut self, uid: &str, depth: usize) -> Result<Vec<Node>> {
+        if !self.visited.insert(uid.to_string()) {
+            return Ok(vec![Node::leaf(format!("↺ {uid} (already shown)"))]);
+        }
+        if depth >= self.max_depth {
+            let page =
+                get_dynamic_fields(&self.config, uid, None, DEPTH_LIMIT_SAMPLE, false).await?;
+            if page.data.is_empty() {
+                return Ok(Vec::new());
+            }
+            return Ok(vec![Node::leaf(format!(
+                "… {} fields (depth limit)",
+                count(page.data.len(), page.next_cursor.is_some())
+            ))]);
+        }
+
+        // Only list as many fields as can still be shown; one more when the budget
+        // is used up, to tell an empty container from a truncated one
+        let remaining = self.max_nodes.saturating_sub(self.nodes);
+        let page = get_dynamic_fields(&self.config, uid, None, remaining.max(1), false).await?;
+        let (fields, truncated) = (page.data, page.next_cursor.is_some());
+        if fields.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<&str> = fields
+            .iter()
+            .filter_map(|f| f["objectId"].as_str())
+            .collect();
+        let objects = multi_get_objects(&self.config, &ids, &ObjectOptions::content()).await?;
+
+        let mut nodes = Vec::new();
+        for (i, (field, object)) in fields.iter().zip(&objects).enumerate() {
+            if self.nodes >= self.max_nodes {
+                nodes.push(Node::leaf(format!(
+                    "… {} more fields (node limit)",
+                    count(fields.len() - i, truncated)
+                )));
+                return Ok(nodes);
+            }
+            self.nodes += 1;
+
+            let is_object = field["type"].as_str() == Some("DynamicObject");
+            let content = &object["data"]["content"]["fields"];
+            // Dynamic object fields point at the child itself; plain dynamic
+            // fields wrap their value in a Field<K, V> object
+            let value = if is_object {
+                content
+            } else {
+                &content["value"]
+            };
+
+            let mut label = format!(
+                "{}: {}",
+                compact(&field["name"]["value"]),
+                field["objectType"].as_str().unwrap_or_default()
+            );
+            if is_object {
+                label.push_str(&format!(
+                    " [object {}]",
+                    field["objectId"].as_str().unwrap_or_default()
+                ));
+            }
+            if self.decode {
+                label.push_str(&format!(" = {}", compact(value)));
+            }
+
+            let mut children = Vec::new();
+            if is_object && let Some(id) = field["objectId"].as_str() {
+                children.extend(Box::pin(self.expand(id, depth + 1)).await?);
+            }
+            for (path, ty, uid) in containers(value) {
+                let nested = Box::pin(self.expand(&uid, depth + 1)).await?;
+                children.push(Node {
+                    label: format!("{path}: {ty}"),
+                    children: nested,
+                });
+            }
+            nodes.push(Node { label, children });
+        }
+        if truncated {
+            nodes.push(Node::leaf("… more fields (node limit)"));
+        }
+        Ok(nodes)
+    }
+}
+
+pub async fn run(cmd: DfCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        DfCommands::Tree {
+            parent_id,
+            depth,
+            max_nodes,
+            decode,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let parent = rpc_request(
+                &config,
+                "sui_getObject",
+                json!([parent_id, { "showType": true, "showContent": true }]),
+            )
+            .await?;
+
+            let mut explorer = Explorer {
+                config,
+                max_depth: depth,
+                max_nodes,
+                decode,
+                visited: HashSet::new(),
+                nodes: 0,
+            };
+
+            let mut root = Node {
+                label: format!(
+                    "{parent_id}: {}",
+                    parent["data"]["type"].as_str().unwrap_or("<unknown type>")
+                ),
+                children: explorer.expand(&parent_id, 0).await?,
+            };
+            // Tables and bags stored directly in the parent's fields
+            for (path, ty, uid) in containers(&parent["data"]["content"]["fields"]) {
+                let nested = explorer.expand(&uid, 1).await?;
+                root.children.push(Node {
+                    label: format!("{path}: {ty}"),
+                    children: nested,
+                });
+            }
+
+            let mut lines = vec![root.label.clone()];
+            root.render("", &mut lines);
+            println!("{}", lines.join("\n"));
+            if explorer.nodes >= max_nodes {
+                eprintln!("Stopped after {max_nodes} fields; raise --max-nodes to see more");
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Nested objects with their own UID (Table, Bag, ...) as `(path, type, uid)`
+fn containers(value: &Value) -> Vec<(String, String, String)> {
+    fn walk(path: &str, value: &Value, out: &mut Vec<(String, String, String)>) {
+        match value {
+            Value::Object(map) => {
+                if let (Some(ty), Some(uid)) = (
+                    map.get("type").and_then(Value::as_str),
+                    map.get("fields").and_then(|f| f["id"]["id"].as_str()),
+                ) {
+                    out.push((path.to_string(), ty.to_string(), uid.to_string()));
+                    return;
+                }
+                for (key, child) in map {
+                    // The object's own UID is not a container
+                    if key == "id" {
+                        continue;
+                    }
+                    let child_path = match (path, key.as_str()) {
+                        (_, "fields") => path.to_string(),
+                        ("", _) => key.clone(),
+                        _ => format!("{path}.{key}"),
+                    };
+                    walk(&child_path, child, out);
+                }
+            }
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    walk(&format!("{path}[{i}]"), item, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut out = Vec::new();
+    walk("", value, &mut out);
+    out
+}
+
+/// Single-line JSON, truncated for display
+fn compact(value: &Value) -> String {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.chars().count() > MAX_VALUE_WIDTH {
+        let truncated: String = text.chars().take(MAX_VALUE_WIDTH - 1).collect();
+        format!("{truncated}…")
+    } else {
+        text
+    }
+}
+
+/// `count` fields, or at least that many when `truncated`
+fn count(count: usize, truncated: bool) -> String {
+    if truncated {
+        format!("{count}+")
+    } else {
+        count.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_containers() {
+        let fields = json!({
+            "id": { "id": "0xself" },
+            "balances": {
+                "type": "0x2::table::Table<address, u64>",
+                "fields": { "id": { "id": "0xtable" }, "size": "2" },
+            },
+            "inner": { "type": "0x1::m::Inner", "fields": {
+                "bags": [{ "type": "0x2::bag::Bag", "fields": { "id": { "id": "0xbag" }, "size": "0" } }],
+            } },
+            "count": "7",
+        });
+        let found: Vec<(String, String)> = containers(&fields)
+            .into_iter()
+            .map(|(path, _, uid)| (path, uid))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("balances".to_string(), "0xtable".to_string()),
+                ("inner.bags[0]".to_string(), "0xbag".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let root = Node {
+            label: "root".to_string(),
+            children: vec![
+                Node {
+                    label: "a".to_string(),
+                    children: vec![Node::leaf("a1")],
+                },
+                Node::leaf("b"),
+            ],
+        };
+        let mut lines = Vec::new();
+        root.render("", &mut lines);
+        assert_eq!(lines, ["├── a", "│   └── a1", "└── b"]);
+    }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(count(3, false), "3");
+        assert_eq!(count(50, true), "50+");
+    }
+}
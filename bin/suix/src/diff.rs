@@ -0,0 +1,136 @@
+use std::fmt;
+
+use serde_json::{Value, json};
+
+/// A single difference between two JSON documents
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added {
+        path: String,
+        value: Value,
+    },
+    Removed {
+        path: String,
+        value: Value,
+    },
+    Changed {
+        path: String,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl Change {
+    pub fn to_json(&self) -> Value {
+        match self {
+            Change::Added { path, value } => json!({ "op": "added", "path": path, "new": value }),
+            Change::Removed { path, value } => {
+                json!({ "op": "removed", "path": path, "old": value })
+            }
+            Change::Changed { path, old, new } => {
+                json!({ "op": "changed", "path": path, "old": old, "new": new })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added { path, value } => write!(f, "+ {path}: {value}"),
+            Change::Removed { path, value } => write!(f, "- {path}: {value}"),
+            Change::Changed { path, old, new } => write!(f, "~ {path}: {old} -> {new}"),
+        }
+    }
+}
+
+/// Structural diff of two JSON values, reported per leaf path
+pub fn diff_values(old: &Value, new: &Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    walk(String::new(), old, new, &mut changes);
+    changes
+}
+
+fn walk(path: String, old: &Value, new: &Value, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, old_value) in a {
+                let child = join(&path, key);
+                match b.get(key) {
+                    Some(new_value) => walk(child, old_value, new_value, changes),
+                    None => changes.push(Change::Removed {
+                        path: child,
+                        value: old_value.clone(),
+                    }),
+                }
+            }
+            for (key, new_value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                changes.push(Change::Added {
+                    path: join(&path, key),
+                    value: new_value.clone(),
+                });
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let child = format!("{path}[{i}]");
+                match (a.get(i), b.get(i)) {
+                    (Some(x), Some(y)) => walk(child, x, y, changes),
+                    (Some(x), None) => changes.push(Change::Removed {
+                        path: child,
+                        value: x.clone(),
+                    }),
+                    (None, Some(y)) => changes.push(Change::Added {
+                        path: child,
+                        value: y.clone(),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => changes.push(Change::Changed {
+            path,
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_values() {
+        let old = json!({ "balance": "10", "items": [1, 2], "gone": true, "nested": { "a": 1 } });
+        let new = json!({ "balance": "25", "items": [1], "nested": { "a": 1, "b": 2 } });
+        let changes: Vec<String> = diff_values(&old, &new)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                "~ balance: \"10\" -> \"25\"",
+                "- gone: true",
+                "- items[1]: 2",
+                "+ nested.b: 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let value = json!({ "a": [1, { "b": null }] });
+        assert!(diff_values(&value, &value).is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+use std::{collections::HashSet, io, path::PathBuf};
+
+use chrono::NaiveDate;
+use clap::Subcommand;
+use eyre::Result;
+use rpc::{
+    coins::{format_amount, format_signed_amount},
+    json_u64,
+};
+use serde_json::{Value, json};
+
+use crate::{
+    at_time::{format_ms, parse_timestamp_ms},
+    context::Context,
+    filter::normalize_address,
+    report::{Since, query_transactions},
+    tx::{SUI_COIN_TYPE, gas_cost},
+};
+
+const DAY_MS: u64 = 86_400_000;
+
+#[derive(Subcommand)]
+pub enum ExportCommands {
+    /// Per-transaction CSV of an address's coin movements and gas, for accounting tools.
+    ///
+    /// Every coin the address received or sent in a transaction is one row;
+    /// SUI amounts exclude gas, which is reported once per transaction in
+    /// `gas_fee_sui`.
+    Ledger {
+        /// Address to export
+        #[arg(long, value_name = "ADDRESS")]
+        address: String,
+        /// Start of the period: YYYY-MM-DD, RFC 3339 or a Unix timestamp
+        #[arg(long, value_name = "DATE")]
+        from: String,
+        /// End of the period, exclusive; a YYYY-MM-DD date includes that whole day [default: now]
+        #[arg(long, value_name = "DATE")]
+        to: Option<String>,
+        /// Write the CSV to this file instead of stdout
+        #[arg(long, short, value_name = "PATH")]
+        out: Option<PathBuf>,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+/// One coin movement of the exported address
+#[derive(Debug, PartialEq)]
+struct LedgerEntry {
+    coin_type: String,
+    /// Positive when received, negative when sent, in raw units
+    amount: i128,
+    counterparties: Vec<String>,
+}
+
+/// Coin movements of `address` in `tx` and the gas it paid, if it was the sender
+fn ledger_entries(address: &str, tx: &Value) -> (Vec<LedgerEntry>, Option<i128>) {
+    let sender = normalize_address(
+        tx["transaction"]["data"]["sender"]
+            .as_str()
+            .unwrap_or_default(),
+    );
+    let gas = (sender == address).then(|| gas_cost(&tx["effects"]));
+
+    let changes: Vec<(String, &str, i128)> = tx["balanceChanges"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|change| {
+            let owner = change["owner"]["AddressOwner"]
+                .as_str()
+                .or(change["owner"]["ObjectOwner"].as_str())?;
+            let amount = change["amount"].as_str()?.parse().ok()?;
+            Some((
+                normalize_address(owner),
+                change["coinType"].as_str().unwrap_or_default(),
+                amount,
+            ))
+        })
+        .collect();
+
+    let mut entries = Vec::new();
+    for (owner, coin_type, amount) in &changes {
+        if owner != address {
+            continue;
+        }
+        // The sender's SUI change includes the gas it paid
+        let amount = match gas {
+            Some(gas) if *coin_type == SUI_COIN_TYPE => amount + gas,
+            _ => *amount,
+        };
+        if amount == 0 {
+            continue;
+        }
+        let mut counterparties: Vec<String> = changes
+            .iter()
+            .filter(|(other, other_type, other_amount)| {
+                other != address
+                    && other_type == coin_type
+                    && other_amount.signum() == -amount.signum()
+            })
+            .map(|(other, _, _)| other.clone())
+            .collect();
+        if counterparties.is_empty() && amount > 0 && sender != address {
+            counterparties.push(sender.clone());
+        }
+        entries.push(LedgerEntry {
+            coin_type: coin_type.to_string(),
+            amount,
+            counterparties,
+        });
+    }
+    (entries, gas)
+}
+
+pub async fn run(cmd: ExportCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        ExportCommands::Ledger {
+            address,
+            from,
+            to,
+            out,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let address = normalize_address(&address);
+            let from_ms = parse_timestamp_ms(&from)?;
+            let to_ms = match to {
+                Some(ref to) => {
+                    let ms = parse_timestamp_ms(to)?;
+                    if NaiveDate::parse_from_str(to, "%Y-%m-%d").is_ok() {
+                        ms + DAY_MS
+                    } else {
+                        ms
+                    }
+                }
+                None => u64::MAX,
+            };
+
+            let mut transactions = Vec::new();
+            let mut seen = HashSet::new();
+            for filter in [
+                json!({ "FromAddress": address }),
+                json!({ "ToAddress": address }),
+            ] {
+                let since = Some(Since::TimestampMs(from_ms));
+                for tx in query_transactions(&config, filter, since).await? {
+                    let timestamp = json_u64(&tx["timestampMs"]).unwrap_or_default();
+                    let digest = tx["digest"].as_str().unwrap_or_default().to_string();
+                    if timestamp < to_ms && seen.insert(digest) {
+                        transactions.push(tx);
+                    }
+                }
+            }
+            transactions
+                .sort_by_key(|tx| (json_u64(&tx["timestampMs"]), json_u64(&tx["checkpoint"])));
+
+            let writer: Box<dyn io::Write> = match out {
+                Some(ref path) => Box::new(
+                    std::fs::File::create(path)
+                        .map_err(|e| eyre::eyre!("Failed to create {}: {}", path.display(), e))?,
+                ),
+                None => Box::new(io::stdout()),
+            };
+            let mut writer = csv::Writer::from_writer(writer);
+            writer.write_record([
+                "timestamp",
+                "digest",
+                "checkpoint",
+                "status",
+                "coin_type",
+                "symbol",
+                "amount_in",
+                "amount_out",
+                "counterparties",
+                "gas_fee_sui",
+            ])?;
+            let registry = ctx.coin_registry(&config);
+            let mut rows = 0;
+            for tx in &transactions {
+                let (entries, gas) = ledger_entries(&address, tx);
+                let timestamp = json_u64(&tx["timestampMs"])
+                    .map(format_ms)
+                    .unwrap_or_default();
+                let digest = tx["digest"].as_str().unwrap_or_default();
+                let checkpoint = json_u64(&tx["checkpoint"])
+                    .map(|c| c.to_string())
+                    .unwrap_or_default();
+                let status = tx["effects"]["status"]["status"]
+                    .as_str()
+                    .unwrap_or_default();
+                let mut gas_fee = gas.map(|g| format_signed_amount(g, 9)).unwrap_or_default();
+                if entries.is_empty() && gas.is_some() {
+                    // Fee-only rows keep failed and no-op transactions in the ledger
+                    writer.write_record([
+                        &timestamp,
+                        digest,
+                        &checkpoint,
+                        status,
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        &gas_fee,
+                    ])?;
+                    rows += 1;
+                }
+                for entry in entries {
+                    let coin = registry.get_or_raw(&entry.coin_type).await;
+                    let amount = format_amount(entry.amount.unsigned_abs(), coin.decimals);
+                    let (amount_in, amount_out) = if entry.amount > 0 {
+                        (amount, String::new())
+                    } else {
+                        (String::new(), amount)
+                    };
+                    writer.write_record([
+                        &timestamp,
+                        digest,
+                        &checkpoint,
+                        status,
+                        &entry.coin_type,
+                        &coin.symbol,
+                        &amount_in,
+                        &amount_out,
+                        &entry.counterparties.join(";"),
+                        &std::mem::take(&mut gas_fee),
+                    ])?;
+                    rows += 1;
+                }
+            }
+            writer.flush()?;
+            eprintln!(
+                "{rows} rows from {} transactions of {address}",
+                transactions.len()
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ledger_entries() {
+        let me = normalize_address("0xa");
+        let tx = json!({
+            "transaction": { "data": { "sender": "0xa" } },
+            "effects": { "gasUsed": {
+                "computationCost": "1000", "storageCost": "500", "storageRebate": "300",
+            } },
+            "balanceChanges": [
+                { "owner": { "AddressOwner": "0xa" }, "coinType": SUI_COIN_TYPE, "amount": "-1001200" },
+                { "owner": { "AddressOwner": "0xb" }, "coinType": SUI_COIN_TYPE, "amount": "1000000" },
+                { "owner": { "AddressOwner": "0xa" }, "coinType": "0x5::usdc::USDC", "amount": "250" },
+                { "owner": { "AddressOwner": "0xc" }, "coinType": "0x5::usdc::USDC", "amount": "-250" },
+            ],
+        });
+        let (entries, gas) = ledger_entries(&me, &tx);
+        assert_eq!(gas, Some(1200));
+        assert_eq!(
+            entries,
+            vec![
+                LedgerEntry {
+                    coin_type: SUI_COIN_TYPE.to_string(),
+                    amount: -1_000_000,
+                    counterparties: vec![normalize_address("0xb")],
+                },
+                LedgerEntry {
+                    coin_type: "0x5::usdc::USDC".to_string(),
+                    amount: 250,
+                    counterparties: vec![normalize_address("0xc")],
+                },
+            ]
+        );
+
+        // Received from someone else: no gas, the sender is the counterparty
+        let (entries, gas) = ledger_entries(&normalize_address("0xb"), &tx);
+        assert_eq!(gas, None);
+        assert_eq!(entries[0].amount, 1_000_000);
+        assert_eq!(entries[0].counterparties, vec![me]);
+    }
+}
@@ -0,0 +1,30 @@
+use clap::Subcommand;
+use eyre::Result;
+pub use filter::{Filter, normalize_address, read_values};
+
+use crate::context::Context;
+
+#[derive(Subcommand)]
+pub enum FilterCommands {
+    /// Show how an expression is parsed and what each backend receives
+    Explain {
+        /// Filter expression
+        expression: String,
+    },
+}
+
+pub async fn run(cmd: FilterCommands, _ctx: &Context) -> Result<()> {
+    match cmd {
+        FilterCommands::Explain { expression } => {
+            let filter = Filter::parse(&expression)?;
+            let (event_filter, exact) = filter.event_filter();
+            println!("Expression:      {filter}");
+            println!("JSON-RPC filter: {event_filter}");
+            println!("GraphQL filter:  {}", filter.graphql_filter());
+            if !exact {
+                println!("Conditions the server cannot express are checked client-side");
+            }
+            Ok(())
+        }
+    }
+}
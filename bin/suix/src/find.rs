@@ -0,0 +1,215 @@
+use std::io::Write;
+
+use clap::Args;
+use eyre::{Result, bail};
+use rpc::{RpcConfig, graphql::GraphqlClient, rpc_request};
+use serde_json::{Value, json};
+
+use crate::{context::Context, filter::Filter, snapshot::objects_page};
+
+/// Page size for both `suix_getOwnedObjects` and GraphQL `objects`
+const PAGE_SIZE: u64 = 50;
+
+const OBJECTS_QUERY: &str = r#"query ($type: String!, $first: Int, $after: String) {
+  objects(filter: { type: $type }, first: $first, after: $after) {
+    pageInfo { hasNextPage endCursor }
+    nodes {
+      address
+      version
+      digest
+      owner {
+        __typename
+        ... on AddressOwner { address { address } }
+        ... on ConsensusAddressOwner { address { address } }
+      }
+      asMoveObject { contents { type { repr } json } }
+    }
+  }
+}"#;
+
+#[derive(Args)]
+pub struct FindArgs {
+    /// Search the objects owned by this address; without it every object of
+    /// the `type == ...` in the expression is searched
+    #[arg(long, value_name = "ADDRESS")]
+    owner: Option<String>,
+
+    /// Filter expression, e.g. 'type == "0x..::pool::Pool" && fields.balance > 1000000'
+    #[arg(long = "where", value_name = "EXPRESSION")]
+    expression: String,
+
+    /// Stop after this many matches
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+
+    /// RPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+
+    /// GraphQL endpoint URL, used without --owner
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://graphql.mainnet.sui.io/graphql"
+    )]
+    graphql_url: String,
+}
+
+/// Match counter that streams matches as NDJSON
+struct Matches {
+    filter: Filter,
+    limit: Option<usize>,
+    scanned: u64,
+    found: usize,
+}
+
+impl Matches {
+    /// Check one object; false once the limit is reached
+    fn offer(&mut self, object: &Value) -> Result<bool> {
+        self.scanned += 1;
+        if self.filter.matches_object(object) {
+            self.found += 1;
+            let mut stdout = std::io::stdout().lock();
+            writeln!(stdout, "{}", serde_json::to_string(object)?)?;
+            stdout.flush()?;
+        }
+        Ok(self.limit.is_none_or(|limit| self.found < limit))
+    }
+}
+
+pub async fn run(args: FindArgs, ctx: &Context) -> Result<()> {
+    let filter = Filter::parse(&args.expression)?;
+    let server_filter = filter.object_filter();
+    let mut matches = Matches {
+        filter,
+        limit: args.limit,
+        scanned: 0,
+        found: 0,
+    };
+    if args.limit == Some(0) {
+        return Ok(());
+    }
+
+    match args.owner {
+        Some(ref owner) => {
+            let config = ctx.rpc_config(args.url, false);
+            find_owned(&config, owner, server_filter, &mut matches).await?;
+        }
+        None => {
+            let Some(object_type) = server_filter
+                .as_ref()
+                .and_then(|f| f["StructType"].as_str())
+            else {
+                bail!("Without --owner the expression needs a `type == ...` condition");
+            };
+            find_by_type(&args.graphql_url, object_type, &mut matches).await?;
+        }
+    }
+    eprintln!(
+        "{} matches in {} objects scanned",
+        matches.found, matches.scanned
+    );
+    Ok(())
+}
+
+/// Page through the objects of `owner`, narrowed by the server-side filter
+async fn find_owned(
+    config: &RpcConfig,
+    owner: &str,
+    server_filter: Option<Value>,
+    matches: &mut Matches,
+) -> Result<()> {
+    let query = json!({
+        "filter": server_filter,
+        "options": { "showType": true, "showContent": true, "showOwner": true },
+    });
+    let mut cursor = Value::Null;
+    loop {
+        let page = rpc_request(
+            config,
+            "suix_getOwnedObjects",
+            json!([owner, query, cursor, PAGE_SIZE]),
+        )
+        .await?;
+        for object in page["data"].as_array().into_iter().flatten() {
+            if !matches.offer(&object["data"])? {
+                return Ok(());
+            }
+        }
+        if !page["hasNextPage"].as_bool().unwrap_or(false) || page["nextCursor"].is_null() {
+            return Ok(());
+        }
+        cursor = page["nextCursor"].clone();
+    }
+}
+
+/// Page through every object of `object_type` via the GraphQL type index
+async fn find_by_type(graphql_url: &str, object_type: &str, matches: &mut Matches) -> Result<()> {
+    let client = GraphqlClient::new(graphql_url);
+    let mut cursor = Value::Null;
+    loop {
+        let variables = json!({ "type": object_type, "first": PAGE_SIZE, "after": cursor });
+        let response = client.request(OBJECTS_QUERY, variables).await?;
+        let page = objects_page(&response, None)?;
+        for node in page["nodes"].as_array().into_iter().flatten() {
+            if !matches.offer(&rpc_object(node))? {
+                return Ok(());
+            }
+        }
+        if !page["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) {
+            return Ok(());
+        }
+        cursor = page["pageInfo"]["endCursor"].clone();
+    }
+}
+
+/// A GraphQL object node in the `sui_getObject` shape, so expressions and
+/// output are the same with and without --owner
+fn rpc_object(node: &Value) -> Value {
+    let contents = &node["asMoveObject"]["contents"];
+    let object_type = &contents["type"]["repr"];
+    let owner = match node["owner"]["address"]["address"].as_str() {
+        Some(address) => json!({ "AddressOwner": address }),
+        None => node["owner"]["__typename"].clone(),
+    };
+    json!({
+        "objectId": node["address"],
+        "version": node["version"].as_u64().map(|v| v.to_string()),
+        "digest": node["digest"],
+        "type": object_type,
+        "owner": owner,
+        "content": {
+            "dataType": "moveObject",
+            "type": object_type,
+            "fields": contents["json"],
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_object() {
+        let node = json!({
+            "address": "0xp",
+            "version": 7,
+            "digest": "D",
+            "owner": { "__typename": "Shared" },
+            "asMoveObject": { "contents": {
+                "type": { "repr": "0x5::pool::Pool" },
+                "json": { "id": "0xp", "balance": "2500000" },
+            } },
+        });
+        let object = rpc_object(&node);
+        assert_eq!(object["version"], "7");
+        assert_eq!(object["owner"], "Shared");
+        let filter = Filter::parse("type == 0x5::pool::Pool && fields.balance > 1000000").unwrap();
+        assert!(filter.matches_object(&object));
+    }
+}
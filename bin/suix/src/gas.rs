@@ -0,0 +1,584 @@
+use std::{
+    collections::VecDeque,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use clap::{Subcommand, ValueEnum};
+use eyre::{Result, bail};
+use rpc::{
+    MAX_DIGESTS_PER_CALL, RpcConfig,
+    client::RpcClient,
+    coins::{format_amount, format_signed_amount, parse_amount},
+    json_u64, latest_checkpoint, rpc_request,
+};
+use serde_json::{Value, json};
+use sui_types::{
+    base_types::SuiAddress, programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::TransactionData,
+};
+
+use crate::{
+    coin_select,
+    context::Context,
+    hooks::Webhook,
+    output::{OutputFormat, Table},
+    tx::{SUI_COIN_TYPE, dry_run, execute, gas_cost, object_ref, owned_coins, reference_gas_price},
+};
+
+/// A transaction may pay gas with at most 256 coins
+const MAX_GAS_PAYMENT_COINS: usize = 256;
+
+#[derive(Subcommand)]
+pub enum GasCommands {
+    /// Sample reference and observed gas prices, keeping rolling percentiles
+    Monitor {
+        /// Sampling interval in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "60")]
+        interval: u64,
+        /// Append NDJSON samples to this file (defaults to stdout)
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+        /// Number of most recent transaction gas prices used for percentiles
+        #[arg(long, value_name = "COUNT", default_value = "10000")]
+        window: usize,
+        /// Maximum number of checkpoints scanned per sample
+        #[arg(long, value_name = "COUNT", default_value = "20")]
+        max_checkpoints: u64,
+        /// Alert when the selected metric rises above this price (in MIST)
+        #[arg(long, value_name = "MIST")]
+        alert_above: Option<u64>,
+        /// Metric compared against --alert-above
+        #[arg(long, value_enum, default_value = "reference")]
+        alert_metric: AlertMetric,
+        /// POST the triggering sample to this URL when an alert fires
+        #[arg(long, value_name = "URL")]
+        webhook: Option<String>,
+        /// Exit with status 2 when an alert fires
+        #[arg(long)]
+        exit_on_alert: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Keep an address's SUI in N similarly sized gas coins.
+    ///
+    /// Independent gas coins let several transactions be submitted in parallel
+    /// without two of them locking the same coin.
+    Manage {
+        /// Address or keystore alias owning the gas coins
+        #[arg(value_name = "ADDRESS|ALIAS")]
+        address: String,
+        /// Number of gas coins to maintain
+        #[arg(long, value_name = "N")]
+        target_coins: usize,
+        /// Rebalance when a coin holds less than this many SUI [default: half the average]
+        #[arg(long, value_name = "SUI")]
+        topup_below: Option<String>,
+        /// Gas budget of the rebalancing transaction in MIST
+        #[arg(long, value_name = "MIST", default_value = "100000000")]
+        gas_budget: u64,
+        /// Sign and execute the rebalancing transaction (without this only the plan is shown)
+        #[arg(long)]
+        execute: bool,
+        /// Keystore holding the key
+        #[arg(long, value_name = "PATH")]
+        keystore: Option<PathBuf>,
+        /// Output format of the coin list
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+/// How to bring a set of gas coins back to the target shape
+#[derive(Debug, PartialEq, Eq)]
+enum GasPlan {
+    /// Already N coins, none below the threshold
+    Healthy,
+    /// Merge the first `inputs` coins (largest first) into the gas coin, then
+    /// split off `target - 1` coins of `each` MIST
+    Rebalance { inputs: usize, each: u64 },
+}
+
+/// Plan for `balances` (sorted largest first) given the target count and top-up threshold
+fn plan_gas_coins(
+    balances: &[u128],
+    target: usize,
+    threshold: u128,
+    gas_budget: u64,
+) -> Result<GasPlan> {
+    if balances.len() == target && balances.iter().all(|&b| b >= threshold) {
+        return Ok(GasPlan::Healthy);
+    }
+    let inputs = balances.len().min(MAX_GAS_PAYMENT_COINS);
+    let total: u128 = balances[..inputs].iter().sum();
+    // The gas coin keeps its share plus whatever the transaction does not spend
+    let spendable = total.saturating_sub(u128::from(gas_budget));
+    let each = u64::try_from(spendable / target as u128).unwrap_or(u64::MAX);
+    if each == 0 || u128::from(each) < threshold {
+        bail!(
+            "{} SUI cannot fund {} gas coins of at least {} SUI after a {} SUI budget",
+            format_amount(total, 9),
+            target,
+            format_amount(threshold.max(1), 9),
+            format_amount(u128::from(gas_budget), 9)
+        );
+    }
+    Ok(GasPlan::Rebalance { inputs, each })
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum AlertMetric {
+    Reference,
+    P50,
+    P90,
+    P99,
+}
+
+impl AlertMetric {
+    fn key(self) -> &'static str {
+        match self {
+            AlertMetric::Reference => "reference_gas_price",
+            AlertMetric::P50 => "p50",
+            AlertMetric::P90 => "p90",
+            AlertMetric::P99 => "p99",
+        }
+    }
+}
+
+/// Edge-triggered threshold: fires when the value rises above it, then stays quiet
+/// until the value drops back to or below it
+#[derive(Debug)]
+struct Alert {
+    threshold: u64,
+    above: bool,
+}
+
+impl Alert {
+    fn new(threshold: u64) -> Self {
+        Self {
+            threshold,
+            above: false,
+        }
+    }
+
+    /// Whether `value` crosses the threshold, re-arming once it is back below
+    fn fires(&mut self, value: u64) -> bool {
+        let was_above = std::mem::replace(&mut self.above, value > self.threshold);
+        self.above && !was_above
+    }
+}
+
+/// Rolling window of observed transaction gas prices
+#[derive(Debug)]
+struct PriceWindow {
+    capacity: usize,
+    prices: VecDeque<u64>,
+}
+
+impl PriceWindow {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            prices: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, price: u64) {
+        if self.prices.len() == self.capacity {
+            self.prices.pop_front();
+        }
+        self.prices.push_back(price);
+    }
+
+    /// Nearest-rank percentile over the current window
+    fn percentile(&self, p: f64) -> Option<u64> {
+        let mut sorted: Vec<u64> = self.prices.iter().copied().collect();
+        sorted.sort_unstable();
+        percentile(&sorted, p)
+    }
+}
+
+/// Nearest-rank percentile of an already sorted slice
+pub(crate) fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+pub async fn run(cmd: GasCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        GasCommands::Monitor {
+            interval,
+            out,
+            window,
+            max_checkpoints,
+            alert_above,
+            alert_metric,
+            webhook,
+            exit_on_alert,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let mut writer: Box<dyn Write> = match out {
+                Some(path) => Box::new(
+                    OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(&path)
+                        .map_err(|e| eyre::eyre!("Failed to open {}: {}", path.display(), e))?,
+                ),
+                None => Box::new(io::stdout()),
+            };
+
+            let mut prices = PriceWindow::new(window);
+            let mut last_checkpoint: Option<u64> = None;
+
+            let mut alert = alert_above.map(Alert::new);
+
+            loop {
+                let (reference, latest) = match chain_tip(&config).await {
+                    Ok(tip) => tip,
+                    Err(e) => {
+                        eprintln!("Failed to sample gas price: {e}");
+                        tokio::time::sleep(Duration::from_secs(interval)).await;
+                        continue;
+                    }
+                };
+
+                let from = last_checkpoint
+                    .map_or(latest, |seq| seq + 1)
+                    .max(latest.saturating_sub(max_checkpoints.saturating_sub(1)));
+                let mut tx_count = 0usize;
+                for seq in from..=latest {
+                    match checkpoint_gas_prices(&config, seq).await {
+                        Ok(observed) => {
+                            tx_count += observed.len();
+                            observed.into_iter().for_each(|p| prices.push(p));
+                        }
+                        Err(e) => eprintln!("Failed to scan checkpoint {seq}: {e}"),
+                    }
+                }
+                last_checkpoint = Some(latest);
+
+                let sample = json!({
+                    "timestamp_ms": now_ms(),
+                    "checkpoint": latest,
+                    "reference_gas_price": reference,
+                    "transactions_sampled": tx_count,
+                    "window_size": prices.prices.len(),
+                    "min": prices.percentile(0.0),
+                    "p50": prices.percentile(50.0),
+                    "p90": prices.percentile(90.0),
+                    "p99": prices.percentile(99.0),
+                    "max": prices.percentile(100.0),
+                });
+                writeln!(writer, "{}", serde_json::to_string(&sample)?)?;
+                writer.flush()?;
+
+                if let Some(ref mut alert) = alert
+                    && let Some(value) = sample[alert_metric.key()].as_u64()
+                    && alert.fires(value)
+                {
+                    let threshold = alert.threshold;
+                    eprintln!(
+                        "Gas price alert: {} = {value} exceeds {threshold}",
+                        alert_metric.key()
+                    );
+                    if let Some(ref webhook) = webhook {
+                        let payload = json!({
+                            "alert": "gas_price",
+                            "threshold": threshold,
+                            "sample": sample,
+                        });
+                        if let Err(e) = Webhook::new(webhook).deliver(&payload).await {
+                            eprintln!("Failed to deliver webhook: {e}");
+                        }
+                    }
+                    if exit_on_alert {
+                        std::process::exit(2);
+                    }
+                }
+
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+            }
+        }
+        GasCommands::Manage {
+            address,
+            target_coins,
+            topup_below,
+            gas_budget,
+            execute: send,
+            keystore,
+            format,
+            url,
+        } => {
+            if target_coins == 0 || target_coins > coin_select::MAX_COINS {
+                bail!(
+                    "--target-coins must be between 1 and {}",
+                    coin_select::MAX_COINS
+                );
+            }
+            let config = ctx.rpc_config(url, false);
+            let sui = ctx.coin_registry(&config).get_or_raw(SUI_COIN_TYPE).await;
+            // A plain address can be inspected without a keystore
+            let (owner, keystore) = match SuiAddress::from_str(&address) {
+                Ok(owner) if !send => (owner, None),
+                _ => {
+                    let keystore = ctx.keystore(keystore)?;
+                    (keystore.get(&address)?.address, Some(keystore))
+                }
+            };
+
+            let mut coins = owned_coins(&config, owner, SUI_COIN_TYPE).await?;
+            if coins.is_empty() {
+                bail!("{} owns no SUI coins", owner);
+            }
+            coins.sort_by_key(|c| std::cmp::Reverse(coin_select::balance(c)));
+            let balances: Vec<u128> = coins.iter().map(coin_select::balance).collect();
+            let total: u128 = balances.iter().sum();
+            let threshold = match topup_below {
+                Some(ref amount) => parse_amount(amount, 9)?,
+                None => total / target_coins as u128 / 2,
+            };
+
+            let mut table = Table::new(["coin", "balance", "status"]);
+            for coin in &coins {
+                let balance = coin_select::balance(coin);
+                table.push_row([
+                    coin_select::coin_id(coin).to_string(),
+                    sui.format(balance),
+                    if balance < threshold { "low" } else { "ok" }.to_string(),
+                ]);
+            }
+            table.print(format)?;
+            eprintln!(
+                "{} holds {} in {} coins; target is {} coins of at least {}",
+                owner,
+                sui.format(total),
+                coins.len(),
+                target_coins,
+                sui.format(threshold)
+            );
+
+            let (inputs, each) =
+                match plan_gas_coins(&balances, target_coins, threshold, gas_budget)? {
+                    GasPlan::Healthy => {
+                        eprintln!("✅ Gas coins are healthy, nothing to do");
+                        return Ok(());
+                    }
+                    GasPlan::Rebalance { inputs, each } => (inputs, each),
+                };
+            eprintln!(
+                "Plan: merge {} coins, then split off {} coins of {}",
+                inputs,
+                target_coins - 1,
+                sui.format(u128::from(each))
+            );
+            if inputs < coins.len() {
+                eprintln!(
+                    "Only {MAX_GAS_PAYMENT_COINS} coins can be merged per transaction; run again afterwards to merge the rest"
+                );
+            }
+
+            // Gas payment merges every input into the first coin, which then pays for the splits
+            let gas = coins[..inputs]
+                .iter()
+                .map(object_ref)
+                .collect::<Result<Vec<_>>>()?;
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder
+                .pay_sui(vec![owner; target_coins - 1], vec![each; target_coins - 1])
+                .map_err(|e| eyre::eyre!("Failed to build splits: {}", e))?;
+            let gas_price = reference_gas_price(&config).await?;
+            let data = TransactionData::new_programmable(
+                owner,
+                gas,
+                builder.finish(),
+                gas_budget,
+                gas_price,
+            );
+
+            let effects = dry_run(&config, &data).await?;
+            if effects["status"]["status"].as_str() != Some("success") {
+                bail!("Dry run failed: {}", effects["status"]);
+            }
+            eprintln!(
+                "Fee: ~{} (estimated from a dry run)",
+                sui.format_signed(gas_cost(&effects))
+            );
+
+            let Some(keystore) = keystore else {
+                eprintln!("Preview only; re-run with --execute to rebalance");
+                return Ok(());
+            };
+            // Spending every gas coin at once: nothing else may use them until this finishes
+            let signature = keystore.sign_transaction(&owner.to_string(), &data)?;
+            let response = execute(&config, &data, &[signature], "WaitForLocalExecution").await?;
+            let status = &response["effects"]["status"];
+            if status["status"].as_str() != Some("success") {
+                bail!("Rebalancing failed: {}", status);
+            }
+            println!(
+                "Rebalanced into {} gas coins: {}",
+                target_coins,
+                response["digest"].as_str().unwrap_or_default()
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Reference gas price and latest checkpoint sequence number
+async fn chain_tip(config: &RpcConfig) -> Result<(u64, u64)> {
+    let reference = rpc_request(config, "suix_getReferenceGasPrice", json!([])).await?;
+    Ok((
+        json_u64(&reference).unwrap_or_default(),
+        latest_checkpoint(config).await?,
+    ))
+}
+
+/// Gas prices of all transactions included in a checkpoint
+async fn checkpoint_gas_prices(config: &RpcConfig, sequence_number: u64) -> Result<Vec<u64>> {
+    let checkpoint = rpc_request(
+        config,
+        "sui_getCheckpoint",
+        json!([sequence_number.to_string()]),
+    )
+    .await?;
+    let digests: Vec<Value> = checkpoint["transactions"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+
+    let mut prices = Vec::with_capacity(digests.len());
+    for chunk in digests.chunks(MAX_DIGESTS_PER_CALL) {
+        let txs = rpc_request(
+            config,
+            "sui_multiGetTransactionBlocks",
+            json!([chunk, { "showInput": true }]),
+        )
+        .await?;
+        prices.extend(
+            txs.as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|tx| json_u64(&tx["transaction"]["data"]["gasData"]["price"])),
+        );
+    }
+    Ok(prices)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Print the reference gas price of the current epoch and the `epochs - 1` before it
+/// for `json-rpc-quick gas-price`, newest first
+pub async fn print_reference_gas_prices(
+    client: &RpcClient,
+    epochs: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    let prices = client.get_reference_gas_price_history(epochs).await?;
+    if format == OutputFormat::Json {
+        let prices: Vec<Value> = prices
+            .iter()
+            .map(|(epoch, price)| json!({ "epoch": epoch, "reference_gas_price": price }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&prices)?);
+        return Ok(());
+    }
+    let mut table = Table::new(["epoch", "reference_gas_price", "change"]);
+    for (i, (epoch, price)) in prices.iter().enumerate() {
+        // The change from the epoch before, which is the next entry
+        let change = prices
+            .get(i + 1)
+            .map(|(_, before)| format_signed_amount(i128::from(*price) - i128::from(*before), 0))
+            .unwrap_or_default();
+        table.push_row([epoch.to_string(), price.to_string(), change]);
+    }
+    if prices.len() < epochs {
+        eprintln!("Only {} epochs of history are available", prices.len());
+    }
+    table.print(format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let sorted = [750, 750, 800, 1000, 5000];
+        assert_eq!(percentile(&sorted, 0.0), Some(750));
+        assert_eq!(percentile(&sorted, 50.0), Some(800));
+        assert_eq!(percentile(&sorted, 99.0), Some(5000));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_plan_gas_coins() {
+        const SUI: u128 = 1_000_000_000;
+        assert_eq!(
+            plan_gas_coins(&[5 * SUI, 4 * SUI], 2, SUI, 0).unwrap(),
+            GasPlan::Healthy
+        );
+        // One coin ran low: merge both and split evenly
+        assert_eq!(
+            plan_gas_coins(&[8 * SUI, SUI / 2], 2, SUI, 500_000_000).unwrap(),
+            GasPlan::Rebalance {
+                inputs: 2,
+                each: 4 * SUI as u64
+            }
+        );
+        // Too many coins are consolidated as well
+        assert_eq!(
+            plan_gas_coins(&[3 * SUI, 3 * SUI, 3 * SUI], 2, SUI, 0).unwrap(),
+            GasPlan::Rebalance {
+                inputs: 3,
+                each: 4_500_000_000
+            }
+        );
+        assert!(plan_gas_coins(&[3 * SUI], 4, SUI, 0).is_err());
+    }
+
+    #[test]
+    fn test_alert_fires_on_crossing() {
+        let mut alert = Alert::new(1000);
+        let fired: Vec<bool> = [900, 1200, 1300, 1000, 1100]
+            .into_iter()
+            .map(|value| alert.fires(value))
+            .collect();
+        assert_eq!(fired, [false, true, false, false, true]);
+    }
+
+    #[test]
+    fn test_price_window_evicts_oldest() {
+        let mut window = PriceWindow::new(2);
+        window.push(1);
+        window.push(2);
+        window.push(3);
+        assert_eq!(window.prices, VecDeque::from([2, 3]));
+        assert_eq!(window.percentile(100.0), Some(3));
+    }
+}
@@ -0,0 +1,168 @@
+use std::{process::Stdio, time::Duration};
+
+use clap::Args;
+use eyre::Result;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+/// Header carrying the hex HMAC-SHA256 of the request body
+pub const SIGNATURE_HEADER: &str = "X-Suix-Signature";
+
+/// Actions triggered for every matching item
+#[derive(Args, Debug, Clone, Default)]
+pub struct HookArgs {
+    /// POST each payload as JSON to this URL
+    #[arg(long, value_name = "URL")]
+    pub webhook: Option<String>,
+
+    /// Run this shell command per payload (JSON on stdin and in $SUIX_PAYLOAD)
+    #[arg(long, value_name = "COMMAND")]
+    pub exec: Option<String>,
+
+    /// Sign webhook bodies with HMAC-SHA256 using this secret
+    #[arg(
+        long,
+        value_name = "SECRET",
+        env = "SUIX_WEBHOOK_SECRET",
+        hide_env_values = true
+    )]
+    pub hmac_secret: Option<String>,
+
+    /// Number of webhook retries with exponential backoff
+    #[arg(long, value_name = "COUNT", default_value = "3")]
+    pub retries: u32,
+}
+
+impl HookArgs {
+    pub fn is_empty(&self) -> bool {
+        self.webhook.is_none() && self.exec.is_none()
+    }
+
+    /// Run every configured action, reporting failures on stderr
+    pub async fn fire(&self, payload: &Value) {
+        if let Some(ref url) = self.webhook {
+            let webhook = Webhook::new(url)
+                .with_secret(self.hmac_secret.clone())
+                .with_retries(self.retries);
+            if let Err(e) = webhook.deliver(payload).await {
+                eprintln!("Failed to deliver webhook: {e}");
+            }
+        }
+        if let Some(ref command) = self.exec
+            && let Err(e) = run_command(command, payload).await
+        {
+            eprintln!("Hook command failed: {e}");
+        }
+    }
+}
+
+/// Webhook endpoint with optional signing and retries
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    url: String,
+    secret: Option<String>,
+    retries: u32,
+}
+
+impl Webhook {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret: None,
+            retries: 0,
+        }
+    }
+
+    pub fn with_secret(mut self, secret: Option<String>) -> Self {
+        self.secret = secret;
+        self
+    }
+
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// POST the payload, retrying failed attempts with exponential backoff
+    pub async fn deliver(&self, payload: &Value) -> Result<()> {
+        let body = serde_json::to_vec(payload)?;
+        let client = reqwest::Client::new();
+        let mut attempt = 0;
+        loop {
+            let mut request = client
+                .post(&self.url)
+                .header("Content-Type", "application/json")
+                .body(body.clone());
+            if let Some(ref secret) = self.secret {
+                request = request.header(SIGNATURE_HEADER, sign_payload(secret, &body));
+            }
+
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => eyre::eyre!("Webhook returned status {}", response.status()),
+                Err(e) => eyre::eyre!("Webhook request failed: {}", e),
+            };
+
+            if attempt >= self.retries {
+                return Err(error);
+            }
+            attempt += 1;
+            tokio::time::sleep(Duration::from_millis(500 * 2u64.pow(attempt - 1))).await;
+        }
+    }
+}
+
+/// `sha256=<hex hmac>` signature of a webhook body
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Run a shell command with the payload on stdin and in `$SUIX_PAYLOAD`
+pub async fn run_command(command: &str, payload: &Value) -> Result<()> {
+    let payload = serde_json::to_string(payload)?;
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("SUIX_PAYLOAD", &payload)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| eyre::eyre!("Failed to spawn `{}`: {}", command, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The command may not read stdin at all; a broken pipe is not an error
+        let _ = stdin.write_all(payload.as_bytes()).await;
+    }
+
+    let status = child.wait().await?;
+    if !status.success() {
+        eyre::bail!("`{}` exited with {}", command, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_sign_payload() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_command_reports_failure() {
+        assert!(run_command("true", &json!({})).await.is_ok());
+        assert!(run_command("exit 3", &json!({})).await.is_err());
+    }
+}
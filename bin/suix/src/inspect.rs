@@ -0,0 +1,465 @@
+use std::io::{self, Write};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use clap::Subcommand;
+use eyre::Result;
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+};
+use rpc::{json_u64, json_u128, rpc_request};
+use serde_json::{Value, json};
+
+use crate::{context::Context, tx::gas_cost};
+
+#[derive(Subcommand)]
+pub enum InspectCommands {
+    /// Break a transaction down into commands, inputs, effects, object changes, events and gas
+    Tx {
+        /// Transaction digest
+        digest: String,
+        /// Open an interactive, navigable view
+        #[arg(long)]
+        tui: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+/// One entry of a section: a one-line label, its full JSON and an optional ID to copy
+#[derive(Debug, Clone)]
+struct Item {
+    label: String,
+    detail: Value,
+    copy: Option<String>,
+}
+
+impl Item {
+    fn new(label: impl Into<String>, detail: &Value) -> Self {
+        Self {
+            label: label.into(),
+            detail: detail.clone(),
+            copy: None,
+        }
+    }
+
+    fn with_copy(mut self, id: Option<&str>) -> Self {
+        self.copy = id.map(str::to_string);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Section {
+    title: &'static str,
+    items: Vec<Item>,
+}
+
+pub async fn run(cmd: InspectCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        InspectCommands::Tx { digest, tui, url } => {
+            let config = ctx.rpc_config(url, false);
+            let tx = rpc_request(
+                &config,
+                "sui_getTransactionBlock",
+                json!([digest, {
+                    "showInput": true,
+                    "showEffects": true,
+                    "showEvents": true,
+                    "showObjectChanges": true,
+                    "showBalanceChanges": true,
+                }]),
+            )
+            .await?;
+            let sections = build_sections(&tx);
+
+            if tui {
+                let mut terminal = ratatui::init();
+                let result = App::new(&sections).run(&mut terminal);
+                ratatui::restore();
+                return result;
+            }
+
+            for section in &sections {
+                println!("== {} ({}) ==", section.title, section.items.len());
+                for item in &section.items {
+                    println!("  {}", item.label);
+                }
+                println!();
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Split a `sui_getTransactionBlock` response into navigable sections
+fn build_sections(tx: &Value) -> Vec<Section> {
+    let data = &tx["transaction"]["data"];
+    let effects = &tx["effects"];
+    let array = |value: &Value| value.as_array().cloned().unwrap_or_default();
+
+    let overview = vec![
+        Item::new(
+            format!("digest      {}", str_or(&tx["digest"])),
+            &tx["digest"],
+        )
+        .with_copy(tx["digest"].as_str()),
+        Item::new(
+            format!("status      {}", str_or(&effects["status"]["status"])),
+            &effects["status"],
+        ),
+        Item::new(
+            format!("sender      {}", str_or(&data["sender"])),
+            &data["sender"],
+        )
+        .with_copy(data["sender"].as_str()),
+        Item::new(
+            format!("checkpoint  {}", str_or(&tx["checkpoint"])),
+            &tx["checkpoint"],
+        ),
+        Item::new(
+            format!("timestamp   {} ms", str_or(&tx["timestampMs"])),
+            &tx["timestampMs"],
+        ),
+        Item::new(
+            format!("kind        {}", str_or(&data["transaction"]["kind"])),
+            &data["transaction"]["kind"],
+        ),
+    ];
+
+    let commands = array(&data["transaction"]["transactions"])
+        .iter()
+        .enumerate()
+        .map(|(i, command)| Item::new(format!("#{i} {}", command_label(command)), command))
+        .collect();
+
+    let inputs = array(&data["transaction"]["inputs"])
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let label = match input["objectId"].as_str() {
+                Some(id) => format!("#{i} object {id}"),
+                None => format!(
+                    "#{i} pure {} = {}",
+                    str_or(&input["valueType"]),
+                    input["value"]
+                ),
+            };
+            Item::new(label, input).with_copy(input["objectId"].as_str())
+        })
+        .collect();
+
+    let mut effect_items = Vec::new();
+    for kind in [
+        "created",
+        "mutated",
+        "unwrapped",
+        "deleted",
+        "wrapped",
+        "unwrappedThenDeleted",
+    ] {
+        for object in array(&effects[kind]) {
+            // Deleted and wrapped objects are bare references without an owner
+            let id = object["reference"]["objectId"]
+                .as_str()
+                .or(object["objectId"].as_str());
+            let label = format!("{kind:<10} {}", id.unwrap_or("-"));
+            effect_items.push(Item::new(label, &object).with_copy(id));
+        }
+    }
+
+    let object_changes = array(&tx["objectChanges"])
+        .iter()
+        .map(|change| {
+            let label = format!(
+                "{:<10} {}",
+                str_or(&change["type"]),
+                change["objectType"]
+                    .as_str()
+                    .or(change["packageId"].as_str())
+                    .unwrap_or("-")
+            );
+            Item::new(label, change)
+                .with_copy(change["objectId"].as_str().or(change["packageId"].as_str()))
+        })
+        .collect();
+
+    let events = array(&tx["events"])
+        .iter()
+        .map(|event| Item::new(str_or(&event["type"]), event).with_copy(event["type"].as_str()))
+        .collect();
+
+    let gas_used = &effects["gasUsed"];
+    let cost = |name: &str| json_u128(&gas_used[name]).unwrap_or_default();
+    let total = gas_cost(effects);
+    let mut gas = vec![
+        Item::new(
+            format!("computation     {} MIST", cost("computationCost")),
+            gas_used,
+        ),
+        Item::new(
+            format!("storage         {} MIST", cost("storageCost")),
+            gas_used,
+        ),
+        Item::new(
+            format!("rebate          {} MIST", cost("storageRebate")),
+            gas_used,
+        ),
+        Item::new(
+            format!("non-refundable  {} MIST", cost("nonRefundableStorageFee")),
+            gas_used,
+        ),
+        Item::new(format!("total           {total} MIST"), gas_used),
+        Item::new(
+            format!(
+                "price {} MIST, budget {} MIST",
+                json_u64(&data["gasData"]["price"]).unwrap_or_default(),
+                json_u64(&data["gasData"]["budget"]).unwrap_or_default()
+            ),
+            &data["gasData"],
+        ),
+        Item::new(
+            format!("owner           {}", str_or(&data["gasData"]["owner"])),
+            &data["gasData"],
+        )
+        .with_copy(data["gasData"]["owner"].as_str()),
+    ];
+    for coin in array(&data["gasData"]["payment"]) {
+        gas.push(
+            Item::new(
+                format!("payment         {}", str_or(&coin["objectId"])),
+                &coin,
+            )
+            .with_copy(coin["objectId"].as_str()),
+        );
+    }
+
+    vec![
+        Section {
+            title: "Overview",
+            items: overview,
+        },
+        Section {
+            title: "Commands",
+            items: commands,
+        },
+        Section {
+            title: "Inputs",
+            items: inputs,
+        },
+        Section {
+            title: "Effects",
+            items: effect_items,
+        },
+        Section {
+            title: "Object changes",
+            items: object_changes,
+        },
+        Section {
+            title: "Events",
+            items: events,
+        },
+        Section {
+            title: "Gas",
+            items: gas,
+        },
+    ]
+}
+
+/// Short description of a PTB command, e.g. `MoveCall 0x2::coin::split`
+fn command_label(command: &Value) -> String {
+    let Some((kind, body)) = command.as_object().and_then(|o| o.iter().next()) else {
+        return command.to_string();
+    };
+    match kind.as_str() {
+        "MoveCall" => format!(
+            "MoveCall {}::{}::{}",
+            str_or(&body["package"]),
+            str_or(&body["module"]),
+            str_or(&body["function"])
+        ),
+        _ => kind.clone(),
+    }
+}
+
+fn str_or(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "-".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Copy text to the system clipboard through the terminal (OSC 52), which also works over SSH
+fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", BASE64.encode(text))?;
+    stdout.flush()
+}
+
+/// Interactive viewer state
+struct App<'a> {
+    sections: &'a [Section],
+    tab: usize,
+    list: ListState,
+    status: String,
+}
+
+impl<'a> App<'a> {
+    fn new(sections: &'a [Section]) -> Self {
+        Self {
+            sections,
+            tab: 0,
+            list: ListState::default().with_selected(Some(0)),
+            status: "←/→ section  ↑/↓ item  c copy ID  q quit".to_string(),
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Right | KeyCode::Tab | KeyCode::Char('l') => {
+                    self.select_tab((self.tab + 1) % self.sections.len())
+                }
+                KeyCode::Left | KeyCode::BackTab | KeyCode::Char('h') => {
+                    self.select_tab((self.tab + self.sections.len() - 1) % self.sections.len())
+                }
+                KeyCode::Down | KeyCode::Char('j') => self.list.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.list.select_previous(),
+                KeyCode::Char('c') => {
+                    self.status = match self.selected().and_then(|item| item.copy.clone()) {
+                        Some(id) => match copy_to_clipboard(&id) {
+                            Ok(()) => format!("Copied {id}"),
+                            Err(e) => format!("Copy failed: {e}"),
+                        },
+                        None => "Nothing to copy for this item".to_string(),
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn select_tab(&mut self, tab: usize) {
+        self.tab = tab;
+        self.list.select(Some(0));
+    }
+
+    fn selected(&self) -> Option<&Item> {
+        self.sections[self.tab].items.get(self.list.selected()?)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, list_area, detail_area, status_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Percentage(45),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let titles = self
+            .sections
+            .iter()
+            .map(|s| format!("{} ({})", s.title, s.items.len()));
+        frame.render_widget(
+            Tabs::new(titles)
+                .select(self.tab)
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD).reversed())
+                .block(Block::bordered().title(" Transaction ")),
+            tabs_area,
+        );
+
+        let section = &self.sections[self.tab];
+        let items: Vec<ListItem> = section
+            .items
+            .iter()
+            .map(|item| ListItem::new(item.label.as_str()))
+            .collect();
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::bordered().title(format!(" {} ", section.title)))
+                .highlight_style(Style::default().reversed())
+                .highlight_symbol("▶ "),
+            list_area,
+            &mut self.list,
+        );
+
+        let detail = self
+            .selected()
+            .map(|item| serde_json::to_string_pretty(&item.detail).unwrap_or_default())
+            .unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(detail)
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered().title(" Details ")),
+            detail_area,
+        );
+        frame.render_widget(Line::from(self.status.as_str()).dim(), status_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_label() {
+        let call =
+            json!({ "MoveCall": { "package": "0x2", "module": "coin", "function": "split" } });
+        assert_eq!(command_label(&call), "MoveCall 0x2::coin::split");
+        assert_eq!(
+            command_label(&json!({ "TransferObjects": [[], "Input"] })),
+            "TransferObjects"
+        );
+    }
+
+    #[test]
+    fn test_build_sections() {
+        let tx = json!({
+            "digest": "D1",
+            "transaction": { "data": {
+                "sender": "0xa",
+                "gasData": { "price": "750", "budget": "1000", "owner": "0xa", "payment": [] },
+                "transaction": {
+                    "kind": "ProgrammableTransaction",
+                    "inputs": [{ "type": "object", "objectId": "0x5" }],
+                    "transactions": [{ "SplitCoins": ["GasCoin", [{ "Input": 0 }]] }],
+                },
+            }},
+            "effects": {
+                "status": { "status": "success" },
+                "gasUsed": { "computationCost": "100", "storageCost": "50", "storageRebate": "30" },
+                "created": [{ "owner": "Immutable", "reference": { "objectId": "0x9" } }],
+            },
+        });
+        let sections = build_sections(&tx);
+        let section = |title| sections.iter().find(|s| s.title == title).unwrap();
+        assert_eq!(section("Commands").items[0].label, "#0 SplitCoins");
+        assert_eq!(section("Inputs").items[0].copy.as_deref(), Some("0x5"));
+        assert_eq!(section("Effects").items[0].copy.as_deref(), Some("0x9"));
+        assert!(
+            section("Gas")
+                .items
+                .iter()
+                .any(|i| i.label == "total           120 MIST")
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use eyre::Result;
+use keystore::{
+    Keystore,
+    audit::{Severity, audit},
+};
+
+#[derive(Subcommand)]
+pub enum KeysCommands {
+    /// Review a Sui keystore and print a JSON report.
+    ///
+    /// Lists every key with its scheme, address and alias, and flags duplicate
+    /// and weak keys, obsolete encodings, loose file permissions, orphaned or
+    /// ambiguous aliases and an active address with no key.
+    Audit {
+        /// Keystore to audit [default: ~/.sui/sui_config/sui.keystore]
+        #[arg(long, value_name = "PATH")]
+        keystore: Option<PathBuf>,
+    },
+}
+
+pub fn run(cmd: KeysCommands) -> Result<()> {
+    match cmd {
+        KeysCommands::Audit { keystore } => {
+            let path = keystore.unwrap_or_else(Keystore::default_path);
+            let report = audit(&path)?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            eprintln!(
+                "{} keys, {} high, {} medium, {} low findings",
+                report.keys.len(),
+                report.count(Severity::High),
+                report.count(Severity::Medium),
+                report.count(Severity::Low)
+            );
+            Ok(())
+        }
+    }
+}
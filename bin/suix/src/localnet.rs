@@ -0,0 +1,436 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use clap::Subcommand;
+use eyre::{Result, bail};
+use keystore::resolve_address;
+use rpc::{RpcConfig, latest_checkpoint, rpc_request};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::context::{Context, Network};
+
+/// Faucet started by `sui start --with-faucet`
+const FAUCET_URL: &str = "http://127.0.0.1:9123";
+
+/// Arguments `up` runs the Sui CLI with
+const NETWORK_ARGS: [&str; 3] = ["start", "--with-faucet", "--force-regenesis"];
+
+#[derive(Subcommand)]
+pub enum LocalnetCommands {
+    /// Start a fresh local network in the background and wait until it serves requests
+    Up {
+        /// Sui CLI binary used to run the network
+        #[arg(long, value_name = "PATH", default_value = "sui")]
+        sui_bin: PathBuf,
+        /// Fund this address or keystore alias once the network is up
+        /// [default: first key of the keystore]
+        #[arg(long, value_name = "ADDRESS|ALIAS")]
+        fund: Option<String>,
+        /// Keystore used to resolve the address to fund
+        #[arg(long, value_name = "PATH")]
+        keystore: Option<PathBuf>,
+        /// Seconds to wait for the network to become ready
+        #[arg(long, value_name = "SECONDS", default_value = "120")]
+        timeout: u64,
+    },
+    /// Stop the network started by `up`
+    Down,
+    /// Show whether the local network is running and its latest checkpoint
+    Status,
+    /// Request SUI from the local faucet
+    Faucet {
+        /// Recipient address or keystore alias [default: the address funded by `up`]
+        address: Option<String>,
+        /// Keystore used to resolve an alias
+        #[arg(long, value_name = "PATH")]
+        keystore: Option<PathBuf>,
+    },
+}
+
+/// What `up` started, kept in `~/.suix/localnet/state.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct LocalnetState {
+    pid: u32,
+    rpc_url: String,
+    faucet_url: String,
+    log: PathBuf,
+    dev_address: Option<String>,
+}
+
+pub async fn run(cmd: LocalnetCommands, ctx: &Context) -> Result<()> {
+    let dir = state_dir();
+    match cmd {
+        LocalnetCommands::Up {
+            sui_bin,
+            fund,
+            keystore,
+            timeout,
+        } => {
+            if let Some(state) = load_state(&dir)?
+                && is_network(state.pid)
+            {
+                bail!(
+                    "A local network is already running (pid {}); run `suix localnet down` first",
+                    state.pid
+                );
+            }
+            let config = localnet_config();
+            if rpc_request(&config, "sui_getChainIdentifier", json!([]))
+                .await
+                .is_ok()
+            {
+                bail!(
+                    "Something already serves {}; stop it before starting a new network",
+                    config.url
+                );
+            }
+
+            fs::create_dir_all(&dir)
+                .map_err(|e| eyre::eyre!("Failed to create {}: {}", dir.display(), e))?;
+            let log = dir.join("sui.log");
+            let mut child = spawn_network(&sui_bin, &log)?;
+            let pid = child.id();
+            let mut state = LocalnetState {
+                pid,
+                rpc_url: config.url.clone(),
+                faucet_url: FAUCET_URL.to_string(),
+                log,
+                dev_address: None,
+            };
+            save_state(&dir, &state)?;
+            eprintln!(
+                "Started {} (pid {pid}), logging to {}",
+                sui_bin.display(),
+                state.log.display()
+            );
+
+            let checkpoint = wait_ready(
+                &config,
+                &mut child,
+                Duration::from_secs(timeout),
+                &state.log,
+            )
+            .await?;
+            println!(
+                "✅ Local network ready at {} (checkpoint {checkpoint})",
+                state.rpc_url
+            );
+
+            let recipient = match fund {
+                Some(ref address) => Some(resolve_recipient(ctx, address, keystore)?),
+                None => ctx
+                    .keystore(keystore)
+                    .ok()
+                    .and_then(|k| k.entries().first().map(|e| e.address.to_string())),
+            };
+            match recipient {
+                Some(address) => {
+                    request_gas(&state.faucet_url, &address, Duration::from_secs(timeout)).await?;
+                    println!("💧 Funded {address}");
+                    state.dev_address = Some(address);
+                    save_state(&dir, &state)?;
+                }
+                None => {
+                    eprintln!("No key in the keystore to fund; use `suix localnet faucet ADDRESS`")
+                }
+            }
+            println!(
+                "Use it with --url {} (or --network {})",
+                state.rpc_url,
+                Network::Localnet.name()
+            );
+            Ok(())
+        }
+        LocalnetCommands::Down => {
+            let Some(state) = load_state(&dir)? else {
+                bail!("No local network was started with `suix localnet up`");
+            };
+            if is_network(state.pid) {
+                stop_process(state.pid)?;
+                let deadline = Instant::now() + Duration::from_secs(30);
+                while process_alive(state.pid) && Instant::now() < deadline {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                }
+                if process_alive(state.pid) {
+                    bail!("pid {} did not exit; stop it manually", state.pid);
+                }
+                println!("✅ Stopped local network (pid {})", state.pid);
+            } else if process_alive(state.pid) {
+                println!(
+                    "Local network (pid {}) was not running; the pid now belongs to another process",
+                    state.pid
+                );
+            } else {
+                println!("Local network (pid {}) was not running", state.pid);
+            }
+            fs::remove_file(dir.join("state.json"))?;
+            Ok(())
+        }
+        LocalnetCommands::Status => {
+            let state = load_state(&dir)?;
+            let config = localnet_config();
+            if let Some(ref state) = state {
+                let running = if is_network(state.pid) {
+                    "running"
+                } else {
+                    "exited"
+                };
+                println!("Process:     pid {} ({running})", state.pid);
+                println!("Log:         {}", state.log.display());
+                if let Some(ref address) = state.dev_address {
+                    println!("Dev address: {address}");
+                }
+            }
+            match rpc_request(&config, "sui_getChainIdentifier", json!([])).await {
+                Ok(chain) => {
+                    let latest = latest_checkpoint(&config).await?;
+                    println!(
+                        "RPC:         {} (chain {})",
+                        config.url,
+                        chain.as_str().unwrap_or_default()
+                    );
+                    println!("Checkpoint:  {latest}");
+                }
+                Err(_) if state.is_none() => bail!("No local network is running at {}", config.url),
+                Err(e) => bail!("{} is not responding: {}", config.url, e),
+            }
+            Ok(())
+        }
+        LocalnetCommands::Faucet { address, keystore } => {
+            let state = load_state(&dir)?;
+            let address = match (address, state.as_ref().and_then(|s| s.dev_address.clone())) {
+                (Some(address), _) => resolve_recipient(ctx, &address, keystore)?,
+                (None, Some(dev)) => dev,
+                (None, None) => bail!("Pass the address to fund"),
+            };
+            let faucet_url = state.map_or_else(|| FAUCET_URL.to_string(), |s| s.faucet_url);
+            request_gas(&faucet_url, &address, Duration::from_secs(30)).await?;
+            println!("💧 Funded {address}");
+            Ok(())
+        }
+    }
+}
+
+/// Directory holding the state file and node log: `~/.suix/localnet`
+fn state_dir() -> PathBuf {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(".suix")
+        .join("localnet")
+}
+
+/// Uncached and without retries: the chain is regenerated on every `up`
+fn localnet_config() -> RpcConfig {
+    RpcConfig {
+        url: Network::Localnet.rpc_url().to_string(),
+        ..RpcConfig::default()
+    }
+}
+
+fn load_state(dir: &Path) -> Result<Option<LocalnetState>> {
+    match fs::read_to_string(dir.join("state.json")) {
+        Ok(content) => Ok(Some(serde_json::from_str(&content).map_err(|e| {
+            eyre::eyre!("Invalid localnet state in {}: {}", dir.display(), e)
+        })?)),
+        Err(_) => Ok(None),
+    }
+}
+
+fn save_state(dir: &Path, state: &LocalnetState) -> Result<()> {
+    fs::write(dir.join("state.json"), serde_json::to_string_pretty(state)?)
+        .map_err(|e| eyre::eyre!("Failed to save localnet state: {}", e))
+}
+
+/// Start `sui start` detached from this process with output going to `log`
+fn spawn_network(sui_bin: &Path, log: &Path) -> Result<Child> {
+    let output = fs::File::create(log)
+        .map_err(|e| eyre::eyre!("Failed to create {}: {}", log.display(), e))?;
+    let mut command = Command::new(sui_bin);
+    command
+        .args(NETWORK_ARGS)
+        .env("RUST_LOG", "off,sui_node=info")
+        .stdin(Stdio::null())
+        .stdout(output.try_clone()?)
+        .stderr(output);
+    // Own process group, so Ctrl-C in this terminal does not stop the network
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    command.spawn().map_err(|e| {
+        eyre::eyre!(
+            "Failed to run {}: {} (install the Sui CLI or pass --sui-bin)",
+            sui_bin.display(),
+            e
+        )
+    })
+}
+
+/// Wait until the node has produced a checkpoint, returning its sequence number
+async fn wait_ready(
+    config: &RpcConfig,
+    child: &mut Child,
+    timeout: Duration,
+    log: &Path,
+) -> Result<u64> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            bail!(
+                "The network exited during startup ({}); see {}",
+                status,
+                log.display()
+            );
+        }
+        if let Ok(checkpoint) = latest_checkpoint(config).await
+            && checkpoint > 0
+        {
+            return Ok(checkpoint);
+        }
+        if Instant::now() >= deadline {
+            bail!(
+                "The network did not become ready within {}s; see {}",
+                timeout.as_secs(),
+                log.display()
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+fn resolve_recipient(ctx: &Context, address: &str, keystore: Option<PathBuf>) -> Result<String> {
+    let keystore = ctx.keystore(keystore).ok();
+    Ok(resolve_address(keystore.as_ref(), address)?.to_string())
+}
+
+/// Ask the faucet for gas, retrying until it accepts requests or `timeout` passes
+async fn request_gas(faucet_url: &str, address: &str, timeout: Duration) -> Result<()> {
+    let client = reqwest::Client::new();
+    let deadline = Instant::now() + timeout;
+    loop {
+        let error = match client
+            .post(format!("{}/v2/gas", faucet_url.trim_end_matches('/')))
+            .json(&faucet_request(address))
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => {
+                let body: Value = response.json().await.unwrap_or_default();
+                match faucet_error(&body) {
+                    None => return Ok(()),
+                    Some(e) => e,
+                }
+            }
+            Ok(response) => format!("faucet returned status {}", response.status()),
+            Err(e) => format!("faucet request failed: {e}"),
+        };
+        if Instant::now() >= deadline {
+            bail!("Failed to fund {}: {}", address, error);
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+fn faucet_request(address: &str) -> Value {
+    json!({ "FixedAmountRequest": { "recipient": address } })
+}
+
+/// Error reported in a faucet response body, if any
+fn faucet_error(body: &Value) -> Option<String> {
+    if let Some(failure) = body["status"].get("Failure") {
+        return Some(failure.to_string());
+    }
+    body["error"]
+        .as_str()
+        .filter(|e| !e.is_empty())
+        .map(str::to_string)
+}
+
+/// Whether `pid` exists (and we may signal it)
+fn process_alive(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Whether `pid` is still the network started by [`spawn_network`], not a
+/// process that reused its pid
+fn is_network(pid: u32) -> bool {
+    Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "args="])
+        .stderr(Stdio::null())
+        .output()
+        .is_ok_and(|output| {
+            let args = String::from_utf8_lossy(&output.stdout);
+            output.status.success() && args.contains(&NETWORK_ARGS.join(" "))
+        })
+}
+
+fn stop_process(pid: u32) -> Result<()> {
+    let status = Command::new("kill")
+        .arg(pid.to_string())
+        .status()
+        .map_err(|e| eyre::eyre!("Failed to run kill: {}", e))?;
+    if !status.success() {
+        bail!("Failed to stop pid {}", pid);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("suix-localnet-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(load_state(&dir).unwrap(), None);
+        let state = LocalnetState {
+            pid: 42,
+            rpc_url: Network::Localnet.rpc_url().to_string(),
+            faucet_url: FAUCET_URL.to_string(),
+            log: dir.join("sui.log"),
+            dev_address: Some("0xa".to_string()),
+        };
+        save_state(&dir, &state).unwrap();
+        assert_eq!(load_state(&dir).unwrap(), Some(state));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_faucet_error() {
+        assert_eq!(faucet_error(&json!({ "status": "Success" })), None);
+        assert_eq!(faucet_error(&json!({ "error": null })), None);
+        assert!(faucet_error(&json!({ "status": { "Failure": { "Internal": "x" } } })).is_some());
+        assert_eq!(
+            faucet_error(&json!({ "error": "rate limited" })).as_deref(),
+            Some("rate limited")
+        );
+    }
+
+    #[test]
+    fn test_process_alive() {
+        assert!(process_alive(std::process::id()));
+    }
+
+    #[test]
+    fn test_is_network() {
+        assert!(!is_network(std::process::id()));
+        let mut child = Command::new("sh")
+            .args(["-c", "sleep 5; true", "sui"])
+            .args(NETWORK_ARGS)
+            .spawn()
+            .unwrap();
+        assert!(is_network(child.id()));
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(!is_network(child.id()));
+    }
+}
@@ -1,11 +1,71 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
 use clap::{Parser, Subcommand};
+use context::{CacheArgs, Context, GrpcArgs, KeyArgs, TransportArgs};
 use eyre::{Result, bail};
-use grpc::{GrpcConfig, SuiGrpcClient};
-use rpc::{RpcConfig, make_rpc_call, methods};
+use futures::StreamExt;
+use grpc::{GrpcConfig, RawOutput, SignedMessage, SuiGrpcClient};
+use output::OutputFormat;
+use rpc::{
+    client::RpcClient,
+    coins::{CoinInfo, CoinRegistry},
+    json_u128, make_rpc_call,
+};
+use serde_json::json;
+use tracing_subscriber::{
+    filter::{LevelFilter, Targets},
+    prelude::*,
+};
 use vanity::{VanityConfig, generate_vanity_addresses};
 
+mod airdrop;
+mod alert;
+mod analyze;
+mod archive;
+mod at_time;
+mod backfill;
+mod bench;
+mod coin_select;
+mod coins;
+mod compare;
+mod context;
+mod convert;
+mod cost;
+mod df;
+mod diff;
+mod export;
+mod filter;
+mod find;
+mod gas;
+mod hooks;
+mod inspect;
+mod keys;
+mod localnet;
+mod msg;
+mod notify;
+mod object;
+mod output;
+mod package;
+mod portfolio;
+mod probe;
+mod protocol;
+mod qr;
+mod replay;
+mod report;
+mod scan;
+mod schedule;
+mod sink;
+mod snapshot;
+mod sponsor;
+mod stats;
+mod store;
+mod stream;
+mod validators;
+mod vault;
+mod wait;
+mod watch;
+
 #[derive(Parser)]
 #[command(name = "suix")]
 #[command(about = "A comprehensive CLI tool for Sui blockchain operations")]
@@ -13,6 +73,18 @@ use vanity::{VanityConfig, generate_vanity_addresses};
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[command(flatten)]
+    cache: CacheArgs,
+
+    #[command(flatten)]
+    transport: TransportArgs,
+
+    #[command(flatten)]
+    keys: KeyArgs,
+
+    #[command(flatten)]
+    grpc: GrpcArgs,
 }
 
 #[derive(Subcommand)]
@@ -105,6 +177,110 @@ enum Commands {
     /// Quick access to common gRPC methods (using sui-rpc-api)
     #[command(subcommand)]
     GrpcQuick(GrpcCommands),
+    /// Aggregate balances per coin type across many addresses
+    Portfolio(portfolio::PortfolioArgs),
+    /// Validator and epoch report (stake, commission, APY, gas price quotes)
+    Validators(validators::ValidatorsArgs),
+    /// Gas price and gas coin tools
+    #[command(subcommand)]
+    Gas(gas::GasCommands),
+    /// Send webhooks or run commands when matching events or checkpoints appear
+    Notify(notify::NotifyArgs),
+    /// Resumable historical backfill of checkpoints, transactions or events
+    Backfill(backfill::BackfillArgs),
+    /// Archive full checkpoints to disk, one file per checkpoint, resuming after interruption
+    Archive(archive::ArchiveArgs),
+    /// Re-execute a historical transaction and diff its effects against the chain
+    Replay(replay::ReplayArgs),
+    /// Object inspection tools
+    #[command(subcommand)]
+    Object(object::ObjectCommands),
+    /// Aggregated activity reports
+    #[command(subcommand)]
+    Report(report::ReportCommands),
+    /// Protocol configuration tools
+    #[command(subcommand)]
+    Protocol(protocol::ProtocolCommands),
+    /// Dynamic field tools
+    #[command(subcommand)]
+    Df(df::DfCommands),
+    /// Coin metadata tools
+    #[command(subcommand)]
+    Coins(coins::CoinsCommands),
+    /// Move package tools (dependency graph, source verification)
+    #[command(subcommand)]
+    Package(package::PackageCommands),
+    /// Sponsored transactions with a separate gas owner
+    #[command(subcommand)]
+    Sponsor(sponsor::SponsorCommands),
+    /// Real-time NDJSON streams derived from checkpoints
+    #[command(subcommand)]
+    Stream(stream::StreamCommands),
+    /// Latency and finality measurements
+    #[command(subcommand)]
+    Probe(probe::ProbeCommands),
+    /// Distribute a coin to the recipients of a CSV file in batched PTBs
+    Airdrop(airdrop::AirdropArgs),
+    /// Encrypted key vault (init, add, unlock)
+    #[command(subcommand)]
+    Vault(vault::VaultCommands),
+    /// Interactive and plain-text inspectors
+    #[command(subcommand)]
+    Inspect(inspect::InspectCommands),
+    /// Mirror chain data into a local store for offline queries
+    #[command(subcommand)]
+    Store(store::StoreCommands),
+    /// Compare packages across networks
+    #[command(subcommand)]
+    Compare(compare::CompareCommands),
+    /// Inspect filter expressions used by event and transaction watchers
+    #[command(subcommand)]
+    Filter(filter::FilterCommands),
+    /// Evaluate alerting rules continuously
+    Alert(alert::AlertArgs),
+    /// Find the checkpoint and epoch current at a timestamp
+    AtTime(at_time::AtTimeArgs),
+    /// Wait for a transaction to reach a state
+    #[command(subcommand)]
+    Wait(wait::WaitCommands),
+    /// Analytics over recent transactions (gas per entry function)
+    #[command(subcommand)]
+    Analyze(analyze::AnalyzeCommands),
+    /// Ownership snapshots of an object type (holders CSV)
+    #[command(subcommand)]
+    Snapshot(snapshot::SnapshotCommands),
+    /// Reassemble a payload from scanned QR chunks (optionally submit it)
+    Scan(scan::ScanArgs),
+    /// Sign and verify personal messages
+    #[command(subcommand)]
+    Msg(msg::MsgCommands),
+    /// Offline key, address and digest conversions
+    #[command(subcommand)]
+    Convert(convert::ConvertCommands),
+    /// Keystore tools (security audit)
+    #[command(subcommand)]
+    Keys(keys::KeysCommands),
+    /// Run suix commands on cron schedules from a jobs file
+    Schedule(schedule::ScheduleArgs),
+    /// Search objects by content with a filter expression (NDJSON)
+    Find(find::FindArgs),
+    /// Run a local Sui network for development (up, down, status, faucet)
+    #[command(subcommand)]
+    Localnet(localnet::LocalnetCommands),
+    /// Publish checkpoints, transactions or events to Kafka, NATS or local sinks as they are produced
+    Sink(sink::SinkArgs),
+    /// Transaction cost tools (compare under several gas prices)
+    #[command(subcommand)]
+    Cost(cost::CostCommands),
+    /// Export address activity for accounting tools (ledger CSV)
+    #[command(subcommand)]
+    Export(export::ExportCommands),
+    /// Watch for chain-level changes (epoch boundaries)
+    #[command(subcommand)]
+    Watch(watch::WatchCommands),
+    /// Statistics over sampled chain state and per-epoch totals
+    #[command(subcommand)]
+    Stats(stats::StatsCommands),
 }
 
 #[derive(Subcommand)]
@@ -135,6 +311,28 @@ enum QueryCommands {
         #[arg(short, long)]
         pretty: bool,
     },
+    /// List consecutive checkpoints, one JSON object per line
+    Checkpoints {
+        /// First checkpoint [default: genesis, or the latest with --descending]
+        #[arg(long, value_name = "SEQUENCE")]
+        start: Option<u64>,
+        /// Number of checkpoints
+        #[arg(long, value_name = "COUNT", default_value = "10")]
+        limit: usize,
+        /// Go back from --start instead of forward
+        #[arg(long)]
+        descending: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Pretty print the checkpoints as a JSON array
+        #[arg(short, long)]
+        pretty: bool,
+    },
     /// Get object information by ID
     Object {
         /// Object ID to query
@@ -186,11 +384,266 @@ enum QueryCommands {
         #[arg(short, long)]
         pretty: bool,
     },
+    /// List the coin objects an address owns; the cursor to continue from is printed
+    /// to stderr
+    Coins {
+        /// Owner address
+        #[arg(value_name = "ADDRESS")]
+        address: String,
+        /// Coin type [default: 0x2::sui::SUI]
+        #[arg(long, value_name = "COIN_TYPE", conflicts_with = "all")]
+        coin_type: Option<String>,
+        /// Coins of every type
+        #[arg(long)]
+        all: bool,
+        /// Maximum number of coins
+        #[arg(long, value_name = "COUNT", default_value = "50")]
+        limit: usize,
+        /// Continue after this cursor, as printed by a previous listing
+        #[arg(long, value_name = "CURSOR")]
+        cursor: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Get the balances of an address in every coin type it holds
+    Balances {
+        /// Owner address
+        #[arg(value_name = "ADDRESS")]
+        address: String,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Get the metadata (symbol, decimals, name, ...) of a coin type
+    Metadata {
+        /// Coin type, e.g. 0x2::sui::SUI
+        #[arg(value_name = "COIN_TYPE")]
+        coin_type: String,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Get the total supply of a coin type
+    Supply {
+        /// Coin type, e.g. 0x2::sui::SUI
+        #[arg(value_name = "COIN_TYPE")]
+        coin_type: String,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// List the stakes of an address with each validator's current APY
+    Stakes {
+        /// Owner address
+        #[arg(value_name = "ADDRESS")]
+        address: String,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Get the latest system state: epoch, protocol version, reference gas price and
+    /// the active validators
+    SystemState {
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Pretty print the JSON response
+        #[arg(short, long)]
+        pretty: bool,
+    },
+    /// Get the estimated APY of every active validator, highest first
+    ValidatorsApy {
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Get the reference gas price, optionally with its history over past epochs
+    GasPrice {
+        /// Number of epochs to show, counting the current one
+        #[arg(long, value_name = "N", default_value = "1")]
+        epochs: usize,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Get the committee of an epoch: each validator's voting power
+    Committee {
+        /// Epoch [default: the current epoch]
+        #[arg(long, value_name = "EPOCH")]
+        epoch: Option<u64>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// List the dynamic fields of an object (e.g. the entries of a Table or Bag), one
+    /// JSON object per line; the cursor to continue from is printed to stderr
+    DynamicFields {
+        /// Parent object ID, e.g. the ID of a Table
+        #[arg(value_name = "OBJECT_ID")]
+        parent_id: String,
+        /// Maximum number of fields
+        #[arg(long, value_name = "COUNT", default_value = "50")]
+        limit: usize,
+        /// Continue after this cursor, as printed by a previous listing
+        #[arg(long, value_name = "CURSOR")]
+        cursor: Option<String>,
+        /// Also fetch each field's value
+        #[arg(long)]
+        content: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Get the object holding one dynamic field of an object, by the field's name
+    DynamicField {
+        /// Parent object ID
+        #[arg(value_name = "OBJECT_ID")]
+        parent_id: String,
+        /// Move type of the name, e.g. address, u64 or 0x1::string::String
+        #[arg(long = "name-type", value_name = "TYPE")]
+        name_type: String,
+        /// The name as JSON, e.g. '"0x7d2..."' or '5'; anything that is not JSON is a string
+        #[arg(long, value_name = "VALUE")]
+        name: String,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Pretty print the JSON response
+        #[arg(short, long)]
+        pretty: bool,
+    },
+    /// Query past events by package, module, type, sender and time, one JSON object
+    /// per line; the cursor to continue from is printed to stderr
+    Events {
+        /// Package of the module the transaction called to emit the event
+        #[arg(long, value_name = "PACKAGE")]
+        package: Option<String>,
+        /// Module the transaction called to emit the event
+        #[arg(long, value_name = "MODULE")]
+        module: Option<String>,
+        /// Event type, e.g. 0x3::validator::StakingRequestEvent
+        #[arg(long = "type", value_name = "TYPE")]
+        event_type: Option<String>,
+        /// Sender of the transaction that emitted the event
+        #[arg(long, value_name = "ADDRESS")]
+        sender: Option<String>,
+        /// Only events at or after this time (RFC 3339, YYYY-MM-DD or Unix timestamp)
+        #[arg(long, value_name = "TIME")]
+        since: Option<String>,
+        /// Only events before this time (RFC 3339, YYYY-MM-DD or Unix timestamp)
+        #[arg(long, value_name = "TIME")]
+        until: Option<String>,
+        /// Maximum number of events
+        #[arg(long, value_name = "COUNT", default_value = "50")]
+        limit: usize,
+        /// Continue after this cursor, as printed by a previous query
+        #[arg(long, value_name = "JSON")]
+        cursor: Option<String>,
+        /// Newest events first
+        #[arg(long)]
+        descending: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Stream events as they are emitted over a WebSocket, one JSON object per line.
+    ///
+    /// A dropped connection is reopened and the subscription made again; events
+    /// emitted in between are missed.
+    SubscribeEvents {
+        /// Event filter, e.g. '{"Sender":"0x..."}' or '{"MoveEventType":"0x3::validator::StakingRequestEvent"}'
+        #[arg(long, value_name = "JSON", default_value = r#"{"All":[]}"#)]
+        filter: String,
+        /// Stop after this many events
+        #[arg(long, value_name = "COUNT")]
+        limit: Option<usize>,
+        /// RPC endpoint URL with WebSocket subscriptions enabled; http(s) is switched to ws(s)
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "wss://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
 }
 
 #[derive(Subcommand)]
 enum GrpcCommands {
-    /// Get service information
+    /// Get service information: chain id, server version, API versions and the lowest
+    /// available checkpoint
     Info {
         /// gRPC endpoint URL
         #[arg(
@@ -209,11 +662,27 @@ enum GrpcCommands {
         #[arg(long, value_name = "SECONDS", default_value = "30")]
         timeout: u64,
     },
-    /// Get object information by ID
+    /// Get object information by ID, or many objects with `-`, one ID per line on stdin
     Object {
-        /// Object ID to query
+        /// Object ID to query, or `-` to read one ID per line from stdin and print one
+        /// JSON line per object (with an `error` field for those that failed)
         #[arg(value_name = "OBJECT_ID")]
         object_id: String,
+        /// Read the object as it was at this version instead of its latest state
+        #[arg(long, value_name = "VERSION")]
+        version: Option<u64>,
+        /// Only these fields, comma-separated (e.g. object_id,version,owner)
+        #[arg(long, value_name = "FIELDS")]
+        fields: Option<String>,
+        /// Maximum number of requests in flight when reading IDs from stdin
+        #[arg(long, value_name = "N", default_value = "16")]
+        concurrency: usize,
+        /// Print the object's BCS bytes as hex instead of decoding it
+        #[arg(long, conflicts_with_all = ["bcs_out", "fields"])]
+        bcs: bool,
+        /// Write the object's BCS bytes to FILE instead of decoding it
+        #[arg(long, value_name = "FILE", conflicts_with = "fields")]
+        bcs_out: Option<PathBuf>,
         /// gRPC endpoint URL
         #[arg(
             long,
@@ -231,11 +700,51 @@ enum GrpcCommands {
         #[arg(long, value_name = "SECONDS", default_value = "30")]
         timeout: u64,
     },
-    /// Get transaction by digest
+    /// Get many objects concurrently, by ID arguments or one ID per line on stdin
+    Objects {
+        /// Object IDs to query (read from stdin when omitted)
+        #[arg(value_name = "OBJECT_ID")]
+        object_ids: Vec<String>,
+        /// Maximum number of requests in flight
+        #[arg(long, value_name = "N", default_value = "16")]
+        concurrency: usize,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Pretty print the response
+        #[arg(short, long)]
+        pretty: bool,
+        /// Output one JSON object per line for pipeline processing
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// Get transaction by digest, optionally with the effects of its execution
     Tx {
         /// Transaction digest
         #[arg(value_name = "DIGEST")]
         digest: String,
+        /// Include the execution status, gas used, epoch and dependencies
+        #[arg(long)]
+        effects: bool,
+        /// Include the events emitted
+        #[arg(long)]
+        events: bool,
+        /// Include the objects created, mutated, deleted, wrapped and unwrapped
+        #[arg(long)]
+        object_changes: bool,
+        /// Print the BCS bytes of the transaction data as hex, e.g. for an offline signer
+        #[arg(long, conflicts_with_all = ["bcs_out", "effects", "events", "object_changes"])]
+        bcs: bool,
+        /// Write the BCS bytes of the transaction data to FILE
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["effects", "events", "object_changes"])]
+        bcs_out: Option<PathBuf>,
         /// gRPC endpoint URL
         #[arg(
             long,
@@ -243,9 +752,12 @@ enum GrpcCommands {
             default_value = "https://fullnode.mainnet.sui.io:443"
         )]
         url: String,
-        /// Pretty print the response
+        /// Print the transaction as pretty-printed JSON
         #[arg(short, long)]
         pretty: bool,
+        /// Print the transaction as compact JSON
+        #[arg(short = 'j', long)]
+        json: bool,
         /// Request timeout in seconds
         #[arg(long, value_name = "SECONDS", default_value = "30")]
         timeout: u64,
@@ -255,7 +767,7 @@ enum GrpcCommands {
         /// Address to query
         #[arg(value_name = "ADDRESS")]
         address: String,
-        /// Coin type (optional)
+        /// Coin type (defaults to SUI)
         #[arg(long, value_name = "COIN_TYPE")]
         coin_type: Option<String>,
         /// gRPC endpoint URL
@@ -265,9 +777,12 @@ enum GrpcCommands {
             default_value = "https://fullnode.mainnet.sui.io:443"
         )]
         url: String,
-        /// Pretty print the response
+        /// Pretty print the response, followed by the balance in whole units
         #[arg(short, long)]
         pretty: bool,
+        /// Output compact JSON on one line
+        #[arg(short = 'j', long)]
+        json: bool,
         /// Request timeout in seconds
         #[arg(long, value_name = "SECONDS", default_value = "30")]
         timeout: u64,
@@ -284,24 +799,21 @@ enum GrpcCommands {
             default_value = "https://fullnode.mainnet.sui.io:443"
         )]
         url: String,
-        /// Pretty print the response
+        /// Pretty print the response, followed by each balance in whole units
         #[arg(short, long)]
         pretty: bool,
+        /// Output compact JSON on one line
+        #[arg(short = 'j', long)]
+        json: bool,
         /// Request timeout in seconds
         #[arg(long, value_name = "SECONDS", default_value = "30")]
         timeout: u64,
     },
-    /// Raw gRPC call (similar to buf curl)
-    Curl {
-        /// gRPC service name
-        #[arg(value_name = "SERVICE")]
-        service: String,
-        /// gRPC method name
-        #[arg(value_name = "METHOD")]
-        method: String,
-        /// Request data as JSON string
-        #[arg(short, long, value_name = "JSON")]
-        data: Option<String>,
+    /// Show the ABI of a published Move package
+    Package {
+        /// Package ID
+        #[arg(value_name = "PACKAGE_ID")]
+        package_id: String,
         /// gRPC endpoint URL
         #[arg(
             long,
@@ -309,15 +821,21 @@ enum GrpcCommands {
             default_value = "https://fullnode.mainnet.sui.io:443"
         )]
         url: String,
-        /// Pretty print the response
-        #[arg(short, long)]
-        pretty: bool,
+        /// Output compact JSON on one line instead of pretty-printed
+        #[arg(short = 'j', long)]
+        json: bool,
         /// Request timeout in seconds
         #[arg(long, value_name = "SECONDS", default_value = "30")]
         timeout: u64,
     },
-    /// List available gRPC methods
-    ListMethods {
+    /// Show the datatypes and functions of one module of a package
+    Module {
+        /// Package ID
+        #[arg(value_name = "PACKAGE_ID")]
+        package_id: String,
+        /// Module name
+        #[arg(value_name = "MODULE")]
+        module: String,
         /// gRPC endpoint URL
         #[arg(
             long,
@@ -325,9 +843,24 @@ enum GrpcCommands {
             default_value = "https://fullnode.mainnet.sui.io:443"
         )]
         url: String,
+        /// Output compact JSON on one line instead of pretty-printed
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
     },
-    /// Subscribe to checkpoint stream (supports continuous streaming)
-    Subscribe {
+    /// Show the signature of a Move function
+    Function {
+        /// Package ID
+        #[arg(value_name = "PACKAGE_ID")]
+        package_id: String,
+        /// Module name
+        #[arg(value_name = "MODULE")]
+        module: String,
+        /// Function name
+        #[arg(value_name = "FUNCTION")]
+        function: String,
         /// gRPC endpoint URL
         #[arg(
             long,
@@ -335,27 +868,27 @@ enum GrpcCommands {
             default_value = "https://fullnode.mainnet.sui.io:443"
         )]
         url: String,
-        /// Pretty print the response
-        #[arg(short, long)]
-        pretty: bool,
-        /// Output only JSON result for pipeline processing
+        /// Output compact JSON on one line instead of pretty-printed
         #[arg(short = 'j', long)]
         json: bool,
-        /// Enable continuous streaming mode (polls for new checkpoints)
-        #[arg(short = 's', long)]
-        stream: bool,
-        /// Polling interval in seconds for streaming mode
-        #[arg(long, value_name = "SECONDS", default_value = "5")]
-        interval: u64,
         /// Request timeout in seconds
         #[arg(long, value_name = "SECONDS", default_value = "30")]
         timeout: u64,
     },
-    /// Get full checkpoint data
-    FullCheckpoint {
-        /// Checkpoint sequence number
-        #[arg(value_name = "SEQUENCE_NUMBER")]
-        sequence_number: u64,
+    /// List every object owned by an address
+    OwnedObjects {
+        /// Owner address
+        #[arg(value_name = "ADDRESS")]
+        owner: String,
+        /// Only objects of this Move type, e.g. 0x2::coin::Coin<0x2::sui::SUI>
+        #[arg(long = "type", value_name = "TYPE")]
+        object_type: Option<String>,
+        /// Stop after this many objects
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+        /// Only these fields, comma-separated (e.g. object_id,object_type,balance)
+        #[arg(long, value_name = "FIELDS")]
+        fields: Option<String>,
         /// gRPC endpoint URL
         #[arg(
             long,
@@ -363,36 +896,421 @@ enum GrpcCommands {
             default_value = "https://fullnode.mainnet.sui.io:443"
         )]
         url: String,
-        /// Pretty print the response
-        #[arg(short, long)]
-        pretty: bool,
+        /// Output one compact JSON object per line
+        #[arg(short = 'j', long)]
+        json: bool,
         /// Request timeout in seconds
         #[arg(long, value_name = "SECONDS", default_value = "30")]
         timeout: u64,
     },
-}
-
-fn main() -> Result<()> {
-    color_eyre::install()?;
-
-    let cli = Cli::parse();
-
-    match cli.command {
-        Commands::Vanity {
-            starts_with,
-            ends_with,
-            save_path,
-            threads,
-            count,
-            addresses_per_round,
-        } => {
-            // Validate arguments
-            if starts_with.is_none() && ends_with.is_none() {
-                bail!("At least one of --starts-with or --ends-with must be specified");
-            }
-
-            if count == 0 {
-                bail!("Count must be greater than 0");
+    /// Show metadata and treasury details of a coin type
+    CoinInfo {
+        /// Coin type, e.g. 0x2::sui::SUI
+        #[arg(value_name = "COIN_TYPE")]
+        coin_type: String,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Output compact JSON on one line instead of pretty-printed
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// List every dynamic field of an object, optionally descending into the tables,
+    /// bags and objects they hold
+    DynamicFields {
+        /// Parent object ID
+        #[arg(value_name = "OBJECT_ID")]
+        parent: String,
+        /// Only these fields, comma-separated (e.g. name,field_id,value_type)
+        #[arg(long, value_name = "FIELDS")]
+        fields: Option<String>,
+        /// Levels to descend below the parent's fields, resolving the objects on the way
+        #[arg(long, value_name = "N", default_value = "0")]
+        depth: usize,
+        /// Stop after this many fields
+        #[arg(long, value_name = "COUNT")]
+        limit: Option<usize>,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Output one compact JSON object per line
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// Check a signature with the node, including multisig and zkLogin signatures
+    VerifySignature {
+        /// Signed message: text, or base64 with --base64 or --transaction
+        #[arg(value_name = "MESSAGE")]
+        message: String,
+        /// Base64 serialized signature
+        #[arg(long, value_name = "BASE64")]
+        signature: String,
+        /// Address expected to have signed
+        #[arg(long, value_name = "ADDRESS")]
+        address: Option<String>,
+        /// MESSAGE is base64-encoded bytes rather than text
+        #[arg(long)]
+        base64: bool,
+        /// MESSAGE is base64 BCS TransactionData rather than a personal message
+        #[arg(long, conflicts_with = "base64")]
+        transaction: bool,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// Measure connection setup and call latency, and show the chain and server version
+    Ping {
+        /// gRPC endpoint URL (repeat to compare providers)
+        #[arg(
+            long = "url",
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        urls: Vec<String>,
+        /// Number of calls to time per endpoint
+        #[arg(short = 'c', long, value_name = "N", default_value = "5")]
+        count: usize,
+        /// Output one JSON object per endpoint
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "10")]
+        timeout: u64,
+    },
+    /// Drive load against an endpoint and report latency percentiles and error rates
+    Bench(bench::BenchArgs),
+    /// Submit a signed transaction and report its status
+    Execute {
+        /// Base64 BCS TransactionData
+        #[arg(long, value_name = "BASE64")]
+        tx_bytes: String,
+        /// Base64 signature (repeat for each signer)
+        #[arg(long = "signature", value_name = "BASE64", required = true)]
+        signatures: Vec<String>,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Pretty print the response
+        #[arg(short, long)]
+        pretty: bool,
+        /// Output only JSON for pipeline processing
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// Simulate an unsigned transaction: status, gas used and balance changes, without
+    /// broadcasting it
+    Simulate {
+        /// Base64 BCS TransactionData
+        #[arg(long, value_name = "BASE64")]
+        tx_bytes: String,
+        /// Skip the node's validation (gas, ownership, entry-only calls), like a dev-inspect
+        #[arg(long)]
+        skip_checks: bool,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Pretty print the response
+        #[arg(short, long)]
+        pretty: bool,
+        /// Output only JSON for pipeline processing
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// Raw gRPC call (similar to buf curl)
+    Curl {
+        /// gRPC service name
+        #[arg(value_name = "SERVICE")]
+        service: String,
+        /// gRPC method name
+        #[arg(value_name = "METHOD")]
+        method: String,
+        /// Request data as JSON string
+        #[arg(short, long, value_name = "JSON")]
+        data: Option<String>,
+        /// Descriptor set (.binpb) or directory of .proto files to use instead of server reflection
+        #[arg(long, value_name = "PATH")]
+        descriptor_set: Option<PathBuf>,
+        /// Print the undecoded protobuf response as base64 instead of JSON
+        #[arg(long, conflicts_with = "raw_out")]
+        raw: bool,
+        /// Write the undecoded protobuf response to FILE instead of printing JSON
+        #[arg(long, value_name = "FILE")]
+        raw_out: Option<PathBuf>,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Pretty print the response
+        #[arg(short, long)]
+        pretty: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// List available gRPC methods
+    ListMethods {
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Subscribe to checkpoint stream (supports continuous streaming)
+    Subscribe {
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Pretty print the response
+        #[arg(short, long)]
+        pretty: bool,
+        /// Output only JSON result for pipeline processing
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Enable continuous streaming mode (polls for new checkpoints)
+        #[arg(short = 's', long)]
+        stream: bool,
+        /// Stream transactions matching a filter expression instead of checkpoints
+        /// (see `suix filter explain`)
+        #[arg(long, value_name = "EXPR")]
+        filter: Option<String>,
+        /// Stream only transactions sent by this address (repeatable)
+        #[arg(long = "sender", value_name = "ADDRESS")]
+        senders: Vec<String>,
+        /// Stream only transactions calling or emitting events from this package (repeatable)
+        #[arg(long = "package", value_name = "PACKAGE_ID")]
+        packages: Vec<String>,
+        /// Stream only transactions of this kind, e.g. ProgrammableTransaction (repeatable)
+        #[arg(long = "kind", value_name = "KIND")]
+        kinds: Vec<String>,
+        /// Stream one flat NDJSON record per transaction (digest, sender, gas, status and
+        /// changed objects) instead of checkpoints
+        #[arg(long)]
+        flat: bool,
+        /// Stream full checkpoints (transactions, effects, events) instead of summaries;
+        /// implies --stream, and with --json each is one NDJSON line
+        #[arg(long, conflicts_with_all = ["flat", "filter", "senders", "packages", "kinds"])]
+        full: bool,
+        /// Initial polling interval in seconds; it shortens while checkpoints pile up
+        /// between polls and lengthens while none arrive
+        #[arg(long, value_name = "SECONDS", default_value = "5")]
+        interval: u64,
+        /// Longest wait between polls while no new checkpoints arrive
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        max_interval: u64,
+        /// Maximum requests per second while polling and backfilling (0 for no limit)
+        #[arg(long, value_name = "RPS", default_value = "20")]
+        poll_rps: f64,
+        /// Backfill from this checkpoint before following new ones (streaming and filters)
+        #[arg(long, value_name = "SEQ")]
+        from_checkpoint: Option<u64>,
+        /// Save the next checkpoint to process here and resume from it after a restart
+        /// (streaming and filters)
+        #[arg(long, value_name = "PATH")]
+        state_file: Option<PathBuf>,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// Get full checkpoint data
+    FullCheckpoint {
+        /// Checkpoint sequence number
+        #[arg(value_name = "SEQUENCE_NUMBER")]
+        sequence_number: u64,
+        /// Only these fields, comma-separated (e.g. digest,summary.epoch,summary.timestamp)
+        #[arg(long, value_name = "FIELDS")]
+        fields: Option<String>,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Pretty print the response
+        #[arg(short, long)]
+        pretty: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// Compare two checkpoints: epochs crossed, transactions, elapsed time and TPS
+    DiffCheckpoints {
+        /// First checkpoint sequence number
+        #[arg(value_name = "A")]
+        a: u64,
+        /// Second checkpoint sequence number
+        #[arg(value_name = "B")]
+        b: u64,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Output the delta as JSON
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// Verify checkpoint signatures against the epoch committee instead of trusting the node.
+    ///
+    /// Each summary's aggregate BLS signature is checked against the validators of its
+    /// epoch, and its contents against the signed digest. Fails if any checkpoint does not verify.
+    VerifyCheckpoints {
+        /// First checkpoint sequence number
+        #[arg(value_name = "FROM")]
+        from: u64,
+        /// Last checkpoint sequence number [default: FROM]
+        #[arg(value_name = "TO")]
+        to: Option<u64>,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Output one JSON line per checkpoint
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// Show epoch metadata: start time, committee, reference gas price, protocol version
+    Epoch {
+        /// Epoch number [default: current epoch]
+        #[arg(value_name = "EPOCH")]
+        epoch: Option<u64>,
+        /// Only these fields, comma-separated (e.g. epoch,start,reference_gas_price)
+        #[arg(long, value_name = "FIELDS")]
+        fields: Option<String>,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Output compact JSON on one line instead of pretty-printed
+        #[arg(short = 'j', long)]
+        json: bool,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+    /// Block until the network reaches a checkpoint, or a transaction is checkpointed.
+    ///
+    /// Exits 0 once reached, 3 if the transaction failed and 4 on timeout.
+    WaitCheckpoint {
+        /// Checkpoint sequence number to wait for
+        #[arg(
+            value_name = "SEQ",
+            required_unless_present = "digest",
+            conflicts_with = "digest"
+        )]
+        sequence_number: Option<u64>,
+        /// Wait for this transaction to be included in a checkpoint instead
+        #[arg(long, value_name = "DIGEST")]
+        digest: Option<String>,
+        /// Give up after this many seconds
+        #[arg(long, value_name = "SECONDS", default_value = "600")]
+        timeout: u64,
+        /// Polling interval in milliseconds
+        #[arg(long, value_name = "MS", default_value = "500")]
+        interval: u64,
+        /// Timeout of each request in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        request_timeout: u64,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Output the result as JSON
+        #[arg(short = 'j', long)]
+        json: bool,
+    },
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let cli = Cli::parse();
+    let ctx = Context::new(cli.cache, cli.transport, cli.keys, cli.grpc)?;
+    if ctx.verbose() {
+        // The grpc crate reports each call (method, endpoint, duration, status) as a span
+        tracing_subscriber::registry()
+            .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+            .with(Targets::new().with_target("grpc", LevelFilter::INFO))
+            .init();
+    }
+
+    let result = match cli.command {
+        Commands::Vanity {
+            starts_with,
+            ends_with,
+            save_path,
+            threads,
+            count,
+            addresses_per_round,
+        } => {
+            // Validate arguments
+            if starts_with.is_none() && ends_with.is_none() {
+                bail!("At least one of --starts-with or --ends-with must be specified");
+            }
+
+            if count == 0 {
+                bail!("Count must be greater than 0");
             }
 
             if addresses_per_round == 0 {
@@ -421,228 +1339,1036 @@ fn main() -> Result<()> {
             params,
             pretty,
         } => {
-            let rt = tokio::runtime::Runtime::new()?;
-            let config = RpcConfig { url, pretty };
-            rt.block_on(make_rpc_call(&config, &method, params.as_deref()))
+            let rt = tokio::runtime::Runtime::new()?;
+            let config = ctx.rpc_config(url, pretty);
+            rt.block_on(make_rpc_call(&config, &method, params.as_deref()))
+        }
+        Commands::Grpc {
+            url,
+            service,
+            method,
+            params,
+            pretty,
+            json,
+            timeout,
+        } => {
+            let rt = tokio::runtime::Runtime::new()?;
+            let config = ctx.grpc_config(url, pretty, json, timeout);
+            rt.block_on(handle_grpc_command(config, service, method, params))
+        }
+        Commands::JsonRpcQuick(query_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(handle_query_command(&ctx, query_cmd))
+        }
+        Commands::GrpcQuick(grpc_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(handle_grpc2_command(&ctx, grpc_cmd))
+        }
+        Commands::Portfolio(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(portfolio::run(args, &ctx))
+        }
+        Commands::Validators(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(validators::run(args, &ctx))
+        }
+        Commands::Gas(gas_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(gas::run(gas_cmd, &ctx))
+        }
+        Commands::Notify(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(notify::run(args, &ctx))
+        }
+        Commands::Backfill(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(backfill::run(args, &ctx))
+        }
+        Commands::Archive(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(archive::run(args, &ctx))
+        }
+        Commands::Replay(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(replay::run(args, &ctx))
+        }
+        Commands::Object(object_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(object::run(object_cmd, &ctx))
+        }
+        Commands::Report(report_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(report::run(report_cmd, &ctx))
+        }
+        Commands::Protocol(protocol_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(protocol::run(protocol_cmd, &ctx))
+        }
+        Commands::Df(df_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(df::run(df_cmd, &ctx))
+        }
+        Commands::Coins(coins_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(coins::run(coins_cmd, &ctx))
+        }
+        Commands::Package(package_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(package::run(package_cmd, &ctx))
+        }
+        Commands::Sponsor(sponsor_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(sponsor::run(sponsor_cmd, &ctx))
+        }
+        Commands::Stream(stream_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(stream::run(stream_cmd, &ctx))
+        }
+        Commands::Probe(probe_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(probe::run(probe_cmd, &ctx))
+        }
+        Commands::Airdrop(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(airdrop::run(args, &ctx))
+        }
+        Commands::Vault(vault_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(vault::run(vault_cmd, &ctx))
+        }
+        Commands::Inspect(inspect_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(inspect::run(inspect_cmd, &ctx))
+        }
+        Commands::Store(store_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(store::run(store_cmd, &ctx))
+        }
+        Commands::Compare(compare_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(compare::run(compare_cmd, &ctx))
+        }
+        Commands::Filter(filter_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(filter::run(filter_cmd, &ctx))
+        }
+        Commands::Alert(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(alert::run(args, &ctx))
+        }
+        Commands::AtTime(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(at_time::run(args, &ctx))
+        }
+        Commands::Wait(wait_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(wait::run(wait_cmd, &ctx))
+        }
+        Commands::Analyze(analyze_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(analyze::run(analyze_cmd, &ctx))
+        }
+        Commands::Snapshot(snapshot_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(snapshot::run(snapshot_cmd, &ctx))
+        }
+        Commands::Scan(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(scan::run(args, &ctx))
+        }
+        Commands::Msg(msg_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(msg::run(msg_cmd, &ctx))
+        }
+        Commands::Convert(convert_cmd) => convert::run(convert_cmd),
+        Commands::Keys(keys_cmd) => keys::run(keys_cmd),
+        Commands::Schedule(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(schedule::run(args, &ctx))
+        }
+        Commands::Find(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(find::run(args, &ctx))
+        }
+        Commands::Localnet(localnet_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(localnet::run(localnet_cmd, &ctx))
+        }
+        Commands::Sink(args) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(sink::run(args, &ctx))
+        }
+        Commands::Cost(cost_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(cost::run(cost_cmd, &ctx))
+        }
+        Commands::Export(export_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(export::run(export_cmd, &ctx))
+        }
+        Commands::Watch(watch_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(watch::run(watch_cmd, &ctx))
+        }
+        Commands::Stats(stats_cmd) => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(stats::run(stats_cmd, &ctx))
+        }
+    };
+
+    ctx.report_transport_stats();
+    result
+}
+
+async fn handle_query_command(ctx: &Context, cmd: QueryCommands) -> Result<()> {
+    match cmd {
+        QueryCommands::Chain { url, pretty } => {
+            let client = RpcClient::new(ctx.rpc_config(url, pretty));
+            print_rpc_result(&json!(client.chain_identifier().await?), pretty)
+        }
+        QueryCommands::Checkpoint { url, pretty } => {
+            let client = RpcClient::new(ctx.rpc_config(url, pretty));
+            let latest = client.latest_checkpoint_sequence_number().await?;
+            print_rpc_result(&json!(latest.to_string()), pretty)
+        }
+        QueryCommands::Checkpoints {
+            start,
+            limit,
+            descending,
+            url,
+            pretty,
+        } => {
+            let client = RpcClient::new(ctx.rpc_config(url, pretty));
+            let checkpoints = client.get_checkpoints(start, limit, descending).await?;
+            if pretty {
+                return print_rpc_result(&json!(checkpoints), true);
+            }
+            for checkpoint in &checkpoints {
+                println!("{checkpoint}");
+            }
+            Ok(())
+        }
+        QueryCommands::Object {
+            object_id,
+            url,
+            pretty,
+        } => {
+            let client = RpcClient::new(ctx.rpc_config(url, pretty));
+            print_rpc_result(&client.get_object(&object_id).await?, pretty)
+        }
+        QueryCommands::Tx {
+            digest,
+            url,
+            pretty,
+        } => {
+            let client = RpcClient::new(ctx.rpc_config(url, pretty));
+            print_rpc_result(&client.get_transaction_block(&digest).await?, pretty)
+        }
+        QueryCommands::Balance {
+            address,
+            coin_type,
+            url,
+            pretty,
+        } => {
+            let config = ctx.rpc_config(url, pretty);
+            let client = RpcClient::new(config.clone());
+            let balance = client.get_balance(&address, coin_type.as_deref()).await?;
+            let result = json!({
+                "coinType": balance.coin_type,
+                "coinObjectCount": balance.coin_object_count,
+                "totalBalance": balance.total_balance.to_string(),
+            });
+            print_rpc_result(&result, pretty)?;
+            if pretty {
+                let coin = ctx
+                    .coin_registry(&config)
+                    .get_or_raw(&balance.coin_type)
+                    .await;
+                println!();
+                println!("Balance: {}", coin.format(balance.total_balance));
+            }
+            Ok(())
+        }
+        QueryCommands::Coins {
+            address,
+            coin_type,
+            all,
+            limit,
+            cursor,
+            format,
+            url,
+        } => {
+            if limit == 0 {
+                bail!("--limit must be greater than 0");
+            }
+            // Cursors are object IDs; accept them with or without JSON quotes
+            let cursor = cursor.map(|cursor| {
+                serde_json::from_str(&cursor).unwrap_or(serde_json::Value::String(cursor))
+            });
+            let config = ctx.rpc_config(url, false);
+            let client = RpcClient::new(config.clone());
+            let page = if all {
+                client.get_all_coins(&address, cursor, limit).await?
+            } else {
+                client
+                    .get_coins(&address, coin_type.as_deref(), cursor, limit)
+                    .await?
+            };
+            coins::print_coins(&ctx.coin_registry(&config), &page.data, format).await?;
+            if let Some(cursor) = page.next_cursor {
+                eprintln!("More coins: continue with --cursor '{cursor}'");
+            }
+            Ok(())
+        }
+        QueryCommands::Balances {
+            address,
+            format,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let balances = RpcClient::new(config.clone())
+                .get_all_balances(&address)
+                .await?;
+            coins::print_balances(&ctx.coin_registry(&config), &balances, format).await
+        }
+        QueryCommands::Metadata {
+            coin_type,
+            format,
+            url,
+        } => {
+            let client = RpcClient::new(ctx.rpc_config(url, false));
+            let metadata = client.get_coin_metadata(&coin_type).await?;
+            coins::print_metadata(&coin_type, metadata.as_ref(), format)
+        }
+        QueryCommands::Supply {
+            coin_type,
+            format,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let supply = RpcClient::new(config.clone())
+                .get_total_supply(&coin_type)
+                .await?;
+            coins::print_supply(&ctx.coin_registry(&config), &coin_type, supply, format).await
+        }
+        QueryCommands::Stakes {
+            address,
+            format,
+            url,
+        } => {
+            let client = RpcClient::new(ctx.rpc_config(url, false));
+            validators::print_stakes(&client, &address, format).await
+        }
+        QueryCommands::SystemState { url, pretty } => {
+            let client = RpcClient::new(ctx.rpc_config(url, pretty));
+            print_rpc_result(&client.get_latest_sui_system_state().await?, pretty)
+        }
+        QueryCommands::ValidatorsApy { format, url } => {
+            let client = RpcClient::new(ctx.rpc_config(url, false));
+            validators::print_validators_apy(&client, format).await
+        }
+        QueryCommands::GasPrice {
+            epochs,
+            format,
+            url,
+        } => {
+            if epochs == 0 {
+                bail!("--epochs must be greater than 0");
+            }
+            let client = RpcClient::new(ctx.rpc_config(url, false));
+            gas::print_reference_gas_prices(&client, epochs, format).await
+        }
+        QueryCommands::Committee { epoch, format, url } => {
+            let client = RpcClient::new(ctx.rpc_config(url, false));
+            validators::print_committee(&client, epoch, format).await
+        }
+        QueryCommands::DynamicFields {
+            parent_id,
+            limit,
+            cursor,
+            content,
+            url,
+        } => {
+            if limit == 0 {
+                bail!("--limit must be greater than 0");
+            }
+            // Cursors are object IDs; accept them with or without JSON quotes
+            let cursor = cursor.map(|cursor| {
+                serde_json::from_str(&cursor).unwrap_or(serde_json::Value::String(cursor))
+            });
+            let client = RpcClient::new(ctx.rpc_config(url, false));
+            let page = client
+                .get_dynamic_fields(&parent_id, cursor, limit, content)
+                .await?;
+            for field in &page.data {
+                println!("{field}");
+            }
+            if let Some(cursor) = page.next_cursor {
+                eprintln!("More fields: continue with --cursor '{cursor}'");
+            }
+            Ok(())
+        }
+        QueryCommands::DynamicField {
+            parent_id,
+            name_type,
+            name,
+            url,
+            pretty,
+        } => {
+            let value = serde_json::from_str(&name).unwrap_or(serde_json::Value::String(name));
+            let name = json!({ "type": name_type, "value": value });
+            let client = RpcClient::new(ctx.rpc_config(url, pretty));
+            let object = client.get_dynamic_field_object(&parent_id, &name).await?;
+            print_rpc_result(&object, pretty)
+        }
+        QueryCommands::Events {
+            package,
+            module,
+            event_type,
+            sender,
+            since,
+            until,
+            limit,
+            cursor,
+            descending,
+            url,
+        } => {
+            if limit == 0 {
+                bail!("--limit must be greater than 0");
+            }
+            let query = rpc::events::EventQuery {
+                package,
+                module,
+                event_type,
+                sender,
+                start_time_ms: since
+                    .as_deref()
+                    .map(at_time::parse_timestamp_ms)
+                    .transpose()?,
+                end_time_ms: until
+                    .as_deref()
+                    .map(at_time::parse_timestamp_ms)
+                    .transpose()?,
+            };
+            let cursor = cursor
+                .map(|cursor| serde_json::from_str(&cursor))
+                .transpose()
+                .map_err(|e| eyre::eyre!("Invalid --cursor JSON: {}", e))?;
+            let client = RpcClient::new(ctx.rpc_config(url, false));
+            let page = client
+                .query_events(&query, cursor, limit, descending)
+                .await?;
+            for event in &page.data {
+                println!("{event}");
+            }
+            if let Some(cursor) = page.next_cursor {
+                eprintln!("More events: continue with --cursor '{cursor}'");
+            }
+            Ok(())
+        }
+        QueryCommands::SubscribeEvents { filter, limit, url } => {
+            let filter: serde_json::Value = serde_json::from_str(&filter)
+                .map_err(|e| eyre::eyre!("Invalid --filter JSON: {}", e))?;
+            let mut events = std::pin::pin!(rpc::ws::subscribe(
+                &url,
+                rpc::ws::Subscription::Events(filter)
+            ));
+            let mut received = 0;
+            while limit.is_none_or(|limit| received < limit) {
+                let Some(event) = events.next().await else {
+                    break;
+                };
+                match event {
+                    Ok(event) => {
+                        println!("{event}");
+                        received += 1;
+                    }
+                    Err(e) => eprintln!("Warning: {e}"),
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Print the result of a JSON-RPC call, compact on one line unless `pretty`
+fn print_rpc_result(result: &serde_json::Value, pretty: bool) -> Result<()> {
+    if pretty {
+        println!("{}", serde_json::to_string_pretty(result)?);
+    } else {
+        println!("{result}");
+    }
+    Ok(())
+}
+
+async fn handle_grpc_command(
+    config: GrpcConfig,
+    service: String,
+    method: String,
+    params: Option<String>,
+) -> Result<()> {
+    let mut client = SuiGrpcClient::new(config)
+        .await
+        .map_err(|e| eyre::eyre!("Failed to create gRPC client: {}", e))?;
+
+    // Map common service.method combinations
+    match (service.as_str(), method.as_str()) {
+        ("CheckpointService", "GetLatestCheckpoint") => {
+            client
+                .get_service_info()
+                .await
+                .map_err(|e| eyre::eyre!("Failed to get service info: {}", e))?;
+        }
+        // Anything else is resolved through server reflection
+        _ => {
+            client
+                .curl(&service, &method, params.as_deref())
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_grpc2_command(ctx: &Context, cmd: GrpcCommands) -> Result<()> {
+    match cmd {
+        GrpcCommands::Info {
+            url,
+            pretty,
+            json,
+            timeout,
+        } => {
+            let config = ctx.grpc_config(url, pretty, json, timeout);
+            let mut client = SuiGrpcClient::new(config)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            client.get_service_info().await.map_err(|e| eyre::eyre!(e))
+        }
+        GrpcCommands::Object {
+            object_id,
+            version,
+            fields,
+            concurrency,
+            bcs,
+            bcs_out,
+            url,
+            pretty,
+            json,
+            timeout,
+        } => {
+            if bcs || bcs_out.is_some() {
+                if object_id == "-" {
+                    bail!("--bcs and --bcs-out take a single object ID");
+                }
+                let client = SuiGrpcClient::new(ctx.grpc_config(url, false, true, timeout))
+                    .await
+                    .map_err(|e| eyre::eyre!(e))?;
+                let bytes = client
+                    .object_bcs(&object_id, version)
+                    .await
+                    .map_err(|e| eyre::eyre!(e))?;
+                return write_bcs(&bytes, bcs_out.as_deref());
+            }
+            if object_id == "-" {
+                let object_ids: Vec<String> = std::io::read_to_string(std::io::stdin())?
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                let total = object_ids.len();
+                let client = SuiGrpcClient::new(ctx.grpc_config(url, false, true, timeout))
+                    .await
+                    .map_err(|e| eyre::eyre!(e))?;
+                let failed = client
+                    .fetch_objects_json(
+                        object_ids,
+                        version,
+                        fields.as_deref(),
+                        concurrency,
+                        |line| {
+                            println!("{line}");
+                            Ok(())
+                        },
+                    )
+                    .await
+                    .map_err(|e| eyre::eyre!(e))?;
+                if failed > 0 {
+                    bail!("{} of {} objects could not be fetched", failed, total);
+                }
+                return Ok(());
+            }
+            let config = ctx.grpc_config(url, pretty, json, timeout);
+            let mut client = SuiGrpcClient::new(config)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            if let Some(fields) = fields {
+                let object = client
+                    .get_object_json(&object_id, version, Some(&fields))
+                    .await
+                    .map_err(|e| eyre::eyre!(e))?;
+                return print_grpc_json(&object, !pretty);
+            }
+            client
+                .get_object(&object_id, version)
+                .await
+                .map_err(|e| eyre::eyre!(e))
+        }
+        GrpcCommands::Objects {
+            object_ids,
+            concurrency,
+            url,
+            pretty,
+            json,
+            timeout,
+        } => {
+            let object_ids: Vec<String> = if object_ids.is_empty() {
+                std::io::read_to_string(std::io::stdin())?
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            } else {
+                object_ids
+            };
+            let ids = object_ids
+                .iter()
+                .map(|id| {
+                    id.parse()
+                        .map_err(|e| eyre::eyre!("Invalid object ID {}: {}", id, e))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let config = ctx.grpc_config(url, pretty, json, timeout);
+            let client = SuiGrpcClient::new(config)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let results = client.get_objects(&ids, concurrency).await;
+            let mut failed = 0;
+            for (id, result) in object_ids.iter().zip(results) {
+                match result {
+                    Ok(object) if json => println!("{}", grpc::object_json(&object)),
+                    Ok(object) if pretty => println!("Object {id}: {object:#?}"),
+                    Ok(object) => println!("Object {id}: {object:?}"),
+                    Err(e) => {
+                        eprintln!("{e}");
+                        failed += 1;
+                    }
+                }
+            }
+            if failed > 0 {
+                bail!("{} of {} objects could not be fetched", failed, ids.len());
+            }
+            Ok(())
+        }
+        GrpcCommands::Tx {
+            digest,
+            effects,
+            events,
+            object_changes,
+            bcs,
+            bcs_out,
+            url,
+            pretty,
+            json,
+            timeout,
+        } => {
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, pretty, true, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            if bcs || bcs_out.is_some() {
+                let bytes = client
+                    .transaction_bcs(&digest)
+                    .await
+                    .map_err(|e| eyre::eyre!(e))?;
+                return write_bcs(&bytes, bcs_out.as_deref());
+            }
+            let parts = grpc::TransactionParts {
+                effects,
+                events,
+                object_changes,
+            };
+            let transaction = client
+                .get_executed_transaction(&digest, parts)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            if json || pretty {
+                print_grpc_json(&transaction, json)
+            } else {
+                print_grpc_transaction(&transaction, parts);
+                Ok(())
+            }
+        }
+        GrpcCommands::Balance {
+            address,
+            coin_type,
+            url,
+            pretty,
+            json,
+            timeout,
+        } => {
+            let registry = ctx.coin_registry(&ctx.rpc_config(url.clone(), false));
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, pretty, json, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let balance = client
+                .get_balance(&address, coin_type.as_deref())
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            print_grpc_json(&balance, json)?;
+            if pretty && !json {
+                let coin_type = balance["coinType"].as_str().unwrap_or_default();
+                let coin = grpc_coin_info(&registry, &client, coin_type).await;
+                println!();
+                println!(
+                    "Balance: {}",
+                    coin.format(json_u128(&balance["balance"]).unwrap_or_default())
+                );
+            }
+            Ok(())
         }
-        Commands::Grpc {
+        GrpcCommands::Balances {
+            address,
             url,
-            service,
-            method,
-            params: _params,
             pretty,
             json,
             timeout,
         } => {
-            let rt = tokio::runtime::Runtime::new()?;
-            rt.block_on(handle_grpc_command(
-                url, service, method, pretty, json, timeout,
-            ))
-        }
-        Commands::JsonRpcQuick(query_cmd) => {
-            let rt = tokio::runtime::Runtime::new()?;
-            rt.block_on(handle_query_command(query_cmd))
-        }
-        Commands::GrpcQuick(grpc_cmd) => {
-            let rt = tokio::runtime::Runtime::new()?;
-            rt.block_on(handle_grpc2_command(grpc_cmd))
-        }
-    }
-}
-
-async fn handle_query_command(cmd: QueryCommands) -> Result<()> {
-    match cmd {
-        QueryCommands::Chain { url, pretty } => {
-            let config = RpcConfig { url, pretty };
-            methods::get_chain_identifier(&config).await
-        }
-        QueryCommands::Checkpoint { url, pretty } => {
-            let config = RpcConfig { url, pretty };
-            methods::get_latest_checkpoint_sequence_number(&config).await
+            let registry = ctx.coin_registry(&ctx.rpc_config(url.clone(), false));
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, pretty, json, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let balances = client
+                .list_balances(&address)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            print_grpc_json(&json!(balances), json)?;
+            if pretty && !json {
+                println!();
+                for balance in &balances {
+                    let coin_type = balance["coinType"].as_str().unwrap_or_default();
+                    let coin = grpc_coin_info(&registry, &client, coin_type).await;
+                    println!(
+                        "{}  {coin_type}",
+                        coin.format(json_u128(&balance["balance"]).unwrap_or_default())
+                    );
+                }
+            }
+            Ok(())
         }
-        QueryCommands::Object {
-            object_id,
+        GrpcCommands::Package {
+            package_id,
             url,
-            pretty,
+            json,
+            timeout,
         } => {
-            let config = RpcConfig { url, pretty };
-            methods::get_object(&config, &object_id).await
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, !json, json, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let package = client
+                .get_package(&package_id)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            print_grpc_json(&package, json)
         }
-        QueryCommands::Tx {
-            digest,
+        GrpcCommands::Module {
+            package_id,
+            module,
             url,
-            pretty,
+            json,
+            timeout,
         } => {
-            let config = RpcConfig { url, pretty };
-            methods::get_transaction_block(&config, &digest).await
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, !json, json, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let module = client
+                .get_module(&package_id, &module)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            print_grpc_json(&module, json)
         }
-        QueryCommands::Balance {
-            address,
-            coin_type,
+        GrpcCommands::Function {
+            package_id,
+            module,
+            function,
             url,
-            pretty,
+            json,
+            timeout,
         } => {
-            let config = RpcConfig { url, pretty };
-            methods::get_balance(&config, &address, coin_type.as_deref()).await
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, !json, json, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let function = client
+                .get_function(&package_id, &module, &function)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            print_grpc_json(&function, json)
         }
-    }
-}
-
-async fn handle_grpc_command(
-    url: String,
-    service: String,
-    method: String,
-    pretty: bool,
-    json: bool,
-    timeout: u64,
-) -> Result<()> {
-    use std::time::Duration;
-
-    use grpc::{GrpcConfig, SuiGrpcClient};
-
-    let config = GrpcConfig {
-        url,
-        pretty,
-        json,
-        timeout: Duration::from_secs(timeout),
-        headers: vec![],
-    };
-
-    let mut client = SuiGrpcClient::new(config)
-        .await
-        .map_err(|e| eyre::eyre!("Failed to create gRPC client: {}", e))?;
-
-    // Map common service.method combinations
-    match (service.as_str(), method.as_str()) {
-        ("CheckpointService", "GetLatestCheckpoint") => {
+        GrpcCommands::OwnedObjects {
+            owner,
+            object_type,
+            limit,
+            fields,
+            url,
+            json,
+            timeout,
+        } => {
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, !json, json, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            // Printed as each page arrives, so large holdings stream out right away
             client
-                .get_service_info()
+                .for_each_owned_object(
+                    &owner,
+                    object_type.as_deref(),
+                    fields.as_deref(),
+                    limit,
+                    |object| {
+                        if json {
+                            println!("{object}");
+                        } else {
+                            println!("{object:#}");
+                        }
+                        Ok(())
+                    },
+                )
                 .await
-                .map_err(|e| eyre::eyre!("Failed to get service info: {}", e))?;
-        }
-        ("ObjectService", "GetObject") => {
-            println!("GetObject requires an object ID parameter");
-            return Ok(());
-        }
-        ("CheckpointService", "GetCheckpoint") => {
-            println!("GetCheckpoint requires a checkpoint ID parameter");
-            return Ok(());
-        }
-        _ => {
-            println!("Service: {service}, Method: {method}");
-            println!("This is a placeholder for raw gRPC call functionality");
-            println!("You can implement specific method calls here");
+                .map_err(|e| eyre::eyre!(e))
         }
-    }
-
-    Ok(())
-}
-
-async fn handle_grpc2_command(cmd: GrpcCommands) -> Result<()> {
-    match cmd {
-        GrpcCommands::Info {
+        GrpcCommands::CoinInfo {
+            coin_type,
             url,
-            pretty,
             json,
             timeout,
         } => {
-            let config = GrpcConfig {
-                url,
-                pretty,
-                json,
-                timeout: std::time::Duration::from_secs(timeout),
-                headers: vec![],
-            };
-            let mut client = SuiGrpcClient::new(config)
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, !json, json, timeout))
                 .await
                 .map_err(|e| eyre::eyre!(e))?;
-            client.get_service_info().await.map_err(|e| eyre::eyre!(e))
+            let info = client
+                .get_coin_info(&coin_type)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            print_grpc_json(&info, json)
         }
-        GrpcCommands::Object {
-            object_id,
+        GrpcCommands::DynamicFields {
+            parent,
+            fields,
+            depth,
+            limit,
             url,
-            pretty,
             json,
             timeout,
         } => {
-            let config = GrpcConfig {
-                url,
-                pretty,
-                json,
-                timeout: std::time::Duration::from_secs(timeout),
-                headers: vec![],
-            };
-            let mut client = SuiGrpcClient::new(config)
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, !json, json, timeout))
                 .await
                 .map_err(|e| eyre::eyre!(e))?;
             client
-                .get_object(&object_id)
+                .walk_dynamic_fields(
+                    &parent,
+                    fields.as_deref(),
+                    depth,
+                    limit,
+                    |level, mut field| {
+                        // Nesting only matters once the walk goes below the parent
+                        if depth > 0 {
+                            field["depth"] = level.into();
+                        }
+                        if json {
+                            println!("{field}");
+                        } else {
+                            println!("{}", serde_json::to_string_pretty(&field)?);
+                        }
+                        Ok(())
+                    },
+                )
                 .await
                 .map_err(|e| eyre::eyre!(e))
         }
-        GrpcCommands::Tx {
-            digest: _digest,
-            url: _url,
-            pretty: _pretty,
-            timeout: _timeout,
+        GrpcCommands::VerifySignature {
+            message,
+            signature,
+            address,
+            base64,
+            transaction,
+            url,
+            timeout,
         } => {
-            println!("gRPC transaction query not yet implemented");
-            Ok(())
+            let message = if base64 || transaction {
+                let bytes = BASE64
+                    .decode(message.trim())
+                    .map_err(|e| eyre::eyre!("Invalid base64 message: {}", e))?;
+                if transaction {
+                    SignedMessage::Transaction(bytes)
+                } else {
+                    SignedMessage::PersonalMessage(bytes)
+                }
+            } else {
+                SignedMessage::PersonalMessage(message.into_bytes())
+            };
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, false, true, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let verdict = client
+                .verify_signature(&message, signature.trim(), address.as_deref())
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            match verdict {
+                Ok(()) => {
+                    println!("✅ Valid signature");
+                    Ok(())
+                }
+                Err(reason) => bail!("Invalid signature: {}", reason),
+            }
         }
-        GrpcCommands::Balance {
-            address: _address,
-            coin_type: _coin_type,
-            url: _url,
-            pretty: _pretty,
-            timeout: _timeout,
+        GrpcCommands::Ping {
+            urls,
+            count,
+            json,
+            timeout,
         } => {
-            println!("gRPC balance query not yet implemented");
+            let mut failed = 0;
+            for url in urls {
+                let client = SuiGrpcClient::new(ctx.grpc_config(url.clone(), false, true, timeout))
+                    .await
+                    .map_err(|e| eyre::eyre!(e))?;
+                match client.probe(count).await {
+                    Ok(probe) => print_probe(&url, &probe, json)?,
+                    Err(e) => {
+                        eprintln!("❌ {url}: {e}");
+                        failed += 1;
+                    }
+                }
+            }
+            if failed > 0 {
+                bail!("{} endpoint(s) could not be reached", failed);
+            }
             Ok(())
         }
-        GrpcCommands::Balances {
-            address: _address,
-            url: _url,
-            pretty: _pretty,
-            timeout: _timeout,
+        GrpcCommands::Bench(args) => bench::run(args, ctx).await,
+        GrpcCommands::Execute {
+            tx_bytes,
+            signatures,
+            url,
+            pretty,
+            json,
+            timeout,
         } => {
-            println!("gRPC balances query not yet implemented");
-            Ok(())
+            let config = ctx.grpc_config(url, pretty, json, timeout);
+            let client = SuiGrpcClient::new(config)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let executed = client
+                .execute_transaction(tx_bytes.trim(), &signatures)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let digest = executed["digest"].as_str().unwrap_or("<unknown>");
+            if json || pretty {
+                print_grpc_json(&executed, json)?;
+            } else {
+                println!("Transaction {digest}");
+                let gas = &executed["effects"]["gasUsed"];
+                if !gas.is_null() {
+                    println!("Gas used: {gas}");
+                }
+            }
+            match grpc::execution_status(&executed) {
+                Ok(()) => {
+                    if !json {
+                        println!("Status: success");
+                    }
+                    Ok(())
+                }
+                Err(e) => bail!("Transaction {} failed: {}", digest, e),
+            }
+        }
+        GrpcCommands::Simulate {
+            tx_bytes,
+            skip_checks,
+            url,
+            pretty,
+            json,
+            timeout,
+        } => {
+            let registry = ctx.coin_registry(&ctx.rpc_config(url.clone(), false));
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, pretty, true, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let simulated = client
+                .simulate_transaction(tx_bytes.trim(), !skip_checks)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            if json || pretty {
+                print_grpc_json(&simulated, json)?;
+            } else {
+                println!(
+                    "Simulated transaction {}",
+                    simulated["digest"].as_str().unwrap_or("-")
+                );
+                match grpc::execution_status(&simulated) {
+                    Ok(()) => println!("  Status:   ✅ success"),
+                    Err(error) => println!("  Status:   ❌ {error}"),
+                }
+                print_gas_used(&simulated["effects"]);
+                let changes = simulated["balanceChanges"]
+                    .as_array()
+                    .cloned()
+                    .unwrap_or_default();
+                println!();
+                println!("Balance changes ({})", changes.len());
+                for change in &changes {
+                    let coin_type = change["coinType"].as_str().unwrap_or_default();
+                    let coin = grpc_coin_info(&registry, &client, coin_type).await;
+                    let amount = change["amount"]
+                        .as_str()
+                        .and_then(|amount| amount.parse().ok())
+                        .unwrap_or_default();
+                    println!(
+                        "  {} {:>24} {coin_type}",
+                        change["address"].as_str().unwrap_or("-"),
+                        coin.format_signed(amount)
+                    );
+                }
+            }
+            grpc::execution_status(&simulated)
+                .map_err(|e| eyre::eyre!("Simulated transaction failed: {}", e))
         }
         GrpcCommands::Curl {
             service,
             method,
             data,
+            descriptor_set,
+            raw,
+            raw_out,
             url,
             pretty,
             timeout,
         } => {
-            let config = GrpcConfig {
-                url,
-                pretty,
-                json: false,
-                timeout: std::time::Duration::from_secs(timeout),
-                headers: vec![],
-            };
+            let config = ctx.grpc_config(url, pretty, false, timeout);
             let mut client = SuiGrpcClient::new(config)
                 .await
                 .map_err(|e| eyre::eyre!(e))?;
+            if let Some(path) = descriptor_set {
+                let descriptors =
+                    grpc::reflection::load_descriptors(&path).map_err(|e| eyre::eyre!(e))?;
+                client = client.with_descriptors(descriptors);
+            }
+            if let Some(path) = raw_out {
+                client = client.with_raw_output(RawOutput::File(path));
+            } else if raw {
+                client = client.with_raw_output(RawOutput::Base64);
+            }
             client
                 .curl(&service, &method, data.as_deref())
                 .await
                 .map_err(|e| eyre::eyre!(e))
         }
         GrpcCommands::ListMethods { url } => {
-            let config = GrpcConfig {
-                url,
-                pretty: false,
-                json: false,
-                timeout: std::time::Duration::from_secs(30),
-                headers: vec![],
-            };
+            let config = ctx.grpc_config(url, false, false, 30);
             let client = SuiGrpcClient::new(config)
                 .await
                 .map_err(|e| eyre::eyre!(e))?;
-            client.show_methods();
+            client.show_methods().await;
             Ok(())
         }
         GrpcCommands::Subscribe {
@@ -650,23 +2376,80 @@ async fn handle_grpc2_command(cmd: GrpcCommands) -> Result<()> {
             pretty,
             json,
             stream,
+            filter,
+            senders,
+            packages,
+            kinds,
+            flat,
+            full,
             interval,
+            max_interval,
+            poll_rps,
+            from_checkpoint,
+            state_file,
             timeout,
         } => {
-            let config = GrpcConfig {
-                url,
-                pretty,
-                json,
-                timeout: std::time::Duration::from_secs(timeout),
-                headers: vec![],
+            // The checkpoint stream has no server-side filters; every condition is
+            // checked here as checkpoints arrive
+            let mut conditions = Vec::new();
+            if let Some(filter) = filter {
+                conditions.push(filter::Filter::parse(&filter)?);
+            }
+            for (field, values) in [
+                (filter::Field::Sender, senders),
+                (filter::Field::Package, packages),
+                (filter::Field::Kind, kinds),
+            ] {
+                if !values.is_empty() {
+                    conditions.push(filter::Filter::any_of(field, &values));
+                }
+            }
+            let filter = match conditions.len() {
+                0 => None,
+                1 => conditions.pop(),
+                _ => Some(filter::Filter::And(conditions)),
             };
+            let stream = stream || full;
+            if !stream && !flat && filter.is_none() {
+                if from_checkpoint.is_some() {
+                    bail!("--from-checkpoint requires --stream or a filter");
+                }
+                if state_file.is_some() {
+                    bail!("--state-file requires --stream or a filter");
+                }
+            }
+            // Flat records are the whole output, without progress lines
+            let mut config = ctx.grpc_config(url, pretty, json || flat, timeout);
+            config.poll.max_interval = std::time::Duration::from_secs(max_interval);
+            config.poll.rps = poll_rps;
+            config.poll.burst = (poll_rps * 2.0).ceil().max(1.0) as u32;
             let mut client = SuiGrpcClient::new(config)
                 .await
                 .map_err(|e| eyre::eyre!(e))?;
 
-            if stream {
+            if flat || filter.is_some() {
+                if flat {
+                    client = client.with_flat_transactions();
+                }
+                client
+                    .subscribe_transactions(
+                        interval,
+                        from_checkpoint,
+                        state_file.as_deref(),
+                        |tx| filter.as_ref().is_none_or(|f| f.matches_transaction(tx)),
+                    )
+                    .await
+                    .map_err(|e| eyre::eyre!(e))
+            } else if stream {
+                if full {
+                    client = client.with_full_checkpoints();
+                }
                 client
-                    .subscribe_checkpoints_continuous(interval)
+                    .subscribe_checkpoints_continuous(
+                        interval,
+                        from_checkpoint,
+                        state_file.as_deref(),
+                    )
                     .await
                     .map_err(|e| eyre::eyre!(e))
             } else {
@@ -678,24 +2461,385 @@ async fn handle_grpc2_command(cmd: GrpcCommands) -> Result<()> {
         }
         GrpcCommands::FullCheckpoint {
             sequence_number,
+            fields,
             url,
             pretty,
             timeout,
         } => {
-            let config = GrpcConfig {
-                url,
-                pretty,
-                json: false,
-                timeout: std::time::Duration::from_secs(timeout),
-                headers: vec![],
-            };
+            let config = ctx.grpc_config(url, pretty, false, timeout);
             let mut client = SuiGrpcClient::new(config)
                 .await
                 .map_err(|e| eyre::eyre!(e))?;
+            if let Some(fields) = fields {
+                let checkpoint = client
+                    .get_checkpoint_json(Some(sequence_number), Some(&fields))
+                    .await
+                    .map_err(|e| eyre::eyre!(e))?;
+                return print_grpc_json(&checkpoint, !pretty);
+            }
             client
                 .get_full_checkpoint(sequence_number)
                 .await
                 .map_err(|e| eyre::eyre!(e))
         }
+        GrpcCommands::DiffCheckpoints {
+            a,
+            b,
+            url,
+            json,
+            timeout,
+        } => {
+            let mut client = SuiGrpcClient::new(ctx.grpc_config(url, false, true, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let delta = client
+                .diff_checkpoints(a, b)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            if json {
+                let mut value = serde_json::to_value(&delta)?;
+                value["epochs_crossed"] = delta.epochs_crossed().into();
+                value["tps"] = delta.tps().into();
+                println!("{value}");
+            } else {
+                print_checkpoint_delta(&delta);
+            }
+            Ok(())
+        }
+        GrpcCommands::VerifyCheckpoints {
+            from,
+            to,
+            url,
+            json,
+            timeout,
+        } => {
+            let to = to.unwrap_or(from);
+            if to < from {
+                bail!("TO must not be before FROM");
+            }
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, false, true, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let mut invalid = 0;
+            client
+                .verify_checkpoints(from, to, |verification| {
+                    if json {
+                        println!("{}", serde_json::to_string(&verification)?);
+                    } else if let Some(error) = &verification.error {
+                        println!(
+                            "❌ Checkpoint {} (epoch {}, {}): {error}",
+                            verification.sequence_number, verification.epoch, verification.digest
+                        );
+                    } else {
+                        println!(
+                            "✅ Checkpoint {} (epoch {}, {})",
+                            verification.sequence_number, verification.epoch, verification.digest
+                        );
+                    }
+                    if !verification.is_valid() {
+                        invalid += 1;
+                    }
+                    Ok(())
+                })
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            if invalid > 0 {
+                bail!(
+                    "{invalid} of {} checkpoints failed verification",
+                    to - from + 1
+                );
+            }
+            Ok(())
+        }
+        GrpcCommands::Epoch {
+            epoch,
+            fields,
+            url,
+            json,
+            timeout,
+        } => {
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, !json, json, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let epoch = client
+                .get_epoch(epoch, fields.as_deref())
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            print_grpc_json(&epoch, json)
+        }
+        GrpcCommands::WaitCheckpoint {
+            sequence_number,
+            digest,
+            timeout,
+            interval,
+            request_timeout,
+            url,
+            json,
+        } => {
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, false, true, request_timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let started = std::time::Instant::now();
+            let timeout = std::time::Duration::from_secs(timeout);
+            let interval = std::time::Duration::from_millis(interval);
+            match (sequence_number, digest) {
+                (_, Some(digest)) => {
+                    let Some(transaction) = client
+                        .wait_for_transaction(&digest, timeout, interval)
+                        .await
+                        .map_err(|e| eyre::eyre!(e))?
+                    else {
+                        eprintln!("⏱️  {digest} not checkpointed within {timeout:?}");
+                        std::process::exit(wait::EXIT_TIMEOUT);
+                    };
+                    if json {
+                        println!("{transaction}");
+                    } else {
+                        println!(
+                            "✅ {digest} in checkpoint {} after {:.1}s",
+                            rpc::json_u64(&transaction["checkpoint"]).unwrap_or_default(),
+                            started.elapsed().as_secs_f64()
+                        );
+                    }
+                    if let Err(error) = grpc::execution_status(&transaction) {
+                        eprintln!("❌ Transaction failed: {error}");
+                        std::process::exit(wait::EXIT_FAILED);
+                    }
+                    Ok(())
+                }
+                (Some(sequence_number), None) => {
+                    let Some(latest) = client
+                        .wait_for_checkpoint(sequence_number, timeout, interval)
+                        .await
+                        .map_err(|e| eyre::eyre!(e))?
+                    else {
+                        eprintln!(
+                            "⏱️  Checkpoint {sequence_number} not reached within {timeout:?}"
+                        );
+                        std::process::exit(wait::EXIT_TIMEOUT);
+                    };
+                    if json {
+                        let reached = serde_json::json!({
+                            "checkpoint": sequence_number,
+                            "latest": latest,
+                            "elapsed_ms": started.elapsed().as_millis() as u64,
+                        });
+                        println!("{reached}");
+                    } else {
+                        println!(
+                            "✅ Checkpoint {sequence_number} reached (latest {latest}) after {:.1}s",
+                            started.elapsed().as_secs_f64()
+                        );
+                    }
+                    Ok(())
+                }
+                (None, None) => bail!("Give a checkpoint sequence number or --digest"),
+            }
+        }
+    }
+}
+
+/// Print a gRPC JSON response, on one line for pipelines or pretty-printed
+fn print_grpc_json(value: &serde_json::Value, json: bool) -> Result<()> {
+    if json {
+        println!("{value}");
+    } else {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    }
+    Ok(())
+}
+
+/// Registry entry for `coin_type`, with metadata missing from the registry taken
+/// from the gRPC node's `GetCoinInfo`
+async fn grpc_coin_info(
+    registry: &CoinRegistry,
+    client: &SuiGrpcClient,
+    coin_type: &str,
+) -> CoinInfo {
+    registry
+        .get_or_raw_with(coin_type, || async {
+            let info = client
+                .get_coin_info(coin_type)
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            Ok(info["metadata"].clone())
+        })
+        .await
+}
+
+/// Write BCS bytes to `path` as-is, or print them as hex
+fn write_bcs(bytes: &[u8], path: Option<&Path>) -> Result<()> {
+    match path {
+        Some(path) => {
+            std::fs::write(path, bytes)
+                .map_err(|e| eyre::eyre!("Failed to write {}: {}", path.display(), e))?;
+            eprintln!("Wrote {} bytes to {}", bytes.len(), path.display());
+        }
+        None => println!("0x{}", hex::encode(bytes)),
+    }
+    Ok(())
+}
+
+/// Print `grpc-quick tx` output: the transaction, then each requested part
+fn print_grpc_transaction(tx: &serde_json::Value, parts: grpc::TransactionParts) {
+    let text = |value: &serde_json::Value| value.as_str().unwrap_or("-").to_string();
+    println!("Transaction {}", text(&tx["digest"]));
+    println!(
+        "  Checkpoint: {}",
+        tx["checkpoint"].as_str().unwrap_or("pending")
+    );
+    println!("  Timestamp:  {}", text(&tx["timestamp"]));
+    println!("  Sender:     {}", text(&tx["transaction"]["sender"]));
+    let gas = &tx["transaction"]["gasPayment"];
+    println!(
+        "  Gas:        budget {} MIST at {} MIST/unit",
+        text(&gas["budget"]),
+        text(&gas["price"])
+    );
+
+    let effects = &tx["effects"];
+    if parts.effects {
+        println!();
+        println!("Effects");
+        match grpc::execution_status(tx) {
+            Ok(()) => println!("  Status:   ✅ success"),
+            Err(error) => println!("  Status:   ❌ {error}"),
+        }
+        print_gas_used(effects);
+        println!("  Epoch:    {}", text(&effects["epoch"]));
+        println!("  Lamport:  {}", text(&effects["lamportVersion"]));
+        let dependencies = effects["dependencies"].as_array().map_or(0, Vec::len);
+        println!("  Depends:  {dependencies} transactions");
+    }
+
+    if parts.events {
+        let events = tx["events"]["events"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        println!();
+        println!("Events ({})", events.len());
+        for event in &events {
+            println!("  {}", text(&event["eventType"]));
+            println!(
+                "    from {}::{} by {}",
+                text(&event["packageId"]),
+                text(&event["module"]),
+                text(&event["sender"])
+            );
+            if !event["json"].is_null() {
+                println!("    {}", event["json"]);
+            }
+        }
     }
+
+    if parts.object_changes {
+        let changes = effects["changedObjects"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        println!();
+        println!("Object changes ({})", changes.len());
+        for change in &changes {
+            println!(
+                "  {:<9} {} v{} → v{} {}",
+                grpc::object_change_operation(change),
+                text(&change["objectId"]),
+                text(&change["inputVersion"]),
+                text(&change["outputVersion"]),
+                change["objectType"].as_str().unwrap_or_default()
+            );
+        }
+    }
+}
+
+/// Print the net gas of gRPC transaction effects and its breakdown
+fn print_gas_used(effects: &serde_json::Value) {
+    let cost = |field: &str| rpc::json_u64(&effects["gasUsed"][field]).unwrap_or_default();
+    let net = i128::from(cost("computationCost")) + i128::from(cost("storageCost"))
+        - i128::from(cost("storageRebate"));
+    println!(
+        "  Gas used: {net} MIST (computation {}, storage {}, rebate {})",
+        cost("computationCost"),
+        cost("storageCost"),
+        cost("storageRebate")
+    );
+}
+
+/// Print the summary of `grpc-quick diff-checkpoints`
+fn print_checkpoint_delta(delta: &grpc::CheckpointDelta) {
+    println!(
+        "Checkpoints {} → {} ({} checkpoints)",
+        delta.from,
+        delta.to,
+        delta.to - delta.from
+    );
+    println!(
+        "  Epochs:       {} → {} ({} crossed)",
+        delta.from_epoch,
+        delta.to_epoch,
+        delta.epochs_crossed()
+    );
+    println!("  Transactions: {}", delta.transactions);
+    let seconds = delta.elapsed_ms / 1000;
+    println!(
+        "  Elapsed:      {}h {:02}m {:02}.{:03}s",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        delta.elapsed_ms % 1000
+    );
+    match delta.tps() {
+        Some(tps) => println!("  Average TPS:  {tps:.1}"),
+        None => println!("  Average TPS:  n/a (same timestamp)"),
+    }
+}
+
+/// Print the measurements of `grpc-quick ping` for one endpoint
+fn print_probe(url: &str, probe: &grpc::Probe, json: bool) -> Result<()> {
+    let ms = |d: &std::time::Duration| d.as_secs_f64() * 1000.0;
+    let times: Vec<f64> = probe.round_trips.iter().map(ms).collect();
+    let min = times.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = times.iter().copied().fold(0.0, f64::max);
+    let avg = times.iter().sum::<f64>() / times.len().max(1) as f64;
+    let info = probe.service_info.clone().unwrap_or_default();
+    if json {
+        let latency = (!times.is_empty()).then(|| json!({ "min": min, "avg": avg, "max": max }));
+        let output = json!({
+            "url": url,
+            "connect_ms": ms(&probe.connect),
+            "latency_ms": latency,
+            "calls": times.len(),
+            "chain_id": info["chainId"],
+            "chain": info["chain"],
+            "server": info["server"],
+            "checkpoint_height": info["checkpointHeight"],
+        });
+        println!("{output}");
+        return Ok(());
+    }
+    println!("{url}");
+    println!("  Connect:    {:.1} ms", ms(&probe.connect));
+    if times.is_empty() {
+        println!("  Latency:    not measured (server reflection not available)");
+    } else {
+        println!(
+            "  Latency:    min {min:.1} / avg {avg:.1} / max {max:.1} ms ({} calls)",
+            times.len()
+        );
+    }
+    match info["chainId"].as_str() {
+        Some(chain_id) => println!(
+            "  Chain:      {} ({chain_id})",
+            info["chain"].as_str().unwrap_or("unknown")
+        ),
+        None => println!("  Chain:      unknown (server reflection not available)"),
+    }
+    if let Some(server) = info["server"].as_str() {
+        println!("  Server:     {server}");
+    }
+    if let Some(height) = info["checkpointHeight"].as_str() {
+        println!("  Checkpoint: {height}");
+    }
+    Ok(())
 }
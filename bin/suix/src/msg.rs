@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use clap::Subcommand;
+use eyre::Result;
+use keystore::{resolve_address, signature_scheme, verify_personal_message};
+use serde_json::json;
+
+use crate::context::Context;
+
+#[derive(Subcommand)]
+pub enum MsgCommands {
+    /// Sign a message with the personal-message intent (as wallets do for dApp logins)
+    Sign {
+        /// Message to sign
+        message: String,
+        /// Address or keystore alias of the signer
+        #[arg(long, value_name = "ADDRESS|ALIAS")]
+        key: String,
+        /// MESSAGE is base64-encoded bytes rather than text
+        #[arg(long)]
+        base64: bool,
+        /// Keystore holding the key
+        #[arg(long, value_name = "PATH")]
+        keystore: Option<PathBuf>,
+        /// Print address, message and signature as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Verify a personal-message signature (Ed25519, Secp256k1, Secp256r1, MultiSig or passkey)
+    Verify {
+        /// Message that was signed
+        message: String,
+        /// Address expected to have signed (a MultiSig address for MultiSig signatures)
+        #[arg(long, value_name = "ADDRESS")]
+        address: String,
+        /// Base64 serialized signature
+        #[arg(long)]
+        signature: String,
+        /// MESSAGE is base64-encoded bytes rather than text
+        #[arg(long)]
+        base64: bool,
+    },
+}
+
+pub async fn run(cmd: MsgCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        MsgCommands::Sign {
+            message,
+            key,
+            base64,
+            keystore,
+            json,
+        } => {
+            let message = message_bytes(&message, base64)?;
+            let keystore = ctx.keystore(keystore)?;
+            let address = keystore.get(&key)?.address;
+            let signature = keystore.sign_personal_message(&key, &message)?;
+            let encoded = BASE64.encode(signature.as_ref());
+            if json {
+                let output = json!({
+                    "address": address.to_string(),
+                    "scheme": signature_scheme(signature.as_ref()),
+                    "message": BASE64.encode(&message),
+                    "signature": encoded,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("{encoded}");
+            }
+            Ok(())
+        }
+        MsgCommands::Verify {
+            message,
+            address,
+            signature,
+            base64,
+        } => {
+            let message = message_bytes(&message, base64)?;
+            let address = resolve_address(None, &address)?;
+            verify_personal_message(address, &message, &signature)?;
+            let scheme = BASE64
+                .decode(signature.trim())
+                .map(|bytes| signature_scheme(&bytes))
+                .unwrap_or("unknown");
+            println!("✅ Valid {scheme} signature by {address}");
+            Ok(())
+        }
+    }
+}
+
+fn message_bytes(message: &str, base64: bool) -> Result<Vec<u8>> {
+    if base64 {
+        return BASE64
+            .decode(message.trim())
+            .map_err(|e| eyre::eyre!("Invalid base64 message: {}", e));
+    }
+    Ok(message.as_bytes().to_vec())
+}
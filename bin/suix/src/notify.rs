@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use clap::{Args, ValueEnum};
+use eyre::{Result, bail};
+use futures::StreamExt;
+use rpc::{RpcConfig, latest_checkpoint, rpc_request, stream::follow_checkpoints};
+use serde_json::{Value, json};
+
+use crate::{context::Context, filter::Filter, hooks::HookArgs};
+
+/// Page size used when polling `suix_queryEvents`
+const EVENTS_PAGE_SIZE: u64 = 50;
+
+#[derive(Args)]
+pub struct NotifyArgs {
+    /// What to watch
+    #[arg(long, value_enum, default_value = "events")]
+    on: Source,
+
+    /// Filter expression (see `suix filter explain`), or a Sui EventFilter as JSON,
+    /// e.g. '{"MoveModule":{"package":"0x3","module":"sui_system"}}'
+    #[arg(long, value_name = "EXPR|JSON")]
+    filter: Option<String>,
+
+    #[command(flatten)]
+    hooks: HookArgs,
+
+    /// Polling interval in seconds
+    #[arg(long, value_name = "SECONDS", default_value = "5")]
+    interval: u64,
+
+    /// RPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Source {
+    /// Events matching --filter
+    Events,
+    /// Every new checkpoint
+    Checkpoints,
+}
+
+pub async fn run(args: NotifyArgs, ctx: &Context) -> Result<()> {
+    if args.hooks.is_empty() {
+        bail!("At least one of --webhook or --exec must be specified");
+    }
+    let config = ctx.rpc_config(args.url.clone(), false);
+    let interval = Duration::from_secs(args.interval);
+
+    match args.on {
+        Source::Events => {
+            let Some(ref filter) = args.filter else {
+                bail!("--filter is required when watching events");
+            };
+            if filter.trim_start().starts_with('{') {
+                let filter: Value = serde_json::from_str(filter)
+                    .map_err(|e| eyre::eyre!("Invalid --filter JSON: {}", e))?;
+                return watch_events(&config, &filter, None, &args.hooks, interval).await;
+            }
+            let expression = Filter::parse(filter)?;
+            let (server_filter, exact) = expression.event_filter();
+            let predicate = (!exact).then_some(&expression);
+            watch_events(&config, &server_filter, predicate, &args.hooks, interval).await
+        }
+        Source::Checkpoints => watch_checkpoints(&config, &args.hooks, interval).await,
+    }
+}
+
+/// Fire hooks for every event matching `filter` (and `predicate`, when set) emitted after startup
+async fn watch_events(
+    config: &RpcConfig,
+    filter: &Value,
+    predicate: Option<&Filter>,
+    hooks: &HookArgs,
+    interval: Duration,
+) -> Result<()> {
+    // Start from the newest matching event so only new ones are delivered
+    let mut cursor = newest_event_id(config, filter).await?;
+    eprintln!("Watching events matching {filter}");
+
+    loop {
+        // Nothing matched at startup: anything that shows up now is new
+        if cursor.is_null() {
+            match rpc_request(
+                config,
+                "suix_queryEvents",
+                json!([filter, null, EVENTS_PAGE_SIZE, true]),
+            )
+            .await
+            {
+                Ok(page) => {
+                    let events = page["data"].as_array().cloned().unwrap_or_default();
+                    for event in events.iter().rev().filter(|e| wanted(predicate, e)) {
+                        hooks
+                            .fire(&json!({ "type": "event", "event": event }))
+                            .await;
+                    }
+                    if let Some(newest) = events.first() {
+                        cursor = newest["id"].clone();
+                    }
+                }
+                Err(e) => eprintln!("Failed to query events: {e}"),
+            }
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        let page = match rpc_request(
+            config,
+            "suix_queryEvents",
+            json!([filter, cursor, EVENTS_PAGE_SIZE, false]),
+        )
+        .await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                eprintln!("Failed to query events: {e}");
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+        };
+
+        for event in page["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|e| wanted(predicate, e))
+        {
+            hooks
+                .fire(&json!({ "type": "event", "event": event }))
+                .await;
+        }
+        if !page["nextCursor"].is_null() {
+            cursor = page["nextCursor"].clone();
+        }
+        if !page["hasNextPage"].as_bool().unwrap_or(false) {
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+/// Fire hooks for every checkpoint produced after startup
+async fn watch_checkpoints(config: &RpcConfig, hooks: &HookArgs, interval: Duration) -> Result<()> {
+    let last_seen = latest_checkpoint(config).await?;
+    eprintln!("Watching checkpoints after {last_seen}");
+
+    let checkpoints = follow_checkpoints(config.clone(), last_seen + 1, interval);
+    let mut checkpoints = std::pin::pin!(checkpoints);
+    while let Some(checkpoint) = checkpoints.next().await {
+        match checkpoint {
+            Ok(checkpoint) => {
+                hooks
+                    .fire(&json!({ "type": "checkpoint", "checkpoint": checkpoint }))
+                    .await
+            }
+            Err(e) => eprintln!("Failed to follow checkpoints: {e}"),
+        }
+    }
+    Ok(())
+}
+
+fn wanted(predicate: Option<&Filter>, event: &Value) -> bool {
+    predicate.is_none_or(|filter| filter.matches_event(event))
+}
+
+/// ID of the newest event matching `filter`, or null if there is none
+async fn newest_event_id(config: &RpcConfig, filter: &Value) -> Result<Value> {
+    let newest = rpc_request(config, "suix_queryEvents", json!([filter, null, 1, true])).await?;
+    Ok(newest["data"]
+        .as_array()
+        .and_then(|events| events.first())
+        .map(|event| event["id"].clone())
+        .unwrap_or(Value::Null))
+}
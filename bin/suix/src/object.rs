@@ -0,0 +1,460 @@
+use clap::Subcommand;
+use eyre::{Result, bail};
+use rpc::{RpcConfig, json_u64, json_u128, rpc_request};
+use serde_json::{Value, json};
+
+use crate::{
+    context::Context,
+    diff::{Change, diff_values},
+    output::{OutputFormat, Table},
+};
+
+/// Field names whose numeric changes are shown with a delta
+const BALANCE_FIELDS: [&str; 2] = ["balance", "value"];
+
+#[derive(Subcommand)]
+pub enum ObjectCommands {
+    /// Field-level diff between two versions of an object
+    Diff {
+        /// Object ID
+        id: String,
+        /// Older version to compare
+        #[arg(long, value_name = "VERSION", conflicts_with = "from_checkpoint")]
+        from_version: Option<u64>,
+        /// Newer version to compare (defaults to the latest version)
+        #[arg(long, value_name = "VERSION", conflicts_with = "to_checkpoint")]
+        to_version: Option<u64>,
+        /// Use the version the object had at the end of this checkpoint as the older side
+        #[arg(long, value_name = "SEQ")]
+        from_checkpoint: Option<u64>,
+        /// Use the version the object had at the end of this checkpoint as the newer side
+        #[arg(long, value_name = "SEQ")]
+        to_checkpoint: Option<u64>,
+        /// Print the diff as JSON
+        #[arg(long)]
+        json: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Timeline of every version of an object and the transaction that produced it
+    History {
+        /// Object ID
+        id: String,
+        /// Start from this version instead of the latest one
+        #[arg(long, value_name = "VERSION")]
+        version: Option<u64>,
+        /// Maximum number of versions to walk back
+        #[arg(long, default_value = "50")]
+        limit: usize,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+pub async fn run(cmd: ObjectCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        ObjectCommands::Diff {
+            id,
+            from_version,
+            to_version,
+            from_checkpoint,
+            to_checkpoint,
+            json,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+
+            let from = match (from_version, from_checkpoint) {
+                (Some(version), _) => version,
+                (None, Some(seq)) => version_at_checkpoint(&config, &id, seq).await?,
+                (None, None) => bail!("One of --from-version or --from-checkpoint is required"),
+            };
+            let to = match (to_version, to_checkpoint) {
+                (Some(version), _) => version,
+                (None, Some(seq)) => version_at_checkpoint(&config, &id, seq).await?,
+                (None, None) => latest_version(&config, &id).await?,
+            };
+
+            let old = object_at_version(&config, &id, from).await?;
+            let new = object_at_version(&config, &id, to).await?;
+
+            let type_changed = old["type"] != new["type"];
+            let owner_changes = diff_values(&old["owner"], &new["owner"]);
+            let field_changes = diff_values(&old["content"]["fields"], &new["content"]["fields"]);
+
+            if json {
+                let report = json!({
+                    "object_id": id,
+                    "from_version": from,
+                    "to_version": to,
+                    "type": { "old": old["type"], "new": new["type"] },
+                    "owner_changed": !owner_changes.is_empty(),
+                    "owner": { "old": old["owner"], "new": new["owner"] },
+                    "changes": field_changes.iter().map(Change::to_json).collect::<Vec<_>>(),
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+
+            println!("Object {id}: version {from} -> {to}");
+            if type_changed {
+                println!("⚠️  Type changed: {} -> {}", old["type"], new["type"]);
+            }
+            if !owner_changes.is_empty() {
+                println!("⚠️  Owner changed: {} -> {}", old["owner"], new["owner"]);
+            }
+            if field_changes.is_empty() {
+                println!("No field changes");
+            }
+            for change in &field_changes {
+                match balance_delta(change) {
+                    Some(delta) => println!("{change}  (Δ {delta})"),
+                    None => println!("{change}"),
+                }
+            }
+            Ok(())
+        }
+        ObjectCommands::History {
+            id,
+            version,
+            limit,
+            format,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let version = match version {
+                Some(version) => version,
+                None => latest_version(&config, &id).await?,
+            };
+
+            let mut entries = object_history(&config, &id, version, limit).await?;
+            entries.reverse();
+
+            if format == OutputFormat::Json {
+                let entries: Vec<Value> = entries.iter().map(HistoryEntry::to_json).collect();
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+                return Ok(());
+            }
+
+            let mut table = Table::new(["Version", "Digest", "Sender", "Change", "Owner"]);
+            for entry in &entries {
+                let owner = match entry.owner_before {
+                    Some(ref before) if before != &entry.owner => {
+                        format!("{} -> {}", format_owner(before), format_owner(&entry.owner))
+                    }
+                    _ => format_owner(&entry.owner),
+                };
+                table.push_row([
+                    entry.version.to_string(),
+                    entry.digest.clone(),
+                    entry.sender.clone(),
+                    entry.change.to_string(),
+                    owner,
+                ]);
+            }
+            table.print(format)
+        }
+    }
+}
+
+/// One version of an object and the transaction that produced it
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    version: u64,
+    digest: String,
+    sender: String,
+    timestamp_ms: Option<u64>,
+    change: &'static str,
+    owner_before: Option<Value>,
+    owner: Value,
+}
+
+impl HistoryEntry {
+    fn to_json(&self) -> Value {
+        json!({
+            "version": self.version,
+            "digest": self.digest,
+            "sender": self.sender,
+            "timestamp_ms": self.timestamp_ms,
+            "change": self.change,
+            "owner_before": self.owner_before,
+            "owner": self.owner,
+        })
+    }
+}
+
+/// Walk an object's version chain backwards from `version`, newest first.
+///
+/// Each step follows the version's previous transaction and uses its
+/// `modifiedAtVersions` to find the version it consumed.
+async fn object_history(
+    config: &RpcConfig,
+    id: &str,
+    version: u64,
+    limit: usize,
+) -> Result<Vec<HistoryEntry>> {
+    let mut entries = Vec::new();
+    let mut current = Some((version, past_object_owner(config, id, version).await?));
+
+    while let Some((version, (owner, previous_tx))) = current.take() {
+        if entries.len() >= limit {
+            break;
+        }
+
+        let tx = rpc_request(
+            config,
+            "sui_getTransactionBlock",
+            json!([previous_tx, { "showInput": true, "showEffects": true }]),
+        )
+        .await?;
+        let prior = tx["effects"]["modifiedAtVersions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|m| m["objectId"].as_str() == Some(id))
+            .and_then(|m| json_u64(&m["sequenceNumber"]));
+
+        let owner_before = match prior {
+            Some(prior) => {
+                let (before, tx) = past_object_owner(config, id, prior).await?;
+                current = Some((prior, (before.clone(), tx)));
+                Some(before)
+            }
+            None => None,
+        };
+
+        entries.push(HistoryEntry {
+            version,
+            digest: previous_tx.as_str().unwrap_or_default().to_string(),
+            sender: tx["transaction"]["data"]["sender"]
+                .as_str()
+                .unwrap_or("-")
+                .to_string(),
+            timestamp_ms: json_u64(&tx["timestampMs"]),
+            change: change_kind(&tx["effects"], id),
+            owner_before,
+            owner,
+        });
+    }
+    Ok(entries)
+}
+
+/// Owner and previous transaction of an object at an exact version
+async fn past_object_owner(config: &RpcConfig, id: &str, version: u64) -> Result<(Value, Value)> {
+    let past = rpc_request(
+        config,
+        "sui_tryGetPastObject",
+        json!([id, version, { "showOwner": true, "showPreviousTransaction": true }]),
+    )
+    .await?;
+    if past["status"].as_str() != Some("VersionFound") {
+        bail!(
+            "Object {} version {} is unavailable: {}",
+            id,
+            version,
+            past["status"]
+        );
+    }
+    Ok((
+        past["details"]["owner"].clone(),
+        past["details"]["previousTransaction"].clone(),
+    ))
+}
+
+/// How a transaction's effects touched the object
+fn change_kind(effects: &Value, id: &str) -> &'static str {
+    let touches = |key: &str| {
+        effects[key]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .any(|o| o["reference"]["objectId"].as_str() == Some(id))
+    };
+    if touches("created") {
+        "created"
+    } else if touches("unwrapped") {
+        "unwrapped"
+    } else if touches("mutated") {
+        "mutated"
+    } else {
+        "unknown"
+    }
+}
+
+/// Short human readable form of an owner
+fn format_owner(owner: &Value) -> String {
+    if let Some(owner) = owner.as_str() {
+        return owner.to_lowercase();
+    }
+    if let Some(address) = owner["AddressOwner"].as_str() {
+        format!("address {address}")
+    } else if let Some(object) = owner["ObjectOwner"].as_str() {
+        format!("object {object}")
+    } else if let Some(version) = json_u64(&owner["Shared"]["initial_shared_version"]) {
+        format!("shared (v{version})")
+    } else if let Some(address) = owner["ConsensusAddressOwner"]["owner"].as_str() {
+        format!("consensus {address}")
+    } else {
+        owner.to_string()
+    }
+}
+
+/// Signed difference for numeric changes of balance-like fields
+fn balance_delta(change: &Change) -> Option<String> {
+    let Change::Changed { path, old, new } = change else {
+        return None;
+    };
+    let field = path.rsplit('.').next()?;
+    if !BALANCE_FIELDS.contains(&field) {
+        return None;
+    }
+    let (old, new) = (json_u128(old)?, json_u128(new)?);
+    Some(if new >= old {
+        format!("+{}", new - old)
+    } else {
+        format!("-{}", old - new)
+    })
+}
+
+/// Decoded object contents at an exact version
+async fn object_at_version(config: &RpcConfig, id: &str, version: u64) -> Result<Value> {
+    let past = rpc_request(
+        config,
+        "sui_tryGetPastObject",
+        json!([id, version, { "showType": true, "showOwner": true, "showContent": true }]),
+    )
+    .await?;
+    match past["status"].as_str() {
+        Some("VersionFound") => Ok(past["details"].clone()),
+        Some(status) => bail!("Object {} version {}: {}", id, version, status),
+        None => bail!(
+            "Unexpected response for {} version {}: {}",
+            id,
+            version,
+            past
+        ),
+    }
+}
+
+async fn latest_version(config: &RpcConfig, id: &str) -> Result<u64> {
+    let object = rpc_request(config, "sui_getObject", json!([id, {}])).await?;
+    json_u64(&object["data"]["version"])
+        .ok_or_else(|| eyre::eyre!("Object {} not found: {}", id, object["error"]))
+}
+
+/// Version of the object as of the end of `checkpoint`.
+///
+/// Walks backwards from the latest version through each version's
+/// previous transaction until one was executed at or before the checkpoint.
+async fn version_at_checkpoint(config: &RpcConfig, id: &str, checkpoint: u64) -> Result<u64> {
+    let object = rpc_request(
+        config,
+        "sui_getObject",
+        json!([id, { "showPreviousTransaction": true }]),
+    )
+    .await?;
+    let mut version = json_u64(&object["data"]["version"])
+        .ok_or_else(|| eyre::eyre!("Object {} not found: {}", id, object["error"]))?;
+    let mut previous_tx = object["data"]["previousTransaction"].clone();
+
+    loop {
+        let tx = rpc_request(
+            config,
+            "sui_getTransactionBlock",
+            json!([previous_tx, { "showEffects": true }]),
+        )
+        .await?;
+        let tx_checkpoint = json_u64(&tx["checkpoint"])
+            .ok_or_else(|| eyre::eyre!("Transaction {} is not checkpointed yet", previous_tx))?;
+        if tx_checkpoint <= checkpoint {
+            return Ok(version);
+        }
+
+        let Some(prior) = tx["effects"]["modifiedAtVersions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|m| m["objectId"].as_str() == Some(id))
+            .and_then(|m| json_u64(&m["sequenceNumber"]))
+        else {
+            bail!("Object {} did not exist at checkpoint {}", id, checkpoint);
+        };
+
+        let past = rpc_request(
+            config,
+            "sui_tryGetPastObject",
+            json!([id, prior, { "showPreviousTransaction": true }]),
+        )
+        .await?;
+        if past["status"].as_str() != Some("VersionFound") {
+            bail!(
+                "Object {} version {} is unavailable: {}",
+                id,
+                prior,
+                past["status"]
+            );
+        }
+        version = prior;
+        previous_tx = past["details"]["previousTransaction"].clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balance_delta() {
+        let change = Change::Changed {
+            path: "balance".to_string(),
+            old: json!("100"),
+            new: json!("40"),
+        };
+        assert_eq!(balance_delta(&change).as_deref(), Some("-60"));
+
+        let change = Change::Changed {
+            path: "id.id".to_string(),
+            old: json!("1"),
+            new: json!("2"),
+        };
+        assert_eq!(balance_delta(&change), None);
+    }
+
+    #[test]
+    fn test_change_kind() {
+        let effects = json!({
+            "created": [{ "reference": { "objectId": "0x1" } }],
+            "mutated": [{ "reference": { "objectId": "0x2" } }],
+        });
+        assert_eq!(change_kind(&effects, "0x1"), "created");
+        assert_eq!(change_kind(&effects, "0x2"), "mutated");
+        assert_eq!(change_kind(&effects, "0x3"), "unknown");
+    }
+
+    #[test]
+    fn test_format_owner() {
+        assert_eq!(
+            format_owner(&json!({ "AddressOwner": "0xa" })),
+            "address 0xa"
+        );
+        assert_eq!(format_owner(&json!({ "ObjectOwner": "0xb" })), "object 0xb");
+        assert_eq!(
+            format_owner(&json!({ "Shared": { "initial_shared_version": 7 } })),
+            "shared (v7)"
+        );
+        assert_eq!(format_owner(&json!("Immutable")), "immutable");
+    }
+}
@@ -0,0 +1,133 @@
+use std::io;
+
+use clap::ValueEnum;
+use eyre::Result;
+
+/// Output format shared by report-style commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable, column aligned table
+    Table,
+    /// Comma separated values with a header row
+    Csv,
+    /// A single JSON document
+    Json,
+}
+
+/// Simple in-memory table that can be rendered as aligned text or CSV
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<I, S>(headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push_row<I, S>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+    }
+
+    /// Render as a column aligned table
+    pub fn render(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                if i < widths.len() {
+                    widths[i] = widths[i].max(cell.chars().count());
+                }
+            }
+        }
+
+        let format_row = |row: &[String]| {
+            row.iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    format!(
+                        "{cell:<width$}",
+                        width = widths.get(i).copied().unwrap_or(0)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut out = format_row(&self.headers);
+        out.push('\n');
+        out.push_str(
+            &widths
+                .iter()
+                .map(|w| "-".repeat(*w))
+                .collect::<Vec<_>>()
+                .join("  "),
+        );
+        for row in &self.rows {
+            out.push('\n');
+            out.push_str(&format_row(row));
+        }
+        out
+    }
+
+    /// Write as CSV (header row first) to the given writer
+    pub fn write_csv<W: io::Write>(&self, writer: W) -> Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record(&self.headers)?;
+        for row in &self.rows {
+            csv_writer.write_record(row)?;
+        }
+        csv_writer.flush()?;
+        Ok(())
+    }
+
+    /// Print to stdout as a table or CSV
+    pub fn print(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Csv => self.write_csv(io::stdout()),
+            OutputFormat::Table | OutputFormat::Json => {
+                println!("{}", self.render());
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_render() {
+        let mut table = Table::new(["coin", "amount"]);
+        table.push_row(["SUI", "1.5"]);
+        let rendered = table.render();
+        assert!(rendered.starts_with("coin  amount"));
+        assert!(rendered.ends_with("SUI   1.5"));
+    }
+
+    #[test]
+    fn test_table_csv() {
+        let mut table = Table::new(["coin", "amount"]);
+        table.push_row(["0x2::sui::SUI", "1,5"]);
+        let mut buf = Vec::new();
+        table.write_csv(&mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "coin,amount\n0x2::sui::SUI,\"1,5\"\n"
+        );
+    }
+}
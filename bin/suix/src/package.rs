@@ -0,0 +1,395 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use clap::Subcommand;
+use eyre::{Result, bail};
+use move_binary_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
+use rpc::{RpcConfig, json_u64, rpc_request};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use tokio::process::Command;
+
+use crate::context::Context;
+
+#[derive(Subcommand)]
+pub enum PackageCommands {
+    /// Transitive dependency graph with versions and upgrade lineage
+    Deps {
+        /// Package ID
+        package_id: String,
+        /// Print the graph as JSON
+        #[arg(long)]
+        json: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Rebuild a package from source and compare its bytecode against the chain
+    Verify {
+        /// Package ID
+        package_id: String,
+        /// Path to the Move package (directory containing Move.toml)
+        #[arg(long, value_name = "PATH")]
+        source: PathBuf,
+        /// Sui CLI used to build the package
+        #[arg(long, value_name = "PATH", default_value = "sui")]
+        sui_bin: String,
+        /// Compare the existing build output instead of rebuilding
+        #[arg(long)]
+        skip_build: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+/// On-chain package contents relevant to dependency and bytecode checks
+#[derive(Debug)]
+pub(crate) struct Package {
+    pub(crate) version: u64,
+    pub(crate) modules: BTreeMap<String, Vec<u8>>,
+    /// original package ID -> (linked package ID, version)
+    linkage: BTreeMap<String, (String, u64)>,
+}
+
+impl Package {
+    fn from_bcs(bcs: &Value) -> Result<Self> {
+        if bcs["dataType"].as_str() != Some("package") {
+            bail!("Object is not a package");
+        }
+        let mut modules = BTreeMap::new();
+        for (name, bytes) in bcs["moduleMap"].as_object().into_iter().flatten() {
+            let bytes = BASE64.decode(bytes.as_str().unwrap_or_default())?;
+            modules.insert(name.clone(), bytes);
+        }
+        let linkage = bcs["linkageTable"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(original, info)| {
+                let id = info["upgraded_id"]
+                    .as_str()
+                    .or(info["upgradedId"].as_str())?;
+                let version = json_u64(&info["upgraded_version"])
+                    .or_else(|| json_u64(&info["upgradedVersion"]))?;
+                Some((original.clone(), (id.to_string(), version)))
+            })
+            .collect();
+        Ok(Self {
+            version: json_u64(&bcs["version"]).unwrap_or_default(),
+            modules,
+            linkage,
+        })
+    }
+}
+
+pub(crate) async fn fetch_package(config: &RpcConfig, id: &str) -> Result<Package> {
+    let object = rpc_request(config, "sui_getObject", json!([id, { "showBcs": true }])).await?;
+    if object["data"].is_null() {
+        bail!("Package {} not found: {}", id, object["error"]);
+    }
+    Package::from_bcs(&object["data"]["bcs"]).map_err(|e| eyre::eyre!("{}: {}", id, e))
+}
+
+pub async fn run(cmd: PackageCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        PackageCommands::Deps {
+            package_id,
+            json,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let root = fetch_package(&config, &package_id).await?;
+
+            // A linkage table already lists every transitive dependency; fetch
+            // each one to learn which dependencies it pulls in itself
+            let mut packages = BTreeMap::new();
+            for (linked_id, _) in root.linkage.values() {
+                packages.insert(linked_id.clone(), fetch_package(&config, linked_id).await?);
+            }
+            let closure: BTreeMap<&str, BTreeSet<&str>> = packages
+                .iter()
+                .map(|(id, p)| (id.as_str(), linked_ids(p)))
+                .collect();
+
+            if json {
+                let dependencies: BTreeMap<&str, Value> = root
+                    .linkage
+                    .iter()
+                    .map(|(original, (id, version))| {
+                        (
+                            id.as_str(),
+                            json!({
+                                "original_id": original,
+                                "version": version,
+                                "dependencies": direct_dependencies(&linked_ids(&packages[id]), &closure),
+                            }),
+                        )
+                    })
+                    .collect();
+                let report = json!({
+                    "package_id": package_id,
+                    "version": root.version,
+                    "dependencies": direct_dependencies(&linked_ids(&root), &closure),
+                    "packages": dependencies,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+
+            let originals: BTreeMap<&str, &str> = root
+                .linkage
+                .iter()
+                .map(|(original, (id, _))| (id.as_str(), original.as_str()))
+                .collect();
+            let label = |id: &str| {
+                let version = packages.get(id).map_or(0, |p| p.version);
+                match originals.get(id) {
+                    Some(original) if *original != id => {
+                        format!("{id} v{version} (upgrade of {original})")
+                    }
+                    _ => format!("{id} v{version}"),
+                }
+            };
+
+            println!("{package_id} v{}", root.version);
+            let mut lines = Vec::new();
+            render_tree(
+                &direct_dependencies(&linked_ids(&root), &closure),
+                &closure,
+                "",
+                &label,
+                &mut lines,
+            );
+            println!("{}", lines.join("\n"));
+            Ok(())
+        }
+        PackageCommands::Verify {
+            package_id,
+            source,
+            sui_bin,
+            skip_build,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let on_chain = fetch_package(&config, &package_id).await?;
+
+            if !skip_build {
+                let status = Command::new(&sui_bin)
+                    .args(["move", "build", "--path"])
+                    .arg(&source)
+                    .status()
+                    .await
+                    .map_err(|e| eyre::eyre!("Failed to run `{} move build`: {}", sui_bin, e))?;
+                if !status.success() {
+                    bail!("`{} move build` exited with {}", sui_bin, status);
+                }
+            }
+            // Unpublished builds use 0x0 as their own address; compare them as if
+            // built at the address the chain has, like `sui client verify-source`
+            let mut local = read_built_modules(&source)?;
+            substitute_self_address(&mut local, self_address(&on_chain.modules)?)?;
+
+            let names: BTreeSet<&String> = on_chain.modules.keys().chain(local.keys()).collect();
+            let mut mismatches = 0;
+            for name in names {
+                let status = match (on_chain.modules.get(name), local.get(name)) {
+                    (Some(chain), Some(built)) if chain == built => {
+                        format!("✅ match     {}", hex_digest(chain))
+                    }
+                    (Some(chain), Some(built)) => {
+                        mismatches += 1;
+                        format!(
+                            "❌ mismatch  chain {} local {}",
+                            hex_digest(chain),
+                            hex_digest(built)
+                        )
+                    }
+                    (Some(_), None) => {
+                        mismatches += 1;
+                        "❌ missing from local build".to_string()
+                    }
+                    (None, Some(_)) => {
+                        mismatches += 1;
+                        "❌ not published on chain".to_string()
+                    }
+                    (None, None) => continue,
+                };
+                println!("{name:<24} {status}");
+            }
+
+            if mismatches > 0 {
+                bail!(
+                    "Package {} does not match the source at {} ({} modules differ)",
+                    package_id,
+                    source.display(),
+                    mismatches
+                );
+            }
+            println!("✅ All {} modules match", on_chain.modules.len());
+            Ok(())
+        }
+    }
+}
+
+fn linked_ids(package: &Package) -> BTreeSet<&str> {
+    package
+        .linkage
+        .values()
+        .map(|(id, _)| id.as_str())
+        .collect()
+}
+
+/// Transitive reduction: dependencies not already pulled in by another dependency
+fn direct_dependencies<'a>(
+    deps: &BTreeSet<&'a str>,
+    closure: &BTreeMap<&str, BTreeSet<&'a str>>,
+) -> Vec<&'a str> {
+    deps.iter()
+        .copied()
+        .filter(|dep| {
+            !deps
+                .iter()
+                .any(|other| other != dep && closure.get(other).is_some_and(|c| c.contains(dep)))
+        })
+        .collect()
+}
+
+fn render_tree(
+    children: &[&str],
+    closure: &BTreeMap<&str, BTreeSet<&str>>,
+    prefix: &str,
+    label: &dyn Fn(&str) -> String,
+    out: &mut Vec<String>,
+) {
+    for (i, child) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        out.push(format!(
+            "{prefix}{}{}",
+            if last { "└── " } else { "├── " },
+            label(child)
+        ));
+        let grandchildren = closure
+            .get(child)
+            .map(|deps| direct_dependencies(deps, closure))
+            .unwrap_or_default();
+        let child_prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+        render_tree(&grandchildren, closure, &child_prefix, label, out);
+    }
+}
+
+/// Compiled modules of the root package under `<source>/build/*/bytecode_modules`
+fn read_built_modules(source: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    let build = source.join("build");
+    let mut modules = BTreeMap::new();
+    for entry in fs::read_dir(&build)
+        .map_err(|e| eyre::eyre!("No build output in {}: {}", build.display(), e))?
+    {
+        let dir = entry?.path().join("bytecode_modules");
+        if !dir.is_dir() {
+            continue;
+        }
+        // Dependencies are compiled into bytecode_modules/dependencies and skipped here
+        for module in fs::read_dir(&dir)? {
+            let path = module?.path();
+            if path.extension().is_some_and(|ext| ext == "mv")
+                && let Some(name) = path.file_stem().and_then(|s| s.to_str())
+            {
+                modules.insert(name.to_string(), fs::read(&path)?);
+            }
+        }
+    }
+    if modules.is_empty() {
+        bail!("No compiled modules found in {}", build.display());
+    }
+    Ok(modules)
+}
+
+/// Address the on-chain modules were published at, the package's original ID
+fn self_address(modules: &BTreeMap<String, Vec<u8>>) -> Result<AccountAddress> {
+    let Some((name, bytes)) = modules.iter().next() else {
+        bail!("Package has no modules");
+    };
+    Ok(*deserialize_module(name, bytes)?.self_id().address())
+}
+
+/// Set the self address of built modules still at `0x0` to `address`
+fn substitute_self_address(
+    modules: &mut BTreeMap<String, Vec<u8>>,
+    address: AccountAddress,
+) -> Result<()> {
+    for (name, bytes) in modules.iter_mut() {
+        let mut module = deserialize_module(name, bytes)?;
+        let index = usize::from(module.self_handle().address.0);
+        let Some(self_address) = module.address_identifiers.get_mut(index) else {
+            bail!("Module {} has no self address", name);
+        };
+        if *self_address != AccountAddress::ZERO {
+            continue;
+        }
+        *self_address = address;
+        let mut rewritten = Vec::new();
+        module
+            .serialize_with_version(module.version, &mut rewritten)
+            .map_err(|e| eyre::eyre!("Failed to serialize module {}: {}", name, e))?;
+        *bytes = rewritten;
+    }
+    Ok(())
+}
+
+fn deserialize_module(name: &str, bytes: &[u8]) -> Result<CompiledModule> {
+    CompiledModule::deserialize_with_defaults(bytes)
+        .map_err(|e| eyre::eyre!("Invalid module {}: {:?}", name, e))
+}
+
+fn hex_digest(bytes: &[u8]) -> String {
+    hex::encode(&Sha256::digest(bytes)[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direct_dependencies() {
+        // app -> {lib, 0x2, 0x1}, lib -> {0x2, 0x1}, 0x2 -> {0x1}
+        let closure: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::from([
+            ("lib", BTreeSet::from(["0x1", "0x2"])),
+            ("0x2", BTreeSet::from(["0x1"])),
+            ("0x1", BTreeSet::new()),
+        ]);
+        let deps = BTreeSet::from(["lib", "0x2", "0x1"]);
+        assert_eq!(direct_dependencies(&deps, &closure), ["lib"]);
+        assert_eq!(direct_dependencies(&closure["lib"], &closure), ["0x2"]);
+    }
+
+    #[test]
+    fn test_package_from_bcs() {
+        let bcs = json!({
+            "dataType": "package",
+            "version": 3,
+            "moduleMap": { "m": "oRzrCw==" },
+            "linkageTable": {
+                "0xa": { "upgraded_id": "0xb", "upgraded_version": 2 },
+            },
+        });
+        let package = Package::from_bcs(&bcs).unwrap();
+        assert_eq!(package.version, 3);
+        assert_eq!(package.modules["m"], [0xa1, 0x1c, 0xeb, 0x0b]);
+        assert_eq!(package.linkage["0xa"], ("0xb".to_string(), 2));
+        assert!(Package::from_bcs(&json!({ "dataType": "moveObject" })).is_err());
+    }
+}
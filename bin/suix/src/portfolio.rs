@@ -0,0 +1,241 @@
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+use clap::Args;
+use eyre::{Result, bail};
+use rpc::{coins::format_amount, json_u128, rpc_request};
+use serde_json::{Value, json};
+
+use crate::{
+    context::Context,
+    filter::read_values,
+    output::{OutputFormat, Table},
+};
+
+const DEFAULT_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/token_price/sui?contract_addresses={coin_type}&vs_currencies=usd";
+const PRICE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Args)]
+pub struct PortfolioArgs {
+    /// File with one address per line (blank lines and `#` comments are ignored)
+    #[arg(long, value_name = "PATH")]
+    addresses_file: PathBuf,
+
+    /// Fetch USD prices and add value columns
+    #[arg(long)]
+    prices: bool,
+
+    /// Price source URL template, `{coin_type}` is replaced with the coin type
+    #[arg(long, value_name = "URL", default_value = DEFAULT_PRICE_URL)]
+    price_url: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "table")]
+    format: OutputFormat,
+
+    /// RPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+}
+
+/// Aggregated holdings of a single coin type
+#[derive(Debug)]
+struct CoinTotal {
+    coin_type: String,
+    symbol: String,
+    decimals: u8,
+    total: u128,
+    holders: usize,
+    usd_price: Option<f64>,
+}
+
+impl CoinTotal {
+    fn usd_value(&self) -> Option<f64> {
+        let price = self.usd_price?;
+        Some(self.total as f64 / 10f64.powi(i32::from(self.decimals)) * price)
+    }
+}
+
+pub async fn run(args: PortfolioArgs, ctx: &Context) -> Result<()> {
+    let addresses = read_values(&args.addresses_file)?;
+    if addresses.is_empty() {
+        bail!("No addresses found in {}", args.addresses_file.display());
+    }
+
+    let config = ctx.rpc_config(args.url, false);
+
+    let mut totals: BTreeMap<String, CoinTotal> = BTreeMap::new();
+    for address in &addresses {
+        let balances = rpc_request(&config, "suix_getAllBalances", json!([address])).await?;
+        for balance in balances.as_array().into_iter().flatten() {
+            let Some(coin_type) = balance["coinType"].as_str() else {
+                continue;
+            };
+            let amount = json_u128(&balance["totalBalance"]).unwrap_or_default();
+            if amount == 0 {
+                continue;
+            }
+            let entry = totals
+                .entry(coin_type.to_string())
+                .or_insert_with(|| CoinTotal {
+                    coin_type: coin_type.to_string(),
+                    symbol: String::new(),
+                    decimals: 0,
+                    total: 0,
+                    holders: 0,
+                    usd_price: None,
+                });
+            entry.total += amount;
+            entry.holders += 1;
+        }
+    }
+
+    let registry = ctx.coin_registry(&config);
+    let http = reqwest::Client::builder().timeout(PRICE_TIMEOUT).build()?;
+    for total in totals.values_mut() {
+        let coin = registry.get_or_raw(&total.coin_type).await;
+        total.symbol = coin.symbol;
+        total.decimals = coin.decimals;
+
+        if args.prices {
+            match fetch_usd_price(&http, &args.price_url, &total.coin_type).await {
+                Ok(price) => total.usd_price = price,
+                Err(e) => eprintln!("Failed to fetch price for {}: {e}", total.coin_type),
+            }
+        }
+    }
+
+    let total_usd: f64 = totals.values().filter_map(CoinTotal::usd_value).sum();
+
+    if args.format == OutputFormat::Json {
+        let coins: Vec<Value> = totals
+            .values()
+            .map(|t| {
+                json!({
+                    "coin_type": t.coin_type,
+                    "symbol": t.symbol,
+                    "decimals": t.decimals,
+                    "total_raw": t.total.to_string(),
+                    "total": format_amount(t.total, t.decimals),
+                    "holders": t.holders,
+                    "usd_price": t.usd_price,
+                    "usd_value": t.usd_value(),
+                })
+            })
+            .collect();
+        let report = json!({
+            "addresses": addresses.len(),
+            "coins": coins,
+            "total_usd": args.prices.then_some(total_usd),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let mut headers = vec!["symbol", "coin_type", "holders", "total"];
+    if args.prices {
+        headers.extend(["usd_price", "usd_value"]);
+    }
+    let mut table = Table::new(headers);
+    for t in totals.values() {
+        let mut row = vec![
+            t.symbol.clone(),
+            t.coin_type.clone(),
+            t.holders.to_string(),
+            format_amount(t.total, t.decimals),
+        ];
+        if args.prices {
+            row.push(t.usd_price.map(|p| p.to_string()).unwrap_or_default());
+            row.push(t.usd_value().map(|v| format!("{v:.2}")).unwrap_or_default());
+        }
+        table.push_row(row);
+    }
+    if args.prices {
+        table.push_row([
+            "TOTAL".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            format!("{total_usd:.2}"),
+        ]);
+    }
+
+    table.print(args.format)
+}
+
+async fn fetch_usd_price(
+    http: &reqwest::Client,
+    url_template: &str,
+    coin_type: &str,
+) -> Result<Option<f64>> {
+    let url = url_template.replace("{coin_type}", &percent_encode(coin_type));
+    let response: Value = http
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| eyre::eyre!("Price request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| eyre::eyre!("Invalid price response: {}", e))?;
+    Ok(extract_usd_price(&response))
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Extract a USD price from a price source response.
+///
+/// Accepts a bare number, an object with a `usd` field, or an object keyed by
+/// coin type whose value has a `usd` field (the CoinGecko shape).
+fn extract_usd_price(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::Object(map) => {
+            if let Some(usd) = map.get("usd") {
+                return usd.as_f64();
+            }
+            map.values().find_map(extract_usd_price)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_encode() {
+        assert_eq!(percent_encode("0x2::sui::SUI"), "0x2%3A%3Asui%3A%3ASUI");
+        assert_eq!(percent_encode("a-b_c.d~e"), "a-b_c.d~e");
+        assert_eq!(
+            percent_encode("0x2::coin::Coin<0x2::sui::SUI>"),
+            "0x2%3A%3Acoin%3A%3ACoin%3C0x2%3A%3Asui%3A%3ASUI%3E"
+        );
+    }
+
+    #[test]
+    fn test_extract_usd_price() {
+        assert_eq!(extract_usd_price(&json!(1.5)), Some(1.5));
+        assert_eq!(extract_usd_price(&json!({"usd": 2.0})), Some(2.0));
+        assert_eq!(
+            extract_usd_price(&json!({"0x2::sui::sui": {"usd": 3.25}})),
+            Some(3.25)
+        );
+        assert_eq!(extract_usd_price(&json!({})), None);
+    }
+}
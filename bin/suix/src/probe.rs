@@ -0,0 +1,280 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use clap::Subcommand;
+use eyre::{Result, bail};
+use keystore::Keystore;
+use rpc::{RpcConfig, json_u64, rpc_request};
+use serde_json::{Value, json};
+use sui_types::{base_types::SuiAddress, transaction::TransactionData};
+
+use crate::{
+    context::{Context, Network},
+    gas::percentile,
+    output::{OutputFormat, Table},
+    tx::{execute, reference_gas_price, select_gas_coins},
+};
+
+/// How often a submitted transaction is polled for checkpoint inclusion
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Subcommand)]
+pub enum ProbeCommands {
+    /// Measure submission-to-execution and submission-to-checkpoint latency
+    Finality {
+        /// Address or keystore alias that sends the self-transfers
+        #[arg(long, value_name = "ADDRESS|ALIAS")]
+        key: String,
+        /// Network to probe
+        #[arg(long, value_enum, default_value = "testnet")]
+        network: Network,
+        /// RPC endpoint URL (overrides --network)
+        #[arg(long, value_name = "URL")]
+        url: Option<String>,
+        /// Number of transactions to submit
+        #[arg(long, default_value = "10")]
+        runs: usize,
+        /// Pause between runs in milliseconds
+        #[arg(long, value_name = "MS", default_value = "1000")]
+        pause: u64,
+        /// Give up waiting for checkpoint inclusion after this many seconds
+        #[arg(long, value_name = "SECONDS", default_value = "60")]
+        timeout: u64,
+        /// Gas budget per transaction in MIST
+        #[arg(long, value_name = "MIST", default_value = "5000000")]
+        gas_budget: u64,
+        /// Keystore holding the key
+        #[arg(long, value_name = "PATH")]
+        keystore: Option<PathBuf>,
+        /// Output format of the summary
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+}
+
+/// Timings of one probe transaction
+#[derive(Debug, Clone)]
+struct Sample {
+    digest: String,
+    submitted_at_ms: u64,
+    executed_ms: u64,
+    checkpointed_ms: u64,
+    checkpoint: u64,
+}
+
+pub async fn run(cmd: ProbeCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        ProbeCommands::Finality {
+            key,
+            network,
+            url,
+            runs,
+            pause,
+            timeout,
+            gas_budget,
+            keystore,
+            format,
+        } => {
+            let url = url.unwrap_or_else(|| network.rpc_url().to_string());
+            // Every probe must reach the node; a cached response would be meaningless
+            let config = RpcConfig {
+                cache: None,
+                ..ctx.rpc_config(url, false)
+            };
+            let keystore = ctx.keystore(keystore)?;
+            let sender = keystore.get(&key)?.address;
+            let gas_price = reference_gas_price(&config).await?;
+
+            let mut samples = Vec::with_capacity(runs);
+            for i in 0..runs {
+                if i > 0 {
+                    tokio::time::sleep(Duration::from_millis(pause)).await;
+                }
+                match probe_once(
+                    &config,
+                    &keystore,
+                    sender,
+                    gas_budget,
+                    gas_price,
+                    Duration::from_secs(timeout),
+                )
+                .await
+                {
+                    Ok(sample) => {
+                        eprintln!(
+                            "[{}/{}] {}  executed {} ms  checkpoint {} after {} ms",
+                            i + 1,
+                            runs,
+                            sample.digest,
+                            sample.executed_ms,
+                            sample.checkpoint,
+                            sample.checkpointed_ms
+                        );
+                        samples.push(sample);
+                    }
+                    Err(e) => eprintln!("[{}/{}] probe failed: {e}", i + 1, runs),
+                }
+            }
+            if samples.is_empty() {
+                bail!("All {} probes failed", runs);
+            }
+
+            let executed = LatencySummary::new(samples.iter().map(|s| s.executed_ms));
+            let checkpointed = LatencySummary::new(samples.iter().map(|s| s.checkpointed_ms));
+
+            if format == OutputFormat::Json {
+                let report = json!({
+                    "runs": runs,
+                    "succeeded": samples.len(),
+                    "executed_ms": executed.to_json(),
+                    "checkpointed_ms": checkpointed.to_json(),
+                    "samples": samples.iter().map(|s| json!({
+                        "digest": s.digest,
+                        "submitted_at_ms": s.submitted_at_ms,
+                        "executed_ms": s.executed_ms,
+                        "checkpointed_ms": s.checkpointed_ms,
+                        "checkpoint": s.checkpoint,
+                    })).collect::<Vec<_>>(),
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+
+            let mut table = Table::new(["Stage", "Min", "P50", "P90", "P99", "Max", "Mean"]);
+            for (stage, summary) in [("executed", &executed), ("checkpointed", &checkpointed)] {
+                table.push_row(summary.row(stage));
+            }
+            if format == OutputFormat::Table {
+                println!(
+                    "{} of {} probes succeeded (latency in ms)",
+                    samples.len(),
+                    runs
+                );
+            }
+            table.print(format)
+        }
+    }
+}
+
+/// Submit one self-transfer of 1 MIST and time it until checkpoint inclusion
+async fn probe_once(
+    config: &RpcConfig,
+    keystore: &Keystore,
+    sender: SuiAddress,
+    gas_budget: u64,
+    gas_price: u64,
+    timeout: Duration,
+) -> Result<Sample> {
+    let Some(gas) = select_gas_coins(config, sender, gas_budget)
+        .await?
+        .into_iter()
+        .next()
+    else {
+        bail!("{} has no gas coins", sender);
+    };
+    let data =
+        TransactionData::new_transfer_sui(sender, sender, Some(1), gas, gas_budget, gas_price);
+    let signature = keystore.sign_transaction(&sender.to_string(), &data)?;
+
+    let submitted_at_ms = now_ms();
+    let start = Instant::now();
+    let response = execute(config, &data, &[signature], "WaitForEffectsCert").await?;
+    let executed_ms = start.elapsed().as_millis() as u64;
+
+    if response["effects"]["status"]["status"].as_str() != Some("success") {
+        bail!("Transaction failed: {}", response["effects"]["status"]);
+    }
+    let Some(digest) = response["digest"].as_str().map(str::to_string) else {
+        bail!("Execution response has no digest: {}", response);
+    };
+
+    loop {
+        // The fullnode may not know the transaction yet right after execution
+        if let Ok(tx) = rpc_request(config, "sui_getTransactionBlock", json!([digest, {}])).await
+            && let Some(checkpoint) = json_u64(&tx["checkpoint"])
+        {
+            return Ok(Sample {
+                digest,
+                submitted_at_ms,
+                executed_ms,
+                checkpointed_ms: start.elapsed().as_millis() as u64,
+                checkpoint,
+            });
+        }
+        if start.elapsed() > timeout {
+            bail!("{} not checkpointed within {:?}", digest, timeout);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Distribution of latencies in milliseconds
+#[derive(Debug)]
+struct LatencySummary {
+    sorted: Vec<u64>,
+}
+
+impl LatencySummary {
+    fn new(values: impl Iterator<Item = u64>) -> Self {
+        let mut sorted: Vec<u64> = values.collect();
+        sorted.sort_unstable();
+        Self { sorted }
+    }
+
+    fn mean(&self) -> u64 {
+        if self.sorted.is_empty() {
+            return 0;
+        }
+        self.sorted.iter().sum::<u64>() / self.sorted.len() as u64
+    }
+
+    fn stats(&self) -> [(&'static str, u64); 6] {
+        let p = |p| percentile(&self.sorted, p).unwrap_or_default();
+        [
+            ("min", p(0.0)),
+            ("p50", p(50.0)),
+            ("p90", p(90.0)),
+            ("p99", p(99.0)),
+            ("max", p(100.0)),
+            ("mean", self.mean()),
+        ]
+    }
+
+    fn row(&self, stage: &str) -> Vec<String> {
+        std::iter::once(stage.to_string())
+            .chain(self.stats().iter().map(|(_, v)| v.to_string()))
+            .collect()
+    }
+
+    fn to_json(&self) -> Value {
+        self.stats()
+            .iter()
+            .map(|(name, v)| (name.to_string(), json!(v)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_summary() {
+        let summary = LatencySummary::new([400, 100, 300, 200].into_iter());
+        assert_eq!(
+            summary.row("executed"),
+            ["executed", "100", "200", "400", "400", "400", "250"]
+        );
+        assert_eq!(summary.to_json()["p50"], 200);
+    }
+}
@@ -0,0 +1,116 @@
+use clap::Subcommand;
+use eyre::Result;
+use rpc::{RpcConfig, rpc_request};
+use serde_json::{Map, Value, json};
+
+use crate::{
+    context::Context,
+    diff::{Change, diff_values},
+};
+
+#[derive(Subcommand)]
+pub enum ProtocolCommands {
+    /// Parameters and feature flags added, removed or changed between two protocol versions
+    Diff {
+        /// Older protocol version
+        v1: u64,
+        /// Newer protocol version
+        v2: u64,
+        /// Print the diff as JSON
+        #[arg(long)]
+        json: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+pub async fn run(cmd: ProtocolCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        ProtocolCommands::Diff { v1, v2, json, url } => {
+            let config = ctx.rpc_config(url, false);
+            let old = protocol_config(&config, v1).await?;
+            let new = protocol_config(&config, v2).await?;
+
+            let flags = diff_values(&old["featureFlags"], &new["featureFlags"]);
+            let params = diff_values(
+                &flatten_attributes(&old["attributes"]),
+                &flatten_attributes(&new["attributes"]),
+            );
+
+            if json {
+                let report = json!({
+                    "from": v1,
+                    "to": v2,
+                    "feature_flags": flags.iter().map(Change::to_json).collect::<Vec<_>>(),
+                    "parameters": params.iter().map(Change::to_json).collect::<Vec<_>>(),
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+
+            println!("Protocol version {v1} -> {v2}");
+            for (title, changes) in [("Feature flags", &flags), ("Parameters", &params)] {
+                println!();
+                println!("{title} ({} changes):", changes.len());
+                for change in changes {
+                    println!("  {change}");
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn protocol_config(config: &RpcConfig, version: u64) -> Result<Value> {
+    rpc_request(
+        config,
+        "sui_getProtocolConfig",
+        json!([version.to_string()]),
+    )
+    .await
+    .map_err(|e| eyre::eyre!("Protocol version {}: {}", version, e))
+}
+
+/// Strip the `{"u64": "..."}` type wrappers from protocol config attributes.
+///
+/// Attributes that are unset in a version are null; they are dropped so that
+/// they show up as added or removed rather than changed from null.
+fn flatten_attributes(attributes: &Value) -> Value {
+    let flattened: Map<String, Value> = attributes
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| {
+            let inner = match value {
+                Value::Object(typed) => typed.values().next()?.clone(),
+                Value::Null => return None,
+                other => other.clone(),
+            };
+            Some((name.clone(), inner))
+        })
+        .collect();
+    Value::Object(flattened)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flatten_attributes() {
+        let attributes = json!({
+            "max_tx_size_bytes": { "u64": "131072" },
+            "gas_rounding_step": null,
+            "scoring_decision_cutoff_value": { "f64": "2.5" },
+        });
+        assert_eq!(
+            flatten_attributes(&attributes),
+            json!({ "max_tx_size_bytes": "131072", "scoring_decision_cutoff_value": "2.5" })
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! Terminal QR codes for moving payloads to and from air-gapped machines.
+//!
+//! Payloads too large for one code are split into chunks framed as
+//! `suix:<id>:<index>/<count>:<data>`, where `id` is derived from the whole
+//! payload so chunks of different payloads are never mixed up.
+
+use std::collections::BTreeMap;
+
+use eyre::{Result, bail};
+use qrcode::{EcLevel, QrCode, render::unicode::Dense1x2};
+use sha2::{Digest, Sha256};
+
+/// Payload bytes per code; keeps each code small enough to scan off a terminal
+const CHUNK_SIZE: usize = 400;
+
+const PREFIX: &str = "suix:";
+
+/// Print `payload` as one or more QR codes on stdout
+pub fn print(payload: &str) -> Result<()> {
+    let chunks = encode_chunks(payload);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let code = QrCode::with_error_correction_level(chunk, EcLevel::M)
+            .map_err(|e| eyre::eyre!("Failed to encode QR code: {}", e))?;
+        // Light modules drawn as blocks so the code scans on dark terminals
+        let image = code
+            .render::<Dense1x2>()
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .quiet_zone(true)
+            .build();
+        if chunks.len() > 1 {
+            println!("Chunk {}/{}", i + 1, chunks.len());
+        }
+        println!("{image}");
+    }
+    if chunks.len() > 1 {
+        eprintln!(
+            "Scan all {} codes and reassemble them with `suix scan`",
+            chunks.len()
+        );
+    }
+    Ok(())
+}
+
+/// Split `payload` into framed chunks
+fn encode_chunks(payload: &str) -> Vec<String> {
+    let id = payload_id(payload);
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    loop {
+        // Cut on character boundaries so every chunk is valid text
+        let mut end = (start + CHUNK_SIZE).min(payload.len());
+        while !payload.is_char_boundary(end) {
+            end -= 1;
+        }
+        pieces.push(&payload[start..end]);
+        if end == payload.len() {
+            break;
+        }
+        start = end;
+    }
+    let count = pieces.len();
+    pieces
+        .into_iter()
+        .enumerate()
+        .map(|(i, piece)| format!("{PREFIX}{id}:{}/{count}:{piece}", i + 1))
+        .collect()
+}
+
+/// Reassemble the payload from scanned chunks, one per line, in any order.
+///
+/// Lines that are not chunks are ignored; repeated chunks are fine.
+pub fn reassemble(input: &str) -> Result<String> {
+    let mut payloads: BTreeMap<&str, (usize, BTreeMap<usize, &str>)> = BTreeMap::new();
+    for line in input.lines() {
+        let Some(chunk) = line.trim().strip_prefix(PREFIX) else {
+            continue;
+        };
+        let mut fields = chunk.splitn(3, ':');
+        let (Some(id), Some(position), Some(data)) = (fields.next(), fields.next(), fields.next())
+        else {
+            bail!("Malformed chunk: {}", line.trim());
+        };
+        let (index, count) = position
+            .split_once('/')
+            .and_then(|(i, n)| Some((i.parse::<usize>().ok()?, n.parse::<usize>().ok()?)))
+            .filter(|&(i, n)| i >= 1 && i <= n)
+            .ok_or_else(|| eyre::eyre!("Malformed chunk position: {}", position))?;
+        let (expected, parts) = payloads.entry(id).or_insert((count, BTreeMap::new()));
+        if *expected != count {
+            bail!("Chunks of payload {} disagree on the chunk count", id);
+        }
+        parts.insert(index, data);
+    }
+
+    let mut payloads = payloads.into_iter();
+    let Some((id, (count, parts))) = payloads.next() else {
+        bail!("No suix QR chunks found in the input");
+    };
+    if payloads.next().is_some() {
+        bail!("The input mixes chunks of several payloads; scan one payload at a time");
+    }
+    let missing: Vec<String> = (1..=count)
+        .filter(|i| !parts.contains_key(i))
+        .map(|i| i.to_string())
+        .collect();
+    if !missing.is_empty() {
+        bail!("Missing chunks {} of {}", missing.join(", "), count);
+    }
+    let payload: String = parts.into_values().collect();
+    if payload_id(&payload) != id {
+        bail!("Reassembled payload does not match its checksum; rescan the codes");
+    }
+    Ok(payload)
+}
+
+/// Short checksum identifying a payload
+fn payload_id(payload: &str) -> String {
+    hex::encode(&Sha256::digest(payload.as_bytes())[..4])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_roundtrip() {
+        let payload = format!("{{\"txBytes\":\"{}\"}}", "A".repeat(1000));
+        let mut chunks = encode_chunks(&payload);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[0].starts_with("suix:") && chunks[0].contains(":1/3:"));
+
+        // Scanned out of order, with noise and a duplicate
+        chunks.reverse();
+        chunks.push(chunks[0].clone());
+        chunks.insert(1, "scanned:".to_string());
+        assert_eq!(reassemble(&chunks.join("\n")).unwrap(), payload);
+
+        assert_eq!(reassemble(&encode_chunks("short")[0]).unwrap(), "short");
+    }
+
+    #[test]
+    fn test_reassemble_errors() {
+        let chunks = encode_chunks(&"x".repeat(900));
+        assert!(
+            reassemble(&chunks[..2].join("\n"))
+                .unwrap_err()
+                .to_string()
+                .contains("Missing chunks 3")
+        );
+        let mixed = format!("{}\n{}", chunks[0], encode_chunks("other")[0]);
+        assert!(reassemble(&mixed).is_err());
+        let tampered = chunks.join("\n").replace("xxx", "xyx");
+        assert!(reassemble(&tampered).is_err());
+        assert!(reassemble("nothing here").is_err());
+    }
+}
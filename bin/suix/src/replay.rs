@@ -0,0 +1,696 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use clap::Args;
+use eyre::{Result, bail};
+use rpc::{RpcConfig, client::RpcClient, json_u64, rpc_request};
+use serde_json::{Value, json};
+use sui_json_rpc_types::{SuiObjectData, SuiObjectResponse, SuiPastObjectResponse};
+use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    committee::EpochId,
+    effects::TransactionEffects,
+    error::SuiResult,
+    gas::SuiGasStatus,
+    is_system_package,
+    metrics::LimitsMetrics,
+    object::{Object, Owner},
+    storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, PackageObject},
+    transaction::{
+        CheckedInputObjects, InputObjectKind, InputObjects, ObjectReadResult, SenderSignedData,
+        TransactionDataAPI,
+    },
+};
+use tokio::runtime::Handle;
+
+use crate::{
+    context::Context,
+    output::{OutputFormat, Table},
+};
+
+/// Chain identifiers of the networks with their own protocol config overrides
+const MAINNET_CHAIN_ID: &str = "35834a8a";
+const TESTNET_CHAIN_ID: &str = "4c78adac";
+
+/// Versions of one object followed back before giving up on finding an older one
+const MAX_HISTORY: usize = 1000;
+
+/// What `sui_getObject` and `sui_tryGetPastObject` return, enough to rebuild the object
+fn object_options() -> Value {
+    json!({
+        "showType": true,
+        "showOwner": true,
+        "showPreviousTransaction": true,
+        "showBcs": true,
+        "showStorageRebate": true,
+    })
+}
+
+#[derive(Args)]
+pub struct ReplayArgs {
+    /// Digest of the transaction to replay
+    digest: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "table")]
+    format: OutputFormat,
+
+    /// RPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+}
+
+/// A single mismatch between on-chain and replayed effects
+#[derive(Debug, PartialEq)]
+struct Difference {
+    field: String,
+    on_chain: Value,
+    replayed: Value,
+}
+
+/// Protocol and gas settings of the epoch the transaction ran in
+struct EpochEnv {
+    protocol_version: u64,
+    protocol_config: ProtocolConfig,
+    start_timestamp_ms: u64,
+    reference_gas_price: u64,
+}
+
+pub async fn run(args: ReplayArgs, ctx: &Context) -> Result<()> {
+    let config = ctx.rpc_config(args.url, false);
+    let client = RpcClient::new(config.clone());
+
+    let tx = client
+        .request(
+            "sui_getTransactionBlock",
+            json!([args.digest, {
+                "showRawInput": true,
+                "showRawEffects": true,
+                "showEffects": true,
+            }]),
+        )
+        .await?;
+    let raw = tx["rawTransaction"]
+        .as_str()
+        .ok_or_else(|| eyre::eyre!("Node did not return the raw transaction"))?;
+    let signed: SenderSignedData = bcs::from_bytes(&BASE64.decode(raw)?)
+        .map_err(|e| eyre::eyre!("Failed to decode transaction: {}", e))?;
+    let data = signed.transaction_data().clone();
+    let on_chain: TransactionEffects = bcs::from_bytes(&raw_bytes(&tx["rawEffects"])?)
+        .map_err(|e| eyre::eyre!("Failed to decode effects: {}", e))?;
+    let effects = &tx["effects"];
+    let epoch = json_u64(&effects["executedEpoch"])
+        .ok_or_else(|| eyre::eyre!("Transaction {} has no executed epoch", args.digest))?;
+    let checkpoint = json_u64(&tx["checkpoint"])
+        .ok_or_else(|| eyre::eyre!("Transaction {} is not in a checkpoint yet", args.digest))?;
+    let env = epoch_env(&client, epoch).await?;
+
+    let store = Arc::new(HistoricalStore::new(config.clone(), checkpoint));
+    // Every object the transaction modified, at the version it found, including
+    // dynamic fields the executor will ask for as child objects
+    for modified in effects["modifiedAtVersions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        let (Some(id), Some(version)) = (
+            modified["objectId"].as_str(),
+            json_u64(&modified["sequenceNumber"]),
+        ) else {
+            bail!("Invalid modifiedAtVersions entry: {}", modified);
+        };
+        let id = parse_object_id(id)?;
+        match past_object(&config, id, version.into()).await? {
+            Some(object) => store.insert(object),
+            None => bail!("{} is no longer available at version {}", id, version),
+        }
+    }
+    let shared_versions: HashMap<&str, u64> = effects["sharedObjects"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|o| Some((o["objectId"].as_str()?, json_u64(&o["version"])?)))
+        .collect();
+
+    let mut inputs = Vec::new();
+    let input_kinds = data
+        .input_objects()
+        .map_err(|e| eyre::eyre!("Invalid transaction inputs: {}", e))?;
+    for kind in input_kinds {
+        let object = match kind {
+            InputObjectKind::MovePackage(id) => fetch_package(&config, id, checkpoint).await?,
+            InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => {
+                store.object_async(id, version).await?
+            }
+            InputObjectKind::SharedMoveObject { id, .. } => {
+                let version = shared_versions
+                    .get(id.to_string().as_str())
+                    .copied()
+                    .ok_or_else(|| eyre::eyre!("No version was assigned to shared input {}", id))?;
+                store.object_async(id, version.into()).await?
+            }
+        };
+        let object = object.ok_or_else(|| {
+            eyre::eyre!(
+                "Input {} is not available at the version the transaction used",
+                kind.object_id()
+            )
+        })?;
+        inputs.push(ObjectReadResult::new(kind, object.into()));
+    }
+    let input_report: Vec<Value> = inputs
+        .iter()
+        .filter_map(|input| input.as_object())
+        .map(|object| {
+            json!({
+                "objectId": object.id().to_string(),
+                "version": object.version().value(),
+                "type": object.type_().map(ToString::to_string),
+            })
+        })
+        .collect();
+
+    let gas_status = if data.kind().is_system_tx() {
+        SuiGasStatus::new_unmetered()
+    } else {
+        SuiGasStatus::new(
+            data.gas_budget(),
+            data.gas_price(),
+            env.reference_gas_price,
+            &env.protocol_config,
+        )
+        .map_err(|e| eyre::eyre!("Invalid gas settings: {}", e))?
+    };
+    let executor = sui_execution::executor(&env.protocol_config, true, None).map_err(|e| {
+        eyre::eyre!(
+            "No executor for protocol version {}: {}",
+            env.protocol_version,
+            e
+        )
+    })?;
+
+    // The executor reads missing objects through the store, which blocks on RPC,
+    // so it runs off the async workers
+    let execution_store = store.clone();
+    let (replayed, execution_error) = tokio::task::spawn_blocking(move || {
+        let metrics = Arc::new(LimitsMetrics::new(&prometheus::Registry::new()));
+        let (_, _, effects, _, result) = executor.execute_transaction_to_effects(
+            execution_store.as_ref(),
+            &env.protocol_config,
+            metrics,
+            false,
+            Ok(()),
+            &epoch,
+            env.start_timestamp_ms,
+            CheckedInputObjects::new_for_replay(InputObjects::new(inputs)),
+            data.gas_data().clone(),
+            gas_status,
+            data.kind().clone(),
+            data.sender(),
+            data.digest(),
+            &mut None,
+        );
+        (effects, result.err().map(|e| e.to_string()))
+    })
+    .await?;
+
+    let differences = diff_effects(
+        &serde_json::to_value(&on_chain)?,
+        &serde_json::to_value(&replayed)?,
+    );
+    let fetch_errors = store.errors();
+
+    if args.format == OutputFormat::Json {
+        let report = json!({
+            "digest": args.digest,
+            "epoch": epoch,
+            "protocol_version": env.protocol_version,
+            "inputs": input_report,
+            "execution_error": execution_error,
+            "fetch_errors": fetch_errors,
+            "matches": differences.is_empty(),
+            "differences": differences
+                .iter()
+                .map(|d| json!({
+                    "field": d.field,
+                    "on_chain": d.on_chain,
+                    "replayed": d.replayed,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if args.format == OutputFormat::Table {
+        println!(
+            "Replayed in epoch {epoch} with protocol version {} against the input objects \
+             at the versions the transaction used:",
+            env.protocol_version
+        );
+        println!();
+    }
+    let mut table = Table::new(["object_id", "version", "type"]);
+    for input in &input_report {
+        table.push_row([
+            input["objectId"].as_str().unwrap_or_default().to_string(),
+            input["version"].to_string(),
+            input["type"].as_str().unwrap_or_default().to_string(),
+        ]);
+    }
+    table.print(args.format)?;
+
+    if args.format == OutputFormat::Table {
+        println!();
+        if let Some(error) = &execution_error {
+            println!("Replayed execution failed: {error}");
+        }
+        for error in &fetch_errors {
+            println!("Warning: {error}");
+        }
+        if differences.is_empty() {
+            println!("✅ Replayed effects match the on-chain effects");
+            return Ok(());
+        }
+        println!("Replayed effects differ from the on-chain effects:");
+        println!();
+    }
+    let mut table = Table::new(["field", "on_chain", "replayed"]);
+    for d in &differences {
+        table.push_row([
+            d.field.clone(),
+            d.on_chain.to_string(),
+            d.replayed.to_string(),
+        ]);
+    }
+    table.print(args.format)
+}
+
+/// Protocol version, start time and reference gas price of `epoch`. The protocol
+/// version is the one the last checkpoint of the epoch before announced.
+async fn epoch_env(client: &RpcClient, epoch: u64) -> Result<EpochEnv> {
+    let info = client.get_epoch(epoch).await?;
+    let reference_gas_price = json_u64(&info["referenceGasPrice"])
+        .ok_or_else(|| eyre::eyre!("Epoch {} has no reference gas price", epoch))?;
+    let first = json_u64(&info["firstCheckpointId"])
+        .ok_or_else(|| eyre::eyre!("Epoch {} has no first checkpoint", epoch))?;
+    let Some(previous) = first.checked_sub(1) else {
+        bail!("Transactions of the genesis epoch cannot be replayed");
+    };
+    let checkpoint = client.get_checkpoint(previous).await?;
+    let protocol_version = json_u64(&checkpoint["endOfEpochData"]["nextEpochProtocolVersion"])
+        .ok_or_else(|| eyre::eyre!("Checkpoint {} has no end-of-epoch data", previous))?;
+    let start_timestamp_ms = json_u64(&checkpoint["timestampMs"])
+        .ok_or_else(|| eyre::eyre!("Checkpoint {} has no timestamp", previous))?;
+    let chain = match client.chain_identifier().await?.as_str() {
+        MAINNET_CHAIN_ID => Chain::Mainnet,
+        TESTNET_CHAIN_ID => Chain::Testnet,
+        _ => Chain::Unknown,
+    };
+    let protocol_config =
+        ProtocolConfig::get_for_version_if_supported(ProtocolVersion::new(protocol_version), chain)
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "Protocol version {} is newer than this build of suix supports",
+                    protocol_version
+                )
+            })?;
+    Ok(EpochEnv {
+        protocol_version,
+        protocol_config,
+        start_timestamp_ms,
+        reference_gas_price,
+    })
+}
+
+/// Object store as of the replayed transaction, backed by the node's object history.
+///
+/// The executor's storage traits are synchronous, so lookups it makes block on RPC;
+/// call them from a blocking thread only.
+struct HistoricalStore {
+    config: RpcConfig,
+    handle: Handle,
+    /// Checkpoint of the replayed transaction; system packages are read as before it
+    checkpoint: u64,
+    objects: Mutex<HashMap<(ObjectID, SequenceNumber), Object>>,
+    packages: Mutex<HashMap<ObjectID, Option<Object>>>,
+    /// Lookups that failed and were reported to the executor as missing objects
+    errors: Mutex<Vec<String>>,
+}
+
+impl HistoricalStore {
+    fn new(config: RpcConfig, checkpoint: u64) -> Self {
+        Self {
+            config,
+            handle: Handle::current(),
+            checkpoint,
+            objects: Mutex::default(),
+            packages: Mutex::default(),
+            errors: Mutex::default(),
+        }
+    }
+
+    fn insert(&self, object: Object) {
+        let key = (object.id(), object.version());
+        lock(&self.objects).insert(key, object);
+    }
+
+    fn cached(&self, id: ObjectID, version: SequenceNumber) -> Option<Object> {
+        lock(&self.objects).get(&(id, version)).cloned()
+    }
+
+    /// `id` at `version`, fetched unless already known
+    async fn object_async(&self, id: ObjectID, version: SequenceNumber) -> Result<Option<Object>> {
+        if let Some(object) = self.cached(id, version) {
+            return Ok(Some(object));
+        }
+        let object = past_object(&self.config, id, version).await?;
+        if let Some(object) = &object {
+            self.insert(object.clone());
+        }
+        Ok(object)
+    }
+
+    fn object_at(&self, id: ObjectID, version: SequenceNumber) -> Option<Object> {
+        self.block_on(
+            format!("{id} at version {version}"),
+            self.object_async(id, version),
+        )
+    }
+
+    fn package(&self, id: ObjectID) -> Option<Object> {
+        if let Some(package) = lock(&self.packages).get(&id) {
+            return package.clone();
+        }
+        let package = self.block_on(
+            format!("package {id}"),
+            fetch_package(&self.config, id, self.checkpoint),
+        );
+        lock(&self.packages).insert(id, package.clone());
+        package
+    }
+
+    /// Run `fetch`, recording its error and reporting the object as missing on failure
+    fn block_on<F: Future<Output = Result<Option<Object>>>>(
+        &self,
+        what: String,
+        fetch: F,
+    ) -> Option<Object> {
+        self.handle.block_on(fetch).unwrap_or_else(|e| {
+            lock(&self.errors).push(format!("failed to fetch {what}: {e}"));
+            None
+        })
+    }
+
+    fn errors(&self) -> Vec<String> {
+        lock(&self.errors).clone()
+    }
+}
+
+impl BackingPackageStore for HistoricalStore {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<PackageObject>> {
+        Ok(self.package(*package_id).map(PackageObject::new))
+    }
+}
+
+impl ChildObjectResolver for HistoricalStore {
+    fn read_child_object(
+        &self,
+        _parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        // Children the transaction modified were loaded up front; others are
+        // followed back from their latest version
+        let known = lock(&self.objects)
+            .iter()
+            .filter(|((id, version), _)| id == child && *version <= child_version_upper_bound)
+            .max_by_key(|((_, version), _)| *version)
+            .map(|(_, object)| object.clone());
+        if known.is_some() {
+            return Ok(known);
+        }
+        let child = *child;
+        Ok(self.block_on(
+            format!("child object {child}"),
+            version_before(&self.config, child, |object, _| {
+                object.version() <= child_version_upper_bound
+            }),
+        ))
+    }
+
+    fn get_object_received_at_version(
+        &self,
+        owner: &ObjectID,
+        receiving_object_id: &ObjectID,
+        receive_object_at_version: SequenceNumber,
+        _epoch_id: EpochId,
+        _use_object_per_epoch_marker_table_v2: bool,
+    ) -> SuiResult<Option<Object>> {
+        Ok(self
+            .object_at(*receiving_object_id, receive_object_at_version)
+            .filter(|object| object.owner == Owner::AddressOwner((*owner).into())))
+    }
+}
+
+impl ObjectStore for HistoricalStore {
+    fn get_object(&self, object_id: &ObjectID) -> Option<Object> {
+        let known = lock(&self.objects)
+            .iter()
+            .filter(|((id, _), _)| id == object_id)
+            .max_by_key(|((_, version), _)| *version)
+            .map(|(_, object)| object.clone());
+        known.or_else(|| self.package(*object_id))
+    }
+
+    fn get_object_by_key(&self, object_id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        self.object_at(*object_id, version)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Package `id` as the replayed transaction saw it. Other packages never change,
+/// but system packages are upgraded in place, so theirs is the version written
+/// before `checkpoint`.
+async fn fetch_package(
+    config: &RpcConfig,
+    id: ObjectID,
+    checkpoint: u64,
+) -> Result<Option<Object>> {
+    if is_system_package(id) {
+        version_before(config, id, |_, written_in| written_in < checkpoint).await
+    } else {
+        latest_object(config, id).await
+    }
+}
+
+/// The newest version of `id` that `accept`s, given the checkpoint of the
+/// transaction that wrote it, found by following each version back through the
+/// transaction that wrote it to the version that transaction modified
+async fn version_before<F>(config: &RpcConfig, id: ObjectID, accept: F) -> Result<Option<Object>>
+where
+    F: Fn(&Object, u64) -> bool,
+{
+    let Some(mut object) = latest_object(config, id).await? else {
+        return Ok(None);
+    };
+    for _ in 0..MAX_HISTORY {
+        let writer = rpc_request(
+            config,
+            "sui_getTransactionBlock",
+            json!([object.previous_transaction.to_string(), { "showEffects": true }]),
+        )
+        .await?;
+        let written_in = json_u64(&writer["checkpoint"]).unwrap_or(u64::MAX);
+        if accept(&object, written_in) {
+            return Ok(Some(object));
+        }
+        let Some(previous) = previous_version(&writer["effects"], id) else {
+            // Created by that transaction: no version old enough exists
+            return Ok(None);
+        };
+        object = past_object(config, id, previous.into())
+            .await?
+            .ok_or_else(|| eyre::eyre!("{} is no longer available at version {}", id, previous))?;
+    }
+    bail!(
+        "{} changed more than {} times since the transaction",
+        id,
+        MAX_HISTORY
+    )
+}
+
+/// Version of `id` that a transaction with these effects modified
+fn previous_version(effects: &Value, id: ObjectID) -> Option<u64> {
+    let id = id.to_string();
+    effects["modifiedAtVersions"]
+        .as_array()?
+        .iter()
+        .find(|o| o["objectId"].as_str() == Some(id.as_str()))
+        .and_then(|o| json_u64(&o["sequenceNumber"]))
+}
+
+async fn latest_object(config: &RpcConfig, id: ObjectID) -> Result<Option<Object>> {
+    let response = rpc_request(
+        config,
+        "sui_getObject",
+        json!([id.to_string(), object_options()]),
+    )
+    .await?;
+    let response: SuiObjectResponse = serde_json::from_value(response)?;
+    response.data.map(into_object).transpose()
+}
+
+async fn past_object(
+    config: &RpcConfig,
+    id: ObjectID,
+    version: SequenceNumber,
+) -> Result<Option<Object>> {
+    let response = rpc_request(
+        config,
+        "sui_tryGetPastObject",
+        json!([id.to_string(), version.value(), object_options()]),
+    )
+    .await?;
+    match serde_json::from_value(response)? {
+        SuiPastObjectResponse::VersionFound(data) => into_object(data).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn into_object(data: SuiObjectData) -> Result<Object> {
+    let id = data.object_id;
+    data.try_into()
+        .map_err(|e| eyre::eyre!("Failed to rebuild object {}: {}", id, e))
+}
+
+fn parse_object_id(id: &str) -> Result<ObjectID> {
+    id.parse()
+        .map_err(|e| eyre::eyre!("Invalid object ID {}: {}", id, e))
+}
+
+/// BCS bytes the node returns either as base64 or as an array of numbers
+fn raw_bytes(value: &Value) -> Result<Vec<u8>> {
+    match value {
+        Value::String(encoded) => Ok(BASE64.decode(encoded)?),
+        Value::Array(bytes) => bytes
+            .iter()
+            .map(|b| {
+                b.as_u64()
+                    .and_then(|b| u8::try_from(b).ok())
+                    .ok_or_else(|| eyre::eyre!("Invalid byte: {}", b))
+            })
+            .collect(),
+        _ => bail!("Node did not return the raw effects"),
+    }
+}
+
+/// Field-by-field comparison of the on-chain and replayed effects, as JSON
+fn diff_effects(on_chain: &Value, replayed: &Value) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    diff_values(String::new(), on_chain, replayed, &mut differences);
+    differences
+}
+
+fn diff_values(path: String, a: &Value, b: &Value, differences: &mut Vec<Difference>) {
+    let field = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match (a, b) {
+        (Value::Object(a_fields), Value::Object(b_fields)) => {
+            let keys: BTreeSet<&String> = a_fields.keys().chain(b_fields.keys()).collect();
+            for key in keys {
+                diff_values(field(key), &a[key], &b[key], differences);
+            }
+        }
+        (Value::Array(a_items), Value::Array(b_items)) if a_items.len() == b_items.len() => {
+            for (i, (a, b)) in a_items.iter().zip(b_items).enumerate() {
+                diff_values(format!("{path}[{i}]"), a, b, differences);
+            }
+        }
+        _ if a != b => differences.push(Difference {
+            field: path,
+            on_chain: a.clone(),
+            replayed: b.clone(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effects(status: &str, changed: &[&str]) -> Value {
+        json!({ "V2": {
+            "status": status,
+            "gas_used": { "computationCost": "1000", "storageCost": "0" },
+            "changed_objects": changed,
+        } })
+    }
+
+    #[test]
+    fn test_diff_effects_identical() {
+        let a = effects("Success", &["0x1", "0x2"]);
+        assert!(diff_effects(&a, &a.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_effects_reports_changes() {
+        let a = effects("Success", &["0x1", "0x2"]);
+        let mut b = effects("Failure", &["0x1", "0x3"]);
+        b["V2"]["gas_used"]["computationCost"] = json!("2000");
+        let fields: Vec<String> = diff_effects(&a, &b).into_iter().map(|d| d.field).collect();
+        assert_eq!(
+            fields,
+            [
+                "V2.changed_objects[1]",
+                "V2.gas_used.computationCost",
+                "V2.status"
+            ]
+        );
+
+        let c = effects("Success", &["0x1"]);
+        let differences = diff_effects(&a, &c);
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].field, "V2.changed_objects");
+        assert_eq!(differences[0].replayed, json!(["0x1"]));
+    }
+
+    #[test]
+    fn test_previous_version() {
+        let id = ObjectID::from_hex_literal("0x5").unwrap();
+        let effects = json!({ "modifiedAtVersions": [
+            { "objectId": id.to_string(), "sequenceNumber": "41" },
+        ] });
+        assert_eq!(previous_version(&effects, id), Some(41));
+        let other = ObjectID::from_hex_literal("0x6").unwrap();
+        assert_eq!(previous_version(&effects, other), None);
+    }
+
+    #[test]
+    fn test_raw_bytes() {
+        assert_eq!(raw_bytes(&json!([1, 2, 255])).unwrap(), [1, 2, 255]);
+        assert_eq!(raw_bytes(&json!("AQL/")).unwrap(), [1, 2, 255]);
+        assert!(raw_bytes(&json!([256])).is_err());
+        assert!(raw_bytes(&Value::Null).is_err());
+    }
+}
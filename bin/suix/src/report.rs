@@ -0,0 +1,313 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use clap::Subcommand;
+use eyre::Result;
+use rpc::{RpcConfig, json_u64, rpc_request};
+use serde_json::{Value, json};
+
+use crate::{
+    context::Context,
+    filter::normalize_address,
+    output::{OutputFormat, Table},
+};
+
+/// Page size used when paging `suix_queryTransactionBlocks`
+const TX_PAGE_SIZE: u64 = 50;
+
+#[derive(Subcommand)]
+pub enum ReportCommands {
+    /// Activity summary for an address: gas, counterparties, packages and balance changes
+    Address {
+        /// Address to report on
+        address: String,
+        /// Start of the report: a checkpoint sequence number or a date (YYYY-MM-DD or RFC 3339)
+        #[arg(long, value_name = "CHECKPOINT|DATE")]
+        since: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+/// Lower bound of a report period
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Since {
+    Checkpoint(u64),
+    TimestampMs(u64),
+}
+
+impl Since {
+    fn parse(value: &str) -> Result<Self> {
+        if let Ok(checkpoint) = value.parse() {
+            return Ok(Since::Checkpoint(checkpoint));
+        }
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+            return Ok(Since::TimestampMs(datetime.timestamp_millis() as u64));
+        }
+        let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+            eyre::eyre!("--since must be a checkpoint, YYYY-MM-DD or RFC 3339 timestamp")
+        })?;
+        let midnight = NaiveDateTime::from(date).and_utc();
+        Ok(Since::TimestampMs(midnight.timestamp_millis() as u64))
+    }
+
+    /// Whether a transaction happened before the report period
+    fn excludes(&self, tx: &Value) -> bool {
+        match *self {
+            Since::Checkpoint(seq) => json_u64(&tx["checkpoint"]).is_some_and(|c| c < seq),
+            Since::TimestampMs(ms) => json_u64(&tx["timestampMs"]).is_some_and(|t| t < ms),
+        }
+    }
+}
+
+/// Aggregated activity of one address
+#[derive(Debug, Default)]
+struct ActivityReport {
+    transactions: u64,
+    sent: u64,
+    failed: u64,
+    gas_spent: i128,
+    counterparties: BTreeMap<String, u64>,
+    packages: BTreeMap<String, u64>,
+    balance_changes: BTreeMap<String, i128>,
+    first_checkpoint: Option<u64>,
+    last_checkpoint: Option<u64>,
+}
+
+impl ActivityReport {
+    /// Count `tx` for `address`, given in normalized form
+    fn add(&mut self, address: &str, tx: &Value) {
+        self.transactions += 1;
+        if let Some(seq) = json_u64(&tx["checkpoint"]) {
+            self.first_checkpoint = Some(self.first_checkpoint.map_or(seq, |s| s.min(seq)));
+            self.last_checkpoint = Some(self.last_checkpoint.map_or(seq, |s| s.max(seq)));
+        }
+
+        let effects = &tx["effects"];
+        if effects["status"]["status"].as_str() == Some("failure") {
+            self.failed += 1;
+        }
+
+        let sender = tx["transaction"]["data"]["sender"]
+            .as_str()
+            .map(normalize_address)
+            .unwrap_or_default();
+        let is_sender = sender == address;
+        if is_sender {
+            self.sent += 1;
+            let gas = &effects["gasUsed"];
+            self.gas_spent += signed(&gas["computationCost"]) + signed(&gas["storageCost"])
+                - signed(&gas["storageRebate"]);
+        } else if !sender.is_empty() {
+            *self.counterparties.entry(sender).or_default() += 1;
+        }
+
+        let mut seen = HashSet::new();
+        for change in tx["balanceChanges"].as_array().into_iter().flatten() {
+            let owner = change["owner"]["AddressOwner"]
+                .as_str()
+                .map(normalize_address)
+                .unwrap_or_default();
+            let coin_type = change["coinType"].as_str().unwrap_or_default();
+            if owner == address {
+                *self
+                    .balance_changes
+                    .entry(coin_type.to_string())
+                    .or_default() += signed(&change["amount"]);
+            } else if is_sender && !owner.is_empty() && seen.insert(owner.clone()) {
+                *self.counterparties.entry(owner).or_default() += 1;
+            }
+        }
+
+        let calls = tx["transaction"]["data"]["transaction"]["transactions"].as_array();
+        let packages: HashSet<&str> = calls
+            .into_iter()
+            .flatten()
+            .filter_map(|command| command["MoveCall"]["package"].as_str())
+            .collect();
+        for package in packages {
+            *self.packages.entry(package.to_string()).or_default() += 1;
+        }
+    }
+
+    fn to_json(&self, address: &str) -> Value {
+        json!({
+            "address": address,
+            "transactions": self.transactions,
+            "sent": self.sent,
+            "failed": self.failed,
+            "first_checkpoint": self.first_checkpoint,
+            "last_checkpoint": self.last_checkpoint,
+            "gas_spent_mist": self.gas_spent.to_string(),
+            "counterparties": self.counterparties,
+            "packages": self.packages,
+            "balance_changes": self
+                .balance_changes
+                .iter()
+                .map(|(coin, amount)| (coin.clone(), amount.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        })
+    }
+}
+
+/// Read a signed integer encoded as a JSON string or number
+fn signed(value: &Value) -> i128 {
+    value
+        .as_str()
+        .and_then(|s| s.parse().ok())
+        .or_else(|| value.as_i64().map(i128::from))
+        .unwrap_or_default()
+}
+
+pub async fn run(cmd: ReportCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        ReportCommands::Address {
+            address,
+            since,
+            format,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let since = since.as_deref().map(Since::parse).transpose()?;
+            let address = normalize_address(&address);
+
+            let mut report = ActivityReport::default();
+            let mut seen = HashSet::new();
+            // Sent and received transactions are queried separately since not
+            // every fullnode supports the combined FromOrToAddress filter
+            for filter in [
+                json!({ "FromAddress": address }),
+                json!({ "ToAddress": address }),
+            ] {
+                for tx in query_transactions(&config, filter, since).await? {
+                    let digest = tx["digest"].as_str().unwrap_or_default().to_string();
+                    if seen.insert(digest) {
+                        report.add(&address, &tx);
+                    }
+                }
+            }
+
+            if format == OutputFormat::Json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report.to_json(&address))?
+                );
+                return Ok(());
+            }
+
+            let mut table = Table::new(["metric", "key", "value"]);
+            table.push_row(["transactions", "", &report.transactions.to_string()]);
+            table.push_row(["sent", "", &report.sent.to_string()]);
+            table.push_row(["failed", "", &report.failed.to_string()]);
+            table.push_row(["gas_spent_mist", "", &report.gas_spent.to_string()]);
+            let registry = ctx.coin_registry(&config);
+            for (coin_type, amount) in &report.balance_changes {
+                let coin = registry.get_or_raw(coin_type).await;
+                table.push_row(["balance_change", coin_type, &coin.format_signed(*amount)]);
+            }
+            for (counterparty, count) in &report.counterparties {
+                table.push_row(["counterparty", counterparty, &count.to_string()]);
+            }
+            for (package, count) in &report.packages {
+                table.push_row(["package", package, &count.to_string()]);
+            }
+            table.print(format)
+        }
+    }
+}
+
+/// All transactions matching `filter`, newest first, back to `since`
+pub(crate) async fn query_transactions(
+    config: &RpcConfig,
+    filter: Value,
+    since: Option<Since>,
+) -> Result<Vec<Value>> {
+    let query = json!({
+        "filter": filter,
+        "options": {
+            "showInput": true,
+            "showEffects": true,
+            "showBalanceChanges": true,
+        },
+    });
+
+    let mut transactions = Vec::new();
+    let mut cursor = Value::Null;
+    loop {
+        let page = rpc_request(
+            config,
+            "suix_queryTransactionBlocks",
+            json!([query, cursor, TX_PAGE_SIZE, true]),
+        )
+        .await?;
+        for tx in page["data"].as_array().into_iter().flatten() {
+            if since.is_some_and(|s| s.excludes(tx)) {
+                return Ok(transactions);
+            }
+            transactions.push(tx.clone());
+        }
+        if !page["hasNextPage"].as_bool().unwrap_or(false) {
+            return Ok(transactions);
+        }
+        cursor = page["nextCursor"].clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_since_parse() {
+        assert_eq!(Since::parse("1000").unwrap(), Since::Checkpoint(1000));
+        assert_eq!(
+            Since::parse("2024-01-01").unwrap(),
+            Since::TimestampMs(1_704_067_200_000)
+        );
+        assert_eq!(
+            Since::parse("2024-01-01T00:00:01Z").unwrap(),
+            Since::TimestampMs(1_704_067_201_000)
+        );
+        assert!(Since::parse("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_activity_report_add() {
+        let tx = json!({
+            "checkpoint": "42",
+            "transaction": { "data": {
+                "sender": "0xA",
+                "transaction": { "transactions": [
+                    { "MoveCall": { "package": "0x2", "module": "coin", "function": "split" } },
+                    { "MoveCall": { "package": "0x2", "module": "pay", "function": "join" } },
+                ] },
+            } },
+            "effects": {
+                "status": { "status": "success" },
+                "gasUsed": { "computationCost": "1000", "storageCost": "500", "storageRebate": "300" },
+            },
+            "balanceChanges": [
+                { "owner": { "AddressOwner": "0x000a" }, "coinType": "0x2::sui::SUI", "amount": "-2200" },
+                { "owner": { "AddressOwner": "0xb" }, "coinType": "0x2::sui::SUI", "amount": "1000" },
+            ],
+        });
+
+        let mut report = ActivityReport::default();
+        report.add(&normalize_address("0xa"), &tx);
+        assert_eq!(report.transactions, 1);
+        assert_eq!(report.sent, 1);
+        assert_eq!(report.gas_spent, 1200);
+        assert_eq!(report.balance_changes["0x2::sui::SUI"], -2200);
+        assert_eq!(report.counterparties[&normalize_address("0xb")], 1);
+        assert_eq!(report.packages["0x2"], 1);
+        assert_eq!(report.first_checkpoint, Some(42));
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    io::{IsTerminal, Read},
+    path::PathBuf,
+};
+
+use clap::Args;
+use eyre::{Result, bail};
+use rpc::rpc_request;
+use serde_json::{Value, json};
+
+use crate::{context::Context, qr};
+
+#[derive(Args)]
+pub struct ScanArgs {
+    /// File with the scanned chunks, one per line (defaults to stdin)
+    #[arg(value_name = "PATH")]
+    input: Option<PathBuf>,
+
+    /// Submit the reassembled `{txBytes, signatures}` envelope instead of printing it
+    #[arg(long)]
+    execute: bool,
+
+    /// RPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+}
+
+pub async fn run(args: ScanArgs, ctx: &Context) -> Result<()> {
+    let input = match args.input {
+        Some(ref path) => std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("Failed to read {}: {}", path.display(), e))?,
+        None => {
+            let mut stdin = std::io::stdin();
+            if stdin.is_terminal() {
+                eprintln!("Paste the scanned chunks, then press Ctrl-D");
+            }
+            let mut input = String::new();
+            stdin.read_to_string(&mut input)?;
+            input
+        }
+    };
+    let payload = qr::reassemble(&input)?;
+
+    if !args.execute {
+        println!("{payload}");
+        return Ok(());
+    }
+
+    let envelope: Value = serde_json::from_str(&payload)
+        .map_err(|e| eyre::eyre!("Scanned payload is not a transaction envelope: {}", e))?;
+    let (Some(tx_bytes), Some(signatures)) = (
+        envelope["txBytes"].as_str(),
+        envelope["signatures"].as_array(),
+    ) else {
+        bail!("Scanned payload has no txBytes and signatures to submit");
+    };
+    let config = ctx.rpc_config(args.url, false);
+    let response = rpc_request(
+        &config,
+        "sui_executeTransactionBlock",
+        json!([
+            tx_bytes,
+            signatures,
+            { "showEffects": true },
+            "WaitForLocalExecution",
+        ]),
+    )
+    .await?;
+    println!(
+        "Digest: {}",
+        response["digest"].as_str().unwrap_or_default()
+    );
+    println!("Status: {}", response["effects"]["status"]);
+    Ok(())
+}
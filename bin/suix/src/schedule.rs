@@ -0,0 +1,558 @@
+use std::{
+    collections::HashSet,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, SecondsFormat, TimeDelta, Timelike, Utc};
+use clap::Args;
+use eyre::{Result, bail};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::process::Command;
+
+use crate::{
+    context::Context,
+    hooks::{Webhook, run_command},
+};
+
+/// How far ahead a schedule is searched for its next run
+const SEARCH_DAYS: u64 = 366 * 5;
+
+#[derive(Args)]
+pub struct ScheduleArgs {
+    /// YAML jobs file
+    #[arg(long, value_name = "PATH")]
+    config: PathBuf,
+
+    /// Validate the jobs file, print the next runs of every job and exit
+    #[arg(long)]
+    check: bool,
+}
+
+/// Top level of the jobs file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobsFile {
+    /// Directory for per-job logs [default: ~/.suix/schedule]
+    #[serde(default)]
+    log_dir: Option<PathBuf>,
+    /// Default maximum random delay before each run, in seconds
+    #[serde(default)]
+    jitter: u64,
+    /// Actions run when any job fails
+    #[serde(default)]
+    on_failure: Vec<FailureAction>,
+    jobs: Vec<Job>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Job {
+    name: String,
+    /// Five-field cron expression (UTC) or @hourly, @daily, @weekly, @monthly
+    schedule: String,
+    /// suix arguments, e.g. ["backfill", "--sink", "/data"]
+    args: Vec<String>,
+    /// Kill the run after this many seconds
+    #[serde(default)]
+    timeout: Option<u64>,
+    /// Overrides the file's `jitter`
+    #[serde(default)]
+    jitter: Option<u64>,
+    /// Actions run when this job fails, after the file's `on_failure`
+    #[serde(default)]
+    on_failure: Vec<FailureAction>,
+}
+
+/// A failure action; one list entry may combine several
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FailureAction {
+    /// POST the failure JSON to this URL
+    webhook: Option<String>,
+    /// Run this shell command with the failure JSON on stdin
+    exec: Option<String>,
+}
+
+/// Parsed cron expression; each field is a bitmask of allowed values
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Cron runs a job when either day field matches if both are restricted
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronSchedule {
+    fn parse(expression: &str) -> Result<Self> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            bail!(
+                "Schedule {} must have five fields (minute hour day month weekday)",
+                expression
+            );
+        };
+        // Sunday may be written as 0 or 7
+        let weekdays_mask = parse_field(weekdays, 0, 7)?;
+        Ok(Self {
+            minutes: parse_field(minutes, 0, 59)?,
+            hours: parse_field(hours, 0, 23)?,
+            days: parse_field(days, 1, 31)?,
+            months: parse_field(months, 1, 12)?,
+            weekdays: (weekdays_mask | (weekdays_mask >> 7)) & 0x7f,
+            days_restricted: !days.starts_with('*'),
+            weekdays_restricted: !weekdays.starts_with('*'),
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// First minute strictly after `after` that the schedule matches
+    fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut at = after
+            .with_second(0)?
+            .with_nanosecond(0)?
+            .checked_add_signed(TimeDelta::minutes(1))?;
+        let limit = after.checked_add_days(Days::new(SEARCH_DAYS))?;
+        while at < limit {
+            if !self.day_matches(at.date_naive()) {
+                at = (at.date_naive() + Days::new(1))
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+            } else if self.hours & (1 << at.hour()) == 0 {
+                at = at.with_minute(0)? + TimeDelta::hours(1);
+            } else if self.minutes & (1 << at.minute()) == 0 {
+                at += TimeDelta::minutes(1);
+            } else {
+                return Some(at);
+            }
+        }
+        None
+    }
+}
+
+/// Bitmask of the values allowed by one cron field (`*`, `a`, `a-b`, `*/n`, `a-b/n`, lists)
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|&s| s > 0)
+                    .ok_or_else(|| eyre::eyre!("Invalid step in cron field {}", field))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let value = |s: &str| -> Result<u32> {
+            s.parse()
+                .ok()
+                .filter(|v| (min..=max).contains(v))
+                .ok_or_else(|| eyre::eyre!("{} is out of range {}-{} in {}", s, min, max, field))
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `a/n` means every n-th value from a
+                None if step > 1 => (value(range)?, max),
+                None => (value(range)?, value(range)?),
+            },
+        };
+        if start > end {
+            bail!("Invalid range {} in cron field {}", range, field);
+        }
+        for v in (start..=end).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+/// A job ready to run
+struct ScheduledJob {
+    job: Job,
+    schedule: CronSchedule,
+    jitter: u64,
+    log: PathBuf,
+    running: AtomicBool,
+}
+
+pub async fn run(args: ScheduleArgs, _ctx: &Context) -> Result<()> {
+    let content = fs::read_to_string(&args.config)
+        .map_err(|e| eyre::eyre!("Failed to read {}: {}", args.config.display(), e))?;
+    let file = parse_jobs(&content)?;
+    let log_dir = file.log_dir.clone().unwrap_or_else(default_log_dir);
+    let now = Utc::now();
+
+    if args.check {
+        for job in &file.jobs {
+            let schedule = CronSchedule::parse(&job.schedule)?;
+            let mut runs = Vec::new();
+            let mut at = now;
+            while runs.len() < 3
+                && let Some(next) = schedule.next_after(at)
+            {
+                runs.push(next.to_rfc3339_opts(SecondsFormat::Secs, true));
+                at = next;
+            }
+            println!(
+                "{} ({}): suix {}\n  next: {}",
+                job.name,
+                job.schedule,
+                job.args.join(" "),
+                runs.join(", ")
+            );
+        }
+        println!("Logs: {}", log_dir.display());
+        return Ok(());
+    }
+
+    fs::create_dir_all(&log_dir)
+        .map_err(|e| eyre::eyre!("Failed to create {}: {}", log_dir.display(), e))?;
+    let exe = Arc::new(std::env::current_exe()?);
+    let JobsFile {
+        jitter,
+        on_failure,
+        jobs,
+        ..
+    } = file;
+    let on_failure = Arc::new(on_failure);
+    let mut scheduled = Vec::with_capacity(jobs.len());
+    let mut slots = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        let schedule = CronSchedule::parse(&job.schedule)?;
+        let slot = schedule
+            .next_after(now)
+            .ok_or_else(|| eyre::eyre!("Job {} never runs", job.name))?;
+        slots.push((slot, with_jitter(slot, job.jitter.unwrap_or(jitter))));
+        scheduled.push(Arc::new(ScheduledJob {
+            log: log_dir.join(format!("{}.log", log_name(&job.name))),
+            jitter: job.jitter.unwrap_or(jitter),
+            schedule,
+            job,
+            running: AtomicBool::new(false),
+        }));
+    }
+    eprintln!(
+        "Scheduling {} jobs, logging to {}",
+        scheduled.len(),
+        log_dir.display()
+    );
+
+    loop {
+        let Some((index, &(slot, due))) = slots.iter().enumerate().min_by_key(|(_, (_, due))| *due)
+        else {
+            return Ok(());
+        };
+        let wait = (due - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+
+        let entry = Arc::clone(&scheduled[index]);
+        if entry.running.swap(true, Ordering::SeqCst) {
+            eprintln!(
+                "{}: previous run still in progress, skipping this one",
+                entry.job.name
+            );
+        } else {
+            let exe = Arc::clone(&exe);
+            let on_failure = Arc::clone(&on_failure);
+            tokio::spawn(async move {
+                run_job(&exe, &entry, &on_failure).await;
+                entry.running.store(false, Ordering::SeqCst);
+            });
+        }
+
+        let entry = &scheduled[index];
+        let next = entry
+            .schedule
+            .next_after(slot)
+            .ok_or_else(|| eyre::eyre!("Job {} has no further runs", entry.job.name))?;
+        slots[index] = (next, with_jitter(next, entry.jitter));
+    }
+}
+
+fn parse_jobs(content: &str) -> Result<JobsFile> {
+    let file: JobsFile =
+        serde_yaml::from_str(content).map_err(|e| eyre::eyre!("Invalid jobs file: {}", e))?;
+    if file.jobs.is_empty() {
+        bail!("The jobs file has no jobs");
+    }
+    let mut names = HashSet::new();
+    for job in &file.jobs {
+        if !names.insert(log_name(&job.name)) {
+            bail!("Job names must be unique, {} is used twice", job.name);
+        }
+        if job.args.is_empty() {
+            bail!("Job {} has no args", job.name);
+        }
+        if job.args[0] == "schedule" {
+            bail!("Job {} must not run `suix schedule` itself", job.name);
+        }
+        let schedule = CronSchedule::parse(&job.schedule)
+            .map_err(|e| eyre::eyre!("Job {}: {}", job.name, e))?;
+        if schedule.next_after(Utc::now()).is_none() {
+            bail!("Job {} never runs ({})", job.name, job.schedule);
+        }
+    }
+    Ok(file)
+}
+
+/// Default log directory: `~/.suix/schedule`
+fn default_log_dir() -> PathBuf {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(".suix")
+        .join("schedule")
+}
+
+/// Job name usable as a file name
+fn log_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// `at` delayed by up to `jitter` random seconds
+fn with_jitter(at: DateTime<Utc>, jitter: u64) -> DateTime<Utc> {
+    if jitter == 0 {
+        return at;
+    }
+    let delay = rand::random_range(0..=jitter);
+    at + TimeDelta::seconds(delay as i64)
+}
+
+/// Run one job with its output appended to its log, reporting failures
+async fn run_job(exe: &Path, entry: &ScheduledJob, on_failure: &[FailureAction]) {
+    let job = &entry.job;
+    let started_at = Utc::now();
+    let started = Instant::now();
+    let outcome = execute(exe, entry).await;
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let status = match &outcome {
+        Ok(()) => "ok".to_string(),
+        Err(e) => e.to_string(),
+    };
+    append_log(
+        &entry.log,
+        &format!(
+            "=== {} finished in {elapsed:.1}s: {status}\n",
+            Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+        ),
+    );
+    let Err(error) = outcome else {
+        eprintln!("✅ {}: finished in {elapsed:.1}s", job.name);
+        return;
+    };
+    eprintln!("❌ {}: {error} (see {})", job.name, entry.log.display());
+
+    let payload = json!({
+        "type": "job_failed",
+        "job": job.name,
+        "args": job.args,
+        "error": error.to_string(),
+        "started_at": started_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        "duration_secs": elapsed,
+        "log": entry.log.display().to_string(),
+    });
+    for action in on_failure.iter().chain(&job.on_failure) {
+        fire(action, &payload).await;
+    }
+}
+
+/// Spawn `suix <args>` and wait for it, honoring the job timeout
+async fn execute(exe: &Path, entry: &ScheduledJob) -> Result<()> {
+    let job = &entry.job;
+    let mut log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&entry.log)
+        .map_err(|e| eyre::eyre!("Failed to open {}: {}", entry.log.display(), e))?;
+    writeln!(
+        log,
+        "=== {} suix {}",
+        Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        job.args.join(" ")
+    )?;
+    let stderr: File = log.try_clone()?;
+    let mut child = Command::new(exe)
+        .args(&job.args)
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(stderr)
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| eyre::eyre!("Failed to start: {}", e))?;
+
+    let status = match job.timeout {
+        Some(seconds) => {
+            match tokio::time::timeout(Duration::from_secs(seconds), child.wait()).await {
+                Ok(status) => status?,
+                Err(_) => {
+                    child.kill().await?;
+                    bail!("timed out after {}s", seconds);
+                }
+            }
+        }
+        None => child.wait().await?,
+    };
+    if !status.success() {
+        bail!("exited with {}", status);
+    }
+    Ok(())
+}
+
+fn append_log(path: &Path, line: &str) {
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()));
+    if let Err(e) = result {
+        eprintln!("Failed to write {}: {e}", path.display());
+    }
+}
+
+async fn fire(action: &FailureAction, payload: &Value) {
+    if let Some(ref url) = action.webhook
+        && let Err(e) = Webhook::new(url).with_retries(3).deliver(payload).await
+    {
+        eprintln!("Failed to deliver webhook: {e}");
+    }
+    if let Some(ref command) = action.exec
+        && let Err(e) = run_command(command, payload).await
+    {
+        eprintln!("Hook command failed: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_parse_field() {
+        assert_eq!(parse_field("*", 0, 3).unwrap(), 0b1111);
+        assert_eq!(
+            parse_field("*/15", 0, 59).unwrap(),
+            1 | 1 << 15 | 1 << 30 | 1 << 45
+        );
+        assert_eq!(parse_field("1-3,5", 0, 7).unwrap(), 0b101110);
+        assert_eq!(
+            parse_field("10/20", 0, 59).unwrap(),
+            1 << 10 | 1 << 30 | 1 << 50
+        );
+        assert!(parse_field("60", 0, 59).is_err());
+        assert!(parse_field("5-1", 0, 59).is_err());
+        assert!(parse_field("*/0", 0, 59).is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        let hourly = CronSchedule::parse("@hourly").unwrap();
+        assert_eq!(
+            hourly.next_after(at("2025-03-01T10:00:00Z")),
+            Some(at("2025-03-01T11:00:00Z"))
+        );
+        let daily = CronSchedule::parse("30 2 * * *").unwrap();
+        assert_eq!(
+            daily.next_after(at("2025-12-31T03:15:42Z")),
+            Some(at("2026-01-01T02:30:00Z"))
+        );
+        // Mondays at 09:00; 2025-03-01 is a Saturday
+        let weekly = CronSchedule::parse("0 9 * * 1").unwrap();
+        assert_eq!(
+            weekly.next_after(at("2025-03-01T00:00:00Z")),
+            Some(at("2025-03-03T09:00:00Z"))
+        );
+        // Either the 15th or a Sunday (7 == 0)
+        let either = CronSchedule::parse("0 0 15 * 7").unwrap();
+        assert_eq!(
+            either.next_after(at("2025-03-10T00:00:00Z")),
+            Some(at("2025-03-15T00:00:00Z"))
+        );
+        assert_eq!(
+            either.next_after(at("2025-03-01T00:00:00Z")),
+            Some(at("2025-03-02T00:00:00Z"))
+        );
+        let never = CronSchedule::parse("0 0 30 2 *").unwrap();
+        assert_eq!(never.next_after(at("2025-01-01T00:00:00Z")), None);
+        assert!(CronSchedule::parse("0 0 * *").is_err());
+    }
+
+    #[test]
+    fn test_parse_jobs() {
+        let file = parse_jobs(
+            r#"
+jitter: 30
+on_failure: [{ webhook: "https://hooks.example.com/ops" }]
+jobs:
+  - name: balances
+    schedule: "@hourly"
+    args: [portfolio, --addresses-file, treasury.txt, --format, csv]
+    timeout: 300
+  - name: checkpoints
+    schedule: "0 1 * * *"
+    args: [backfill, --sink, /data/checkpoints]
+    jitter: 0
+"#,
+        )
+        .unwrap();
+        assert_eq!(file.jobs.len(), 2);
+        assert_eq!(file.jitter, 30);
+        assert_eq!(file.jobs[1].jitter, Some(0));
+
+        let duplicate = "jobs:\n  - { name: a, schedule: '@daily', args: [gas] }\n  - { name: a, schedule: '@daily', args: [gas] }\n";
+        assert!(parse_jobs(duplicate).is_err());
+        assert!(
+            parse_jobs("jobs:\n  - { name: a, schedule: '0 0 30 2 *', args: [gas] }\n").is_err()
+        );
+        assert!(parse_jobs("jobs:\n  - { name: a, schedule: '@daily', args: [] }\n").is_err());
+        assert!(parse_jobs("jobs: []\n").is_err());
+    }
+
+    #[test]
+    fn test_log_name() {
+        assert_eq!(log_name("daily export/ck"), "daily_export_ck");
+    }
+}
@@ -0,0 +1,540 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use async_nats::jetstream::{self, context::Publish};
+use clap::Args;
+use eyre::{Result, bail};
+use futures::{StreamExt, TryStreamExt, future::BoxFuture, stream};
+use rpc::{RpcConfig, json_u64, latest_checkpoint};
+use rskafka::{
+    client::{
+        ClientBuilder,
+        partition::{Compression, PartitionClient, UnknownTopicHandling},
+    },
+    record::Record,
+};
+use serde_json::{Value, json};
+
+use crate::{
+    backfill::{Kind, fetch_items},
+    context::Context,
+    filter::Filter,
+};
+
+/// Kafka rejects produce requests above `message.max.bytes` (1 MiB by default)
+const KAFKA_MAX_REQUEST_BYTES: usize = 512 * 1024;
+
+#[derive(Args)]
+pub struct SinkArgs {
+    /// Destination: `kafka://BROKER[,BROKER]/TOPIC[?partition=N]`,
+    /// `nats://HOST[:PORT]/SUBJECT`, `sled:<PATH>` or a directory
+    #[arg(long, value_name = "SINK")]
+    to: String,
+
+    /// Kind of data to publish
+    #[arg(long, value_enum, default_value = "checkpoints")]
+    what: Kind,
+
+    /// Only publish events or transactions matching this filter expression
+    #[arg(long, value_name = "EXPRESSION")]
+    filter: Option<String>,
+
+    /// First checkpoint when there is no resume cursor (defaults to the next one)
+    #[arg(long, value_name = "SEQ")]
+    from: Option<u64>,
+
+    /// Checkpoints per batch; the cursor is saved once a batch is acknowledged
+    #[arg(long, value_name = "COUNT", default_value = "10")]
+    batch_size: u64,
+
+    /// Maximum number of checkpoints fetched concurrently
+    #[arg(short = 'j', long, value_name = "COUNT", default_value = "8")]
+    concurrency: usize,
+
+    /// Polling interval in seconds
+    #[arg(long, value_name = "SECONDS", default_value = "1")]
+    interval: u64,
+
+    /// RPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+}
+
+/// Destination for checkpoint data and the progress watermark.
+///
+/// `write_batch` returns once the destination has durably accepted every
+/// item; callers save progress only afterwards, so delivery is at least once.
+pub(crate) trait Sink: Send {
+    fn write_batch<'a>(
+        &'a mut self,
+        start: u64,
+        end: u64,
+        items: &'a [Value],
+    ) -> BoxFuture<'a, Result<()>>;
+    fn load_progress(&self) -> Result<Option<u64>>;
+    fn save_progress(&mut self, next: u64) -> Result<()>;
+}
+
+/// `{"next": N}` watermark kept in a local JSON file
+pub(crate) struct Cursor {
+    pub(crate) path: PathBuf,
+}
+
+impl Cursor {
+    pub(crate) fn load(&self) -> Result<Option<u64>> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => {
+                let progress: Value = serde_json::from_str(&content)?;
+                Ok(json_u64(&progress["next"]))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub(crate) fn save(&self, next: u64) -> Result<()> {
+        write_atomic(&self.path, json!({ "next": next }).to_string())
+    }
+
+    /// Cursor of a broker destination: `~/.suix/sink/<kind>-<spec>.json`
+    fn for_broker(spec: &str, kind: Kind) -> Result<Self> {
+        let dir = std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(".suix")
+            .join("sink");
+        fs::create_dir_all(&dir)?;
+        let name: String = spec
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        Ok(Cursor {
+            path: dir.join(format!("{}-{name}.json", kind.name())),
+        })
+    }
+}
+
+/// One NDJSON file per batch plus a `progress-<kind>.json` watermark
+struct DirSink {
+    dir: PathBuf,
+    kind: Kind,
+    cursor: Cursor,
+}
+
+impl Sink for DirSink {
+    fn write_batch<'a>(
+        &'a mut self,
+        start: u64,
+        end: u64,
+        items: &'a [Value],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let path = self
+                .dir
+                .join(format!("{}-{start:012}-{end:012}.ndjson", self.kind.name()));
+            let tmp = path.with_extension("ndjson.tmp");
+            let mut writer = BufWriter::new(File::create(&tmp)?);
+            for item in items {
+                writeln!(writer, "{}", serde_json::to_string(item)?)?;
+            }
+            writer.flush()?;
+            fs::rename(&tmp, &path)?;
+            Ok(())
+        })
+    }
+
+    fn load_progress(&self) -> Result<Option<u64>> {
+        self.cursor.load()
+    }
+
+    fn save_progress(&mut self, next: u64) -> Result<()> {
+        self.cursor.save(next)
+    }
+}
+
+/// Items stored in a sled database keyed by `<kind>/<checkpoint>/<index>`
+struct SledSink {
+    db: sled::Db,
+    kind: Kind,
+}
+
+impl SledSink {
+    fn progress_key(&self) -> String {
+        format!("progress/{}", self.kind.name())
+    }
+}
+
+impl Sink for SledSink {
+    fn write_batch<'a>(
+        &'a mut self,
+        _start: u64,
+        _end: u64,
+        items: &'a [Value],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut batch = sled::Batch::default();
+            for (index, item) in items.iter().enumerate() {
+                let checkpoint = json_u64(&item["checkpoint"]).unwrap_or_default();
+                let key = format!("{}/{checkpoint:020}/{index:08}", self.kind.name());
+                batch.insert(key.as_bytes(), serde_json::to_vec(item)?);
+            }
+            self.db.apply_batch(batch)?;
+            Ok(())
+        })
+    }
+
+    fn load_progress(&self) -> Result<Option<u64>> {
+        Ok(self
+            .db
+            .get(self.progress_key())?
+            .and_then(|v| std::str::from_utf8(&v).ok()?.parse().ok()))
+    }
+
+    fn save_progress(&mut self, next: u64) -> Result<()> {
+        self.db
+            .insert(self.progress_key(), next.to_string().as_bytes())?;
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+/// Records on one partition of a Kafka topic, keyed by checkpoint with the
+/// item id in a `suix-id` header for consumer-side deduplication
+struct KafkaSink {
+    partition: PartitionClient,
+    kind: Kind,
+    cursor: Cursor,
+}
+
+impl Sink for KafkaSink {
+    fn write_batch<'a>(
+        &'a mut self,
+        _start: u64,
+        _end: u64,
+        items: &'a [Value],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut records = Vec::new();
+            let mut size = 0;
+            for item in items {
+                let value = serde_json::to_vec(item)?;
+                if size + value.len() > KAFKA_MAX_REQUEST_BYTES && !records.is_empty() {
+                    self.partition
+                        .produce(std::mem::take(&mut records), Compression::default())
+                        .await?;
+                    size = 0;
+                }
+                size += value.len();
+                let checkpoint = json_u64(&item["checkpoint"]).unwrap_or_default();
+                records.push(Record {
+                    key: Some(checkpoint.to_string().into_bytes()),
+                    value: Some(value),
+                    headers: BTreeMap::from([(
+                        "suix-id".to_string(),
+                        item_id(self.kind, item).into_bytes(),
+                    )]),
+                    timestamp: chrono::Utc::now(),
+                });
+            }
+            if !records.is_empty() {
+                self.partition
+                    .produce(records, Compression::default())
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn load_progress(&self) -> Result<Option<u64>> {
+        self.cursor.load()
+    }
+
+    fn save_progress(&mut self, next: u64) -> Result<()> {
+        self.cursor.save(next)
+    }
+}
+
+/// Messages published to a JetStream subject; the item id is sent as
+/// `Nats-Msg-Id` so the stream drops duplicates after a resume
+struct NatsSink {
+    jetstream: jetstream::Context,
+    subject: String,
+    kind: Kind,
+    cursor: Cursor,
+}
+
+impl Sink for NatsSink {
+    fn write_batch<'a>(
+        &'a mut self,
+        _start: u64,
+        _end: u64,
+        items: &'a [Value],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut acks = Vec::with_capacity(items.len());
+            for item in items {
+                let publish = Publish::build()
+                    .payload(serde_json::to_vec(item)?.into())
+                    .message_id(item_id(self.kind, item));
+                acks.push(
+                    self.jetstream
+                        .send_publish(self.subject.clone(), publish)
+                        .await?,
+                );
+            }
+            for ack in acks {
+                ack.await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn load_progress(&self) -> Result<Option<u64>> {
+        self.cursor.load()
+    }
+
+    fn save_progress(&mut self, next: u64) -> Result<()> {
+        self.cursor.save(next)
+    }
+}
+
+/// Stable identity of an item, the same every time it is published
+fn item_id(kind: Kind, item: &Value) -> String {
+    let data = &item["data"];
+    let checkpoint = json_u64(&item["checkpoint"]).unwrap_or_default();
+    match kind {
+        Kind::Checkpoints => format!("checkpoint:{checkpoint}"),
+        Kind::Txs => format!("tx:{}", data["digest"].as_str().unwrap_or_default()),
+        Kind::Events => format!(
+            "event:{}:{}",
+            data["id"]["txDigest"].as_str().unwrap_or_default(),
+            json_u64(&data["id"]["eventSeq"]).unwrap_or_default()
+        ),
+    }
+}
+
+pub(crate) fn write_atomic(path: &Path, content: impl AsRef<[u8]>) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Broker spec split into hosts, topic or subject and query options
+struct BrokerSpec {
+    hosts: String,
+    path: String,
+    query: BTreeMap<String, String>,
+}
+
+impl BrokerSpec {
+    fn parse(rest: &str) -> Self {
+        let (location, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (hosts, path) = location.split_once('/').unwrap_or((location, ""));
+        BrokerSpec {
+            hosts: hosts.to_string(),
+            path: path.to_string(),
+            query: query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Open a sink from `kafka://...`, `nats://...`, `sled:<PATH>` or a directory
+pub(crate) async fn open_sink(spec: &str, kind: Kind) -> Result<Box<dyn Sink>> {
+    if let Some(rest) = spec.strip_prefix("kafka://") {
+        let target = BrokerSpec::parse(rest);
+        if target.hosts.is_empty() || target.path.is_empty() {
+            bail!("Kafka sink must look like kafka://BROKER[,BROKER]/TOPIC");
+        }
+        let partition = match target.query.get("partition") {
+            Some(p) => p
+                .parse()
+                .map_err(|_| eyre::eyre!("Invalid Kafka partition: {}", p))?,
+            None => 0,
+        };
+        let brokers = target.hosts.split(',').map(str::to_string).collect();
+        let client = ClientBuilder::new(brokers)
+            .build()
+            .await
+            .map_err(|e| eyre::eyre!("Failed to connect to Kafka {}: {}", target.hosts, e))?;
+        let partition = client
+            .partition_client(target.path.clone(), partition, UnknownTopicHandling::Retry)
+            .await?;
+        return Ok(Box::new(KafkaSink {
+            partition,
+            kind,
+            cursor: Cursor::for_broker(spec, kind)?,
+        }));
+    }
+    if let Some(rest) = spec.strip_prefix("nats://") {
+        let target = BrokerSpec::parse(rest);
+        if target.path.is_empty() {
+            bail!("NATS sink must look like nats://HOST[:PORT]/SUBJECT");
+        }
+        let client = async_nats::connect(format!("nats://{}", target.hosts))
+            .await
+            .map_err(|e| eyre::eyre!("Failed to connect to NATS {}: {}", target.hosts, e))?;
+        return Ok(Box::new(NatsSink {
+            jetstream: jetstream::new(client),
+            subject: target.path,
+            kind,
+            cursor: Cursor::for_broker(spec, kind)?,
+        }));
+    }
+    if let Some(path) = spec.strip_prefix("sled:") {
+        let db = sled::open(path).map_err(|e| eyre::eyre!("Failed to open {}: {}", path, e))?;
+        return Ok(Box::new(SledSink { db, kind }));
+    }
+    let dir = PathBuf::from(spec.strip_prefix("dir:").unwrap_or(spec));
+    fs::create_dir_all(&dir)?;
+    Ok(Box::new(DirSink {
+        cursor: Cursor {
+            path: dir.join(format!("progress-{}.json", kind.name())),
+        },
+        dir,
+        kind,
+    }))
+}
+
+/// Follow the chain tip and publish every new checkpoint's items to the sink
+pub async fn run(args: SinkArgs, ctx: &Context) -> Result<()> {
+    if args.batch_size == 0 || args.concurrency == 0 {
+        bail!("--batch-size and --concurrency must be greater than 0");
+    }
+    let filter = match args.filter {
+        Some(_) if matches!(args.what, Kind::Checkpoints) => {
+            bail!("--filter applies to --what events or --what txs")
+        }
+        Some(ref expression) => Some(Filter::parse(expression)?),
+        None => None,
+    };
+    let config = ctx.rpc_config(args.url.clone(), false);
+    let interval = Duration::from_secs(args.interval);
+
+    let mut sink = open_sink(&args.to, args.what).await?;
+    let mut next = match (sink.load_progress()?, args.from) {
+        (Some(next), _) => {
+            eprintln!("Resuming {} at checkpoint {next}", args.what.name());
+            next
+        }
+        (None, Some(from)) => from,
+        (None, None) => latest_checkpoint(&config).await? + 1,
+    };
+    eprintln!(
+        "Publishing {} to {} from checkpoint {next}",
+        args.what.name(),
+        args.to
+    );
+
+    loop {
+        let latest = match latest_checkpoint(&config).await {
+            Ok(latest) => latest,
+            Err(e) => {
+                eprintln!("Failed to get latest checkpoint: {e}");
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+        };
+        while next <= latest {
+            let end = (next + args.batch_size - 1).min(latest);
+            match publish_batch(&config, sink.as_mut(), next, end, &args, &filter).await {
+                Ok(count) => {
+                    eprintln!(
+                        "Published {} for checkpoints {next}..={end} ({count} items)",
+                        args.what.name()
+                    );
+                    next = end + 1;
+                }
+                Err(e) => {
+                    // The cursor was not advanced, so the batch is retried
+                    eprintln!("Failed to publish checkpoints {next}..={end}: {e}");
+                    break;
+                }
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Fetch, filter and publish one batch, then save the cursor
+async fn publish_batch(
+    config: &RpcConfig,
+    sink: &mut dyn Sink,
+    start: u64,
+    end: u64,
+    args: &SinkArgs,
+    filter: &Option<Filter>,
+) -> Result<usize> {
+    let per_checkpoint: Vec<Vec<Value>> = stream::iter(start..=end)
+        .map(|seq| fetch_items(config, seq, args.what))
+        .buffered(args.concurrency)
+        .try_collect()
+        .await?;
+    let items: Vec<Value> = per_checkpoint
+        .into_iter()
+        .flatten()
+        .filter(|item| match (filter, args.what) {
+            (Some(filter), Kind::Events) => filter.matches_event(&item["data"]),
+            (Some(filter), Kind::Txs) => filter.matches_transaction(&item["data"]),
+            _ => true,
+        })
+        .collect();
+    sink.write_batch(start, end, &items).await?;
+    sink.save_progress(end + 1)?;
+    Ok(items.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dir_sink_progress_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("suix-sink-test-{}", std::process::id()));
+        let mut sink = open_sink(dir.to_str().unwrap(), Kind::Checkpoints)
+            .await
+            .unwrap();
+        assert_eq!(sink.load_progress().unwrap(), None);
+        sink.write_batch(0, 1, &[json!({"checkpoint": 0})])
+            .await
+            .unwrap();
+        sink.save_progress(2).unwrap();
+        assert_eq!(sink.load_progress().unwrap(), Some(2));
+        assert!(
+            dir.join("checkpoints-000000000000-000000000001.ndjson")
+                .exists()
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_broker_spec_and_item_id() {
+        let spec = BrokerSpec::parse("k1:9092,k2:9092/sui-events?partition=3");
+        assert_eq!(spec.hosts, "k1:9092,k2:9092");
+        assert_eq!(spec.path, "sui-events");
+        assert_eq!(spec.query["partition"], "3");
+
+        let event = json!({
+            "checkpoint": 9,
+            "data": { "id": { "txDigest": "D1", "eventSeq": "2" } },
+        });
+        assert_eq!(item_id(Kind::Events, &event), "event:D1:2");
+        assert_eq!(
+            item_id(Kind::Checkpoints, &json!({"checkpoint": 9})),
+            "checkpoint:9"
+        );
+    }
+}
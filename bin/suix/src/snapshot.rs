@@ -0,0 +1,207 @@
+use std::{collections::BTreeMap, io, path::PathBuf};
+
+use clap::Subcommand;
+use eyre::{Result, bail};
+use rpc::graphql::{self, GraphqlClient};
+use serde_json::{Value, json};
+
+use crate::context::Context;
+
+/// Largest page the GraphQL service returns
+const OBJECTS_PAGE_SIZE: u64 = 50;
+
+const OBJECTS_SELECTION: &str = r#"objects(filter: { type: $type }, first: $first, after: $after) {
+      pageInfo { hasNextPage endCursor }
+      nodes {
+        owner {
+          __typename
+          ... on AddressOwner { address { address } }
+          ... on ConsensusAddressOwner { address { address } }
+        }
+      }
+    }"#;
+
+#[derive(Subcommand)]
+pub enum SnapshotCommands {
+    /// Export an address,count CSV of everyone holding objects of a type.
+    ///
+    /// Objects held by other objects (e.g. in kiosks), shared or immutable are
+    /// counted but not attributed to an address.
+    Holders {
+        /// Struct tag of the objects, e.g. 0x...::nft::Nft
+        #[arg(long = "type", value_name = "STRUCT_TAG")]
+        object_type: String,
+        /// Snapshot as of this checkpoint instead of the latest
+        #[arg(long, value_name = "N")]
+        at_checkpoint: Option<u64>,
+        /// Write the CSV to this file (defaults to stdout)
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+        /// GraphQL endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://graphql.mainnet.sui.io/graphql"
+        )]
+        graphql_url: String,
+    },
+}
+
+/// Holders of one object type
+#[derive(Debug, Default)]
+struct HolderSnapshot {
+    holders: BTreeMap<String, u64>,
+    objects: u64,
+    /// Objects not owned by an address (object-owned, shared or immutable)
+    unattributed: u64,
+}
+
+impl HolderSnapshot {
+    fn add(&mut self, object: &Value) {
+        self.objects += 1;
+        match object["owner"]["address"]["address"].as_str() {
+            Some(address) => *self.holders.entry(address.to_string()).or_default() += 1,
+            None => self.unattributed += 1,
+        }
+    }
+
+    /// Holders with the most objects first
+    fn ranked(&self) -> Vec<(&String, u64)> {
+        let mut ranked: Vec<_> = self.holders.iter().map(|(a, c)| (a, *c)).collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked
+    }
+}
+
+pub async fn run(cmd: SnapshotCommands, _ctx: &Context) -> Result<()> {
+    match cmd {
+        SnapshotCommands::Holders {
+            object_type,
+            at_checkpoint,
+            out,
+            graphql_url,
+        } => {
+            let client = GraphqlClient::new(graphql_url);
+            let query = holders_query(at_checkpoint.is_some());
+            let mut snapshot = HolderSnapshot::default();
+            let mut cursor = Value::Null;
+            loop {
+                let variables = json!({
+                    "type": object_type,
+                    "first": OBJECTS_PAGE_SIZE,
+                    "after": cursor,
+                    "checkpoint": at_checkpoint,
+                });
+                let response = client.request(&query, variables).await?;
+                let page = objects_page(&response, at_checkpoint)?;
+                for object in page["nodes"].as_array().into_iter().flatten() {
+                    snapshot.add(object);
+                }
+                eprint!("\r{} objects scanned", snapshot.objects);
+                if !page["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) {
+                    break;
+                }
+                cursor = page["pageInfo"]["endCursor"].clone();
+            }
+            eprintln!(
+                "\r{} objects held by {} addresses{}",
+                snapshot.objects,
+                snapshot.holders.len(),
+                match at_checkpoint {
+                    Some(seq) => format!(" at checkpoint {seq}"),
+                    None => String::new(),
+                }
+            );
+            if snapshot.unattributed > 0 {
+                eprintln!(
+                    "{} objects are held by other objects, shared or immutable and were not attributed",
+                    snapshot.unattributed
+                );
+            }
+
+            let writer: Box<dyn io::Write> = match out {
+                Some(ref path) => Box::new(
+                    std::fs::File::create(path)
+                        .map_err(|e| eyre::eyre!("Failed to create {}: {}", path.display(), e))?,
+                ),
+                None => Box::new(io::stdout()),
+            };
+            let mut writer = csv::Writer::from_writer(writer);
+            writer.write_record(["address", "count"])?;
+            for (address, count) in snapshot.ranked() {
+                writer.write_record([address, &count.to_string()])?;
+            }
+            writer.flush()?;
+            Ok(())
+        }
+    }
+}
+
+/// GraphQL query for one page of objects, optionally scoped to a checkpoint
+fn holders_query(at_checkpoint: bool) -> String {
+    if at_checkpoint {
+        format!(
+            "query ($type: String!, $first: Int, $after: String, $checkpoint: UInt53) {{\n  checkpoint(sequenceNumber: $checkpoint) {{\n    query {{\n    {OBJECTS_SELECTION}\n    }}\n  }}\n}}"
+        )
+    } else {
+        format!("query ($type: String!, $first: Int, $after: String) {{\n  {OBJECTS_SELECTION}\n}}")
+    }
+}
+
+/// The `objects` connection of a response, or the server's error
+pub(crate) fn objects_page(response: &Value, at_checkpoint: Option<u64>) -> Result<&Value> {
+    if let Some(message) = graphql::error_message(response) {
+        bail!("GraphQL error: {}", message);
+    }
+    let data = &response["data"];
+    let page = match at_checkpoint {
+        Some(seq) => {
+            if data["checkpoint"].is_null() {
+                bail!(
+                    "Checkpoint {} is not available from this GraphQL service",
+                    seq
+                );
+            }
+            &data["checkpoint"]["query"]["objects"]
+        }
+        None => &data["objects"],
+    };
+    if !page.is_object() {
+        bail!("Unexpected GraphQL response: {}", response);
+    }
+    Ok(page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_holder_snapshot() {
+        let owned = |address: &str| json!({ "owner": { "__typename": "AddressOwner", "address": { "address": address } } });
+        let mut snapshot = HolderSnapshot::default();
+        snapshot.add(&owned("0xb"));
+        snapshot.add(&owned("0xa"));
+        snapshot.add(&owned("0xb"));
+        snapshot.add(&json!({ "owner": { "__typename": "ObjectOwner" } }));
+        snapshot.add(&json!({ "owner": { "__typename": "Shared" } }));
+
+        assert_eq!(snapshot.objects, 5);
+        assert_eq!(snapshot.unattributed, 2);
+        let ranked = snapshot.ranked();
+        assert_eq!(ranked, [(&"0xb".to_string(), 2), (&"0xa".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_objects_page() {
+        let latest = json!({ "data": { "objects": { "nodes": [] } } });
+        assert!(objects_page(&latest, None).is_ok());
+        let scoped =
+            json!({ "data": { "checkpoint": { "query": { "objects": { "nodes": [] } } } } });
+        assert!(objects_page(&scoped, Some(7)).is_ok());
+        let pruned = json!({ "data": { "checkpoint": null } });
+        assert!(objects_page(&pruned, Some(7)).is_err());
+        let error = json!({ "errors": [{ "message": "bad type" }], "data": null });
+        assert!(objects_page(&error, None).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use clap::Subcommand;
+use eyre::{Result, bail};
+use keystore::resolve_address;
+use rpc::rpc_request;
+use serde_json::{Value, json};
+use sui_types::{
+    base_types::SuiAddress,
+    transaction::{TransactionData, TransactionDataAPI, TransactionKind},
+};
+
+use crate::{
+    context::Context,
+    qr,
+    tx::{decode_bcs, gas_coin_refs, reference_gas_price, select_gas_coins},
+};
+
+#[derive(Subcommand)]
+pub enum SponsorCommands {
+    /// Attach the sponsor's gas to a transaction and sign it as gas owner
+    Sign {
+        /// Base64 BCS TransactionKind from the sender, to be wrapped with sponsor gas
+        #[arg(long, value_name = "BASE64", required_unless_present = "tx_bytes")]
+        tx_kind: Option<String>,
+        /// Base64 BCS TransactionData that already names the sponsor as gas owner
+        #[arg(long, value_name = "BASE64", conflicts_with = "tx_kind")]
+        tx_bytes: Option<String>,
+        /// Sender of the transaction (required with --tx-kind)
+        #[arg(long, value_name = "ADDRESS", required_unless_present = "tx_bytes")]
+        sender: Option<String>,
+        /// Sponsor address or keystore alias paying for gas
+        #[arg(long, value_name = "ADDRESS|ALIAS")]
+        sponsor: String,
+        /// Gas coins to use (defaults to the sponsor's largest SUI coins)
+        #[arg(long = "gas-coin", value_name = "OBJECT_ID")]
+        gas_coins: Vec<String>,
+        /// Gas budget in MIST
+        #[arg(long, value_name = "MIST", default_value = "50000000")]
+        gas_budget: u64,
+        /// Gas price in MIST (defaults to the reference gas price)
+        #[arg(long, value_name = "MIST")]
+        gas_price: Option<u64>,
+        /// Keystore holding the sponsor key
+        #[arg(long, value_name = "PATH")]
+        keystore: Option<PathBuf>,
+        /// Print the envelope as terminal QR codes (read them back with `suix scan`)
+        #[arg(long)]
+        qr: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Combine sender and sponsor signatures into a submittable envelope
+    Assemble {
+        /// Base64 BCS TransactionData signed by both parties
+        #[arg(long, value_name = "BASE64")]
+        tx_bytes: String,
+        /// Base64 signature (repeat for each signer)
+        #[arg(long = "signature", value_name = "BASE64")]
+        signatures: Vec<String>,
+        /// Also sign with this local key (address or alias), e.g. as the sender
+        #[arg(long, value_name = "ADDRESS|ALIAS")]
+        sign_as: Option<String>,
+        /// Keystore used with --sign-as
+        #[arg(long, value_name = "PATH")]
+        keystore: Option<PathBuf>,
+        /// Submit the transaction instead of printing the envelope
+        #[arg(long)]
+        execute: bool,
+        /// Print the envelope as terminal QR codes (read them back with `suix scan`)
+        #[arg(long, conflicts_with = "execute")]
+        qr: bool,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+pub async fn run(cmd: SponsorCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        SponsorCommands::Sign {
+            tx_kind,
+            tx_bytes,
+            sender,
+            sponsor,
+            gas_coins,
+            gas_budget,
+            gas_price,
+            keystore,
+            qr,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let keystore = ctx.keystore(keystore)?;
+            let sponsor = keystore.get(&sponsor)?.address;
+
+            let data = match (tx_kind, tx_bytes) {
+                (_, Some(tx_bytes)) => {
+                    let data: TransactionData = decode_bcs(&tx_bytes)?;
+                    if data.gas_owner() != sponsor {
+                        bail!(
+                            "Transaction gas owner is {}, not the sponsor {}",
+                            data.gas_owner(),
+                            sponsor
+                        );
+                    }
+                    data
+                }
+                (Some(tx_kind), None) => {
+                    let kind: TransactionKind = decode_bcs(&tx_kind)?;
+                    let sender =
+                        resolve_address(Some(&keystore), sender.as_deref().unwrap_or_default())?;
+                    let gas_price = match gas_price {
+                        Some(price) => price,
+                        None => reference_gas_price(&config).await?,
+                    };
+                    let payment = if gas_coins.is_empty() {
+                        select_gas_coins(&config, sponsor, gas_budget).await?
+                    } else {
+                        gas_coin_refs(&config, &gas_coins).await?
+                    };
+                    TransactionData::new_with_gas_coins_allow_sponsor(
+                        kind, sender, payment, gas_budget, gas_price, sponsor,
+                    )
+                }
+                (None, None) => bail!("One of --tx-kind or --tx-bytes is required"),
+            };
+
+            let signature = keystore.sign_transaction(&sponsor.to_string(), &data)?;
+            let envelope = json!({
+                "txBytes": BASE64.encode(bcs::to_bytes(&data)?),
+                "sender": data.sender().to_string(),
+                "sponsor": sponsor.to_string(),
+                "sponsorSignature": BASE64.encode(signature.as_ref()),
+            });
+            print_envelope(&envelope, qr)
+        }
+        SponsorCommands::Assemble {
+            tx_bytes,
+            mut signatures,
+            sign_as,
+            keystore,
+            execute,
+            qr,
+            url,
+        } => {
+            let data: TransactionData = decode_bcs(&tx_bytes)?;
+            if let Some(signer) = sign_as {
+                let keystore = ctx.keystore(keystore)?;
+                let signature = keystore.sign_transaction(&signer, &data)?;
+                signatures.push(BASE64.encode(signature.as_ref()));
+            }
+
+            let signers: BTreeSet<SuiAddress> = [data.sender(), data.gas_owner()].into();
+            if signatures.len() != signers.len() {
+                bail!(
+                    "Expected {} signatures (sender {} and gas owner {}), got {}",
+                    signers.len(),
+                    data.sender(),
+                    data.gas_owner(),
+                    signatures.len()
+                );
+            }
+
+            if !execute {
+                let envelope = json!({ "txBytes": tx_bytes, "signatures": signatures });
+                return print_envelope(&envelope, qr);
+            }
+
+            let config = ctx.rpc_config(url, false);
+            let response = rpc_request(
+                &config,
+                "sui_executeTransactionBlock",
+                json!([
+                    tx_bytes,
+                    signatures,
+                    { "showEffects": true },
+                    "WaitForLocalExecution",
+                ]),
+            )
+            .await?;
+            println!(
+                "Digest: {}",
+                response["digest"].as_str().unwrap_or_default()
+            );
+            println!("Status: {}", response["effects"]["status"]);
+            Ok(())
+        }
+    }
+}
+
+fn print_envelope(envelope: &Value, as_qr: bool) -> Result<()> {
+    if as_qr {
+        return qr::print(&serde_json::to_string(envelope)?);
+    }
+    println!("{}", serde_json::to_string_pretty(envelope)?);
+    Ok(())
+}
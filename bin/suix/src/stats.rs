@@ -0,0 +1,627 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chrono::DateTime;
+use clap::{Subcommand, ValueEnum};
+use eyre::{Result, bail};
+use futures::{StreamExt, stream};
+use grpc::SuiGrpcClient;
+use rpc::{
+    MAX_DIGESTS_PER_CALL, RpcConfig, json_u64, latest_checkpoint,
+    objects::{ObjectOptions, multi_get_objects},
+    rpc_request,
+};
+use serde_json::{Value, json};
+use store::ChainStore;
+
+use crate::{
+    context::Context,
+    output::{OutputFormat, Table},
+    store::written_objects,
+};
+
+/// Give up walking back after this many checkpoints without enough objects
+const MAX_CHECKPOINTS: u64 = 10_000;
+
+/// Fields of each checkpoint `stats epochs` aggregates
+const EPOCH_CHECKPOINT_FIELDS: &str = "sequence_number,summary.timestamp,\
+    transactions.transaction.sender,transactions.effects.gas_used";
+
+/// Report progress through an epoch every this many checkpoints
+const PROGRESS_EVERY: u64 = 10_000;
+
+#[derive(Subcommand)]
+pub enum StatsCommands {
+    /// Distribution of a sample of live objects by type or owner kind, with sizes.
+    ///
+    /// The sample is the objects most recently written on chain, in their
+    /// current state, or a uniform random sample of a local store (--db).
+    Objects {
+        /// Number of objects to sample
+        #[arg(long, value_name = "N", default_value = "1000")]
+        sample: usize,
+        /// Group objects by
+        #[arg(long, value_enum, default_value = "type")]
+        by: GroupBy,
+        /// Only show the largest N groups
+        #[arg(long, value_name = "N", default_value = "20")]
+        top: usize,
+        /// Sample a local store (see `suix store sync --objects`) instead of the network
+        #[arg(long, value_name = "PATH")]
+        db: Option<PathBuf>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Totals over every checkpoint of an epoch or range of epochs: transactions, gas,
+    /// distinct senders and checkpoint cadence (over gRPC)
+    Epochs {
+        /// First epoch
+        #[arg(value_name = "EPOCH")]
+        from: u64,
+        /// Last epoch [default: EPOCH]; the current epoch counts up to the latest checkpoint
+        #[arg(long, value_name = "EPOCH")]
+        to: Option<u64>,
+        /// Maximum number of checkpoints fetched concurrently
+        #[arg(long, value_name = "COUNT", default_value = "16")]
+        concurrency: usize,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+        /// gRPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+        /// Request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "30")]
+        timeout: u64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum GroupBy {
+    /// Move type, or `package`
+    Type,
+    /// address, object, shared, immutable, ...
+    OwnerKind,
+}
+
+/// Count and sizes of one group of objects
+#[derive(Debug, Default, PartialEq)]
+struct GroupStats {
+    count: u64,
+    total_bytes: u64,
+    max_bytes: u64,
+}
+
+/// Objects grouped by one property
+#[derive(Debug)]
+struct ObjectStats {
+    by: GroupBy,
+    objects: u64,
+    total_bytes: u64,
+    groups: BTreeMap<String, GroupStats>,
+}
+
+impl ObjectStats {
+    fn new(by: GroupBy) -> Self {
+        Self {
+            by,
+            objects: 0,
+            total_bytes: 0,
+            groups: BTreeMap::new(),
+        }
+    }
+
+    /// Add a `sui_getObject` data entry (with type, owner and BCS)
+    fn add(&mut self, object: &Value) {
+        let key = match self.by {
+            GroupBy::Type => object["type"].as_str().unwrap_or("unknown").to_string(),
+            GroupBy::OwnerKind => owner_kind(&object["owner"]),
+        };
+        let size = object_size(object);
+        self.objects += 1;
+        self.total_bytes += size;
+        let group = self.groups.entry(key).or_default();
+        group.count += 1;
+        group.total_bytes += size;
+        group.max_bytes = group.max_bytes.max(size);
+    }
+
+    /// Groups by count, then total size, largest first
+    fn ranked(&self) -> Vec<(&String, &GroupStats)> {
+        let mut ranked: Vec<_> = self.groups.iter().collect();
+        ranked.sort_by(|a, b| {
+            (b.1.count, b.1.total_bytes)
+                .cmp(&(a.1.count, a.1.total_bytes))
+                .then_with(|| a.0.cmp(b.0))
+        });
+        ranked
+    }
+}
+
+/// Totals of the checkpoints of one epoch
+#[derive(Debug, Default)]
+struct EpochStats {
+    epoch: u64,
+    first_checkpoint: Option<u64>,
+    last_checkpoint: Option<u64>,
+    checkpoints: u64,
+    transactions: u64,
+    computation_cost: u64,
+    storage_cost: u64,
+    storage_rebate: u64,
+    senders: HashSet<String>,
+    first_timestamp_ms: Option<i64>,
+    last_timestamp_ms: Option<i64>,
+    max_interval_ms: i64,
+}
+
+impl EpochStats {
+    fn new(epoch: u64) -> Self {
+        Self {
+            epoch,
+            ..Self::default()
+        }
+    }
+
+    /// Add a `GetCheckpoint` response with [`EPOCH_CHECKPOINT_FIELDS`]; checkpoints
+    /// must come in order for the cadence to be right
+    fn add(&mut self, seq: u64, checkpoint: &Value) -> Result<()> {
+        self.first_checkpoint.get_or_insert(seq);
+        self.last_checkpoint = Some(seq);
+        self.checkpoints += 1;
+
+        if let Some(timestamp) = checkpoint["summary"]["timestamp"].as_str() {
+            let timestamp = DateTime::parse_from_rfc3339(timestamp)
+                .map_err(|e| eyre::eyre!("Checkpoint {} has an invalid timestamp: {}", seq, e))?
+                .timestamp_millis();
+            if let Some(previous) = self.last_timestamp_ms {
+                self.max_interval_ms = self.max_interval_ms.max(timestamp - previous);
+            }
+            self.first_timestamp_ms.get_or_insert(timestamp);
+            self.last_timestamp_ms = Some(timestamp);
+        }
+
+        for tx in checkpoint["transactions"].as_array().into_iter().flatten() {
+            self.transactions += 1;
+            if let Some(sender) = tx["transaction"]["sender"].as_str() {
+                self.senders.insert(sender.to_string());
+            }
+            let gas = &tx["effects"]["gasUsed"];
+            let cost = |field: &str| json_u64(&gas[field]).unwrap_or_default();
+            self.computation_cost += cost("computationCost");
+            self.storage_cost += cost("storageCost");
+            self.storage_rebate += cost("storageRebate");
+        }
+        Ok(())
+    }
+
+    /// Computation and storage charged minus storage rebated, in MIST
+    fn gas_used(&self) -> i128 {
+        i128::from(self.computation_cost) + i128::from(self.storage_cost)
+            - i128::from(self.storage_rebate)
+    }
+
+    /// Wall-clock time from the first to the last checkpoint
+    fn duration_ms(&self) -> Option<i64> {
+        Some(self.last_timestamp_ms? - self.first_timestamp_ms?)
+    }
+
+    /// Mean time between consecutive checkpoints
+    fn avg_interval_ms(&self) -> Option<f64> {
+        let intervals = self.checkpoints.checked_sub(1).filter(|n| *n > 0)?;
+        Some(self.duration_ms()? as f64 / intervals as f64)
+    }
+
+    fn tps(&self) -> Option<f64> {
+        let duration = self.duration_ms().filter(|ms| *ms > 0)?;
+        Some(self.transactions as f64 * 1000.0 / duration as f64)
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "epoch": self.epoch,
+            "first_checkpoint": self.first_checkpoint,
+            "last_checkpoint": self.last_checkpoint,
+            "checkpoints": self.checkpoints,
+            "transactions": self.transactions,
+            "distinct_senders": self.senders.len(),
+            "computation_cost": self.computation_cost,
+            "storage_cost": self.storage_cost,
+            "storage_rebate": self.storage_rebate,
+            "gas_used": self.gas_used().to_string(),
+            "duration_ms": self.duration_ms(),
+            "avg_checkpoint_interval_ms": self.avg_interval_ms(),
+            "max_checkpoint_interval_ms": self.max_interval_ms,
+            "tps": self.tps(),
+        })
+    }
+}
+
+/// Aggregate every checkpoint of `epoch`, with at most `concurrency` fetches in flight
+async fn epoch_stats(client: &SuiGrpcClient, epoch: u64, concurrency: usize) -> Result<EpochStats> {
+    let info = client
+        .get_epoch(Some(epoch), Some("epoch,first_checkpoint,last_checkpoint"))
+        .await
+        .map_err(|e| eyre::eyre!(e))?;
+    let first = json_u64(&info["firstCheckpoint"])
+        .ok_or_else(|| eyre::eyre!("Epoch {} has no first checkpoint", epoch))?;
+    let last = match json_u64(&info["lastCheckpoint"]) {
+        Some(last) => last,
+        // The current epoch has no last checkpoint yet
+        None => client
+            .latest_checkpoint_sequence()
+            .await
+            .map_err(|e| eyre::eyre!(e))?,
+    };
+    eprintln!("Epoch {epoch}: checkpoints {first}..={last}");
+
+    let mut stats = EpochStats::new(epoch);
+    let mut checkpoints = stream::iter(first..=last)
+        .map(|seq| async move {
+            let checkpoint = client
+                .get_checkpoint_json(Some(seq), Some(EPOCH_CHECKPOINT_FIELDS))
+                .await;
+            (seq, checkpoint)
+        })
+        .buffered(concurrency);
+    while let Some((seq, checkpoint)) = checkpoints.next().await {
+        let checkpoint = checkpoint.map_err(|e| eyre::eyre!("Checkpoint {}: {}", seq, e))?;
+        stats.add(seq, &checkpoint)?;
+        if (seq + 1 - first) % PROGRESS_EVERY == 0 {
+            eprintln!("Epoch {epoch}: aggregated checkpoints {first}..={seq}");
+        }
+    }
+    Ok(stats)
+}
+
+/// Owner kind of a JSON-RPC owner: `immutable`, `address`, `object`, `shared`, ...
+fn owner_kind(owner: &Value) -> String {
+    let kind = match owner {
+        Value::String(kind) => kind.as_str(),
+        Value::Object(map) => map.keys().next().map_or("unknown", String::as_str),
+        _ => "unknown",
+    };
+    match kind {
+        "AddressOwner" => "address".to_string(),
+        "ObjectOwner" => "object".to_string(),
+        "ConsensusAddressOwner" => "consensus-address".to_string(),
+        other => other.to_lowercase(),
+    }
+}
+
+/// Serialized size in bytes: the Move object contents or every module of a package
+fn object_size(object: &Value) -> u64 {
+    let decoded_len = |b64: &Value| {
+        b64.as_str()
+            .and_then(|s| BASE64.decode(s).ok())
+            .map_or(0, |bytes| bytes.len() as u64)
+    };
+    let bcs = &object["bcs"];
+    match bcs["moduleMap"].as_object() {
+        Some(modules) => modules.values().map(decoded_len).sum(),
+        None => decoded_len(&bcs["bcsBytes"]),
+    }
+}
+
+pub async fn run(cmd: StatsCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        StatsCommands::Objects {
+            sample,
+            by,
+            top,
+            db,
+            format,
+            url,
+        } => {
+            if sample == 0 {
+                bail!("--sample must be greater than 0");
+            }
+            let objects = match db {
+                Some(ref path) => sample_store(&ChainStore::open(path)?, sample)?,
+                None => sample_recent(&ctx.rpc_config(url, false), sample).await?,
+            };
+            let mut stats = ObjectStats::new(by);
+            for object in &objects {
+                stats.add(object);
+            }
+            eprintln!(
+                "{} objects sampled, {} bytes in total",
+                stats.objects, stats.total_bytes
+            );
+
+            let ranked: Vec<_> = stats.ranked().into_iter().take(top).collect();
+            let share = |count: u64| count as f64 * 100.0 / stats.objects.max(1) as f64;
+            if format == OutputFormat::Json {
+                let groups: Vec<Value> = ranked
+                    .iter()
+                    .map(|(key, group)| {
+                        json!({
+                            "group": key,
+                            "count": group.count,
+                            "share_pct": share(group.count),
+                            "total_bytes": group.total_bytes,
+                            "avg_bytes": group.total_bytes / group.count.max(1),
+                            "max_bytes": group.max_bytes,
+                        })
+                    })
+                    .collect();
+                let report = json!({
+                    "objects": stats.objects,
+                    "total_bytes": stats.total_bytes,
+                    "distinct_groups": stats.groups.len(),
+                    "groups": groups,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+
+            let mut table = Table::new([
+                match by {
+                    GroupBy::Type => "type",
+                    GroupBy::OwnerKind => "owner_kind",
+                },
+                "count",
+                "share",
+                "avg_bytes",
+                "max_bytes",
+                "total_bytes",
+            ]);
+            for (key, group) in ranked {
+                table.push_row([
+                    key.clone(),
+                    group.count.to_string(),
+                    format!("{:.1}%", share(group.count)),
+                    (group.total_bytes / group.count.max(1)).to_string(),
+                    group.max_bytes.to_string(),
+                    group.total_bytes.to_string(),
+                ]);
+            }
+            table.print(format)
+        }
+        StatsCommands::Epochs {
+            from,
+            to,
+            concurrency,
+            format,
+            url,
+            timeout,
+        } => {
+            let to = to.unwrap_or(from);
+            if to < from {
+                bail!("--to must not be before EPOCH");
+            }
+            if concurrency == 0 {
+                bail!("--concurrency must be greater than 0");
+            }
+            let client = SuiGrpcClient::new(ctx.grpc_config(url, false, true, timeout))
+                .await
+                .map_err(|e| eyre::eyre!(e))?;
+            let mut epochs = Vec::new();
+            for epoch in from..=to {
+                epochs.push(epoch_stats(&client, epoch, concurrency).await?);
+            }
+
+            if format == OutputFormat::Json {
+                let epochs: Vec<Value> = epochs.iter().map(EpochStats::to_json).collect();
+                println!("{}", serde_json::to_string_pretty(&epochs)?);
+                return Ok(());
+            }
+            let optional =
+                |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{v:.1}"));
+            let mut table = Table::new([
+                "epoch",
+                "checkpoints",
+                "transactions",
+                "senders",
+                "gas_used",
+                "computation_cost",
+                "storage_cost",
+                "storage_rebate",
+                "avg_interval_ms",
+                "max_interval_ms",
+                "tps",
+            ]);
+            for stats in &epochs {
+                table.push_row([
+                    stats.epoch.to_string(),
+                    stats.checkpoints.to_string(),
+                    stats.transactions.to_string(),
+                    stats.senders.len().to_string(),
+                    stats.gas_used().to_string(),
+                    stats.computation_cost.to_string(),
+                    stats.storage_cost.to_string(),
+                    stats.storage_rebate.to_string(),
+                    optional(stats.avg_interval_ms()),
+                    stats.max_interval_ms.to_string(),
+                    optional(stats.tps()),
+                ]);
+            }
+            table.print(format)
+        }
+    }
+}
+
+/// Uniform random sample (reservoir) of the newest version of each stored object
+fn sample_store(store: &ChainStore, sample: usize) -> Result<Vec<Value>> {
+    let mut reservoir = Vec::with_capacity(sample);
+    for (seen, object) in store.latest_objects().enumerate() {
+        let object = object?;
+        if reservoir.len() < sample {
+            reservoir.push(object);
+        } else {
+            let slot = rand::random_range(0..=seen);
+            if slot < sample {
+                reservoir[slot] = object;
+            }
+        }
+    }
+    if reservoir.is_empty() {
+        bail!("The store holds no objects; sync it with `suix store sync --objects`");
+    }
+    Ok(reservoir)
+}
+
+/// Current state of the objects written most recently, walking back from the latest checkpoint
+async fn sample_recent(config: &RpcConfig, sample: usize) -> Result<Vec<Value>> {
+    let latest = latest_checkpoint(config).await?;
+
+    let mut ids: Vec<String> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut oldest = latest;
+    for seq in (latest.saturating_sub(MAX_CHECKPOINTS - 1)..=latest).rev() {
+        if ids.len() >= sample {
+            break;
+        }
+        let checkpoint = rpc_request(config, "sui_getCheckpoint", json!([seq.to_string()])).await?;
+        let digests: Vec<Value> = checkpoint["transactions"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        for chunk in digests.chunks(MAX_DIGESTS_PER_CALL) {
+            let txs = rpc_request(
+                config,
+                "sui_multiGetTransactionBlocks",
+                json!([chunk, { "showEffects": true }]),
+            )
+            .await?;
+            for tx in txs.as_array().into_iter().flatten() {
+                for written in written_objects(tx) {
+                    let id = written["objectId"].as_str().unwrap_or_default();
+                    if ids.len() < sample && seen.insert(id.to_string()) {
+                        ids.push(id.to_string());
+                    }
+                }
+            }
+        }
+        oldest = seq;
+    }
+    eprintln!(
+        "Collected {} objects from checkpoints {oldest}..={latest}",
+        ids.len()
+    );
+
+    let options = ObjectOptions {
+        show_type: true,
+        show_owner: true,
+        show_bcs: true,
+        ..ObjectOptions::default()
+    };
+    let objects = multi_get_objects(config, &ids, &options).await?;
+    // Objects deleted or wrapped since are no longer live
+    Ok(objects
+        .into_iter()
+        .filter_map(|mut entry| match entry["data"].take() {
+            Value::Null => None,
+            data => Some(data),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_stats() {
+        let tx = |sender: &str, computation: &str, storage: &str, rebate: &str| {
+            json!({
+                "transaction": { "sender": sender },
+                "effects": { "gasUsed": {
+                    "computationCost": computation,
+                    "storageCost": storage,
+                    "storageRebate": rebate,
+                } },
+            })
+        };
+        let checkpoints = [
+            json!({
+                "summary": { "timestamp": "2025-01-01T00:00:00Z" },
+                "transactions": [tx("0xa", "1000", "500", "200"), tx("0xb", "1000", "0", "0")],
+            }),
+            json!({ "summary": { "timestamp": "2025-01-01T00:00:00.250Z" } }),
+            json!({
+                "summary": { "timestamp": "2025-01-01T00:00:01Z" },
+                "transactions": [tx("0xa", "2000", "0", "900")],
+            }),
+        ];
+
+        let mut stats = EpochStats::new(7);
+        for (seq, checkpoint) in (100..).zip(&checkpoints) {
+            stats.add(seq, checkpoint).unwrap();
+        }
+        assert_eq!(stats.first_checkpoint, Some(100));
+        assert_eq!(stats.last_checkpoint, Some(102));
+        assert_eq!(stats.transactions, 3);
+        assert_eq!(stats.senders.len(), 2);
+        assert_eq!(stats.gas_used(), 4000 + 500 - 1100);
+        assert_eq!(stats.duration_ms(), Some(1000));
+        assert_eq!(stats.avg_interval_ms(), Some(500.0));
+        assert_eq!(stats.max_interval_ms, 750);
+        assert_eq!(stats.tps(), Some(3.0));
+
+        let report = stats.to_json();
+        assert_eq!(report["distinct_senders"], 2);
+        assert_eq!(report["gas_used"], "3400");
+
+        let invalid = json!({ "summary": { "timestamp": "yesterday" } });
+        assert!(EpochStats::new(7).add(1, &invalid).is_err());
+    }
+
+    #[test]
+    fn test_object_stats() {
+        let coin = json!({
+            "type": "0x2::coin::Coin<0x2::sui::SUI>",
+            "owner": { "AddressOwner": "0xa" },
+            "bcs": { "bcsBytes": BASE64.encode([0u8; 40]) },
+        });
+        let pool = json!({
+            "type": "0x5::pool::Pool",
+            "owner": { "Shared": { "initial_shared_version": 3 } },
+            "bcs": { "bcsBytes": BASE64.encode([0u8; 200]) },
+        });
+        let package = json!({
+            "type": "package",
+            "owner": "Immutable",
+            "bcs": { "moduleMap": { "a": BASE64.encode([0u8; 10]), "b": BASE64.encode([0u8; 5]) } },
+        });
+
+        let mut by_type = ObjectStats::new(GroupBy::Type);
+        for object in [&coin, &coin, &pool, &package] {
+            by_type.add(object);
+        }
+        assert_eq!(by_type.total_bytes, 295);
+        let ranked = by_type.ranked();
+        assert_eq!(ranked[0].0, "0x2::coin::Coin<0x2::sui::SUI>");
+        assert_eq!(
+            ranked[0].1,
+            &GroupStats {
+                count: 2,
+                total_bytes: 80,
+                max_bytes: 40
+            }
+        );
+        assert_eq!(ranked[1].0, "0x5::pool::Pool");
+
+        let mut by_owner = ObjectStats::new(GroupBy::OwnerKind);
+        for object in [&coin, &pool, &package] {
+            by_owner.add(object);
+        }
+        let kinds: Vec<&String> = by_owner.groups.keys().collect();
+        assert_eq!(kinds, ["address", "immutable", "shared"]);
+    }
+}
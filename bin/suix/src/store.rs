@@ -0,0 +1,259 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use eyre::{Result, bail};
+use futures::{StreamExt, TryStreamExt, stream};
+use rpc::{RpcConfig, json_u64, latest_checkpoint, rpc_request};
+use serde_json::{Value, json};
+use store::ChainStore;
+
+use crate::context::Context;
+
+/// `sui_multiGetTransactionBlocks` and `sui_tryMultiGetPastObjects` accept at most 50 entries per call
+const MAX_PER_CALL: usize = 50;
+
+#[derive(Subcommand)]
+pub enum StoreCommands {
+    /// Download checkpoints, transactions and (optionally) objects into a local store
+    Sync {
+        /// Store directory
+        #[arg(long, value_name = "PATH")]
+        db: PathBuf,
+        /// First checkpoint to sync (ignored when resuming past it)
+        #[arg(long, value_name = "SEQ", default_value = "0")]
+        from: u64,
+        /// Last checkpoint to sync (a sequence number or `latest`)
+        #[arg(long, value_name = "SEQ", default_value = "latest")]
+        to: String,
+        /// Also store every object version written by the synced transactions
+        #[arg(long)]
+        objects: bool,
+        /// Maximum number of checkpoints fetched concurrently
+        #[arg(short = 'j', long, value_name = "COUNT", default_value = "8")]
+        concurrency: usize,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+    /// Show what a local store contains
+    Info {
+        /// Store directory
+        #[arg(long, value_name = "PATH")]
+        db: PathBuf,
+    },
+}
+
+/// Everything fetched for one checkpoint
+struct CheckpointData {
+    seq: u64,
+    checkpoint: Value,
+    transactions: Vec<Value>,
+    objects: Vec<Value>,
+}
+
+pub async fn run(cmd: StoreCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        StoreCommands::Sync {
+            db,
+            from,
+            to,
+            objects,
+            concurrency,
+            url,
+        } => {
+            if concurrency == 0 {
+                bail!("--concurrency must be greater than 0");
+            }
+            let store = ChainStore::open(&db)?;
+            // Syncing always talks to the network, even with --local set
+            let config = RpcConfig {
+                local: None,
+                ..ctx.rpc_config(url, false)
+            };
+            sync(&store, &config, from, &to, objects, concurrency).await
+        }
+        StoreCommands::Info { db } => {
+            let store = ChainStore::open(&db)?;
+            let stats = store.stats();
+            println!("Store: {}", db.display());
+            match (store.first_checkpoint()?, store.latest_checkpoint()?) {
+                (Some(first), Some(last)) => println!("Checkpoints: {first}..={last}"),
+                _ => println!("Checkpoints: none"),
+            }
+            println!("Stored checkpoints: {}", stats.checkpoints);
+            println!("Transactions: {}", stats.transactions);
+            println!("Object versions: {}", stats.objects);
+            if let Some(next) = store.next_checkpoint()? {
+                println!("Next sync starts at: {next}");
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn sync(
+    store: &ChainStore,
+    config: &RpcConfig,
+    from: u64,
+    to: &str,
+    objects: bool,
+    concurrency: usize,
+) -> Result<()> {
+    let to = if to == "latest" {
+        latest_checkpoint(config).await?
+    } else {
+        to.parse()
+            .map_err(|_| eyre::eyre!("--to must be a sequence number or `latest`"))?
+    };
+
+    let mut next = match store.next_checkpoint()? {
+        Some(next) if next > from => {
+            eprintln!("Resuming sync at checkpoint {next}");
+            next
+        }
+        _ => from,
+    };
+
+    while next <= to {
+        let end = (next + concurrency as u64 * 4 - 1).min(to);
+        let fetched: Vec<CheckpointData> = stream::iter(next..=end)
+            .map(|seq| fetch_checkpoint(config, seq, objects))
+            .buffered(concurrency)
+            .try_collect()
+            .await?;
+
+        let (mut txs, mut objs) = (0, 0);
+        for data in &fetched {
+            for tx in &data.transactions {
+                let digest = tx["digest"]
+                    .as_str()
+                    .ok_or_else(|| eyre::eyre!("Transaction without digest in {}", data.seq))?;
+                store.put_transaction(digest, tx)?;
+            }
+            for object in &data.objects {
+                let id = object["objectId"].as_str().unwrap_or_default();
+                let version = json_u64(&object["version"])
+                    .ok_or_else(|| eyre::eyre!("Object {} without version", id))?;
+                store.put_object(id, version, object)?;
+            }
+            // Written last so a stored checkpoint implies its contents are stored
+            store.put_checkpoint(data.seq, &data.checkpoint)?;
+            txs += data.transactions.len();
+            objs += data.objects.len();
+        }
+        store.set_next_checkpoint(end + 1)?;
+        eprintln!("Synced checkpoints {next}..={end} ({txs} transactions, {objs} objects)");
+        next = end + 1;
+    }
+
+    eprintln!("✅ Store synced up to checkpoint {to}");
+    Ok(())
+}
+
+async fn fetch_checkpoint(config: &RpcConfig, seq: u64, objects: bool) -> Result<CheckpointData> {
+    let checkpoint = rpc_request(config, "sui_getCheckpoint", json!([seq.to_string()]))
+        .await
+        .map_err(|e| eyre::eyre!("Checkpoint {}: {}", seq, e))?;
+
+    let digests: Vec<Value> = checkpoint["transactions"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let options = json!({
+        "showInput": true,
+        "showRawInput": true,
+        "showEffects": true,
+        "showEvents": true,
+        "showObjectChanges": true,
+        "showBalanceChanges": true,
+    });
+    let mut transactions = Vec::new();
+    for chunk in digests.chunks(MAX_PER_CALL) {
+        let txs = rpc_request(
+            config,
+            "sui_multiGetTransactionBlocks",
+            json!([chunk, options]),
+        )
+        .await?;
+        transactions.extend(txs.as_array().cloned().unwrap_or_default());
+    }
+
+    let mut stored_objects = Vec::new();
+    if objects {
+        let refs: Vec<Value> = transactions.iter().flat_map(written_objects).collect();
+        let options = json!({
+            "showType": true,
+            "showOwner": true,
+            "showPreviousTransaction": true,
+            "showContent": true,
+            "showBcs": true,
+            "showStorageRebate": true,
+        });
+        for chunk in refs.chunks(MAX_PER_CALL) {
+            let response = rpc_request(
+                config,
+                "sui_tryMultiGetPastObjects",
+                json!([chunk, options]),
+            )
+            .await?;
+            stored_objects.extend(
+                response
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|entry| entry["status"] == "VersionFound")
+                    .map(|entry| entry["details"].clone()),
+            );
+        }
+    }
+
+    Ok(CheckpointData {
+        seq,
+        checkpoint,
+        transactions,
+        objects: stored_objects,
+    })
+}
+
+/// `{objectId, version}` of every object a transaction created, mutated or unwrapped
+pub(crate) fn written_objects(tx: &Value) -> Vec<Value> {
+    let effects = &tx["effects"];
+    ["created", "mutated", "unwrapped"]
+        .iter()
+        .flat_map(|kind| effects[kind].as_array().into_iter().flatten())
+        .filter_map(|change| {
+            let reference = &change["reference"];
+            Some(json!({
+                "objectId": reference["objectId"].as_str()?,
+                "version": json_u64(&reference["version"])?.to_string(),
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_written_objects() {
+        let tx = json!({
+            "effects": {
+                "created": [{ "reference": { "objectId": "0xa", "version": 7 } }],
+                "mutated": [{ "reference": { "objectId": "0xb", "version": "7" } }],
+                "deleted": [{ "objectId": "0xc", "version": "7" }],
+            }
+        });
+        assert_eq!(
+            written_objects(&tx),
+            vec![
+                json!({ "objectId": "0xa", "version": "7" }),
+                json!({ "objectId": "0xb", "version": "7" }),
+            ]
+        );
+    }
+}
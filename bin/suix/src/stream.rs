@@ -0,0 +1,212 @@
+use std::{io::Write, time::Duration};
+
+use clap::Subcommand;
+use eyre::Result;
+use rpc::{MAX_DIGESTS_PER_CALL, RpcConfig, json_u64, latest_checkpoint, rpc_request};
+use serde_json::{Value, json};
+
+use crate::context::Context;
+
+#[derive(Subcommand)]
+pub enum StreamCommands {
+    /// Emit NDJSON balance changes of an address as checkpoints are produced
+    BalanceChanges {
+        /// Address whose balance changes are streamed
+        #[arg(long, value_name = "ADDRESS")]
+        address: String,
+        /// Only stream changes of this coin type
+        #[arg(long, value_name = "TYPE")]
+        coin_type: Option<String>,
+        /// First checkpoint to process (defaults to the next one)
+        #[arg(long, value_name = "SEQ")]
+        from: Option<u64>,
+        /// Polling interval in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "1")]
+        interval: u64,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+pub async fn run(cmd: StreamCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        StreamCommands::BalanceChanges {
+            address,
+            coin_type,
+            from,
+            interval,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let filter = BalanceFilter::new(&address, coin_type.as_deref());
+            let interval = Duration::from_secs(interval);
+
+            let mut next = match from {
+                Some(seq) => seq,
+                None => latest_checkpoint(&config).await? + 1,
+            };
+            eprintln!("Streaming balance changes of {address} from checkpoint {next}");
+
+            let mut stdout = std::io::stdout();
+            loop {
+                let latest = match latest_checkpoint(&config).await {
+                    Ok(latest) => latest,
+                    Err(e) => {
+                        eprintln!("Failed to get latest checkpoint: {e}");
+                        tokio::time::sleep(interval).await;
+                        continue;
+                    }
+                };
+                while next <= latest {
+                    match checkpoint_balance_changes(&config, next, &filter).await {
+                        Ok(changes) => {
+                            for change in changes {
+                                writeln!(stdout, "{change}")?;
+                            }
+                            stdout.flush()?;
+                            next += 1;
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to process checkpoint {next}: {e}");
+                            break;
+                        }
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        }
+    }
+}
+
+/// Selects balance changes by owner address and optionally coin type
+struct BalanceFilter {
+    address: String,
+    coin_type: Option<String>,
+}
+
+impl BalanceFilter {
+    fn new(address: &str, coin_type: Option<&str>) -> Self {
+        Self {
+            address: normalize_address(address),
+            coin_type: coin_type.map(normalize_coin_type),
+        }
+    }
+
+    /// NDJSON records for every matching balance change of a transaction
+    fn matches(&self, checkpoint: u64, tx: &Value) -> Vec<Value> {
+        tx["balanceChanges"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|change| {
+                change["owner"]["AddressOwner"]
+                    .as_str()
+                    .is_some_and(|owner| normalize_address(owner) == self.address)
+            })
+            .filter(|change| match self.coin_type {
+                Some(ref coin_type) => change["coinType"]
+                    .as_str()
+                    .is_some_and(|t| &normalize_coin_type(t) == coin_type),
+                None => true,
+            })
+            .map(|change| {
+                json!({
+                    "checkpoint": checkpoint,
+                    "timestamp_ms": json_u64(&tx["timestampMs"]),
+                    "digest": tx["digest"],
+                    "address": self.address,
+                    "coin_type": change["coinType"],
+                    "amount": change["amount"],
+                })
+            })
+            .collect()
+    }
+}
+
+/// Matching balance changes of every transaction in a checkpoint, in order
+async fn checkpoint_balance_changes(
+    config: &RpcConfig,
+    sequence_number: u64,
+    filter: &BalanceFilter,
+) -> Result<Vec<Value>> {
+    let checkpoint = rpc_request(
+        config,
+        "sui_getCheckpoint",
+        json!([sequence_number.to_string()]),
+    )
+    .await?;
+    let digests: Vec<Value> = checkpoint["transactions"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+
+    let mut changes = Vec::new();
+    for chunk in digests.chunks(MAX_DIGESTS_PER_CALL) {
+        let txs = rpc_request(
+            config,
+            "sui_multiGetTransactionBlocks",
+            json!([chunk, { "showBalanceChanges": true }]),
+        )
+        .await?;
+        for tx in txs.as_array().into_iter().flatten() {
+            changes.extend(filter.matches(sequence_number, tx));
+        }
+    }
+    Ok(changes)
+}
+
+/// Lowercase, zero-padded 32-byte hex address
+fn normalize_address(address: &str) -> String {
+    let hex = address.trim_start_matches("0x").to_lowercase();
+    format!("0x{hex:0>64}")
+}
+
+/// Coin type with its package address in canonical form
+fn normalize_coin_type(coin_type: &str) -> String {
+    match coin_type.split_once("::") {
+        Some((address, rest)) => format!("{}::{}", normalize_address(address), rest),
+        None => coin_type.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_coin_type() {
+        assert_eq!(
+            normalize_coin_type("0x2::sui::SUI"),
+            normalize_coin_type(
+                "0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI"
+            )
+        );
+    }
+
+    #[test]
+    fn test_balance_filter() {
+        let tx = json!({
+            "digest": "D1",
+            "timestampMs": "1700000000000",
+            "balanceChanges": [
+                { "owner": { "AddressOwner": "0xa" }, "coinType": "0x2::sui::SUI", "amount": "-10" },
+                { "owner": { "AddressOwner": "0xa" }, "coinType": "0xc::usdc::USDC", "amount": "5" },
+                { "owner": { "AddressOwner": "0xb" }, "coinType": "0x2::sui::SUI", "amount": "10" },
+            ],
+        });
+
+        let all = BalanceFilter::new("0xA", None).matches(7, &tx);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0]["digest"], "D1");
+        assert_eq!(all[0]["checkpoint"], 7);
+
+        let sui = BalanceFilter::new("0xa", Some("0x2::sui::SUI")).matches(7, &tx);
+        assert_eq!(sui.len(), 1);
+        assert_eq!(sui[0]["amount"], "-10");
+    }
+}
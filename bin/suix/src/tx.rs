@@ -0,0 +1,166 @@
+use std::str::FromStr;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use eyre::{Result, bail};
+use rpc::{
+    RpcConfig, json_u64, json_u128,
+    objects::{ObjectOptions, multi_get_objects},
+    rpc_request,
+};
+use serde_json::{Value, json};
+use sui_types::{
+    base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress},
+    crypto::Signature,
+    transaction::TransactionData,
+};
+
+use crate::coin_select::{self, Strategy};
+
+/// Page size used when listing an address's coins
+const COINS_PAGE_SIZE: u64 = 50;
+
+pub const SUI_COIN_TYPE: &str = "0x2::sui::SUI";
+
+pub fn decode_bcs<T: serde::de::DeserializeOwned>(base64: &str) -> Result<T> {
+    let bytes = BASE64
+        .decode(base64.trim())
+        .map_err(|e| eyre::eyre!("Invalid base64: {}", e))?;
+    bcs::from_bytes(&bytes).map_err(|e| eyre::eyre!("Invalid BCS payload: {}", e))
+}
+
+/// Object reference from a JSON-RPC object or coin
+pub fn object_ref(object: &Value) -> Result<ObjectRef> {
+    let id = object["objectId"]
+        .as_str()
+        .or(object["coinObjectId"].as_str())
+        .unwrap_or_default();
+    let digest = object["digest"].as_str().unwrap_or_default();
+    Ok((
+        ObjectID::from_hex_literal(id)
+            .map_err(|e| eyre::eyre!("Invalid object ID {}: {}", id, e))?,
+        SequenceNumber::from_u64(json_u64(&object["version"]).unwrap_or_default()),
+        ObjectDigest::from_str(digest)
+            .map_err(|e| eyre::eyre!("Invalid digest {}: {}", digest, e))?,
+    ))
+}
+
+/// Current references of explicitly chosen gas coins
+pub async fn gas_coin_refs(config: &RpcConfig, ids: &[String]) -> Result<Vec<ObjectRef>> {
+    let objects = multi_get_objects(config, ids, &ObjectOptions::default()).await?;
+    objects
+        .iter()
+        .zip(ids)
+        .map(|(object, id)| {
+            if object["data"].is_null() {
+                bail!("Gas coin {} not found", id);
+            }
+            object_ref(&object["data"])
+        })
+        .collect()
+}
+
+/// Every coin of `coin_type` owned by `owner`
+pub async fn owned_coins(
+    config: &RpcConfig,
+    owner: SuiAddress,
+    coin_type: &str,
+) -> Result<Vec<Value>> {
+    let mut coins = Vec::new();
+    let mut cursor = Value::Null;
+    loop {
+        let page = rpc_request(
+            config,
+            "suix_getCoins",
+            json!([owner.to_string(), coin_type, cursor, COINS_PAGE_SIZE]),
+        )
+        .await?;
+        coins.extend(page["data"].as_array().into_iter().flatten().cloned());
+        if !page["hasNextPage"].as_bool().unwrap_or(false) {
+            break;
+        }
+        cursor = page["nextCursor"].clone();
+    }
+    Ok(coins)
+}
+
+/// The owner's largest SUI coins until they cover the budget
+pub async fn select_gas_coins(
+    config: &RpcConfig,
+    owner: SuiAddress,
+    budget: u64,
+) -> Result<Vec<ObjectRef>> {
+    let coins = owned_coins(config, owner, SUI_COIN_TYPE).await?;
+    let available: u128 = coins.iter().map(coin_select::balance).sum();
+    if available < u128::from(budget) {
+        bail!(
+            "{} has only {} MIST, budget is {}",
+            owner,
+            available,
+            budget
+        );
+    }
+    coin_select::select(&coins, u128::from(budget), Strategy::LargestFirst, None)?
+        .coins
+        .iter()
+        .map(object_ref)
+        .collect()
+}
+
+pub async fn reference_gas_price(config: &RpcConfig) -> Result<u64> {
+    let price = rpc_request(config, "suix_getReferenceGasPrice", json!([])).await?;
+    json_u64(&price).ok_or_else(|| eyre::eyre!("Invalid reference gas price: {}", price))
+}
+
+/// Submit a signed transaction and return the execution response
+pub async fn execute(
+    config: &RpcConfig,
+    data: &TransactionData,
+    signatures: &[Signature],
+    request_type: &str,
+) -> Result<Value> {
+    let signatures: Vec<String> = signatures
+        .iter()
+        .map(|s| BASE64.encode(s.as_ref()))
+        .collect();
+    rpc_request(
+        config,
+        "sui_executeTransactionBlock",
+        json!([
+            BASE64.encode(bcs::to_bytes(data)?),
+            signatures,
+            { "showEffects": true },
+            request_type,
+        ]),
+    )
+    .await
+}
+
+/// Dry-run a transaction and return its effects
+pub async fn dry_run(config: &RpcConfig, data: &TransactionData) -> Result<Value> {
+    let response = rpc_request(
+        config,
+        "sui_dryRunTransactionBlock",
+        json!([BASE64.encode(bcs::to_bytes(data)?)]),
+    )
+    .await?;
+    Ok(response["effects"].clone())
+}
+
+/// Net gas cost in MIST (computation + storage - rebate) from transaction effects
+pub fn gas_cost(effects: &Value) -> i128 {
+    let used = &effects["gasUsed"];
+    let field = |name: &str| json_u128(&used[name]).unwrap_or_default() as i128;
+    field("computationCost") + field("storageCost") - field("storageRebate")
+}
+
+/// Effects of a transaction if it has been executed, e.g. after a lost response
+pub async fn executed_effects(config: &RpcConfig, digest: &str) -> Option<Value> {
+    let tx = rpc_request(
+        config,
+        "sui_getTransactionBlock",
+        json!([digest, { "showEffects": true }]),
+    )
+    .await
+    .ok()?;
+    Some(tx["effects"].clone()).filter(|effects| !effects.is_null())
+}
@@ -0,0 +1,515 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use clap::{Args, ValueEnum};
+use eyre::Result;
+use rpc::{client::RpcClient, coins::format_amount, json_u64};
+use serde_json::{Value, json};
+
+use crate::{
+    context::Context,
+    output::{OutputFormat, Table},
+};
+
+/// SUI has 9 decimals (1 SUI = 10^9 MIST)
+const SUI_DECIMALS: u8 = 9;
+
+#[derive(Args)]
+pub struct ValidatorsArgs {
+    /// Epoch to report [default: the current epoch]. Past epochs come from
+    /// `suix_getEpochs`, without APY, reports or at-risk status.
+    #[arg(long, value_name = "EPOCH")]
+    epoch: Option<u64>,
+
+    /// Column to sort validators by (descending, except for name)
+    #[arg(long, value_enum, default_value = "stake")]
+    sort: SortKey,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "table")]
+    format: OutputFormat,
+
+    /// RPC endpoint URL
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "https://fullnode.mainnet.sui.io:443"
+    )]
+    url: String,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SortKey {
+    Name,
+    Stake,
+    Commission,
+    Apy,
+    GasPrice,
+    VotingPower,
+}
+
+/// One row of the validator report
+#[derive(Debug)]
+struct ValidatorRow {
+    name: String,
+    address: String,
+    stake: u128,
+    voting_power: u64,
+    commission_bps: u64,
+    next_commission_bps: u64,
+    gas_price: u64,
+    next_gas_price: u64,
+    apy: Option<f64>,
+    /// Only known for the current epoch
+    reported_by: Option<usize>,
+    at_risk_epochs: Option<u64>,
+}
+
+/// Epoch-wide figures printed above the validators
+#[derive(Debug)]
+struct EpochSummary {
+    /// Only known for the current epoch
+    protocol_version: Option<u64>,
+    reference_gas_price: u64,
+    total_stake: u128,
+    /// Only known for past epochs
+    transactions: Option<u64>,
+}
+
+pub async fn run(args: ValidatorsArgs, ctx: &Context) -> Result<()> {
+    let client = RpcClient::new(ctx.rpc_config(args.url, false));
+
+    let state = client.get_latest_sui_system_state().await?;
+    let current_epoch = parse_u64(&state["epoch"]);
+    let epoch = args.epoch.unwrap_or(current_epoch);
+    let committee = client.get_committee_info(Some(epoch)).await?;
+    // Committee entries are (authority name, voting power) keyed by protocol pubkey
+    let power_by_pubkey: HashMap<&str, u64> = committee
+        .validators
+        .iter()
+        .map(|(pubkey, power)| (pubkey.as_str(), *power))
+        .collect();
+
+    let (summary, mut rows) = if epoch == current_epoch {
+        let apys = client.get_validators_apy().await?;
+        let apy_by_address: HashMap<&str, f64> = apys
+            .apys
+            .iter()
+            .map(|(address, apy)| (address.as_str(), *apy))
+            .collect();
+        let reports = pair_map(&state["validatorReportRecords"]);
+        let at_risk = pair_map(&state["atRiskValidators"]);
+        let rows = validator_rows(&state["activeValidators"], &power_by_pubkey)
+            .map(|mut row| {
+                let address = row.address.as_str();
+                row.apy = apy_by_address.get(address).copied();
+                row.reported_by = Some(
+                    reports
+                        .get(address)
+                        .and_then(|v| v.as_array())
+                        .map_or(0, Vec::len),
+                );
+                row.at_risk_epochs = at_risk.get(address).map(|v| parse_u64(v));
+                row
+            })
+            .collect();
+        let summary = EpochSummary {
+            protocol_version: Some(parse_u64(&state["protocolVersion"])),
+            reference_gas_price: parse_u64(&state["referenceGasPrice"]),
+            total_stake: parse_u64(&state["totalStake"]).into(),
+            transactions: None,
+        };
+        (summary, rows)
+    } else {
+        let info = client.get_epoch(epoch).await?;
+        let rows: Vec<ValidatorRow> =
+            validator_rows(&info["validators"], &power_by_pubkey).collect();
+        let summary = EpochSummary {
+            protocol_version: None,
+            reference_gas_price: parse_u64(&info["referenceGasPrice"]),
+            total_stake: rows.iter().map(|r| r.stake).sum(),
+            transactions: json_u64(&info["epochTotalTransactions"]),
+        };
+        (summary, rows)
+    };
+
+    sort_rows(&mut rows, args.sort);
+
+    if args.format == OutputFormat::Json {
+        let validators: Vec<Value> = rows
+            .iter()
+            .map(|r| {
+                json!({
+                    "name": r.name,
+                    "address": r.address,
+                    "stake": r.stake.to_string(),
+                    "voting_power": r.voting_power,
+                    "commission_bps": r.commission_bps,
+                    "next_epoch_commission_bps": r.next_commission_bps,
+                    "gas_price": r.gas_price,
+                    "next_epoch_gas_price": r.next_gas_price,
+                    "apy": r.apy,
+                    "reported_by": r.reported_by,
+                    "at_risk_epochs": r.at_risk_epochs,
+                })
+            })
+            .collect();
+        let report = json!({
+            "epoch": epoch,
+            "current_epoch": current_epoch,
+            "protocol_version": summary.protocol_version,
+            "reference_gas_price": summary.reference_gas_price,
+            "total_stake": summary.total_stake.to_string(),
+            "transactions": summary.transactions,
+            "validators": validators,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if args.format == OutputFormat::Table {
+        let mut header = format!("Epoch {epoch}");
+        if let Some(version) = summary.protocol_version {
+            header.push_str(&format!(" | protocol v{version}"));
+        }
+        header.push_str(&format!(
+            " | reference gas price {} | total stake {} SUI",
+            summary.reference_gas_price,
+            format_amount(summary.total_stake, SUI_DECIMALS),
+        ));
+        if let Some(transactions) = summary.transactions {
+            header.push_str(&format!(" | {transactions} transactions"));
+        }
+        println!("{header}");
+        println!();
+    }
+
+    let mut table = Table::new([
+        "name",
+        "address",
+        "stake_sui",
+        "voting_power",
+        "commission",
+        "next_commission",
+        "gas_price",
+        "next_gas_price",
+        "apy",
+        "reported_by",
+        "at_risk",
+    ]);
+    for r in &rows {
+        table.push_row([
+            r.name.clone(),
+            r.address.clone(),
+            format_amount(r.stake, SUI_DECIMALS),
+            r.voting_power.to_string(),
+            format_bps(r.commission_bps),
+            format_bps(r.next_commission_bps),
+            r.gas_price.to_string(),
+            r.next_gas_price.to_string(),
+            r.apy
+                .map(|a| format!("{:.2}%", a * 100.0))
+                .unwrap_or_default(),
+            r.reported_by.map(|n| n.to_string()).unwrap_or_default(),
+            r.at_risk_epochs.map(|e| e.to_string()).unwrap_or_default(),
+        ]);
+    }
+    table.print(args.format)
+}
+
+/// Rows for validator summaries as found in the system state or an epoch's info,
+/// with the committee's voting power; APY, reports and risk are left unknown
+fn validator_rows<'a>(
+    validators: &'a Value,
+    power_by_pubkey: &'a HashMap<&str, u64>,
+) -> impl Iterator<Item = ValidatorRow> + 'a {
+    validators
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|v| ValidatorRow {
+            name: v["name"].as_str().unwrap_or_default().to_string(),
+            address: v["suiAddress"].as_str().unwrap_or_default().to_string(),
+            stake: parse_u64(&v["stakingPoolSuiBalance"]).into(),
+            voting_power: v["protocolPubkeyBytes"]
+                .as_str()
+                .and_then(|pk| power_by_pubkey.get(pk).copied())
+                .unwrap_or_else(|| parse_u64(&v["votingPower"])),
+            commission_bps: parse_u64(&v["commissionRate"]),
+            next_commission_bps: parse_u64(&v["nextEpochCommissionRate"]),
+            gas_price: parse_u64(&v["gasPrice"]),
+            next_gas_price: parse_u64(&v["nextEpochGasPrice"]),
+            apy: None,
+            reported_by: None,
+            at_risk_epochs: None,
+        })
+}
+
+/// Print the stakes of `owner` for `json-rpc-quick stakes`, with each validator's
+/// name and current APY
+pub async fn print_stakes(client: &RpcClient, owner: &str, format: OutputFormat) -> Result<()> {
+    let stakes = client.get_stakes(owner).await?;
+    let state = client.get_latest_sui_system_state().await?;
+    let apys = client.get_validators_apy().await?;
+    let names = validator_names(&state);
+    let apy_by_address: HashMap<&str, f64> = apys
+        .apys
+        .iter()
+        .map(|(address, apy)| (address.as_str(), *apy))
+        .collect();
+
+    if format == OutputFormat::Json {
+        let stakes: Vec<Value> = stakes
+            .iter()
+            .map(|delegated| {
+                let address = delegated.validator_address.as_str();
+                json!({
+                    "validator": names.get(address),
+                    "validator_address": address,
+                    "staking_pool": delegated.staking_pool,
+                    "apy": apy_by_address.get(address),
+                    "stakes": delegated.stakes.iter().map(|stake| json!({
+                        "staked_sui_id": stake.staked_sui_id,
+                        "status": stake.status,
+                        "principal": stake.principal.to_string(),
+                        "estimated_reward": stake.estimated_reward.map(|r| r.to_string()),
+                        "stake_request_epoch": stake.stake_request_epoch,
+                        "stake_active_epoch": stake.stake_active_epoch,
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&stakes)?);
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        "validator",
+        "staked_sui_id",
+        "status",
+        "principal_sui",
+        "reward_sui",
+        "active_epoch",
+        "apy",
+    ]);
+    let (mut principal, mut reward) = (0u128, 0u128);
+    for delegated in &stakes {
+        let address = delegated.validator_address.as_str();
+        for stake in &delegated.stakes {
+            principal += u128::from(stake.principal);
+            reward += u128::from(stake.estimated_reward.unwrap_or_default());
+            table.push_row([
+                names
+                    .get(address)
+                    .cloned()
+                    .unwrap_or_else(|| address.to_string()),
+                stake.staked_sui_id.clone(),
+                stake.status.clone(),
+                format_amount(stake.principal.into(), SUI_DECIMALS),
+                stake
+                    .estimated_reward
+                    .map(|r| format_amount(r.into(), SUI_DECIMALS))
+                    .unwrap_or_default(),
+                stake.stake_active_epoch.to_string(),
+                apy_by_address
+                    .get(address)
+                    .map(|a| format!("{:.2}%", a * 100.0))
+                    .unwrap_or_default(),
+            ]);
+        }
+    }
+    table.print(format)?;
+    if format == OutputFormat::Table {
+        println!();
+        println!(
+            "Total: {} SUI staked, {} SUI estimated rewards (epoch {})",
+            format_amount(principal, SUI_DECIMALS),
+            format_amount(reward, SUI_DECIMALS),
+            parse_u64(&state["epoch"]),
+        );
+    }
+    Ok(())
+}
+
+/// Print the APY of every active validator for `json-rpc-quick validators-apy`,
+/// highest first
+pub async fn print_validators_apy(client: &RpcClient, format: OutputFormat) -> Result<()> {
+    let mut apys = client.get_validators_apy().await?;
+    let state = client.get_latest_sui_system_state().await?;
+    let names = validator_names(&state);
+    apys.apys.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    if format == OutputFormat::Json {
+        let validators: Vec<Value> = apys
+            .apys
+            .iter()
+            .map(|(address, apy)| json!({ "name": names.get(address), "address": address, "apy": apy }))
+            .collect();
+        let report = json!({ "epoch": apys.epoch, "apys": validators });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    let mut table = Table::new(["name", "address", "apy"]);
+    for (address, apy) in &apys.apys {
+        table.push_row([
+            names.get(address).cloned().unwrap_or_default(),
+            address.clone(),
+            format!("{:.2}%", apy * 100.0),
+        ]);
+    }
+    table.print(format)
+}
+
+/// Print the committee of `epoch` (the current one when `None`) for
+/// `json-rpc-quick committee`, largest voting power first
+pub async fn print_committee(
+    client: &RpcClient,
+    epoch: Option<u64>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut committee = client.get_committee_info(epoch).await?;
+    let state = client.get_latest_sui_system_state().await?;
+    committee
+        .validators
+        .sort_by_key(|(_, power)| Reverse(*power));
+    // Only validators still active can be named
+    let by_pubkey: HashMap<&str, (&str, &str)> = state["activeValidators"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| {
+            Some((
+                v["protocolPubkeyBytes"].as_str()?,
+                (v["name"].as_str()?, v["suiAddress"].as_str()?),
+            ))
+        })
+        .collect();
+    let total: u64 = committee.validators.iter().map(|(_, power)| power).sum();
+
+    if format == OutputFormat::Json {
+        let validators: Vec<Value> = committee
+            .validators
+            .iter()
+            .map(|(pubkey, power)| {
+                let (name, address) = by_pubkey.get(pubkey.as_str()).copied().unzip();
+                json!({
+                    "name": name,
+                    "address": address,
+                    "protocol_pubkey": pubkey,
+                    "voting_power": power,
+                })
+            })
+            .collect();
+        let report = json!({ "epoch": committee.epoch, "validators": validators });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    if format == OutputFormat::Table {
+        println!(
+            "Epoch {} | {} validators | total voting power {total}",
+            committee.epoch,
+            committee.validators.len()
+        );
+        println!();
+    }
+    let mut table = Table::new(["name", "address", "voting_power", "share"]);
+    for (pubkey, power) in &committee.validators {
+        let (name, address) = by_pubkey.get(pubkey.as_str()).copied().unwrap_or_default();
+        table.push_row([
+            name.to_string(),
+            address.to_string(),
+            power.to_string(),
+            format_bps(power * 10_000 / total.max(1)),
+        ]);
+    }
+    table.print(format)
+}
+
+/// Names of the active validators by address, from the system state
+fn validator_names(state: &Value) -> HashMap<String, String> {
+    state["activeValidators"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| {
+            Some((
+                v["suiAddress"].as_str()?.to_string(),
+                v["name"].as_str()?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+fn sort_rows(rows: &mut [ValidatorRow], key: SortKey) {
+    match key {
+        SortKey::Name => rows.sort_by_key(|r| r.name.to_lowercase()),
+        SortKey::Stake => rows.sort_by_key(|r| Reverse(r.stake)),
+        SortKey::Commission => rows.sort_by_key(|r| Reverse(r.commission_bps)),
+        SortKey::Apy => rows.sort_by(|a, b| b.apy.unwrap_or(0.0).total_cmp(&a.apy.unwrap_or(0.0))),
+        SortKey::GasPrice => rows.sort_by_key(|r| Reverse(r.gas_price)),
+        SortKey::VotingPower => rows.sort_by_key(|r| Reverse(r.voting_power)),
+    }
+}
+
+/// Convert `[[key, value], ...]` pairs (as used by VecMap fields) into a map
+fn pair_map(value: &Value) -> HashMap<&str, &Value> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|pair| Some((pair.get(0)?.as_str()?, pair.get(1)?)))
+        .collect()
+}
+
+/// Read a u64 field, treating missing or malformed values as zero
+fn parse_u64(value: &Value) -> u64 {
+    json_u64(value).unwrap_or_default()
+}
+
+/// Commission rates are expressed in basis points
+fn format_bps(bps: u64) -> String {
+    format!("{:.2}%", bps as f64 / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pair_map() {
+        let value = json!([["0x1", "3"], ["0x2", ["0x3"]]]);
+        let map = pair_map(&value);
+        assert_eq!(parse_u64(map["0x1"]), 3);
+        assert_eq!(map["0x2"].as_array().map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn test_validator_rows() {
+        let validators = json!([
+            {
+                "name": "a",
+                "suiAddress": "0xa",
+                "protocolPubkeyBytes": "pk1",
+                "stakingPoolSuiBalance": "5000",
+                "votingPower": "1",
+                "commissionRate": "200",
+                "gasPrice": "750",
+            },
+            { "name": "b", "suiAddress": "0xb", "protocolPubkeyBytes": "pk2", "votingPower": "7" },
+        ]);
+        let power = HashMap::from([("pk1", 6000)]);
+        let rows: Vec<ValidatorRow> = validator_rows(&validators, &power).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].stake, 5000);
+        assert_eq!(rows[0].voting_power, 6000);
+        assert_eq!(rows[0].commission_bps, 200);
+        assert_eq!(rows[1].voting_power, 7);
+        assert_eq!(rows[1].apy, None);
+        assert_eq!(rows[1].reported_by, None);
+    }
+
+    #[test]
+    fn test_format_bps() {
+        assert_eq!(format_bps(200), "2.00%");
+        assert_eq!(format_bps(1025), "10.25%");
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::Subcommand;
+use eyre::{Result, bail};
+use keystore::{
+    Keystore,
+    vault::{Vault, VaultKey},
+};
+use sui_types::{
+    base_types::SuiAddress,
+    crypto::{EncodeDecodeBase64, SuiKeyPair},
+};
+
+use crate::context::Context;
+
+/// Environment variable consulted before prompting for the vault password
+const PASSWORD_ENV: &str = "SUIX_VAULT_PASSWORD";
+
+/// How long `unlock` waits for the agent it started to answer
+const AGENT_START_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Subcommand)]
+pub enum VaultCommands {
+    /// Create an empty vault
+    Init,
+    /// Add a key, either imported from a Sui keystore or pasted at the prompt
+    Add {
+        /// Import this address or alias from the Sui keystore
+        #[arg(long, value_name = "ADDRESS|ALIAS")]
+        from_keystore: Option<String>,
+        /// Sui keystore to import from
+        #[arg(long, value_name = "PATH", requires = "from_keystore")]
+        keystore: Option<PathBuf>,
+        /// Alias for the key inside the vault
+        #[arg(long)]
+        alias: Option<String>,
+    },
+    /// List the addresses and aliases in the vault
+    List,
+    /// Start a session so signing commands do not prompt for the password.
+    ///
+    /// A background agent process keeps the key in memory until the session ends.
+    Unlock {
+        /// Session length in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "900")]
+        timeout: u64,
+    },
+    /// End the unlock session
+    Lock,
+    /// Unlock agent started by `unlock`; reads the vault key from stdin
+    #[command(hide = true)]
+    Agent {
+        #[arg(long, value_name = "SECONDS")]
+        timeout: u64,
+        #[arg(long, value_name = "PATH")]
+        path: PathBuf,
+    },
+}
+
+pub async fn run(cmd: VaultCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        VaultCommands::Init => {
+            let path = ctx.vault_path();
+            let password = read_password("New vault password: ")?;
+            if std::env::var_os(PASSWORD_ENV).is_none()
+                && read_password("Repeat password: ")? != password
+            {
+                bail!("Passwords do not match");
+            }
+            if password.is_empty() {
+                bail!("The vault password must not be empty");
+            }
+            let vault = Vault::init(&path, &password)?;
+            println!("✅ Created vault {}", vault.path().display());
+            Ok(())
+        }
+        VaultCommands::Add {
+            from_keystore,
+            keystore,
+            alias,
+        } => {
+            let mut vault = Vault::open(ctx.vault_path())?;
+            let key = unlock_key(&vault)?;
+
+            let (keypair, alias) = match from_keystore {
+                Some(address_or_alias) => {
+                    let keystore = Keystore::load(keystore.unwrap_or_else(Keystore::default_path))?;
+                    let entry = keystore.get(&address_or_alias)?;
+                    (entry.keypair.copy(), alias.or_else(|| entry.alias.clone()))
+                }
+                None => {
+                    let encoded = rpassword::prompt_password("Base64 private key (flag || key): ")?;
+                    let keypair = SuiKeyPair::decode_base64(encoded.trim())
+                        .map_err(|e| eyre::eyre!("Invalid key: {}", e))?;
+                    (keypair, alias)
+                }
+            };
+
+            let address = SuiAddress::from(&keypair.public());
+            vault.add(&key, &keypair, alias)?;
+            println!("✅ Added {address} to {}", vault.path().display());
+            Ok(())
+        }
+        VaultCommands::List => {
+            let vault = Vault::open(ctx.vault_path())?;
+            let keystore = vault.keystore(&unlock_key(&vault)?)?;
+            for entry in keystore.entries() {
+                match entry.alias {
+                    Some(ref alias) => println!("{}  {}", entry.address, alias),
+                    None => println!("{}", entry.address),
+                }
+            }
+            Ok(())
+        }
+        VaultCommands::Unlock { timeout } => {
+            let vault = Vault::open(ctx.vault_path())?;
+            let password = read_password("Vault password: ")?;
+            let key = vault.derive_key(&password)?;
+            vault.verify(&key)?;
+            vault.lock()?;
+            start_agent(&vault, &key, timeout)?;
+            println!("🔓 Vault unlocked for {timeout} seconds");
+            Ok(())
+        }
+        VaultCommands::Lock => {
+            let vault = Vault::open(ctx.vault_path())?;
+            vault.lock()?;
+            println!("🔒 Vault locked");
+            Ok(())
+        }
+        VaultCommands::Agent { timeout, path } => {
+            let vault = Vault::open(path)?;
+            let key = VaultKey::read_from(&mut std::io::stdin().lock())?;
+            vault.serve_session(&key, Duration::from_secs(timeout))
+        }
+    }
+}
+
+/// Start `suix vault agent` in the background with `key` and wait until it serves it
+fn start_agent(vault: &Vault, key: &VaultKey, timeout: u64) -> Result<()> {
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args([
+            "vault",
+            "agent",
+            "--timeout",
+            &timeout.to_string(),
+            "--path",
+        ])
+        .arg(vault.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Out of the terminal's process group, so Ctrl-C in the shell does not end it
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    let mut agent = command
+        .spawn()
+        .map_err(|e| eyre::eyre!("Failed to start the unlock agent: {}", e))?;
+    if let Some(mut stdin) = agent.stdin.take() {
+        key.write_to(&mut stdin)?;
+    }
+
+    let deadline = Instant::now() + AGENT_START_TIMEOUT;
+    while vault.session_key().is_none() {
+        if let Some(status) = agent.try_wait()? {
+            bail!("The unlock agent exited with {}", status);
+        }
+        if Instant::now() >= deadline {
+            let _ = agent.kill();
+            bail!("The unlock agent did not start");
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    Ok(())
+}
+
+/// Key of the current unlock session, or derived from a prompted password
+pub fn unlock_key(vault: &Vault) -> Result<VaultKey> {
+    match vault.session_key() {
+        Some(key) => Ok(key),
+        None => vault.derive_key(&read_password("Vault password: ")?),
+    }
+}
+
+/// Password from `SUIX_VAULT_PASSWORD`, or read from the terminal without echo
+fn read_password(prompt: &str) -> Result<String> {
+    if let Ok(password) = std::env::var(PASSWORD_ENV) {
+        return Ok(password);
+    }
+    rpassword::prompt_password(prompt).map_err(|e| eyre::eyre!("Failed to read password: {}", e))
+}
@@ -0,0 +1,134 @@
+use std::time::{Duration, Instant};
+
+use clap::{Subcommand, ValueEnum};
+use eyre::Result;
+use rpc::{json_u64, rpc_request};
+use serde_json::{Value, json};
+
+use crate::context::Context;
+
+/// Exit code when the transaction executed but failed (e.g. a Move abort)
+pub(crate) const EXIT_FAILED: i32 = 3;
+/// Exit code when the requested state was not reached in time
+pub(crate) const EXIT_TIMEOUT: i32 = 4;
+
+#[derive(Subcommand)]
+pub enum WaitCommands {
+    /// Wait for a transaction to execute or be checkpointed, then print its effects.
+    ///
+    /// Exits 0 on success, 3 if the transaction failed and 4 on timeout.
+    Tx {
+        /// Transaction digest
+        digest: String,
+        /// State to wait for
+        #[arg(long = "for", value_enum, default_value = "checkpointed")]
+        state: State,
+        /// Give up after this many seconds
+        #[arg(long, value_name = "SECONDS", default_value = "60")]
+        timeout: u64,
+        /// Polling interval in milliseconds
+        #[arg(long, value_name = "MS", default_value = "500")]
+        interval: u64,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum State {
+    /// Effects are known to the fullnode
+    Executed,
+    /// Included in a certified checkpoint (final)
+    Checkpointed,
+}
+
+pub async fn run(cmd: WaitCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        WaitCommands::Tx {
+            digest,
+            state,
+            timeout,
+            interval,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let start = Instant::now();
+            let timeout = Duration::from_secs(timeout);
+            let options = json!({ "showEffects": true, "showEvents": true });
+
+            let tx = loop {
+                // Unknown digests are reported as errors until the fullnode sees the transaction
+                if let Ok(tx) =
+                    rpc_request(&config, "sui_getTransactionBlock", json!([digest, options])).await
+                    && reached(&tx, state)
+                {
+                    break tx;
+                }
+                if start.elapsed() >= timeout {
+                    eprintln!(
+                        "⏱️  {digest} not {} within {:?}",
+                        state_name(state),
+                        timeout
+                    );
+                    std::process::exit(EXIT_TIMEOUT);
+                }
+                tokio::time::sleep(Duration::from_millis(interval)).await;
+            };
+
+            let elapsed = start.elapsed().as_secs_f64();
+            match json_u64(&tx["checkpoint"]) {
+                Some(checkpoint) => {
+                    eprintln!("{digest} in checkpoint {checkpoint} after {elapsed:.1}s")
+                }
+                None => eprintln!("{digest} executed after {elapsed:.1}s"),
+            }
+            println!("{}", serde_json::to_string_pretty(&tx["effects"])?);
+
+            let status = &tx["effects"]["status"];
+            if status["status"].as_str() != Some("success") {
+                eprintln!(
+                    "❌ Transaction failed: {}",
+                    status["error"].as_str().unwrap_or("unknown error")
+                );
+                std::process::exit(EXIT_FAILED);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Whether a `sui_getTransactionBlock` response shows the requested state
+fn reached(tx: &Value, state: State) -> bool {
+    let executed = tx["effects"].is_object();
+    match state {
+        State::Executed => executed,
+        State::Checkpointed => executed && json_u64(&tx["checkpoint"]).is_some(),
+    }
+}
+
+fn state_name(state: State) -> &'static str {
+    match state {
+        State::Executed => "executed",
+        State::Checkpointed => "checkpointed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reached() {
+        let executed = json!({ "effects": { "status": { "status": "success" } } });
+        let checkpointed = json!({ "effects": {}, "checkpoint": "42" });
+        assert!(reached(&executed, State::Executed));
+        assert!(!reached(&executed, State::Checkpointed));
+        assert!(reached(&checkpointed, State::Checkpointed));
+        assert!(!reached(&json!({ "digest": "D" }), State::Executed));
+    }
+}
@@ -0,0 +1,180 @@
+use std::{collections::BTreeMap, io::Write, time::Duration};
+
+use clap::Subcommand;
+use eyre::Result;
+use rpc::{RpcConfig, json_u64, latest_checkpoint, rpc_request};
+use serde_json::{Value, json};
+
+use crate::{context::Context, hooks::HookArgs};
+
+#[derive(Subcommand)]
+pub enum WatchCommands {
+    /// Report epoch changes: protocol version, reference gas price and validator set diffs.
+    ///
+    /// Only the latest checkpoint is polled; the system state is fetched once
+    /// per epoch boundary. Each change is printed as a JSON line and passed to
+    /// the hooks, if any.
+    Epoch {
+        #[command(flatten)]
+        hooks: HookArgs,
+        /// Polling interval in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "10")]
+        interval: u64,
+        /// RPC endpoint URL
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "https://fullnode.mainnet.sui.io:443"
+        )]
+        url: String,
+    },
+}
+
+/// The parts of the system state compared across epochs
+#[derive(Debug, Clone, PartialEq)]
+struct EpochState {
+    epoch: u64,
+    protocol_version: u64,
+    reference_gas_price: u64,
+    start_ms: u64,
+    /// Validator names by address
+    validators: BTreeMap<String, String>,
+}
+
+impl EpochState {
+    fn from_system_state(state: &Value) -> Self {
+        let field = |name: &str| json_u64(&state[name]).unwrap_or_default();
+        Self {
+            epoch: field("epoch"),
+            protocol_version: field("protocolVersion"),
+            reference_gas_price: field("referenceGasPrice"),
+            start_ms: field("epochStartTimestampMs"),
+            validators: state["activeValidators"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|v| {
+                    (
+                        v["suiAddress"].as_str().unwrap_or_default().to_string(),
+                        v["name"].as_str().unwrap_or_default().to_string(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Payload describing what changed between two epochs
+fn epoch_change(previous: &EpochState, current: &EpochState) -> Value {
+    let validators = |from: &EpochState, to: &EpochState| -> Vec<Value> {
+        to.validators
+            .iter()
+            .filter(|(address, _)| !from.validators.contains_key(*address))
+            .map(|(address, name)| json!({ "address": address, "name": name }))
+            .collect()
+    };
+    let change = |from: u64, to: u64| json!({ "from": from, "to": to, "changed": from != to });
+    json!({
+        "type": "epoch_change",
+        "epoch": current.epoch,
+        "previous_epoch": previous.epoch,
+        "epoch_start_timestamp_ms": current.start_ms,
+        "protocol_version": change(previous.protocol_version, current.protocol_version),
+        "reference_gas_price": change(previous.reference_gas_price, current.reference_gas_price),
+        "validators": {
+            "count": current.validators.len(),
+            "joined": validators(previous, current),
+            "left": validators(current, previous),
+        },
+    })
+}
+
+pub async fn run(cmd: WatchCommands, ctx: &Context) -> Result<()> {
+    match cmd {
+        WatchCommands::Epoch {
+            hooks,
+            interval,
+            url,
+        } => {
+            let config = ctx.rpc_config(url, false);
+            let interval = Duration::from_secs(interval);
+            let mut previous = system_state(&config).await?;
+            eprintln!(
+                "Watching for the end of epoch {} (protocol version {}, reference gas price {})",
+                previous.epoch, previous.protocol_version, previous.reference_gas_price
+            );
+
+            loop {
+                tokio::time::sleep(interval).await;
+                match checkpoint_epoch(&config).await {
+                    Ok(epoch) if epoch > previous.epoch => {}
+                    Ok(_) => continue,
+                    Err(e) => {
+                        eprintln!("Failed to get latest checkpoint: {e}");
+                        continue;
+                    }
+                }
+                // The node may serve the new checkpoint before its system state
+                let current = match system_state(&config).await {
+                    Ok(current) if current.epoch > previous.epoch => current,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        eprintln!("Failed to get system state: {e}");
+                        continue;
+                    }
+                };
+                let payload = epoch_change(&previous, &current);
+                let mut stdout = std::io::stdout().lock();
+                writeln!(stdout, "{payload}")?;
+                stdout.flush()?;
+                drop(stdout);
+                hooks.fire(&payload).await;
+                previous = current;
+            }
+        }
+    }
+}
+
+async fn system_state(config: &RpcConfig) -> Result<EpochState> {
+    let state = rpc_request(config, "suix_getLatestSuiSystemState", json!([])).await?;
+    Ok(EpochState::from_system_state(&state))
+}
+
+/// Epoch of the latest checkpoint
+async fn checkpoint_epoch(config: &RpcConfig) -> Result<u64> {
+    let seq = latest_checkpoint(config).await?;
+    let checkpoint = rpc_request(config, "sui_getCheckpoint", json!([seq.to_string()])).await?;
+    json_u64(&checkpoint["epoch"]).ok_or_else(|| eyre::eyre!("Checkpoint {} has no epoch", seq))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_change() {
+        let state = |epoch: u64, version: u64, price: u64, validators: &[(&str, &str)]| {
+            EpochState::from_system_state(&json!({
+                "epoch": epoch.to_string(),
+                "protocolVersion": version.to_string(),
+                "referenceGasPrice": price.to_string(),
+                "epochStartTimestampMs": "1700000000000",
+                "activeValidators": validators
+                    .iter()
+                    .map(|(address, name)| json!({ "suiAddress": address, "name": name }))
+                    .collect::<Vec<_>>(),
+            }))
+        };
+        let previous = state(700, 80, 750, &[("0xa", "Alpha"), ("0xb", "Beta")]);
+        let current = state(701, 81, 750, &[("0xb", "Beta"), ("0xc", "Gamma")]);
+        assert_eq!(previous.validators.len(), 2);
+
+        let change = epoch_change(&previous, &current);
+        assert_eq!(change["epoch"], 701);
+        assert_eq!(change["protocol_version"]["changed"], true);
+        assert_eq!(change["reference_gas_price"]["changed"], false);
+        assert_eq!(change["validators"]["joined"][0]["name"], "Gamma");
+        assert_eq!(change["validators"]["left"][0]["address"], "0xa");
+        assert_eq!(change["validators"]["count"], 2);
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use eyre::Result;
+use serde_json::Value;
+
+/// Length of the timestamp prefix stored in front of every cached value
+const TIMESTAMP_LEN: usize = 8;
+
+/// Key of a cached response: (network, method, params)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKey(Vec<u8>);
+
+impl CacheKey {
+    /// `network` identifies the endpoint or chain, `params` is the canonical request parameters
+    pub fn new(network: &str, method: &str, params: &str) -> Self {
+        let mut key = Vec::with_capacity(network.len() + method.len() + params.len() + 2);
+        key.extend_from_slice(network.as_bytes());
+        key.push(0);
+        key.extend_from_slice(method.as_bytes());
+        key.push(0);
+        key.extend_from_slice(params.as_bytes());
+        Self(key)
+    }
+}
+
+/// Persistent on-disk cache for immutable chain data (finalized checkpoints,
+/// executed transactions, past object versions, published modules).
+///
+/// Callers decide what is safe to cache; the cache only stores bytes with the
+/// time they were written so entries can expire after an optional TTL.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    db: sled::Db,
+    ttl: Option<Duration>,
+}
+
+impl ResponseCache {
+    /// Open (or create) a cache stored in the given directory
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let db = sled::open(path)
+            .map_err(|e| eyre::eyre!("Failed to open cache at {}: {}", path.display(), e))?;
+        Ok(Self { db, ttl: None })
+    }
+
+    /// Open a throwaway cache that is deleted when dropped
+    pub fn temporary() -> Result<Self> {
+        let db = sled::Config::new()
+            .temporary(true)
+            .open()
+            .map_err(|e| eyre::eyre!("Failed to open temporary cache: {}", e))?;
+        Ok(Self { db, ttl: None })
+    }
+
+    /// Default cache location: `$XDG_CACHE_HOME/suix` or `~/.cache/suix`
+    pub fn default_dir() -> PathBuf {
+        if let Some(dir) = std::env::var_os("XDG_CACHE_HOME") {
+            return PathBuf::from(dir).join("suix");
+        }
+        std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir)
+            .join(".cache")
+            .join("suix")
+    }
+
+    /// Treat entries older than `ttl` as missing
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Look up raw bytes, honoring the TTL
+    pub fn get(&self, key: &CacheKey) -> Result<Option<Vec<u8>>> {
+        let Some(entry) = self
+            .db
+            .get(&key.0)
+            .map_err(|e| eyre::eyre!("Cache read failed: {}", e))?
+        else {
+            return Ok(None);
+        };
+
+        if entry.len() < TIMESTAMP_LEN {
+            return Ok(None);
+        }
+        let (stored_at, value) = entry.split_at(TIMESTAMP_LEN);
+        let mut ts = [0u8; TIMESTAMP_LEN];
+        ts.copy_from_slice(stored_at);
+        let stored_at_ms = u64::from_be_bytes(ts);
+
+        if let Some(ttl) = self.ttl
+            && now_ms().saturating_sub(stored_at_ms) > ttl.as_millis() as u64
+        {
+            return Ok(None);
+        }
+        Ok(Some(value.to_vec()))
+    }
+
+    /// Store raw bytes
+    pub fn put(&self, key: &CacheKey, value: &[u8]) -> Result<()> {
+        let mut entry = Vec::with_capacity(TIMESTAMP_LEN + value.len());
+        entry.extend_from_slice(&now_ms().to_be_bytes());
+        entry.extend_from_slice(value);
+        self.db
+            .insert(&key.0, entry)
+            .map_err(|e| eyre::eyre!("Cache write failed: {}", e))?;
+        Ok(())
+    }
+
+    /// Look up a JSON value
+    pub fn get_json(&self, key: &CacheKey) -> Result<Option<Value>> {
+        match self.get(key)? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes).ok()),
+            None => Ok(None),
+        }
+    }
+
+    /// Store a JSON value
+    pub fn put_json(&self, key: &CacheKey, value: &Value) -> Result<()> {
+        self.put(key, &serde_json::to_vec(value)?)
+    }
+
+    /// Number of cached entries
+    pub fn len(&self) -> usize {
+        self.db.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.db.is_empty()
+    }
+
+    /// Remove all cached entries
+    pub fn clear(&self) -> Result<()> {
+        self.db
+            .clear()
+            .map_err(|e| eyre::eyre!("Failed to clear cache: {}", e))
+    }
+}
+
+/// Milliseconds since the Unix epoch
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let cache = ResponseCache::temporary().unwrap();
+        let key = CacheKey::new("mainnet", "sui_getCheckpoint", r#"["1"]"#);
+        assert_eq!(cache.get_json(&key).unwrap(), None);
+
+        cache
+            .put_json(&key, &json!({"sequenceNumber": "1"}))
+            .unwrap();
+        assert_eq!(
+            cache.get_json(&key).unwrap(),
+            Some(json!({"sequenceNumber": "1"}))
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_keys_are_namespaced() {
+        let cache = ResponseCache::temporary().unwrap();
+        let mainnet = CacheKey::new("mainnet", "sui_getCheckpoint", r#"["1"]"#);
+        let testnet = CacheKey::new("testnet", "sui_getCheckpoint", r#"["1"]"#);
+        cache.put(&mainnet, b"a").unwrap();
+        assert_eq!(cache.get(&testnet).unwrap(), None);
+    }
+
+    #[test]
+    fn test_ttl_expiry() {
+        let cache = ResponseCache::temporary()
+            .unwrap()
+            .with_ttl(Some(Duration::ZERO));
+        let key = CacheKey::new("mainnet", "m", "p");
+        cache.put(&key, b"a").unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(cache.get(&key).unwrap(), None);
+    }
+}
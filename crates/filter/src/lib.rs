@@ -0,0 +1,742 @@
+//! Filter expressions shared by event, transaction and object commands.
+//!
+//! ```text
+//! package == 0x2 && type =~ "::swap::" && sender in @addrs.txt
+//! !(module == pool) || digest in [Abc.., Def..]
+//! type == 0x..::pool::Pool && fields.balance > 1000000
+//! ```
+//!
+//! Fields are `package`, `module`, `type`, `sender`, `digest`, `kind`
+//! (transaction kind, e.g. `ProgrammableTransaction`) and `fields.<path>`
+//! (Move content of objects, parsed JSON of events); operators
+//! are `==`, `!=`, `=~` (regex), `in` (a `[..]` list or an `@file` of values)
+//! and `<`, `<=`, `>`, `>=` (numeric when both sides are numbers).
+//! An expression compiles to a JSON-RPC `EventFilter` and a GraphQL event
+//! filter that narrow what the server returns, and is always re-checked
+//! client-side with [`Filter::matches_event`] / [`Filter::matches_transaction`].
+
+use std::{cmp::Ordering, fmt, path::Path};
+
+use eyre::{Result, bail};
+use regex::Regex;
+use serde_json::{Map, Value, json};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Field {
+    Package,
+    Module,
+    Type,
+    Sender,
+    Digest,
+    /// Transaction kind, e.g. `ProgrammableTransaction` or `ConsensusCommitPrologueV3`
+    Kind,
+    /// `fields.a.b`: a path into Move content
+    Content(Vec<String>),
+}
+
+impl Field {
+    fn parse(word: &str) -> Result<Self> {
+        Ok(match word {
+            "package" => Field::Package,
+            "module" => Field::Module,
+            "type" => Field::Type,
+            "sender" => Field::Sender,
+            "digest" => Field::Digest,
+            "kind" => Field::Kind,
+            _ => match word.strip_prefix("fields.") {
+                Some(path) if path.split('.').all(|s| !s.is_empty()) => {
+                    Field::Content(path.split('.').map(str::to_string).collect())
+                }
+                _ => bail!(
+                    "Unknown field `{}` (expected package, module, type, sender, digest, kind or fields.<path>)",
+                    word
+                ),
+            },
+        })
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Field::Package => "package".to_string(),
+            Field::Module => "module".to_string(),
+            Field::Type => "type".to_string(),
+            Field::Sender => "sender".to_string(),
+            Field::Digest => "digest".to_string(),
+            Field::Kind => "kind".to_string(),
+            Field::Content(path) => format!("fields.{}", path.join(".")),
+        }
+    }
+
+    /// Canonical form used for comparisons
+    fn normalize(&self, value: &str) -> String {
+        match self {
+            Field::Package | Field::Sender => normalize_address(value),
+            Field::Type => match value.split_once("::") {
+                Some((address, rest)) => format!("{}::{}", normalize_address(address), rest),
+                None => value.to_string(),
+            },
+            Field::Module | Field::Digest | Field::Kind | Field::Content(_) => value.to_string(),
+        }
+    }
+}
+
+/// Ordering operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Cmp {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Cmp::Lt => ordering.is_lt(),
+            Cmp::Le => ordering.is_le(),
+            Cmp::Gt => ordering.is_gt(),
+            Cmp::Ge => ordering.is_ge(),
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Cmp::Lt => "<",
+            Cmp::Le => "<=",
+            Cmp::Gt => ">",
+            Cmp::Ge => ">=",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Filter {
+    Eq(Field, String),
+    Ne(Field, String),
+    Matches(Field, Regex),
+    In(Field, Vec<String>),
+    Compare(Field, Cmp, String),
+    Not(Box<Filter>),
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+}
+
+impl Filter {
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let filter = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            bail!("Unexpected {} in filter expression", token);
+        }
+        Ok(filter)
+    }
+
+    /// `field == value`, for filters assembled from command-line options
+    pub fn eq(field: Field, value: &str) -> Self {
+        let value = field.normalize(value);
+        Filter::Eq(field, value)
+    }
+
+    /// `field in [values]`, for filters assembled from command-line options
+    pub fn any_of(field: Field, values: &[String]) -> Self {
+        let values = values.iter().map(|v| field.normalize(v)).collect();
+        Filter::In(field, values)
+    }
+
+    /// Whether a JSON-RPC event (`suix_queryEvents` shape) matches
+    pub fn matches_event(&self, event: &Value) -> bool {
+        self.eval(&|field| match field {
+            Field::Package => str_values(&event["packageId"]),
+            Field::Module => str_values(&event["transactionModule"]),
+            Field::Type => str_values(&event["type"]),
+            Field::Sender => str_values(&event["sender"]),
+            Field::Digest => str_values(&event["id"]["txDigest"]),
+            Field::Kind => Vec::new(),
+            Field::Content(path) => json_values(lookup(&event["parsedJson"], path)),
+        })
+    }
+
+    /// Whether a transaction matches: `package`, `module` and `type` compare
+    /// against every event it emitted and every Move call it made
+    pub fn matches_transaction(&self, tx: &Value) -> bool {
+        let events = tx["events"].as_array().cloned().unwrap_or_default();
+        let calls: Vec<&Value> = tx["transaction"]["data"]["transaction"]["transactions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|command| command.get("MoveCall"))
+            .collect();
+        self.eval(&|field| match field {
+            Field::Package => events
+                .iter()
+                .flat_map(|e| str_values(&e["packageId"]))
+                .chain(calls.iter().flat_map(|c| str_values(&c["package"])))
+                .collect(),
+            Field::Module => events
+                .iter()
+                .flat_map(|e| str_values(&e["transactionModule"]))
+                .chain(calls.iter().flat_map(|c| str_values(&c["module"])))
+                .collect(),
+            Field::Type => events.iter().flat_map(|e| str_values(&e["type"])).collect(),
+            Field::Sender => str_values(&tx["transaction"]["data"]["sender"])
+                .into_iter()
+                .chain(str_values(&tx["sender"]))
+                .collect(),
+            Field::Digest => str_values(&tx["digest"]),
+            Field::Kind => str_values(&tx["transaction"]["data"]["transaction"]["kind"]),
+            Field::Content(_) => Vec::new(),
+        })
+    }
+
+    /// Whether an object (`sui_getObject` data with type and content) matches:
+    /// `package` and `module` come from its type, `digest` is the object digest
+    /// and `sender` and `kind` never match
+    pub fn matches_object(&self, object: &Value) -> bool {
+        let object_type = object["type"].as_str().unwrap_or_default();
+        let mut parts = object_type.splitn(3, "::");
+        let (package, module) = (parts.next(), parts.next());
+        self.eval(&|field| match field {
+            Field::Package => package.map(str::to_string).into_iter().collect(),
+            Field::Module => module.map(str::to_string).into_iter().collect(),
+            Field::Type => str_values(&object["type"]),
+            Field::Sender => Vec::new(),
+            Field::Digest => str_values(&object["digest"]),
+            Field::Kind => Vec::new(),
+            Field::Content(path) => json_values(lookup(&object["content"]["fields"], path)),
+        })
+    }
+
+    /// Evaluate against a record exposing zero or more values per field;
+    /// a comparison holds if any value satisfies it
+    fn eval(&self, values: &dyn Fn(&Field) -> Vec<String>) -> bool {
+        let any = |field: &Field, pred: &dyn Fn(&str) -> bool| {
+            values(field).iter().any(|v| pred(&field.normalize(v)))
+        };
+        match self {
+            Filter::Eq(field, expected) => any(field, &|v| v == expected),
+            Filter::Ne(field, expected) => !any(field, &|v| v == expected),
+            Filter::Matches(field, regex) => values(field).iter().any(|v| regex.is_match(v)),
+            Filter::In(field, set) => any(field, &|v| set.iter().any(|s| s == v)),
+            Filter::Compare(field, cmp, bound) => any(field, &|v| {
+                compare_values(v, bound).is_some_and(|ordering| cmp.holds(ordering))
+            }),
+            Filter::Not(inner) => !inner.eval(values),
+            Filter::And(all) => all.iter().all(|f| f.eval(values)),
+            Filter::Or(any_of) => any_of.iter().any(|f| f.eval(values)),
+        }
+    }
+
+    /// Top-level conjuncts
+    fn conjuncts(&self) -> Vec<&Filter> {
+        match self {
+            Filter::And(all) => all.iter().flat_map(Filter::conjuncts).collect(),
+            other => vec![other],
+        }
+    }
+
+    /// The most selective `suix_queryEvents` filter implied by this expression,
+    /// and whether it is exact (no client-side check needed).
+    ///
+    /// Fullnodes only index single criteria, so one equality condition is sent
+    /// and the rest is left to [`Filter::matches_event`].
+    pub fn event_filter(&self) -> (Value, bool) {
+        let conjuncts = self.conjuncts();
+        let eq = |field: Field| {
+            conjuncts.iter().find_map(|c| match c {
+                Filter::Eq(f, value) if *f == field => Some(value.clone()),
+                _ => None,
+            })
+        };
+        let (filter, used) = if let Some(digest) = eq(Field::Digest) {
+            (json!({ "Transaction": digest }), 1)
+        } else if let Some(event_type) = eq(Field::Type) {
+            (json!({ "MoveEventType": event_type }), 1)
+        } else if let (Some(package), Some(module)) = (eq(Field::Package), eq(Field::Module)) {
+            (
+                json!({ "MoveEventModule": { "package": package, "module": module } }),
+                2,
+            )
+        } else if let Some(sender) = eq(Field::Sender) {
+            (json!({ "Sender": sender }), 1)
+        } else {
+            // Every event: the whole expression is checked client-side
+            (
+                json!({ "TimeRange": { "startTime": "0", "endTime": u64::MAX.to_string() } }),
+                0,
+            )
+        };
+        (filter, used == conjuncts.len())
+    }
+
+    /// The `suix_getOwnedObjects` filter implied by this expression, if any
+    pub fn object_filter(&self) -> Option<Value> {
+        let conjuncts = self.conjuncts();
+        let eq = |field: Field| {
+            conjuncts.iter().find_map(|c| match c {
+                Filter::Eq(f, value) if *f == field => Some(value.clone()),
+                _ => None,
+            })
+        };
+        if let Some(object_type) = eq(Field::Type) {
+            return Some(json!({ "StructType": object_type }));
+        }
+        match (eq(Field::Package), eq(Field::Module)) {
+            (Some(package), Some(module)) => {
+                Some(json!({ "MoveModule": { "package": package, "module": module } }))
+            }
+            (Some(package), None) => Some(json!({ "Package": package })),
+            _ => None,
+        }
+    }
+
+    /// GraphQL `EventFilter` input built from the equality conditions
+    pub fn graphql_filter(&self) -> Value {
+        let mut filter = Map::new();
+        let conjuncts = self.conjuncts();
+        let eq = |field: Field| {
+            conjuncts.iter().find_map(|c| match c {
+                Filter::Eq(f, value) if *f == field => Some(value.clone()),
+                _ => None,
+            })
+        };
+        if let Some(sender) = eq(Field::Sender) {
+            filter.insert("sender".to_string(), json!(sender));
+        }
+        if let Some(digest) = eq(Field::Digest) {
+            filter.insert("transactionDigest".to_string(), json!(digest));
+        }
+        if let Some(package) = eq(Field::Package) {
+            let emitting = match eq(Field::Module) {
+                Some(module) => format!("{package}::{module}"),
+                None => package,
+            };
+            filter.insert("emittingModule".to_string(), json!(emitting));
+        }
+        if let Some(event_type) = eq(Field::Type) {
+            filter.insert("eventType".to_string(), json!(event_type));
+        }
+        Value::Object(filter)
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, items: &[Filter], op: &str| {
+            write!(f, "(")?;
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {op} ")?;
+                }
+                write!(f, "{item}")?;
+            }
+            write!(f, ")")
+        };
+        match self {
+            Filter::Eq(field, value) => write!(f, "{} == {value:?}", field.name()),
+            Filter::Ne(field, value) => write!(f, "{} != {value:?}", field.name()),
+            Filter::Matches(field, regex) => write!(f, "{} =~ {:?}", field.name(), regex.as_str()),
+            Filter::In(field, values) => write!(f, "{} in [{} values]", field.name(), values.len()),
+            Filter::Compare(field, cmp, value) => {
+                write!(f, "{} {} {value:?}", field.name(), cmp.symbol())
+            }
+            Filter::Not(inner) => write!(f, "!{inner}"),
+            Filter::And(all) => join(f, all, "&&"),
+            Filter::Or(any) => join(f, any, "||"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    File(String),
+    Op(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(w) => write!(f, "`{w}`"),
+            Token::Str(s) => write!(f, "{s:?}"),
+            Token::File(p) => write!(f, "`@{p}`"),
+            Token::Op(op) => write!(f, "`{op}`"),
+        }
+    }
+}
+
+const OPERATORS: &[&str] = &[
+    "&&", "||", "==", "!=", "=~", "<=", ">=", "<", ">", "!", "(", ")", "[", "]", ",",
+];
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            rest = &rest[op.len()..];
+        } else if let Some(quoted) = rest.strip_prefix('"') {
+            let Some(end) = quoted.find('"') else {
+                bail!("Unterminated string in filter expression");
+            };
+            tokens.push(Token::Str(quoted[..end].to_string()));
+            rest = &quoted[end + 1..];
+        } else {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || "&|=!<>()[],\"".contains(c))
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            tokens.push(match word.strip_prefix('@') {
+                Some(path) => Token::File(path.to_string()),
+                None => Token::Word(word.to_string()),
+            });
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        if matches!(self.tokens.get(self.pos), Some(Token::Op(o)) if *o == op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Result<Filter> {
+        let mut any = vec![self.and()?];
+        while self.eat("||") {
+            any.push(self.and()?);
+        }
+        Ok(if any.len() == 1 {
+            any.remove(0)
+        } else {
+            Filter::Or(any)
+        })
+    }
+
+    fn and(&mut self) -> Result<Filter> {
+        let mut all = vec![self.unary()?];
+        while self.eat("&&") {
+            all.push(self.unary()?);
+        }
+        Ok(if all.len() == 1 {
+            all.remove(0)
+        } else {
+            Filter::And(all)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Filter> {
+        if self.eat("!") {
+            return Ok(Filter::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let inner = self.or()?;
+            if !self.eat(")") {
+                bail!("Missing `)` in filter expression");
+            }
+            return Ok(inner);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Filter> {
+        let field = match self.next() {
+            Some(Token::Word(word)) => Field::parse(&word)?,
+            Some(token) => bail!("Expected a field name, found {}", token),
+            None => bail!("Unexpected end of filter expression"),
+        };
+        match self.next() {
+            Some(Token::Op("==")) => Ok(Filter::Eq(field.clone(), field.normalize(&self.value()?))),
+            Some(Token::Op("!=")) => Ok(Filter::Ne(field.clone(), field.normalize(&self.value()?))),
+            Some(Token::Op(op @ ("<" | "<=" | ">" | ">="))) => {
+                let cmp = match op {
+                    "<" => Cmp::Lt,
+                    "<=" => Cmp::Le,
+                    ">" => Cmp::Gt,
+                    _ => Cmp::Ge,
+                };
+                Ok(Filter::Compare(field, cmp, self.value()?))
+            }
+            Some(Token::Op("=~")) => {
+                let pattern = self.value()?;
+                let regex = Regex::new(&pattern)
+                    .map_err(|e| eyre::eyre!("Invalid regex {:?}: {}", pattern, e))?;
+                Ok(Filter::Matches(field, regex))
+            }
+            Some(Token::Word(word)) if word == "in" => {
+                let values = self.list()?;
+                let values = values.iter().map(|v| field.normalize(v)).collect();
+                Ok(Filter::In(field, values))
+            }
+            Some(token) => bail!(
+                "Expected ==, !=, =~, <, <=, >, >= or in after {}, found {}",
+                field.name(),
+                token
+            ),
+            None => bail!("Unexpected end of filter expression"),
+        }
+    }
+
+    fn value(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Word(value) | Token::Str(value)) => Ok(value),
+            Some(token) => bail!("Expected a value, found {}", token),
+            None => bail!("Unexpected end of filter expression"),
+        }
+    }
+
+    /// `[a, b, ..]` or `@file` with one value per line
+    fn list(&mut self) -> Result<Vec<String>> {
+        if let Some(Token::File(path)) = self.tokens.get(self.pos).cloned() {
+            self.pos += 1;
+            return read_values(Path::new(&path));
+        }
+        if !self.eat("[") {
+            bail!("Expected `[` or `@file` after `in`");
+        }
+        let mut values = Vec::new();
+        while !self.eat("]") {
+            values.push(self.value()?);
+            if !self.eat(",") && !matches!(self.tokens.get(self.pos), Some(Token::Op("]"))) {
+                bail!("Expected `,` or `]` in list");
+            }
+        }
+        Ok(values)
+    }
+}
+
+fn str_values(value: &Value) -> Vec<String> {
+    value.as_str().map(str::to_string).into_iter().collect()
+}
+
+/// Follow a field path through Move content, stepping into the `fields` of
+/// nested structs as `sui_getObject` renders them
+fn lookup<'a>(mut value: &'a Value, path: &[String]) -> &'a Value {
+    for segment in path {
+        value = match value.get(segment) {
+            Some(next) => next,
+            None => &value["fields"][segment],
+        };
+    }
+    value
+}
+
+/// Comparable values of a JSON value; a vector contributes each element
+fn json_values(value: &Value) -> Vec<String> {
+    match value {
+        Value::Null => Vec::new(),
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items.iter().flat_map(json_values).collect(),
+        other => vec![other.to_string()],
+    }
+}
+
+/// Numeric ordering if both sides are numbers (Sui renders u64+ as strings),
+/// text ordering otherwise
+fn compare_values(value: &str, bound: &str) -> Option<Ordering> {
+    if let (Ok(a), Ok(b)) = (value.parse::<u128>(), bound.parse::<u128>()) {
+        return Some(a.cmp(&b));
+    }
+    match (value.parse::<f64>(), bound.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b),
+        (Err(_), Err(_)) => Some(value.cmp(bound)),
+        _ => None,
+    }
+}
+
+/// Values of an `@file` list: one per line, first column, `#` starts a comment
+pub fn read_values(path: &Path) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| eyre::eyre!("Failed to read {}: {}", path.display(), e))?;
+    Ok(parse_values(&content))
+}
+
+fn parse_values(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| line.split([',', ' ', '\t']).next())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Lowercase, zero-padded form of a `0x` address, so short and long forms compare equal
+pub fn normalize_address(address: &str) -> String {
+    match address.strip_prefix("0x") {
+        Some(hex) => format!("0x{:0>64}", hex.to_lowercase()),
+        None => address.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(package: &str, module: &str, event_type: &str, sender: &str) -> Value {
+        json!({
+            "id": { "txDigest": "D1", "eventSeq": "0" },
+            "packageId": package,
+            "transactionModule": module,
+            "type": event_type,
+            "sender": sender,
+        })
+    }
+
+    #[test]
+    fn test_parse_and_match_event() {
+        let filter =
+            Filter::parse(r#"package == 0x2 && type =~ "::swap::" && !(sender in [0xa, 0xb])"#)
+                .unwrap();
+        assert!(filter.matches_event(&event("0x2", "pool", "0x2::swap::Swapped", "0xc")));
+        assert!(!filter.matches_event(&event("0x2", "pool", "0x2::swap::Swapped", "0xa")));
+        assert!(!filter.matches_event(&event("0x3", "pool", "0x3::swap::Swapped", "0xc")));
+        assert!(!filter.matches_event(&event("0x2", "pool", "0x2::coin::Minted", "0xc")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Filter::parse("package ==").is_err());
+        assert!(Filter::parse("owner == 0x1").is_err());
+        assert!(Filter::parse("(sender == 0x1").is_err());
+        assert!(Filter::parse("sender == 0x1 sender").is_err());
+        assert!(Filter::parse(r#"type =~ "[""#).is_err());
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let (filter, exact) = Filter::parse("type == 0x2::coin::Minted")
+            .unwrap()
+            .event_filter();
+        assert_eq!(
+            filter,
+            json!({ "MoveEventType": format!("0x{:0>64}::coin::Minted", "2") })
+        );
+        assert!(exact);
+
+        let (filter, exact) = Filter::parse("package == 0x2 && module == pool && sender != 0x1")
+            .unwrap()
+            .event_filter();
+        assert_eq!(filter["MoveEventModule"]["module"], "pool");
+        assert!(!exact);
+
+        let (filter, exact) = Filter::parse("sender == 0x1 || sender == 0x2")
+            .unwrap()
+            .event_filter();
+        assert!(filter.get("TimeRange").is_some());
+        assert!(!exact);
+    }
+
+    #[test]
+    fn test_graphql_filter() {
+        let filter = Filter::parse("package == 0x2 && module == pool && sender == 0x1").unwrap();
+        let graphql = filter.graphql_filter();
+        assert_eq!(graphql["emittingModule"], format!("0x{:0>64}::pool", "2"));
+        assert_eq!(graphql["sender"], format!("0x{:0>64}", "1"));
+    }
+
+    #[test]
+    fn test_matches_transaction() {
+        let tx = json!({
+            "digest": "D1",
+            "transaction": { "data": {
+                "sender": "0x1",
+                "transaction": { "kind": "ProgrammableTransaction", "transactions": [
+                    { "MoveCall": { "package": "0x9", "module": "router", "function": "swap" } },
+                ] },
+            } },
+            "events": [event("0x2", "pool", "0x2::swap::Swapped", "0x1")],
+        });
+        assert!(
+            Filter::parse("package == 0x9 && sender == 0x1")
+                .unwrap()
+                .matches_transaction(&tx)
+        );
+        assert!(
+            Filter::parse("module == pool")
+                .unwrap()
+                .matches_transaction(&tx)
+        );
+        assert!(
+            !Filter::parse("digest == D2")
+                .unwrap()
+                .matches_transaction(&tx)
+        );
+        assert!(
+            Filter::parse("kind == ProgrammableTransaction")
+                .unwrap()
+                .matches_transaction(&tx)
+        );
+        assert!(Filter::any_of(Field::Sender, &["0x01".to_string()]).matches_transaction(&tx));
+        assert!(!Filter::any_of(Field::Package, &["0x3".to_string()]).matches_transaction(&tx));
+    }
+
+    #[test]
+    fn test_matches_object() {
+        let pool = json!({
+            "objectId": "0xp",
+            "type": "0x5::pool::Pool<0x2::sui::SUI>",
+            "content": { "dataType": "moveObject", "fields": {
+                "balance": "2500000",
+                "fee": { "type": "0x5::pool::Fee", "fields": { "bps": 30 } },
+                "admins": ["0xa", "0xb"],
+            } },
+        });
+        let matches = |expression: &str| Filter::parse(expression).unwrap().matches_object(&pool);
+        assert!(matches(
+            r#"type =~ "::pool::Pool<" && fields.balance > 1000000"#
+        ));
+        assert!(!matches("fields.balance > 2500000"));
+        assert!(matches("fields.balance >= 2500000 && fields.fee.bps < 50"));
+        assert!(matches("package == 0x5 && module == pool"));
+        assert!(matches("fields.admins in [0xb]"));
+        // Missing fields and non-numeric bounds never satisfy a comparison
+        assert!(!matches("fields.missing > 0"));
+        assert!(!matches("fields.balance > abc"));
+    }
+
+    #[test]
+    fn test_object_filter() {
+        let filter = Filter::parse("type == 0x5::pool::Pool && fields.balance > 1").unwrap();
+        assert_eq!(
+            filter.object_filter(),
+            Some(json!({ "StructType": format!("0x{:0>64}::pool::Pool", "5") }))
+        );
+        let filter = Filter::parse("package == 0x5").unwrap();
+        assert!(filter.object_filter().unwrap().get("Package").is_some());
+        assert_eq!(
+            Filter::parse("fields.a <= 3").unwrap().object_filter(),
+            None
+        );
+        assert_eq!(
+            Filter::parse("fields.a.b<=3").unwrap().to_string(),
+            r#"fields.a.b <= "3""#
+        );
+        assert!(Filter::parse("fields. > 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_values() {
+        let content = "0x1 # treasury\n\n# comment\n0x2,ops\n";
+        assert_eq!(parse_values(content), vec!["0x1", "0x2"]);
+    }
+}
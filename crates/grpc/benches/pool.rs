@@ -0,0 +1,58 @@
+//! Bulk object fetch throughput by connection pool size.
+//!
+//! Needs network access: `cargo bench -p grpc --bench pool`, with `SUIX_BENCH_URL`
+//! pointing at another node if desired.
+
+use std::time::{Duration, Instant};
+
+use grpc::{GrpcConfig, SuiGrpcClient};
+use sui_types::base_types::ObjectID;
+
+const REQUESTS: usize = 2000;
+const CONCURRENCY: usize = 256;
+const POOL_SIZES: &[usize] = &[1, 2, 4, 8];
+/// Objects every network has: system state, clock, randomness and deny list
+const OBJECT_IDS: &[&str] = &["0x5", "0x6", "0x8", "0x403"];
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let url = std::env::var("SUIX_BENCH_URL").unwrap_or_else(|_| GrpcConfig::default().url);
+    let ids = OBJECT_IDS
+        .iter()
+        .cycle()
+        .take(REQUESTS)
+        .map(|id| {
+            id.parse()
+                .map_err(|e| anyhow::anyhow!("Invalid object ID {}: {}", id, e))
+        })
+        .collect::<anyhow::Result<Vec<ObjectID>>>()?;
+
+    println!("{REQUESTS} GetObject calls, {CONCURRENCY} in flight, against {url}");
+    for &pool_size in POOL_SIZES {
+        let config = GrpcConfig {
+            url: url.clone(),
+            json: true,
+            timeout: Duration::from_secs(60),
+            pool_size,
+            ..GrpcConfig::default()
+        };
+        let client = SuiGrpcClient::new(config).await?;
+        // Open every connection of the pool before timing
+        client.get_objects(&ids[..pool_size], pool_size).await;
+
+        let started = Instant::now();
+        let results = client.get_objects(&ids, CONCURRENCY).await;
+        let elapsed = started.elapsed();
+        let failed = results.iter().filter(|result| result.is_err()).count();
+        if failed == REQUESTS
+            && let Some(Err(e)) = results.into_iter().next()
+        {
+            anyhow::bail!("Every call failed: {}", e);
+        }
+        println!(
+            "pool size {pool_size}: {:.0} calls/s in {elapsed:.2?} ({failed} failed)",
+            REQUESTS as f64 / elapsed.as_secs_f64()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,235 @@
+//! Read-through SQLite cache of immutable chain data: certified checkpoints and
+//! object versions, keyed by endpoint so several networks can share one file
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use cache::ResponseCache;
+use rusqlite::{Connection, OptionalExtension, Params, params};
+use serde::{Serialize, de::DeserializeOwned};
+use sui_types::{
+    base_types::ObjectID, full_checkpoint_content::CheckpointData,
+    messages_checkpoint::CertifiedCheckpointSummary, object::Object,
+};
+
+/// Values are stored as BCS; a checkpoint row holds its summary and, once fetched, its contents
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS checkpoints (
+        network TEXT NOT NULL,
+        sequence_number INTEGER NOT NULL,
+        summary BLOB,
+        full BLOB,
+        PRIMARY KEY (network, sequence_number)
+    );
+    CREATE TABLE IF NOT EXISTS objects (
+        network TEXT NOT NULL,
+        object_id TEXT NOT NULL,
+        version INTEGER NOT NULL,
+        object BLOB NOT NULL,
+        PRIMARY KEY (network, object_id, version)
+    );
+";
+
+/// Installed through [`GrpcConfig::checkpoint_cache`](crate::GrpcConfig::checkpoint_cache)
+#[derive(Debug, Clone)]
+pub struct CheckpointCache {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl CheckpointCache {
+    /// Open (or create) the cache database at `path`, creating its directory
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path).map_err(|e| {
+            anyhow::anyhow!("Failed to open checkpoint cache {}: {}", path.display(), e)
+        })?;
+        Self::init(conn)
+    }
+
+    /// A cache that lives as long as the process
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| anyhow::anyhow!("Failed to create checkpoint cache tables: {}", e))?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// `checkpoints.db` in the default cache directory
+    pub fn default_path() -> PathBuf {
+        ResponseCache::default_dir().join("checkpoints.db")
+    }
+
+    pub fn checkpoint_summary(
+        &self,
+        network: &str,
+        sequence_number: u64,
+    ) -> Result<Option<CertifiedCheckpointSummary>> {
+        self.checkpoint(network, sequence_number, "summary")
+    }
+
+    pub fn put_checkpoint_summary(
+        &self,
+        network: &str,
+        summary: &CertifiedCheckpointSummary,
+    ) -> Result<()> {
+        self.put_checkpoint(network, *summary.sequence_number(), "summary", summary)
+    }
+
+    pub fn full_checkpoint(
+        &self,
+        network: &str,
+        sequence_number: u64,
+    ) -> Result<Option<CheckpointData>> {
+        self.checkpoint(network, sequence_number, "full")
+    }
+
+    /// Store a full checkpoint, which also answers later lookups of its summary
+    pub fn put_full_checkpoint(&self, network: &str, checkpoint: &CheckpointData) -> Result<()> {
+        let summary = &checkpoint.checkpoint_summary;
+        self.put_checkpoint(network, *summary.sequence_number(), "summary", summary)?;
+        self.put_checkpoint(network, *summary.sequence_number(), "full", checkpoint)
+    }
+
+    pub fn object(
+        &self,
+        network: &str,
+        object_id: &ObjectID,
+        version: u64,
+    ) -> Result<Option<Object>> {
+        self.read(
+            "SELECT object FROM objects WHERE network = ?1 AND object_id = ?2 AND version = ?3",
+            params![network, object_id.to_string(), version as i64],
+        )
+    }
+
+    /// Store an object under its own ID and version
+    pub fn put_object(&self, network: &str, object: &Object) -> Result<()> {
+        self.write(
+            "INSERT OR REPLACE INTO objects (network, object_id, version, object)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                network,
+                object.id().to_string(),
+                object.version().value() as i64,
+                bcs::to_bytes(object)?
+            ],
+        )
+    }
+
+    /// Column `column` of a checkpoint row
+    fn checkpoint<T: DeserializeOwned>(
+        &self,
+        network: &str,
+        sequence_number: u64,
+        column: &str,
+    ) -> Result<Option<T>> {
+        let sql =
+            format!("SELECT {column} FROM checkpoints WHERE network = ?1 AND sequence_number = ?2");
+        self.read(&sql, params![network, sequence_number as i64])
+    }
+
+    /// Set column `column` of a checkpoint row, keeping its other column
+    fn put_checkpoint<T: Serialize>(
+        &self,
+        network: &str,
+        sequence_number: u64,
+        column: &str,
+        value: &T,
+    ) -> Result<()> {
+        let sql = format!(
+            "INSERT INTO checkpoints (network, sequence_number, {column}) VALUES (?1, ?2, ?3)
+             ON CONFLICT (network, sequence_number) DO UPDATE SET {column} = excluded.{column}"
+        );
+        self.write(
+            &sql,
+            params![network, sequence_number as i64, bcs::to_bytes(value)?],
+        )
+    }
+
+    fn read<T: DeserializeOwned>(&self, sql: &str, params: impl Params) -> Result<Option<T>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Checkpoint cache lock poisoned"))?;
+        let bytes: Option<Option<Vec<u8>>> =
+            conn.query_row(sql, params, |row| row.get(0)).optional()?;
+        match bytes.flatten() {
+            Some(bytes) => Ok(Some(bcs::from_bytes(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn write(&self, sql: &str, params: impl Params) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Checkpoint cache lock poisoned"))?;
+        conn.execute(sql, params)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_columns() {
+        let cache = CheckpointCache::in_memory().unwrap();
+        assert_eq!(
+            cache.checkpoint::<u64>("mainnet", 7, "summary").unwrap(),
+            None
+        );
+
+        cache
+            .put_checkpoint("mainnet", 7, "summary", &42u64)
+            .unwrap();
+        assert_eq!(cache.checkpoint::<u64>("mainnet", 7, "full").unwrap(), None);
+        cache
+            .put_checkpoint("mainnet", 7, "full", &"contents".to_string())
+            .unwrap();
+        assert_eq!(
+            cache.checkpoint("mainnet", 7, "summary").unwrap(),
+            Some(42u64)
+        );
+        assert_eq!(
+            cache.checkpoint("mainnet", 7, "full").unwrap(),
+            Some("contents".to_string())
+        );
+        assert_eq!(
+            cache.checkpoint::<u64>("testnet", 7, "summary").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_persistence() {
+        let path = std::env::temp_dir()
+            .join(format!("suix-{}", std::process::id()))
+            .join("checkpoints.db");
+        CheckpointCache::open(&path)
+            .unwrap()
+            .put_checkpoint("mainnet", 1, "summary", &5u64)
+            .unwrap();
+        let reopened = CheckpointCache::open(&path).unwrap();
+        assert_eq!(
+            reopened.checkpoint("mainnet", 1, "summary").unwrap(),
+            Some(5u64)
+        );
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
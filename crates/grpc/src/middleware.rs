@@ -0,0 +1,35 @@
+//! Hooks run around every call a [`SuiGrpcClient`](crate::SuiGrpcClient) makes, so
+//! applications can log, add headers, collect metrics or refuse calls without
+//! wrapping each client method
+
+use std::{fmt, time::Duration};
+
+use anyhow::Result;
+use tonic::metadata::MetadataMap;
+
+/// Installed through [`GrpcConfig::middleware`](crate::GrpcConfig::middleware).
+///
+/// `before_call` hooks run in installation order and `after_call` hooks in reverse,
+/// each once per attempt when a retry policy is configured.
+pub trait Middleware: fmt::Debug + Send + Sync {
+    /// Runs before the call is sent and may change its request metadata. An error
+    /// short-circuits the call, which then fails with it without touching the network.
+    fn before_call(&self, _call: &mut CallInfo) -> Result<()> {
+        Ok(())
+    }
+
+    /// Runs once the call finished, with its duration and error, if any.
+    /// Also runs for calls a `before_call` hook refused.
+    fn after_call(&self, _call: &CallInfo, _elapsed: Duration, _error: Option<&anyhow::Error>) {}
+}
+
+/// A call as seen by [`Middleware`]
+#[derive(Debug, Clone)]
+pub struct CallInfo {
+    /// Fully qualified service, e.g. `sui.rpc.v2.LedgerService`
+    pub service: String,
+    /// Method name, e.g. `GetCheckpoint`
+    pub method: String,
+    /// Request metadata, starting from the configured headers
+    pub metadata: MetadataMap,
+}
@@ -0,0 +1,156 @@
+//! Pacing of the polling fallback used by subscriptions: an interval that adapts to
+//! how fast checkpoints arrive, and a token bucket bounding the request rate
+
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// How subscriptions poll a node for new checkpoints
+#[derive(Debug, Clone)]
+pub struct PollPolicy {
+    /// Shortest wait between polls, reached while checkpoints pile up between polls
+    pub min_interval: Duration,
+    /// Longest wait between polls, reached while no new checkpoints appear
+    pub max_interval: Duration,
+    /// Sustained requests per second while polling and backfilling; 0 disables the limit
+    pub rps: f64,
+    /// Requests that may be sent at once before `rps` applies
+    pub burst: u32,
+}
+
+impl Default for PollPolicy {
+    fn default() -> Self {
+        Self {
+            min_interval: Duration::from_millis(250),
+            max_interval: Duration::from_secs(30),
+            rps: 20.0,
+            burst: 40,
+        }
+    }
+}
+
+/// Wait between polls: halved while each poll finds a backlog, doubled while polls
+/// find nothing, kept while each finds a single checkpoint
+pub(crate) struct AdaptiveInterval {
+    current: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+impl AdaptiveInterval {
+    /// Starting at `initial`, which also widens the policy's bounds if outside them
+    pub(crate) fn new(initial: Duration, policy: &PollPolicy) -> Self {
+        Self {
+            current: initial,
+            min: policy.min_interval.min(initial),
+            max: policy.max_interval.max(initial),
+        }
+    }
+
+    /// Wait before the next poll, given how many new checkpoints the last one found
+    pub(crate) fn after_poll(&mut self, new_checkpoints: u64) -> Duration {
+        self.current = match new_checkpoints {
+            0 => (self.current * 2).min(self.max),
+            1 => self.current,
+            _ => (self.current / 2).max(self.min),
+        };
+        self.current
+    }
+}
+
+/// Token bucket holding up to `burst` requests, refilled at `rps`
+pub(crate) struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    rate: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(policy: &PollPolicy) -> Self {
+        let capacity = f64::from(policy.burst.max(1));
+        Self {
+            capacity,
+            tokens: capacity,
+            rate: policy.rps,
+            updated: Instant::now(),
+        }
+    }
+
+    /// Wait until a request may be sent
+    pub(crate) async fn acquire(&mut self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take a token at `now`, returning how long to wait until it is available
+    fn reserve(&mut self, now: Instant) -> Duration {
+        if self.rate <= 0.0 {
+            return Duration::ZERO;
+        }
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity) - 1.0;
+        self.updated = now;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_interval() {
+        let policy = PollPolicy {
+            min_interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(8),
+            ..PollPolicy::default()
+        };
+        let mut interval = AdaptiveInterval::new(Duration::from_secs(2), &policy);
+        let secs = |d: Duration| d.as_secs();
+        assert_eq!(secs(interval.after_poll(0)), 4);
+        assert_eq!(secs(interval.after_poll(0)), 8);
+        assert_eq!(secs(interval.after_poll(0)), 8);
+        assert_eq!(secs(interval.after_poll(1)), 8);
+        assert_eq!(secs(interval.after_poll(30)), 4);
+        assert_eq!(secs(interval.after_poll(30)), 2);
+        assert_eq!(secs(interval.after_poll(30)), 1);
+        assert_eq!(secs(interval.after_poll(30)), 1);
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let policy = PollPolicy {
+            rps: 10.0,
+            burst: 2,
+            ..PollPolicy::default()
+        };
+        let mut bucket = TokenBucket::new(&policy);
+        let start = bucket.updated;
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        // The burst is spent: later requests queue behind each other at 10 per second
+        assert_eq!(bucket.reserve(start), Duration::from_millis(100));
+        assert_eq!(bucket.reserve(start), Duration::from_millis(200));
+        // A quiet second refills the bucket, but never beyond the burst
+        let later = start + Duration::from_secs(1);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::from_millis(100));
+
+        let mut unlimited = TokenBucket::new(&PollPolicy {
+            rps: 0.0,
+            burst: 1,
+            ..PollPolicy::default()
+        });
+        for _ in 0..10 {
+            assert_eq!(unlimited.reserve(start), Duration::ZERO);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Round-robin pool of connections, so bulk calls are not limited by the
+//! concurrent stream cap of a single HTTP/2 connection
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub(crate) struct Pool<T> {
+    members: Vec<T>,
+    next: AtomicUsize,
+}
+
+impl<T: Clone> Pool<T> {
+    /// Pool of `size` members (at least one) created by `connect`
+    pub(crate) fn new<E>(
+        size: usize,
+        mut connect: impl FnMut() -> Result<T, E>,
+    ) -> Result<Self, E> {
+        let members = (0..size.max(1))
+            .map(|_| connect())
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            members,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// The member after the one handed out last
+    pub(crate) fn get(&self) -> T {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.members.len();
+        self.members[index].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_round_robin() {
+        let mut created = 0;
+        let pool = Pool::new(3, || {
+            created += 1;
+            Ok::<_, ()>(created)
+        })
+        .unwrap();
+        assert_eq!(pool.members.len(), 3);
+        let handed_out: Vec<_> = (0..5).map(|_| pool.get()).collect();
+        assert_eq!(handed_out, [1, 2, 3, 1, 2]);
+
+        let single = Pool::new(0, || Ok::<_, ()>("only")).unwrap();
+        assert_eq!(single.members.len(), 1);
+        assert_eq!(single.get(), "only");
+    }
+}
@@ -0,0 +1,537 @@
+//! Calls to any method a node exposes, described at runtime by gRPC server reflection
+//! or by local descriptor files
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Instant,
+};
+
+use anyhow::{Result, bail};
+use futures::stream;
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor,
+    prost::{Message, bytes::Buf},
+    prost_types::FileDescriptorProto,
+};
+use serde_json::Value;
+use tonic::{
+    Request, Status,
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    metadata::{KeyAndValueRef, MetadataMap},
+    transport::Channel,
+};
+use tonic_reflection::pb::v1::{
+    ServerReflectionRequest, server_reflection_client::ServerReflectionClient,
+    server_reflection_request::MessageRequest, server_reflection_response::MessageResponse,
+};
+
+/// Services every reflection-enabled server exposes about itself
+const REFLECTION_SERVICES: &[&str] = &[
+    "grpc.reflection.v1.ServerReflection",
+    "grpc.reflection.v1alpha.ServerReflection",
+];
+
+/// Reflection client for one endpoint
+pub struct Reflection {
+    channel: Channel,
+    metadata: MetadataMap,
+}
+
+impl Reflection {
+    pub fn new(channel: Channel, metadata: MetadataMap) -> Self {
+        Self { channel, metadata }
+    }
+
+    /// Send one reflection request and return its answer
+    async fn request(&self, request: MessageRequest) -> Result<MessageResponse> {
+        let mut client = ServerReflectionClient::new(self.channel.clone());
+        let mut outbound = Request::new(stream::iter([ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(request),
+        }]));
+        *outbound.metadata_mut() = self.metadata.clone();
+        let mut responses = client
+            .server_reflection_info(outbound)
+            .await
+            .map_err(|e| anyhow::anyhow!("Server reflection is not available: {}", e.message()))?
+            .into_inner();
+        let response = responses
+            .message()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Server reflection returned no response"))?;
+        match response.message_response {
+            Some(MessageResponse::ErrorResponse(error)) => {
+                bail!("Server reflection failed: {}", error.error_message)
+            }
+            Some(response) => Ok(response),
+            None => bail!("Server reflection returned an empty response"),
+        }
+    }
+
+    /// Fully qualified names of the services the server exposes, except reflection itself
+    pub async fn list_services(&self) -> Result<Vec<String>> {
+        let MessageResponse::ListServicesResponse(response) = self
+            .request(MessageRequest::ListServices(String::new()))
+            .await?
+        else {
+            bail!("Unexpected response to ListServices");
+        };
+        let mut services: Vec<String> = response
+            .service
+            .into_iter()
+            .map(|service| service.name)
+            .filter(|name| !REFLECTION_SERVICES.contains(&name.as_str()))
+            .collect();
+        services.sort();
+        Ok(services)
+    }
+
+    /// Descriptors of the file defining `symbol` and everything it imports
+    pub async fn descriptor_pool(&self, symbol: &str) -> Result<DescriptorPool> {
+        // The well-known types are built in; servers do not always send them
+        let mut pool = DescriptorPool::global();
+        let mut files = Vec::new();
+        let mut requested = HashSet::new();
+        let mut pending = vec![MessageRequest::FileContainingSymbol(symbol.to_string())];
+        while let Some(request) = pending.pop() {
+            let MessageResponse::FileDescriptorResponse(response) = self.request(request).await?
+            else {
+                bail!("Unexpected response to a file descriptor request");
+            };
+            for bytes in response.file_descriptor_proto {
+                let file = FileDescriptorProto::decode(bytes.as_slice())?;
+                requested.insert(file.name().to_string());
+                for dependency in &file.dependency {
+                    if pool.get_file_by_name(dependency).is_none()
+                        && requested.insert(dependency.clone())
+                    {
+                        pending.push(MessageRequest::FileByFilename(dependency.clone()));
+                    }
+                }
+                files.push(file);
+            }
+        }
+        pool.add_file_descriptor_protos(files)?;
+        Ok(pool)
+    }
+}
+
+/// Load descriptors from a compiled descriptor set (`buf build -o api.binpb`,
+/// `protoc --include_imports --descriptor_set_out`) or a directory of `.proto` files.
+///
+/// Directories are compiled with `protoc`, which must be on `PATH`.
+pub fn load_descriptors(path: &Path) -> Result<DescriptorPool> {
+    let bytes = if path.is_dir() {
+        compile_protos(path)?
+    } else {
+        std::fs::read(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?
+    };
+    let mut pool = DescriptorPool::global();
+    pool.decode_file_descriptor_set(bytes.as_slice())
+        .map_err(|e| anyhow::anyhow!("Invalid descriptor set {}: {}", path.display(), e))?;
+    Ok(pool)
+}
+
+/// Compile every `.proto` file under `dir` into an encoded descriptor set
+fn compile_protos(dir: &Path) -> Result<Vec<u8>> {
+    let mut protos = Vec::new();
+    collect_protos(dir, dir, &mut protos)?;
+    if protos.is_empty() {
+        bail!("No .proto files found in {}", dir.display());
+    }
+    protos.sort();
+
+    let out = std::env::temp_dir().join(format!("suix-descriptors-{}.binpb", std::process::id()));
+    let output = Command::new("protoc")
+        .arg("--include_imports")
+        .arg(format!("--descriptor_set_out={}", out.display()))
+        .arg("-I")
+        .arg(dir)
+        .args(&protos)
+        .output()
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to run protoc ({}); install it or pass a compiled descriptor set",
+                e
+            )
+        })?;
+    if !output.status.success() {
+        bail!(
+            "protoc failed to compile {}: {}",
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let bytes = std::fs::read(&out)?;
+    let _ = std::fs::remove_file(&out);
+    Ok(bytes)
+}
+
+/// Paths of the `.proto` files under `dir`, relative to `root`
+fn collect_protos(root: &Path, dir: &Path, protos: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_protos(root, &path, protos)?;
+        } else if path.extension().is_some_and(|ext| ext == "proto") {
+            protos.push(path.strip_prefix(root)?.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+/// Find `service`.`method` in `pool`
+pub fn find_method(pool: &DescriptorPool, service: &str, method: &str) -> Result<MethodDescriptor> {
+    let descriptor = pool
+        .get_service_by_name(service)
+        .ok_or_else(|| anyhow::anyhow!("Unknown gRPC service: {}", service))?;
+    let found = descriptor.methods().find(|m| m.name() == method);
+    found.ok_or_else(|| {
+        let methods: Vec<String> = descriptor.methods().map(|m| m.name().to_string()).collect();
+        anyhow::anyhow!(
+            "Unknown method {} of {} (available: {})",
+            method,
+            service,
+            methods.join(", ")
+        )
+    })
+}
+
+/// Fully qualified `service.Method` names of every method of `services` found in `pool`
+pub fn method_names(pool: &DescriptorPool, services: &[String]) -> Vec<String> {
+    services
+        .iter()
+        .filter_map(|service| pool.get_service_by_name(service))
+        .flat_map(|service| {
+            service
+                .methods()
+                .map(|method| method.full_name().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Settings of a call made with [`invoke`] or [`invoke_raw`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallOptions {
+    /// Print request and response metadata, the status and timing to stderr, like `curl -v`
+    pub verbose: bool,
+    /// Largest response message accepted, in bytes, instead of tonic's 4 MiB
+    pub max_message_size: Option<usize>,
+}
+
+/// Call `method` with a JSON request, passing every JSON response to `on_response`.
+///
+/// Unary and server-streaming methods are supported.
+pub async fn invoke<F>(
+    channel: Channel,
+    metadata: MetadataMap,
+    options: CallOptions,
+    method: &MethodDescriptor,
+    request: Value,
+    mut on_response: F,
+) -> Result<()>
+where
+    F: FnMut(Value) -> Result<()>,
+{
+    let codec = DynamicCodec {
+        output: method.output(),
+    };
+    call(
+        channel,
+        metadata,
+        options,
+        method,
+        request,
+        codec,
+        |response| on_response(serde_json::to_value(&response)?),
+    )
+    .await
+}
+
+/// Call `method` with a JSON request, passing the undecoded protobuf bytes of every
+/// response to `on_response`
+pub async fn invoke_raw<F>(
+    channel: Channel,
+    metadata: MetadataMap,
+    options: CallOptions,
+    method: &MethodDescriptor,
+    request: Value,
+    on_response: F,
+) -> Result<()>
+where
+    F: FnMut(Vec<u8>) -> Result<()>,
+{
+    call(
+        channel,
+        metadata,
+        options,
+        method,
+        request,
+        RawCodec,
+        on_response,
+    )
+    .await
+}
+
+async fn call<C, F>(
+    channel: Channel,
+    metadata: MetadataMap,
+    options: CallOptions,
+    method: &MethodDescriptor,
+    request: Value,
+    codec: C,
+    mut on_response: F,
+) -> Result<()>
+where
+    C: Codec<Encode = DynamicMessage> + Send + 'static,
+    C::Decode: Send + Sync + 'static,
+    F: FnMut(C::Decode) -> Result<()>,
+{
+    if method.is_client_streaming() {
+        bail!(
+            "{} is a client-streaming method, which is not supported",
+            method.full_name()
+        );
+    }
+    let message = DynamicMessage::deserialize(method.input(), request).map_err(|e| {
+        anyhow::anyhow!("Invalid request for {}: {}", method.input().full_name(), e)
+    })?;
+    let verbose = options.verbose;
+    let path = format!("/{}/{}", method.parent_service().full_name(), method.name());
+    if verbose {
+        eprintln!("> POST {path}");
+        trace_metadata('>', &metadata);
+    }
+    let path = path
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid method path: {}", e))?;
+    let mut request = Request::new(message);
+    *request.metadata_mut() = metadata;
+
+    let started = Instant::now();
+    let mut grpc = tonic::client::Grpc::new(channel);
+    if let Some(limit) = options.max_message_size {
+        grpc = grpc.max_decoding_message_size(limit);
+    }
+    grpc.ready()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to connect: {}", e))?;
+    // An error of `on_response` ends the call without being a gRPC status
+    let mut failure = None;
+    let result = async {
+        if method.is_server_streaming() {
+            let response = grpc.server_streaming(request, path, codec).await?;
+            if verbose {
+                trace_metadata('<', response.metadata());
+            }
+            let mut responses = response.into_inner();
+            while let Some(response) = responses.message().await? {
+                if let Err(e) = on_response(response) {
+                    failure = Some(e);
+                    return Ok(());
+                }
+            }
+            if verbose && let Some(trailers) = responses.trailers().await? {
+                trace_metadata('<', &trailers);
+            }
+        } else {
+            let response = grpc.unary(request, path, codec).await?;
+            // Headers and trailers, which tonic merges for unary calls
+            if verbose {
+                trace_metadata('<', response.metadata());
+            }
+            failure = on_response(response.into_inner()).err();
+        }
+        Ok::<_, Status>(())
+    }
+    .await;
+    if verbose {
+        match &result {
+            Ok(()) => eprintln!("< grpc-status: 0 (OK)"),
+            Err(status) => {
+                trace_metadata('<', status.metadata());
+                eprintln!(
+                    "< grpc-status: {} ({:?})",
+                    i32::from(status.code()),
+                    status.code()
+                );
+                eprintln!("< grpc-message: {}", status.message());
+            }
+        }
+        eprintln!("* Completed in {:.1?}", started.elapsed());
+    }
+    result.map_err(status_error)?;
+    failure.map_or(Ok(()), Err)
+}
+
+/// Print metadata to stderr, one `<direction> key: value` line per entry
+fn trace_metadata(direction: char, metadata: &MetadataMap) {
+    for entry in metadata.iter() {
+        match entry {
+            KeyAndValueRef::Ascii(key, value) => {
+                if key != "grpc-status" && key != "grpc-message" {
+                    let value = value.to_str().unwrap_or("<invalid>");
+                    eprintln!("{direction} {key}: {value}");
+                }
+            }
+            KeyAndValueRef::Binary(key, value) => {
+                let value = String::from_utf8_lossy(value.as_encoded_bytes());
+                eprintln!("{direction} {key}: {value}");
+            }
+        }
+    }
+}
+
+fn status_error(status: Status) -> anyhow::Error {
+    anyhow::anyhow!("{:?}: {}", status.code(), status.message())
+}
+
+/// Encodes and decodes messages described at runtime
+struct DynamicCodec {
+    output: MessageDescriptor,
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder {
+            output: self.output.clone(),
+        }
+    }
+}
+
+struct DynamicEncoder;
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, buf: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(buf)
+            .map_err(|e| Status::internal(format!("Failed to encode request: {e}")))
+    }
+}
+
+struct DynamicDecoder {
+    output: MessageDescriptor,
+}
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, buf: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.output.clone(), buf)
+            .map(Some)
+            .map_err(|e| Status::internal(format!("Failed to decode response: {e}")))
+    }
+}
+
+/// Encodes requests described at runtime and passes responses through undecoded
+struct RawCodec;
+
+impl Codec for RawCodec {
+    type Encode = DynamicMessage;
+    type Decode = Vec<u8>;
+    type Encoder = DynamicEncoder;
+    type Decoder = RawDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        RawDecoder
+    }
+}
+
+struct RawDecoder;
+
+impl Decoder for RawDecoder {
+    type Item = Vec<u8>;
+    type Error = Status;
+
+    fn decode(&mut self, buf: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(Some(buf.copy_to_bytes(buf.remaining()).to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_method() {
+        // The reflection protocol describes itself, which makes a handy fixture
+        let mut pool = DescriptorPool::new();
+        pool.decode_file_descriptor_set(tonic_reflection::pb::v1::FILE_DESCRIPTOR_SET)
+            .unwrap();
+        let service = "grpc.reflection.v1.ServerReflection";
+        let method = find_method(&pool, service, "ServerReflectionInfo").unwrap();
+        assert!(method.is_client_streaming() && method.is_server_streaming());
+        assert_eq!(
+            method.input().full_name(),
+            "grpc.reflection.v1.ServerReflectionRequest"
+        );
+        assert!(find_method(&pool, service, "Missing").is_err());
+        assert!(find_method(&pool, "sui.rpc.v2.LedgerService", "GetObject").is_err());
+        assert_eq!(
+            method_names(&pool, &[service.to_string()]),
+            ["grpc.reflection.v1.ServerReflection.ServerReflectionInfo"]
+        );
+
+        let request =
+            DynamicMessage::deserialize(method.input(), serde_json::json!({ "listServices": "" }))
+                .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({ "listServices": "" })
+        );
+    }
+
+    #[test]
+    fn test_load_descriptors() {
+        let dir =
+            std::env::temp_dir().join(format!("suix-descriptors-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("reflection.binpb");
+        std::fs::write(&path, tonic_reflection::pb::v1::FILE_DESCRIPTOR_SET).unwrap();
+
+        let pool = load_descriptors(&path).unwrap();
+        assert!(
+            find_method(
+                &pool,
+                "grpc.reflection.v1.ServerReflection",
+                "ServerReflectionInfo"
+            )
+            .is_ok()
+        );
+        // Well-known types stay available alongside the loaded files
+        assert!(
+            pool.get_message_by_name("google.protobuf.Timestamp")
+                .is_some()
+        );
+
+        std::fs::write(&path, b"not a descriptor set").unwrap();
+        assert!(load_descriptors(&path).is_err());
+        assert!(load_descriptors(&dir.join("missing.binpb")).is_err());
+        // A directory without .proto files is rejected before protoc is needed
+        let empty = dir.join("empty");
+        std::fs::create_dir_all(&empty).unwrap();
+        let error = load_descriptors(&empty).unwrap_err().to_string();
+        assert!(error.starts_with("No .proto files found"), "{error}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,67 @@
+//! `tracing` spans around every call a [`SuiGrpcClient`](crate::SuiGrpcClient) makes,
+//! built with the `tracing` feature.
+//!
+//! Each attempt gets a `grpc.call` span with its `service`, `method` and `endpoint`;
+//! `duration_ms` and `status` are recorded on it when the attempt ends, together with
+//! an event at `INFO` (or `WARN` when the call failed).
+
+use std::{future::Future, time::Instant};
+
+use anyhow::Result;
+use tracing::{Instrument, field};
+
+/// Run `call` inside a `grpc.call` span
+pub(crate) async fn traced<T>(
+    endpoint: &str,
+    service: &str,
+    method: &str,
+    call: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let span = tracing::info_span!(
+        "grpc.call",
+        service,
+        method,
+        endpoint,
+        duration_ms = field::Empty,
+        status = field::Empty,
+    );
+    let started = Instant::now();
+    let result = call.instrument(span.clone()).await;
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let status = status(result.as_ref().err());
+    span.record("duration_ms", duration_ms);
+    span.record("status", status.as_str());
+    span.in_scope(|| match &result {
+        Ok(_) => tracing::info!(status, duration_ms, "gRPC call finished"),
+        Err(e) => tracing::warn!(status, duration_ms, error = %e, "gRPC call failed"),
+    });
+    result
+}
+
+/// `OK`, the gRPC code of a failed call, or `ERROR` when it failed before getting one
+fn status(error: Option<&anyhow::Error>) -> String {
+    match error {
+        None => "OK".to_string(),
+        Some(e) => e
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<tonic::Status>())
+            .map(|status| format!("{:?}", status.code()))
+            .unwrap_or_else(|| "ERROR".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        assert_eq!(status(None), "OK");
+        let unavailable = anyhow::Error::new(tonic::Status::unavailable("node down"));
+        assert_eq!(status(Some(&unavailable)), "Unavailable");
+        let wrapped = unavailable.context("Failed to get checkpoint 7");
+        assert_eq!(status(Some(&wrapped)), "Unavailable");
+        assert_eq!(status(Some(&anyhow::anyhow!("timed out"))), "ERROR");
+    }
+}
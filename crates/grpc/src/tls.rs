@@ -0,0 +1,211 @@
+//! TLS settings for private fullnodes and proxies that terminate TLS with internal certificates
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use anyhow::{Result, bail};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpStream;
+use tokio_rustls::{
+    TlsConnector,
+    client::TlsStream,
+    rustls::{
+        self, ClientConfig, DigitallySignedStruct, SignatureScheme,
+        client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        crypto::{self, CryptoProvider},
+        pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime, pem::PemObject},
+    },
+};
+use tonic::{
+    codegen::Service,
+    transport::{Certificate, ClientTlsConfig, Identity, Uri},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct TlsOptions {
+    /// PEM CA bundle trusted in addition to the public roots
+    pub ca_cert: Option<PathBuf>,
+    /// PEM client certificate for mutual TLS, used with `client_key`
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key of `client_cert`
+    pub client_key: Option<PathBuf>,
+    /// Accept any server certificate; for testing against self-signed endpoints only
+    pub insecure: bool,
+}
+
+impl TlsOptions {
+    /// Settings for a verified connection: the public roots plus `ca_cert`, and the client identity
+    pub(crate) fn client_config(&self) -> Result<ClientTlsConfig> {
+        let mut tls = ClientTlsConfig::new().with_enabled_roots();
+        if let Some(path) = &self.ca_cert {
+            tls = tls.ca_certificate(Certificate::from_pem(read(path)?));
+        }
+        if let Some((cert, key)) = self.identity()? {
+            tls = tls.identity(Identity::from_pem(read(cert)?, read(key)?));
+        }
+        Ok(tls)
+    }
+
+    /// Connector that skips server certificate verification, when `insecure` is set
+    pub(crate) fn insecure_connector(&self) -> Result<Option<InsecureConnector>> {
+        if !self.insecure {
+            return Ok(None);
+        }
+        let provider = Arc::new(crypto::ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)));
+        let mut config = match self.identity()? {
+            Some((cert, key)) => {
+                let certs = CertificateDer::pem_file_iter(cert)
+                    .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+                    .map_err(|e| {
+                        anyhow::anyhow!("Invalid certificate {}: {}", cert.display(), e)
+                    })?;
+                let key = PrivateKeyDer::from_pem_file(key)
+                    .map_err(|e| anyhow::anyhow!("Invalid private key {}: {}", key.display(), e))?;
+                builder.with_client_auth_cert(certs, key)?
+            }
+            None => builder.with_no_client_auth(),
+        };
+        config.alpn_protocols.push(b"h2".to_vec());
+        Ok(Some(InsecureConnector {
+            config: Arc::new(config),
+        }))
+    }
+
+    fn identity(&self) -> Result<Option<(&PathBuf, &PathBuf)>> {
+        match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => Ok(Some((cert, key))),
+            (None, None) => Ok(None),
+            _ => bail!("A client certificate and its key must be given together"),
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Opens TLS connections without checking who the server is
+#[derive(Clone)]
+pub(crate) struct InsecureConnector {
+    config: Arc<ClientConfig>,
+}
+
+impl Service<Uri> for InsecureConnector {
+    type Response = TokioIo<TlsStream<TcpStream>>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let connector = TlsConnector::from(self.config.clone());
+        Box::pin(async move {
+            let host = uri
+                .host()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "URL has no host"))?
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string();
+            let port = uri.port_u16().unwrap_or(443);
+            let tcp = TcpStream::connect((host.as_str(), port)).await?;
+            let domain = ServerName::try_from(host)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            Ok(TokioIo::new(connector.connect(domain, tcp).await?))
+        })
+    }
+}
+
+/// Trusts any certificate chain, but still checks handshake signatures
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_identity_needs_cert_and_key() {
+        let options = TlsOptions {
+            client_cert: Some(PathBuf::from("client.pem")),
+            ..TlsOptions::default()
+        };
+        let error = options.client_config().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "A client certificate and its key must be given together"
+        );
+
+        let options = TlsOptions {
+            ca_cert: Some(PathBuf::from("/nonexistent/ca.pem")),
+            ..TlsOptions::default()
+        };
+        assert!(options.client_config().is_err());
+        assert!(
+            TlsOptions::default()
+                .insecure_connector()
+                .unwrap()
+                .is_none()
+        );
+        let insecure = TlsOptions {
+            insecure: true,
+            ..TlsOptions::default()
+        };
+        assert!(insecure.insecure_connector().unwrap().is_some());
+    }
+}
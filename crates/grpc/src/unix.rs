@@ -0,0 +1,109 @@
+//! `unix:///path/to/socket` endpoints for co-located fullnodes.
+//!
+//! The typed sui-rpc-api client only dials TCP, so calls go to a loopback listener
+//! that forwards each connection to the socket.
+
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use tokio::{
+    io,
+    net::{TcpListener, TcpStream, UnixStream},
+    task::JoinHandle,
+};
+
+/// Socket path of a `unix://` URL
+pub(crate) fn socket_path(url: &str) -> Option<&Path> {
+    url.strip_prefix("unix://").map(Path::new)
+}
+
+/// Loopback listener forwarding to a unix socket, stopped when dropped
+#[derive(Debug)]
+pub(crate) struct UnixBridge {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl UnixBridge {
+    pub(crate) async fn bind(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            anyhow::bail!("Socket {} does not exist", path.display());
+        }
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let path = path.to_path_buf();
+        let task = tokio::spawn(async move {
+            while let Ok((tcp, _)) = listener.accept().await {
+                tokio::spawn(forward(tcp, path.clone()));
+            }
+        });
+        Ok(Self { addr, task })
+    }
+
+    /// Plaintext URL of the listener
+    pub(crate) fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+}
+
+impl Drop for UnixBridge {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn forward(mut tcp: TcpStream, path: PathBuf) {
+    match UnixStream::connect(&path).await {
+        Ok(mut socket) => {
+            // Either side closing ends the connection; its error is the caller's to see
+            let _ = io::copy_bidirectional(&mut tcp, &mut socket).await;
+        }
+        Err(e) => eprintln!("Warning: failed to connect to {}: {e}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::UnixListener,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_socket_path() {
+        assert_eq!(
+            socket_path("unix:///run/sui/rpc.sock"),
+            Some(Path::new("/run/sui/rpc.sock"))
+        );
+        assert_eq!(socket_path("http://localhost:9000"), None);
+    }
+
+    #[tokio::test]
+    async fn test_bridge_forwards() {
+        let path = std::env::temp_dir().join(format!("suix-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let server = UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = server.accept().await.unwrap();
+            let mut request = [0; 4];
+            socket.read_exact(&mut request).await.unwrap();
+            socket.write_all(&request.map(|b| b + 1)).await.unwrap();
+        });
+
+        let bridge = UnixBridge::bind(&path).await.unwrap();
+        assert!(bridge.url().starts_with("http://127.0.0.1:"));
+        let mut tcp = TcpStream::connect(bridge.addr).await.unwrap();
+        tcp.write_all(&[1, 2, 3, 4]).await.unwrap();
+        let mut response = [0; 4];
+        tcp.read_exact(&mut response).await.unwrap();
+        assert_eq!(response, [2, 3, 4, 5]);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(UnixBridge::bind(&path).await.is_err());
+    }
+}
@@ -0,0 +1,354 @@
+//! Security review of a Sui CLI keystore and the files around it.
+//!
+//! Unlike [`Keystore::load`](crate::Keystore::load) the audit never fails on a
+//! bad entry: every key is inspected on its own and problems are reported as
+//! [`Finding`]s.
+
+use std::{
+    collections::{BTreeMap, btree_map::Entry},
+    fs,
+    path::Path,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use eyre::Result;
+use serde::Serialize;
+use sui_types::{
+    base_types::SuiAddress,
+    crypto::{EncodeDecodeBase64, SuiKeyPair},
+};
+
+use crate::AliasEntry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Stable identifier of the check, e.g. `duplicate-key`
+    pub check: &'static str,
+    pub message: String,
+}
+
+/// One keystore entry as found in the file
+#[derive(Debug, Serialize)]
+pub struct AuditedKey {
+    /// Position in the keystore file
+    pub index: usize,
+    pub scheme: &'static str,
+    /// Unset when the entry cannot be decoded
+    pub address: Option<String>,
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditReport {
+    pub keystore: String,
+    pub keys: Vec<AuditedKey>,
+    /// `active_address` of `client.yaml` next to the keystore, if set
+    pub active_address: Option<String>,
+    /// Highest severity first
+    pub findings: Vec<Finding>,
+}
+
+impl AuditReport {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    fn add(&mut self, severity: Severity, check: &'static str, message: String) {
+        self.findings.push(Finding {
+            severity,
+            check,
+            message,
+        });
+    }
+}
+
+/// Audit the keystore at `path` together with its `sui.aliases` and `client.yaml`
+pub fn audit(path: impl AsRef<Path>) -> Result<AuditReport> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .map_err(|e| eyre::eyre!("Failed to read keystore {}: {}", path.display(), e))?;
+    let encoded: Vec<String> = serde_json::from_str(&content)
+        .map_err(|e| eyre::eyre!("Invalid keystore {}: {}", path.display(), e))?;
+    let mut report = AuditReport {
+        keystore: path.display().to_string(),
+        keys: Vec::with_capacity(encoded.len()),
+        active_address: None,
+        findings: Vec::new(),
+    };
+
+    check_permissions(&mut report, path, true);
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        check_permissions(&mut report, dir, false);
+    }
+
+    let aliases_path = path.with_extension("aliases");
+    let aliases: Vec<AliasEntry> = match fs::read_to_string(&aliases_path) {
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(aliases) => aliases,
+            Err(e) => {
+                report.add(
+                    Severity::Medium,
+                    "invalid-aliases",
+                    format!("{} is not valid: {e}", aliases_path.display()),
+                );
+                Vec::new()
+            }
+        },
+        Err(_) => Vec::new(),
+    };
+
+    // Entries decoding to the same bytes, by first index
+    let mut seen: BTreeMap<Vec<u8>, usize> = BTreeMap::new();
+    let mut matched_aliases = vec![false; aliases.len()];
+    for (index, key) in encoded.iter().enumerate() {
+        let Ok(bytes) = BASE64.decode(key.trim()) else {
+            report.add(
+                Severity::High,
+                "undecodable-key",
+                format!("Key #{index} is not base64 and cannot be used"),
+            );
+            report.keys.push(AuditedKey {
+                index,
+                scheme: "unknown",
+                address: None,
+                alias: None,
+            });
+            continue;
+        };
+        match seen.entry(bytes.clone()) {
+            Entry::Occupied(first) => report.add(
+                Severity::Low,
+                "duplicate-key",
+                format!("Key #{index} is a duplicate of key #{}", first.get()),
+            ),
+            Entry::Vacant(slot) => {
+                slot.insert(index);
+            }
+        }
+
+        let (scheme, secret) = match (bytes.len(), bytes.first()) {
+            (33, Some(0x00)) => ("ed25519", &bytes[1..]),
+            (33, Some(0x01)) => ("secp256k1", &bytes[1..]),
+            (33, Some(0x02)) => ("secp256r1", &bytes[1..]),
+            (64, _) => {
+                report.add(
+                    Severity::Medium,
+                    "legacy-format",
+                    format!(
+                        "Key #{index} uses the obsolete unflagged Ed25519 keypair encoding; re-import it with the current Sui CLI"
+                    ),
+                );
+                ("ed25519-legacy", &bytes[..32])
+            }
+            _ => {
+                report.add(
+                    Severity::High,
+                    "unknown-format",
+                    format!(
+                        "Key #{index} is {} bytes with flag {:?}, not a supported private key",
+                        bytes.len(),
+                        bytes.first()
+                    ),
+                );
+                ("unknown", &bytes[..0])
+            }
+        };
+        if is_weak_secret(secret) {
+            report.add(
+                Severity::High,
+                "weak-key",
+                format!("Key #{index} has a trivially guessable private key; move its funds and retire it"),
+            );
+        }
+
+        let decoded = (scheme != "unknown")
+            .then(|| SuiKeyPair::decode_base64(key.trim()).ok())
+            .flatten();
+        let (address, alias) = match decoded {
+            Some(keypair) => {
+                let public = keypair.public().encode_base64();
+                let alias = aliases
+                    .iter()
+                    .position(|a| a.public_key_base64 == public)
+                    .map(|i| {
+                        matched_aliases[i] = true;
+                        aliases[i].alias.clone()
+                    });
+                (Some(SuiAddress::from(&keypair.public()).to_string()), alias)
+            }
+            None => (None, None),
+        };
+        if address.is_some() && alias.is_none() && !aliases.is_empty() {
+            report.add(
+                Severity::Info,
+                "missing-alias",
+                format!("Key #{index} has no alias in {}", aliases_path.display()),
+            );
+        }
+        report.keys.push(AuditedKey {
+            index,
+            scheme,
+            address,
+            alias,
+        });
+    }
+
+    let mut alias_names: BTreeMap<&str, usize> = BTreeMap::new();
+    for (entry, matched) in aliases.iter().zip(&matched_aliases) {
+        *alias_names.entry(&entry.alias).or_default() += 1;
+        if !matched {
+            report.add(
+                Severity::Low,
+                "orphaned-alias",
+                format!("Alias {} belongs to no key in the keystore", entry.alias),
+            );
+        }
+    }
+    for (alias, count) in alias_names.into_iter().filter(|(_, c)| *c > 1) {
+        report.add(
+            Severity::Medium,
+            "duplicate-alias",
+            format!("Alias {alias} is used by {count} keys; commands may sign with the wrong one"),
+        );
+    }
+
+    report.active_address = active_address(&path.with_file_name("client.yaml"));
+    if let Some(ref active) = report.active_address {
+        let known = report.keys.iter().any(|k| {
+            k.address
+                .as_deref()
+                .is_some_and(|a| a.eq_ignore_ascii_case(active))
+        });
+        if !known {
+            report.add(
+                Severity::Medium,
+                "unknown-active-address",
+                format!("Active address {active} of client.yaml has no key in the keystore"),
+            );
+        }
+    }
+
+    report
+        .findings
+        .sort_by_key(|f| std::cmp::Reverse(f.severity));
+    Ok(report)
+}
+
+/// Secrets whose bytes are all equal (e.g. all zeros) are guessable by anyone
+fn is_weak_secret(secret: &[u8]) -> bool {
+    secret.windows(2).all(|w| w[0] == w[1]) && !secret.is_empty()
+}
+
+/// `active_address` from a Sui CLI `client.yaml`
+fn active_address(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    content.lines().find_map(|line| {
+        let value = line.strip_prefix("active_address:")?.trim();
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        (value.starts_with("0x")).then(|| value.to_string())
+    })
+}
+
+/// Flag files other users can read and directories they can write
+#[cfg(unix)]
+fn check_permissions(report: &mut AuditReport, path: &Path, secret: bool) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+    let mode = metadata.permissions().mode() & 0o777;
+    if secret && mode & 0o077 != 0 {
+        report.add(
+            Severity::High,
+            "file-permissions",
+            format!(
+                "{} has mode {mode:o}; other users can read the keys (chmod 600)",
+                path.display()
+            ),
+        );
+    } else if !secret && mode & 0o022 != 0 {
+        report.add(
+            Severity::Medium,
+            "directory-permissions",
+            format!(
+                "{} has mode {mode:o}; other users can replace the keystore (chmod 700)",
+                path.display()
+            ),
+        );
+    }
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_report: &mut AuditReport, _path: &Path, _secret: bool) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TEST_KEY;
+
+    #[test]
+    fn test_audit_findings() {
+        let dir = std::env::temp_dir().join(format!("suix-audit-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sui.keystore");
+        let legacy = BASE64.encode([9u8; 64]);
+        fs::write(
+            &path,
+            serde_json::to_string(&[TEST_KEY, TEST_KEY, &legacy, "not base64!"]).unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            path.with_extension("aliases"),
+            r#"[{"alias":"old","public_key_base64":"AQID"},{"alias":"old","public_key_base64":"AQIE"}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("client.yaml"),
+            "keystore:\n  File: sui.keystore\nactive_address: \"0x0000000000000000000000000000000000000000000000000000000000000001\"\n",
+        )
+        .unwrap();
+
+        let report = audit(&path).unwrap();
+        let checks: Vec<&str> = report.findings.iter().map(|f| f.check).collect();
+        for check in [
+            "duplicate-key",
+            "weak-key",
+            "legacy-format",
+            "undecodable-key",
+            "orphaned-alias",
+            "duplicate-alias",
+            "unknown-active-address",
+        ] {
+            assert!(checks.contains(&check), "missing {check} in {checks:?}");
+        }
+        assert_eq!(report.keys.len(), 4);
+        assert_eq!(report.keys[0].scheme, "ed25519");
+        assert_eq!(report.keys[2].scheme, "ed25519-legacy");
+        assert!(report.keys[3].address.is_none());
+        assert_eq!(report.findings[0].severity, Severity::High);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_weak_secret() {
+        assert!(is_weak_secret(&[0; 32]));
+        assert!(!is_weak_secret(&[]));
+        let mut secret = [0u8; 32];
+        secret[31] = 1;
+        assert!(!is_weak_secret(&secret));
+    }
+}
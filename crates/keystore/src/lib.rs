@@ -0,0 +1,230 @@
+pub mod audit;
+pub mod vault;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use eyre::{Result, bail};
+use serde::Deserialize;
+use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+use sui_types::{
+    base_types::SuiAddress,
+    crypto::{EncodeDecodeBase64, Signature, SuiKeyPair, ToFromBytes},
+    signature::{GenericSignature, VerifyParams},
+    signature_verification::VerifiedDigestCache,
+    transaction::TransactionData,
+};
+
+/// A key from the keystore together with its address and optional alias
+#[derive(Debug)]
+pub struct KeyEntry {
+    pub address: SuiAddress,
+    pub alias: Option<String>,
+    pub keypair: SuiKeyPair,
+}
+
+impl KeyEntry {
+    pub fn new(keypair: SuiKeyPair, alias: Option<String>) -> Self {
+        Self {
+            address: SuiAddress::from(&keypair.public()),
+            alias,
+            keypair,
+        }
+    }
+}
+
+/// Read-only view of a Sui CLI keystore (`sui.keystore` plus `sui.aliases`)
+#[derive(Debug)]
+pub struct Keystore {
+    entries: Vec<KeyEntry>,
+}
+
+/// One entry of `sui.aliases`
+#[derive(Debug, Deserialize)]
+struct AliasEntry {
+    alias: String,
+    public_key_base64: String,
+}
+
+impl Keystore {
+    /// Default keystore of the Sui CLI: `~/.sui/sui_config/sui.keystore`
+    pub fn default_path() -> PathBuf {
+        std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(".sui")
+            .join("sui_config")
+            .join("sui.keystore")
+    }
+
+    /// Load a keystore file, picking up aliases from `sui.aliases` next to it if present
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("Failed to read keystore {}: {}", path.display(), e))?;
+        let encoded: Vec<String> = serde_json::from_str(&content)
+            .map_err(|e| eyre::eyre!("Invalid keystore {}: {}", path.display(), e))?;
+
+        let aliases: Vec<AliasEntry> = match fs::read_to_string(path.with_extension("aliases")) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| eyre::eyre!("Invalid aliases file: {}", e))?,
+            Err(_) => Vec::new(),
+        };
+
+        let mut entries = Vec::with_capacity(encoded.len());
+        for key in encoded {
+            let keypair = SuiKeyPair::decode_base64(&key)
+                .map_err(|e| eyre::eyre!("Invalid key in {}: {}", path.display(), e))?;
+            let public_base64 = keypair.public().encode_base64();
+            let alias = aliases
+                .iter()
+                .find(|a| a.public_key_base64 == public_base64)
+                .map(|a| a.alias.clone());
+            entries.push(KeyEntry::new(keypair, alias));
+        }
+        Ok(Self { entries })
+    }
+
+    /// Keystore over keys that did not come from a keystore file (e.g. a vault)
+    pub fn from_entries(entries: Vec<KeyEntry>) -> Self {
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[KeyEntry] {
+        &self.entries
+    }
+
+    /// Look up a key by address or alias
+    pub fn get(&self, address_or_alias: &str) -> Result<&KeyEntry> {
+        let address = SuiAddress::from_str(address_or_alias).ok();
+        self.entries
+            .iter()
+            .find(|e| Some(e.address) == address || e.alias.as_deref() == Some(address_or_alias))
+            .ok_or_else(|| eyre::eyre!("No key for {} in keystore", address_or_alias))
+    }
+
+    /// Sign transaction data with the key of `signer`
+    pub fn sign_transaction(&self, signer: &str, data: &TransactionData) -> Result<Signature> {
+        let entry = self.get(signer)?;
+        let message = IntentMessage::new(Intent::sui_transaction(), data.clone());
+        Ok(Signature::new_secure(&message, &entry.keypair))
+    }
+
+    /// Sign arbitrary bytes with the personal-message intent, as wallets do for off-chain auth
+    pub fn sign_personal_message(&self, signer: &str, message: &[u8]) -> Result<Signature> {
+        let entry = self.get(signer)?;
+        let message = IntentMessage::new(
+            Intent::personal_message(),
+            PersonalMessage {
+                message: message.to_vec(),
+            },
+        );
+        Ok(Signature::new_secure(&message, &entry.keypair))
+    }
+}
+
+/// Signature scheme named by the flag byte of a serialized signature
+pub fn signature_scheme(signature: &[u8]) -> &'static str {
+    match signature.first() {
+        Some(0x00) => "ed25519",
+        Some(0x01) => "secp256k1",
+        Some(0x02) => "secp256r1",
+        Some(0x03) => "multisig",
+        Some(0x05) => "zklogin",
+        Some(0x06) => "passkey",
+        _ => "unknown",
+    }
+}
+
+/// Check a base64 personal-message signature (single key, MultiSig or passkey) against `address`
+pub fn verify_personal_message(address: SuiAddress, message: &[u8], signature: &str) -> Result<()> {
+    let bytes = BASE64
+        .decode(signature.trim())
+        .map_err(|e| eyre::eyre!("Invalid base64 signature: {}", e))?;
+    if signature_scheme(&bytes) == "zklogin" {
+        bail!("zkLogin signatures need the provider's JWKs and cannot be verified offline");
+    }
+    let signature = GenericSignature::from_bytes(&bytes)
+        .map_err(|e| eyre::eyre!("Invalid signature: {}", e))?;
+    let message = IntentMessage::new(
+        Intent::personal_message(),
+        PersonalMessage {
+            message: message.to_vec(),
+        },
+    );
+    signature
+        .verify_authenticator(
+            &message,
+            address,
+            0,
+            &VerifyParams::default(),
+            Arc::new(VerifiedDigestCache::new_empty()),
+        )
+        .map_err(|e| eyre::eyre!("Signature does not verify for {}: {}", address, e))
+}
+
+/// Resolve an address argument that may also be a keystore alias
+pub fn resolve_address(keystore: Option<&Keystore>, address_or_alias: &str) -> Result<SuiAddress> {
+    if let Ok(address) = SuiAddress::from_str(address_or_alias) {
+        return Ok(address);
+    }
+    match keystore {
+        Some(keystore) => Ok(keystore.get(address_or_alias)?.address),
+        None => bail!("Invalid address: {}", address_or_alias),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ed25519 key (flag 0x00) with an all-zero private key
+    pub(crate) const TEST_KEY: &str = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+
+    #[test]
+    fn test_load_with_alias() {
+        let dir = std::env::temp_dir().join(format!("suix-keystore-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sui.keystore");
+        fs::write(&path, format!("[\"{TEST_KEY}\"]")).unwrap();
+
+        let keystore = Keystore::load(&path).unwrap();
+        let address = keystore.entries()[0].address;
+        let public = keystore.entries()[0].keypair.public().encode_base64();
+        fs::write(
+            path.with_extension("aliases"),
+            format!("[{{\"alias\":\"ops\",\"public_key_base64\":\"{public}\"}}]"),
+        )
+        .unwrap();
+
+        let keystore = Keystore::load(&path).unwrap();
+        assert_eq!(keystore.get("ops").unwrap().address, address);
+        assert_eq!(
+            keystore.get(&address.to_string()).unwrap().alias.as_deref(),
+            Some("ops")
+        );
+        assert!(keystore.get("unknown").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_personal_message_roundtrip() {
+        let keypair = SuiKeyPair::decode_base64(TEST_KEY).unwrap();
+        let keystore = Keystore::from_entries(vec![KeyEntry::new(keypair, None)]);
+        let address = keystore.entries()[0].address;
+        let signature = keystore
+            .sign_personal_message(&address.to_string(), b"hello")
+            .unwrap();
+        assert_eq!(signature_scheme(signature.as_ref()), "ed25519");
+
+        let encoded = BASE64.encode(signature.as_ref());
+        verify_personal_message(address, b"hello", &encoded).unwrap();
+        assert!(verify_personal_message(address, b"hullo", &encoded).is_err());
+        assert!(verify_personal_message(address, b"hello", "not base64!").is_err());
+    }
+}
@@ -0,0 +1,531 @@
+//! Password-encrypted key vault with time-limited unlock sessions
+//!
+//! An unlock session is an agent process that keeps the derived key in memory
+//! and hands it to the user's other processes over a Unix socket in
+//! `~/.suix/agents`, a directory only the user can enter. The key is never
+//! written to disk.
+
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, Nonce,
+    aead::{Aead, KeyInit},
+};
+use eyre::{Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sui_types::crypto::{EncodeDecodeBase64, SuiKeyPair};
+use zeroize::Zeroize;
+
+use crate::{KeyEntry, Keystore};
+
+const VAULT_VERSION: u32 = 1;
+
+/// Argon2id parameters stored alongside the ciphertext
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfParams {
+    salt: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            salt: BASE64.encode(rand::random::<[u8; 16]>()),
+            m_cost: 64 * 1024,
+            t_cost: 3,
+            p_cost: 1,
+        }
+    }
+}
+
+/// On-disk vault: everything except the KDF parameters is encrypted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// One decrypted key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredKey {
+    alias: Option<String>,
+    key: String,
+}
+
+/// Symmetric key derived from the vault password, wiped from memory when dropped
+#[derive(Clone)]
+pub struct VaultKey([u8; 32]);
+
+impl VaultKey {
+    /// Read a key written by [`VaultKey::write_to`], e.g. from the unlock agent's stdin
+    pub fn read_from(reader: &mut impl Read) -> Result<Self> {
+        let mut key = Self([0; 32]);
+        reader
+            .read_exact(&mut key.0)
+            .map_err(|e| eyre::eyre!("Failed to read vault key: {}", e))?;
+        Ok(key)
+    }
+
+    /// Write the raw key, for handing it to the unlock agent
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<()> {
+        writer.write_all(&self.0)?;
+        Ok(())
+    }
+}
+
+impl Drop for VaultKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl std::fmt::Debug for VaultKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("VaultKey(..)")
+    }
+}
+
+/// Encrypted alternative to the plaintext Sui keystore.
+///
+/// Keys are sealed with ChaCha20-Poly1305 under a key derived from the
+/// password with Argon2id.
+#[derive(Debug)]
+pub struct Vault {
+    path: PathBuf,
+    file: VaultFile,
+}
+
+impl Vault {
+    /// Default vault location: `~/.suix/vault.json`
+    pub fn default_path() -> PathBuf {
+        std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(".suix")
+            .join("vault.json")
+    }
+
+    /// Create an empty vault protected by `password`
+    pub fn init(path: impl AsRef<Path>, password: &str) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            bail!("Vault {} already exists", path.display());
+        }
+        let mut vault = Self {
+            path: path.to_path_buf(),
+            file: VaultFile {
+                version: VAULT_VERSION,
+                kdf: KdfParams::default(),
+                nonce: String::new(),
+                ciphertext: String::new(),
+            },
+        };
+        let key = vault.derive_key(password)?;
+        vault.store(&key, &[])?;
+        Ok(vault)
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("Failed to read vault {}: {}", path.display(), e))?;
+        let file: VaultFile = serde_json::from_str(&content)
+            .map_err(|e| eyre::eyre!("Invalid vault {}: {}", path.display(), e))?;
+        if file.version != VAULT_VERSION {
+            bail!("Unsupported vault version {}", file.version);
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn derive_key(&self, password: &str) -> Result<VaultKey> {
+        let kdf = &self.file.kdf;
+        let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(32))
+            .map_err(|e| eyre::eyre!("Invalid KDF parameters: {}", e))?;
+        let salt = BASE64.decode(&kdf.salt)?;
+        let mut key = VaultKey([0; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), &salt, &mut key.0)
+            .map_err(|e| eyre::eyre!("Key derivation failed: {}", e))?;
+        Ok(key)
+    }
+
+    /// Fail unless `key` opens the vault, i.e. the password was right
+    pub fn verify(&self, key: &VaultKey) -> Result<()> {
+        self.load(key).map(drop)
+    }
+
+    /// Decrypt the vault into a keystore; fails on a wrong password
+    pub fn keystore(&self, key: &VaultKey) -> Result<Keystore> {
+        let entries = self
+            .load(key)?
+            .into_iter()
+            .map(|stored| {
+                let keypair = SuiKeyPair::decode_base64(&stored.key)
+                    .map_err(|e| eyre::eyre!("Invalid key in vault: {}", e))?;
+                Ok(KeyEntry::new(keypair, stored.alias))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Keystore::from_entries(entries))
+    }
+
+    /// Add a key, re-encrypting the whole vault
+    pub fn add(
+        &mut self,
+        key: &VaultKey,
+        keypair: &SuiKeyPair,
+        alias: Option<String>,
+    ) -> Result<()> {
+        let mut stored = self.load(key)?;
+        let encoded = keypair.encode_base64();
+        if stored.iter().any(|s| s.key == encoded) {
+            bail!("Key is already in the vault");
+        }
+        if let Some(ref alias) = alias
+            && stored.iter().any(|s| s.alias.as_ref() == Some(alias))
+        {
+            bail!("Alias {} is already in use", alias);
+        }
+        stored.push(StoredKey {
+            alias,
+            key: encoded,
+        });
+        self.store(key, &stored)
+    }
+
+    /// Act as the unlock agent: hand `key` to this user's processes until `ttl`
+    /// passes or [`Vault::lock`] ends the session. Blocks until then.
+    pub fn serve_session(&self, key: &VaultKey, ttl: Duration) -> Result<()> {
+        self.verify(key)?;
+        agent::serve(&self.agent_socket()?, key, ttl)
+    }
+
+    /// Key held by this vault's unlock agent, if one is running
+    pub fn session_key(&self) -> Option<VaultKey> {
+        agent::key(&self.agent_socket().ok()?)
+    }
+
+    /// End the unlock session, stopping its agent
+    pub fn lock(&self) -> Result<()> {
+        agent::stop(&self.agent_socket()?)
+    }
+
+    /// Socket of this vault's unlock agent, named after the vault's absolute path
+    fn agent_socket(&self) -> Result<PathBuf> {
+        let path = fs::canonicalize(&self.path).unwrap_or_else(|_| self.path.clone());
+        let id = Sha256::digest(path.to_string_lossy().as_bytes());
+        Ok(agent::socket_dir()?.join(format!("{}.sock", hex::encode(&id[..8]))))
+    }
+
+    fn cipher(key: &VaultKey) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&key.0))
+    }
+
+    fn load(&self, key: &VaultKey) -> Result<Vec<StoredKey>> {
+        let nonce = BASE64.decode(&self.file.nonce)?;
+        if nonce.len() != 12 {
+            bail!("Invalid vault nonce");
+        }
+        let ciphertext = BASE64.decode(&self.file.ciphertext)?;
+        let plaintext = Self::cipher(key)
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| eyre::eyre!("Wrong vault password"))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    fn store(&mut self, key: &VaultKey, stored: &[StoredKey]) -> Result<()> {
+        let nonce = rand::random::<[u8; 12]>();
+        let ciphertext = Self::cipher(key)
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                serde_json::to_vec(stored)?.as_slice(),
+            )
+            .map_err(|_| eyre::eyre!("Encryption failed"))?;
+        self.file.nonce = BASE64.encode(nonce);
+        self.file.ciphertext = BASE64.encode(ciphertext);
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_private(&self.path, &serde_json::to_vec_pretty(&self.file)?)
+    }
+}
+
+/// Write a file readable only by the current user.
+///
+/// The content goes to a new file that then replaces `path`, so nothing already at
+/// `path`, such as a symlink, is ever written through.
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    match fs::remove_file(&tmp) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(&tmp)
+        .map_err(|e| eyre::eyre!("Failed to write {}: {}", tmp.display(), e))?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path).map_err(|e| eyre::eyre!("Failed to write {}: {}", path.display(), e))
+}
+
+/// The unlock agent and its clients; one request per connection, a command line
+/// answered by the raw key or by shutting down
+#[cfg(unix)]
+mod agent {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, ErrorKind, Write},
+        os::unix::{
+            fs::{DirBuilderExt, FileTypeExt, MetadataExt},
+            net::{UnixListener, UnixStream},
+        },
+        path::{Path, PathBuf},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use eyre::{Result, bail};
+
+    use super::VaultKey;
+
+    const GET_KEY: &str = "key";
+    const STOP: &str = "stop";
+
+    /// How often the agent checks for requests and expiry
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// How long clients wait for the agent
+    const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// `~/.suix/agents`, created if needed and checked to be private to the user
+    pub(super) fn socket_dir() -> Result<PathBuf> {
+        let home = std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .ok_or_else(|| eyre::eyre!("HOME is not set"))?;
+        let owner = fs::metadata(&home)?.uid();
+        let dir = home.join(".suix").join("agents");
+        private_dir(&dir, owner)?;
+        Ok(dir)
+    }
+
+    /// Create `dir` with mode 0700 if needed; refuse one that is a symlink, belongs
+    /// to someone other than `owner` or can be entered by other users
+    pub(super) fn private_dir(dir: &Path, owner: u32) -> Result<()> {
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .map_err(|e| eyre::eyre!("Failed to create {}: {}", dir.display(), e))?;
+        let metadata = fs::symlink_metadata(dir)?;
+        if !metadata.is_dir() {
+            bail!("{} is not a directory", dir.display());
+        }
+        if metadata.uid() != owner {
+            bail!("{} belongs to another user", dir.display());
+        }
+        if metadata.mode() & 0o077 != 0 {
+            bail!(
+                "{} is accessible by other users; run `chmod 700` on it",
+                dir.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Answer requests on `socket` until `ttl` passes or a client asks to stop
+    pub(super) fn serve(socket: &Path, key: &VaultKey, ttl: Duration) -> Result<()> {
+        if UnixStream::connect(socket).is_ok() {
+            bail!("The vault is already unlocked; lock it first");
+        }
+        remove_socket(socket)?;
+        let listener = UnixListener::bind(socket)
+            .map_err(|e| eyre::eyre!("Failed to listen on {}: {}", socket.display(), e))?;
+        listener.set_nonblocking(true)?;
+
+        let deadline = Instant::now().checked_add(ttl);
+        let result = loop {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break Ok(());
+            }
+            match listener.accept() {
+                // A misbehaving client must not end the session
+                Ok((stream, _)) => {
+                    if answer(&stream, key).unwrap_or(false) {
+                        break Ok(());
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                Err(e) => break Err(e.into()),
+            }
+        };
+        remove_socket(socket)?;
+        result
+    }
+
+    /// Handle one request, returning whether the agent should stop
+    fn answer(mut stream: &UnixStream, key: &VaultKey) -> Result<bool> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line)?;
+        match line.trim() {
+            GET_KEY => {
+                key.write_to(&mut stream)?;
+                Ok(false)
+            }
+            STOP => Ok(true),
+            _ => Ok(false),
+        }
+    }
+
+    /// Key served on `socket`, if an agent answers there
+    pub(super) fn key(socket: &Path) -> Option<VaultKey> {
+        let mut stream = UnixStream::connect(socket).ok()?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT)).ok()?;
+        writeln!(stream, "{GET_KEY}").ok()?;
+        VaultKey::read_from(&mut stream).ok()
+    }
+
+    /// Stop the agent on `socket`, if any, and wait for it to go away
+    pub(super) fn stop(socket: &Path) -> Result<()> {
+        let mut stream = match UnixStream::connect(socket) {
+            Ok(stream) => stream,
+            // Nobody listening: at most a socket left behind by an agent that was killed
+            Err(_) => return remove_socket(socket),
+        };
+        writeln!(stream, "{STOP}")?;
+        drop(stream);
+        let deadline = Instant::now() + CLIENT_TIMEOUT;
+        while fs::symlink_metadata(socket).is_ok() {
+            if Instant::now() >= deadline {
+                bail!("The unlock agent on {} did not stop", socket.display());
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        Ok(())
+    }
+
+    /// Remove the socket file at `path`, refusing to touch anything else there
+    fn remove_socket(path: &Path) -> Result<()> {
+        match fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_socket() => Ok(fs::remove_file(path)?),
+            Ok(_) => bail!("{} exists and is not a socket", path.display()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Unlock sessions need Unix sockets; elsewhere every command asks for the password
+#[cfg(not(unix))]
+mod agent {
+    use std::{
+        path::{Path, PathBuf},
+        time::Duration,
+    };
+
+    use eyre::{Result, bail};
+
+    use super::VaultKey;
+
+    pub(super) fn socket_dir() -> Result<PathBuf> {
+        bail!("Unlock sessions are only supported on Unix")
+    }
+
+    pub(super) fn serve(_: &Path, _: &VaultKey, _: Duration) -> Result<()> {
+        bail!("Unlock sessions are only supported on Unix")
+    }
+
+    pub(super) fn key(_: &Path) -> Option<VaultKey> {
+        None
+    }
+
+    pub(super) fn stop(_: &Path) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("suix-vault-{}", std::process::id()));
+        let path = dir.join("vault.json");
+        let mut vault = Vault::init(&path, "correct horse").unwrap();
+        let key = vault.derive_key("correct horse").unwrap();
+        let keypair = SuiKeyPair::decode_base64(crate::tests::TEST_KEY).unwrap();
+        vault.add(&key, &keypair, Some("ops".to_string())).unwrap();
+
+        let vault = Vault::open(&path).unwrap();
+        let wrong = vault.derive_key("battery staple").unwrap();
+        assert!(vault.keystore(&wrong).is_err());
+        let keystore = vault.keystore(&key).unwrap();
+        assert_eq!(keystore.get("ops").unwrap().alias.as_deref(), Some("ops"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_agent_session() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let dir = std::env::temp_dir().join(format!("suix-agent-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let owner = fs::metadata(&dir).unwrap().uid();
+        let sockets = dir.join("agents");
+        agent::private_dir(&sockets, owner).unwrap();
+        let socket = sockets.join("test.sock");
+
+        let key = VaultKey([7; 32]);
+        let served = key.clone();
+        let agent = {
+            let socket = socket.clone();
+            std::thread::spawn(move || agent::serve(&socket, &served, Duration::from_secs(60)))
+        };
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        let fetched = loop {
+            if let Some(fetched) = agent::key(&socket) {
+                break fetched;
+            }
+            assert!(std::time::Instant::now() < deadline, "agent did not start");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(fetched.0, key.0);
+
+        agent::stop(&socket).unwrap();
+        agent.join().unwrap().unwrap();
+        assert!(agent::key(&socket).is_none());
+        assert!(!socket.exists());
+
+        // Directories other users can enter are refused
+        fs::set_permissions(&sockets, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(agent::private_dir(&sockets, owner).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,779 @@
+//! Typed client for the Sui JSON-RPC API
+//!
+//! Every method returns the call's `result`, decoded where a typed struct exists,
+//! and leaves printing to the caller.
+
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{
+    RpcConfig,
+    dynamic_fields::{self, DynamicFieldPage},
+    events::{self, EventPage, EventQuery},
+    json_u64, json_u128,
+    objects::{self, ObjectOptions},
+    rpc_request,
+};
+
+/// Checkpoints per `sui_getCheckpoints` call, the most fullnodes return
+const CHECKPOINTS_PAGE_SIZE: usize = 100;
+
+/// Coins per `suix_getCoins` or `suix_getAllCoins` call, the most fullnodes return
+const COINS_PAGE_SIZE: usize = 50;
+
+/// Emitted when an epoch ends, with the reference gas price and stake it had
+const SYSTEM_EPOCH_INFO_EVENT: &str = "0x3::sui_system_state_inner::SystemEpochInfoEvent";
+
+/// Balance of one coin type, from `suix_getBalance` or `suix_getAllBalances`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Balance {
+    pub coin_type: String,
+    pub coin_object_count: u64,
+    /// Total in the coin's smallest unit
+    pub total_balance: u128,
+}
+
+impl Balance {
+    fn from_json(balance: &Value) -> Result<Self> {
+        let coin_type = balance["coinType"]
+            .as_str()
+            .ok_or_else(|| eyre::eyre!("Balance has no coinType: {}", balance))?;
+        Ok(Self {
+            coin_type: coin_type.to_string(),
+            coin_object_count: json_u64(&balance["coinObjectCount"]).unwrap_or_default(),
+            total_balance: json_u128(&balance["totalBalance"])
+                .ok_or_else(|| eyre::eyre!("Invalid totalBalance for {}", coin_type))?,
+        })
+    }
+}
+
+/// Coin object, from `suix_getCoins` or `suix_getAllCoins`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coin {
+    pub coin_type: String,
+    pub coin_object_id: String,
+    pub version: u64,
+    pub digest: String,
+    /// Balance in the coin's smallest unit
+    pub balance: u128,
+    pub previous_transaction: String,
+}
+
+impl Coin {
+    fn from_json(coin: &Value) -> Result<Self> {
+        let text = |key: &str| coin[key].as_str().unwrap_or_default().to_string();
+        let coin_object_id = coin["coinObjectId"]
+            .as_str()
+            .ok_or_else(|| eyre::eyre!("Coin has no coinObjectId: {}", coin))?;
+        Ok(Self {
+            coin_type: text("coinType"),
+            coin_object_id: coin_object_id.to_string(),
+            version: json_u64(&coin["version"]).unwrap_or_default(),
+            digest: text("digest"),
+            balance: json_u128(&coin["balance"])
+                .ok_or_else(|| eyre::eyre!("Invalid balance for coin {}", coin_object_id))?,
+            previous_transaction: text("previousTransaction"),
+        })
+    }
+}
+
+/// Coins found by [`RpcClient::get_coins`] or [`RpcClient::get_all_coins`]
+#[derive(Debug, Clone, Default)]
+pub struct CoinPage {
+    pub data: Vec<Coin>,
+    /// Cursor to pass to the next call for the coins after these, `None` at the end
+    pub next_cursor: Option<Value>,
+}
+
+/// `CoinMetadata` of a coin type, from `suix_getCoinMetadata`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinMetadata {
+    /// ID of the `CoinMetadata` object
+    pub id: Option<String>,
+    pub decimals: u8,
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    pub icon_url: Option<String>,
+}
+
+/// Stakes of one owner with one validator, from `suix_getStakes`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelegatedStake {
+    pub validator_address: String,
+    /// ID of the validator's staking pool
+    pub staking_pool: String,
+    pub stakes: Vec<Stake>,
+}
+
+/// A `StakedSui` object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stake {
+    pub staked_sui_id: String,
+    pub stake_request_epoch: u64,
+    /// First epoch the stake earns rewards in
+    pub stake_active_epoch: u64,
+    /// Staked amount in MIST
+    pub principal: u64,
+    /// `Pending`, `Active` or `Unstaked`
+    pub status: String,
+    /// Rewards earned so far in MIST, for active stakes
+    pub estimated_reward: Option<u64>,
+}
+
+impl DelegatedStake {
+    fn from_json(delegated: &Value) -> Result<Self> {
+        let text = |value: &Value| value.as_str().unwrap_or_default().to_string();
+        let stakes = delegated["stakes"]
+            .as_array()
+            .ok_or_else(|| eyre::eyre!("Invalid delegated stake: {}", delegated))?
+            .iter()
+            .map(|stake| Stake {
+                staked_sui_id: text(&stake["stakedSuiId"]),
+                stake_request_epoch: json_u64(&stake["stakeRequestEpoch"]).unwrap_or_default(),
+                stake_active_epoch: json_u64(&stake["stakeActiveEpoch"]).unwrap_or_default(),
+                principal: json_u64(&stake["principal"]).unwrap_or_default(),
+                status: text(&stake["status"]),
+                estimated_reward: json_u64(&stake["estimatedReward"]),
+            })
+            .collect();
+        Ok(Self {
+            validator_address: text(&delegated["validatorAddress"]),
+            staking_pool: text(&delegated["stakingPool"]),
+            stakes,
+        })
+    }
+}
+
+/// Estimated APY of every active validator, from `suix_getValidatorsApy`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorsApy {
+    /// Epoch the estimate was made in
+    pub epoch: u64,
+    /// `(validator address, APY)`, the APY as a fraction (0.05 for 5%)
+    pub apys: Vec<(String, f64)>,
+}
+
+/// Voting power of the validators in one epoch, from `suix_getCommitteeInfo`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitteeInfo {
+    pub epoch: u64,
+    /// `(protocol public key, voting power)`, the powers adding up to 10,000
+    pub validators: Vec<(String, u64)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcClient {
+    config: RpcConfig,
+}
+
+impl RpcClient {
+    pub fn new(config: RpcConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &RpcConfig {
+        &self.config
+    }
+
+    /// Call any method and return its `result`, see [`rpc_request`]
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        rpc_request(&self.config, method, params).await
+    }
+
+    /// Identifier of the chain the node serves, e.g. `35834a8a` on mainnet
+    pub async fn chain_identifier(&self) -> Result<String> {
+        let chain = self.request("sui_getChainIdentifier", json!([])).await?;
+        chain
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| eyre::eyre!("Invalid chain identifier: {}", chain))
+    }
+
+    pub async fn latest_checkpoint_sequence_number(&self) -> Result<u64> {
+        let latest = self
+            .request("sui_getLatestCheckpointSequenceNumber", json!([]))
+            .await?;
+        json_u64(&latest)
+            .ok_or_else(|| eyre::eyre!("Invalid checkpoint sequence number: {}", latest))
+    }
+
+    pub async fn get_checkpoint(&self, sequence_number: u64) -> Result<Value> {
+        self.request("sui_getCheckpoint", json!([sequence_number.to_string()]))
+            .await
+    }
+
+    /// Up to `limit` consecutive checkpoints starting at `start`, going back when
+    /// `descending`; from genesis (or the latest checkpoint when `descending`) without one
+    pub async fn get_checkpoints(
+        &self,
+        start: Option<u64>,
+        limit: usize,
+        descending: bool,
+    ) -> Result<Vec<Value>> {
+        // The cursor is exclusive: the checkpoint just before `start` in query order
+        let mut cursor = match start {
+            Some(start) if descending => json!(start.saturating_add(1).to_string()),
+            Some(start) => start
+                .checked_sub(1)
+                .map_or(Value::Null, |cursor| json!(cursor.to_string())),
+            None => Value::Null,
+        };
+        let mut checkpoints = Vec::new();
+        while checkpoints.len() < limit {
+            let page_size = (limit - checkpoints.len()).min(CHECKPOINTS_PAGE_SIZE);
+            let mut page = self
+                .request("sui_getCheckpoints", json!([cursor, page_size, descending]))
+                .await?;
+            match page["data"].take() {
+                Value::Array(data) => checkpoints.extend(data),
+                other => eyre::bail!("Invalid sui_getCheckpoints result: {}", other),
+            }
+            if page["hasNextPage"] != true {
+                break;
+            }
+            cursor = page["nextCursor"].take();
+        }
+        checkpoints.truncate(limit);
+        Ok(checkpoints)
+    }
+
+    /// An object with its type, owner, previous transaction, content and storage rebate
+    pub async fn get_object(&self, object_id: &str) -> Result<Value> {
+        let options = json!({
+            "showType": true,
+            "showOwner": true,
+            "showPreviousTransaction": true,
+            "showDisplay": false,
+            "showContent": true,
+            "showBcs": false,
+            "showStorageRebate": true,
+        });
+        self.request("sui_getObject", json!([object_id, options]))
+            .await
+    }
+
+    /// Any number of objects, one entry per ID in order, see [`objects::multi_get_objects`]
+    pub async fn multi_get_objects<S: AsRef<str>>(
+        &self,
+        object_ids: &[S],
+        options: &ObjectOptions,
+    ) -> Result<Vec<Value>> {
+        objects::multi_get_objects(&self.config, object_ids, options).await
+    }
+
+    /// A transaction with its input, effects, events, object and balance changes
+    pub async fn get_transaction_block(&self, digest: &str) -> Result<Value> {
+        let options = json!({
+            "showInput": true,
+            "showRawInput": false,
+            "showEffects": true,
+            "showEvents": true,
+            "showObjectChanges": true,
+            "showBalanceChanges": true,
+        });
+        self.request("sui_getTransactionBlock", json!([digest, options]))
+            .await
+    }
+
+    /// Balance of `owner` in `coin_type`, SUI when `None`
+    pub async fn get_balance(&self, owner: &str, coin_type: Option<&str>) -> Result<Balance> {
+        let params = match coin_type {
+            Some(coin_type) => json!([owner, coin_type]),
+            None => json!([owner]),
+        };
+        let balance = self.request("suix_getBalance", params).await?;
+        Balance::from_json(&balance)
+    }
+
+    /// Balances of `owner` in every coin type it holds
+    pub async fn get_all_balances(&self, owner: &str) -> Result<Vec<Balance>> {
+        let balances = self.request("suix_getAllBalances", json!([owner])).await?;
+        balances
+            .as_array()
+            .ok_or_else(|| eyre::eyre!("Invalid balances: {}", balances))?
+            .iter()
+            .map(Balance::from_json)
+            .collect()
+    }
+
+    /// Up to `limit` coins of `coin_type` (SUI when `None`) owned by `owner`, starting
+    /// after `cursor` (the `next_cursor` of a previous page) when given
+    pub async fn get_coins(
+        &self,
+        owner: &str,
+        coin_type: Option<&str>,
+        cursor: Option<Value>,
+        limit: usize,
+    ) -> Result<CoinPage> {
+        self.coin_pages("suix_getCoins", json!([owner, coin_type]), cursor, limit)
+            .await
+    }
+
+    /// Up to `limit` coins of any type owned by `owner`, see [`RpcClient::get_coins`]
+    pub async fn get_all_coins(
+        &self,
+        owner: &str,
+        cursor: Option<Value>,
+        limit: usize,
+    ) -> Result<CoinPage> {
+        self.coin_pages("suix_getAllCoins", json!([owner]), cursor, limit)
+            .await
+    }
+
+    /// Pages of `method` until `limit` coins; `params` are those before the cursor
+    async fn coin_pages(
+        &self,
+        method: &str,
+        params: Value,
+        cursor: Option<Value>,
+        limit: usize,
+    ) -> Result<CoinPage> {
+        let mut cursor = cursor.unwrap_or(Value::Null);
+        let mut data = Vec::new();
+        while data.len() < limit {
+            let page_size = (limit - data.len()).min(COINS_PAGE_SIZE);
+            let mut call_params = params.clone();
+            if let Some(params) = call_params.as_array_mut() {
+                params.extend([cursor.clone(), json!(page_size)]);
+            }
+            let mut page = self.request(method, call_params).await?;
+            let coins = match page["data"].take() {
+                Value::Array(coins) => coins,
+                other => eyre::bail!("Invalid {} result: {}", method, other),
+            };
+            for coin in &coins {
+                data.push(Coin::from_json(coin)?);
+            }
+            if page["hasNextPage"] != true {
+                return Ok(CoinPage {
+                    data,
+                    next_cursor: None,
+                });
+            }
+            cursor = page["nextCursor"].take();
+        }
+        Ok(CoinPage {
+            data,
+            next_cursor: Some(cursor),
+        })
+    }
+
+    /// Metadata of `coin_type`, `None` for coins created without any
+    pub async fn get_coin_metadata(&self, coin_type: &str) -> Result<Option<CoinMetadata>> {
+        let metadata = self
+            .request("suix_getCoinMetadata", json!([coin_type]))
+            .await?;
+        if metadata.is_null() {
+            return Ok(None);
+        }
+        serde_json::from_value(metadata)
+            .map(Some)
+            .map_err(|e| eyre::eyre!("Invalid metadata for {}: {}", coin_type, e))
+    }
+
+    /// Total supply of `coin_type` in its smallest unit
+    pub async fn get_total_supply(&self, coin_type: &str) -> Result<u128> {
+        let supply = self
+            .request("suix_getTotalSupply", json!([coin_type]))
+            .await?;
+        json_u128(&supply["value"])
+            .ok_or_else(|| eyre::eyre!("Invalid total supply of {}: {}", coin_type, supply))
+    }
+
+    /// Events matching `query`, see [`events::query_events`]
+    pub async fn query_events(
+        &self,
+        query: &EventQuery,
+        cursor: Option<Value>,
+        limit: usize,
+        descending: bool,
+    ) -> Result<EventPage> {
+        events::query_events(&self.config, query, cursor, limit, descending).await
+    }
+
+    /// Dynamic fields of `parent`, see [`dynamic_fields::get_dynamic_fields`]
+    pub async fn get_dynamic_fields(
+        &self,
+        parent: &str,
+        cursor: Option<Value>,
+        limit: usize,
+        content: bool,
+    ) -> Result<DynamicFieldPage> {
+        dynamic_fields::get_dynamic_fields(&self.config, parent, cursor, limit, content).await
+    }
+
+    /// The object holding the dynamic field of `parent` named `name`
+    pub async fn get_dynamic_field_object(&self, parent: &str, name: &Value) -> Result<Value> {
+        dynamic_fields::get_dynamic_field_object(&self.config, parent, name).await
+    }
+
+    /// Stakes of `owner`, grouped by validator
+    pub async fn get_stakes(&self, owner: &str) -> Result<Vec<DelegatedStake>> {
+        let stakes = self.request("suix_getStakes", json!([owner])).await?;
+        stakes
+            .as_array()
+            .ok_or_else(|| eyre::eyre!("Invalid stakes: {}", stakes))?
+            .iter()
+            .map(DelegatedStake::from_json)
+            .collect()
+    }
+
+    /// The `SuiSystemStateSummary`: epoch, protocol version, reference gas price, stake
+    /// and the active validators with their staking pools
+    pub async fn get_latest_sui_system_state(&self) -> Result<Value> {
+        self.request("suix_getLatestSuiSystemState", json!([]))
+            .await
+    }
+
+    pub async fn get_validators_apy(&self) -> Result<ValidatorsApy> {
+        let apys = self.request("suix_getValidatorsApy", json!([])).await?;
+        let epoch = json_u64(&apys["epoch"])
+            .ok_or_else(|| eyre::eyre!("Invalid validators APY: {}", apys))?;
+        let apys = apys["apys"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|apy| Some((apy["address"].as_str()?.to_string(), apy["apy"].as_f64()?)))
+            .collect();
+        Ok(ValidatorsApy { epoch, apys })
+    }
+
+    pub async fn get_reference_gas_price(&self) -> Result<u64> {
+        let price = self.request("suix_getReferenceGasPrice", json!([])).await?;
+        json_u64(&price).ok_or_else(|| eyre::eyre!("Invalid reference gas price: {}", price))
+    }
+
+    /// Reference gas price of the current epoch and the `epochs - 1` before it as
+    /// `(epoch, price)`, newest first. Past prices come from the event recorded as
+    /// each epoch ended, with the system state it ended in.
+    pub async fn get_reference_gas_price_history(&self, epochs: usize) -> Result<Vec<(u64, u64)>> {
+        let state = self.get_latest_sui_system_state().await?;
+        let current = json_u64(&state["epoch"])
+            .zip(json_u64(&state["referenceGasPrice"]))
+            .ok_or_else(|| eyre::eyre!("System state has no epoch or reference gas price"))?;
+        let mut prices = vec![current];
+        if epochs > 1 {
+            let query = EventQuery {
+                event_type: Some(SYSTEM_EPOCH_INFO_EVENT.to_string()),
+                ..EventQuery::default()
+            };
+            let ended = self.query_events(&query, None, epochs - 1, true).await?;
+            for event in &ended.data {
+                let info = &event["parsedJson"];
+                let price = json_u64(&info["epoch"]).zip(json_u64(&info["reference_gas_price"]));
+                prices.push(price.ok_or_else(|| eyre::eyre!("Invalid epoch info: {}", info))?);
+            }
+        }
+        prices.truncate(epochs.max(1));
+        Ok(prices)
+    }
+
+    /// Validators, transaction count, first checkpoint, start time, reference gas
+    /// price and, once it ended, end-of-epoch info of `epoch`, from `suix_getEpochs`
+    pub async fn get_epoch(&self, epoch: u64) -> Result<Value> {
+        // The cursor is exclusive: the epoch just before the one wanted
+        let cursor = epoch
+            .checked_sub(1)
+            .map_or(Value::Null, |cursor| json!(cursor.to_string()));
+        let page = self
+            .request("suix_getEpochs", json!([cursor, 1, false]))
+            .await?;
+        let info = page["data"].get(0).cloned().unwrap_or_default();
+        if json_u64(&info["epoch"]) != Some(epoch) {
+            eyre::bail!("Epoch {} not found", epoch);
+        }
+        Ok(info)
+    }
+
+    /// Committee of `epoch`, the current one when `None`
+    pub async fn get_committee_info(&self, epoch: Option<u64>) -> Result<CommitteeInfo> {
+        let committee = self
+            .request(
+                "suix_getCommitteeInfo",
+                json!([epoch.map(|epoch| epoch.to_string())]),
+            )
+            .await?;
+        let epoch = json_u64(&committee["epoch"])
+            .ok_or_else(|| eyre::eyre!("Invalid committee info: {}", committee))?;
+        // Validators are [authority name, voting power] pairs
+        let validators = committee["validators"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| Some((v.get(0)?.as_str()?.to_string(), json_u64(v.get(1)?)?)))
+            .collect();
+        Ok(CommitteeInfo { epoch, validators })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn client() -> RpcClient {
+        let path = format!("{}/tests/fixtures/mainnet.json", env!("CARGO_MANIFEST_DIR"));
+        let cassette = transport::vcr::Cassette::from_env(&path).unwrap();
+        RpcClient::new(RpcConfig {
+            mock: Some(Arc::new(cassette)),
+            ..RpcConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_typed_calls() {
+        let client = client();
+        assert_eq!(client.chain_identifier().await.unwrap(), "35834a8a");
+        let checkpoint = client.get_checkpoint(5).await.unwrap();
+        assert_eq!(checkpoint["digest"], "Ckpt5Digest");
+
+        let sui = client.get_balance("0xa11ce", None).await.unwrap();
+        assert_eq!(sui.coin_type, "0x2::sui::SUI");
+        assert_eq!(sui.coin_object_count, 3);
+        assert_eq!(sui.total_balance, 1_500_000_000);
+
+        let all = client.get_all_balances("0xa11ce").await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0], sui);
+        assert_eq!(all[1].total_balance, 42);
+    }
+
+    #[tokio::test]
+    async fn test_stakes_and_apys() {
+        let client = client();
+        let stakes = client.get_stakes("0xa11ce").await.unwrap();
+        assert_eq!(stakes.len(), 1);
+        assert_eq!(stakes[0].validator_address, "0xva1");
+        let [active, pending] = stakes[0].stakes.as_slice() else {
+            panic!("expected two stakes: {:?}", stakes[0].stakes);
+        };
+        assert_eq!(active.principal, 1_000_000_000);
+        assert_eq!(active.estimated_reward, Some(12_345));
+        assert_eq!(pending.status, "Pending");
+        assert_eq!(pending.estimated_reward, None);
+
+        let apys = client.get_validators_apy().await.unwrap();
+        assert_eq!(apys.epoch, 700);
+        assert_eq!(apys.apys, [("0xva1".to_string(), 0.031)]);
+    }
+
+    #[tokio::test]
+    async fn test_get_committee_info() {
+        let committee = client().get_committee_info(None).await.unwrap();
+        assert_eq!(committee.epoch, 700);
+        assert_eq!(
+            committee.validators,
+            [("pk1".to_string(), 6000), ("pk2".to_string(), 4000)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_epoch() {
+        let epoch = client().get_epoch(699).await.unwrap();
+        assert_eq!(epoch["firstCheckpointId"], "150000000");
+        assert_eq!(epoch["endOfEpochInfo"]["lastCheckpointId"], "150099999");
+        assert!(client().get_epoch(800).await.is_err());
+    }
+
+    /// Epoch 10 with a reference gas price of 1000, ended epochs 0 to 9 at `100 * epoch`
+    #[derive(Debug)]
+    struct Epochs;
+
+    impl transport::vcr::MockTransport for Epochs {
+        fn replay(&self, key: &str) -> Option<Result<Value, String>> {
+            let (method, params) = key.split_once(' ')?;
+            let result = match method {
+                "suix_getLatestSuiSystemState" => {
+                    json!({ "epoch": "10", "referenceGasPrice": "1000" })
+                }
+                "suix_queryEvents" => {
+                    let params: Value = serde_json::from_str(params).ok()?;
+                    let event_type = params[0]["MoveEventType"].as_str()?;
+                    assert!(
+                        event_type.ends_with("3::sui_system_state_inner::SystemEpochInfoEvent")
+                    );
+                    assert_eq!(params[3], true);
+                    let events: Vec<Value> = (0..10u64)
+                        .rev()
+                        .map(|epoch| {
+                            json!({
+                                "id": { "txDigest": format!("E{epoch}"), "eventSeq": "0" },
+                                "type": SYSTEM_EPOCH_INFO_EVENT,
+                                "parsedJson": {
+                                    "epoch": epoch.to_string(),
+                                    "reference_gas_price": (100 * epoch).to_string(),
+                                },
+                            })
+                        })
+                        .collect();
+                    json!({ "data": events, "nextCursor": null, "hasNextPage": false })
+                }
+                _ => return None,
+            };
+            Some(Ok(json!({ "result": result })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reference_gas_price_history() {
+        let client = RpcClient::new(RpcConfig {
+            mock: Some(Arc::new(Epochs)),
+            ..RpcConfig::default()
+        });
+        let history = client.get_reference_gas_price_history(3).await.unwrap();
+        assert_eq!(history, [(10, 1000), (9, 900), (8, 800)]);
+        let current = client.get_reference_gas_price_history(1).await.unwrap();
+        assert_eq!(current, [(10, 1000)]);
+    }
+
+    #[tokio::test]
+    async fn test_coin_metadata_and_supply() {
+        let client = client();
+        let sui = client
+            .get_coin_metadata("0x2::sui::SUI")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((sui.symbol.as_str(), sui.decimals), ("SUI", 9));
+        assert_eq!(sui.icon_url, None);
+        assert!(
+            client
+                .get_coin_metadata("0xabc::meme::MEME")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            client.get_total_supply("0x2::sui::SUI").await.unwrap(),
+            10_000_000_000_000_000_000
+        );
+    }
+
+    /// Seven SUI coins `0xc0` to `0xc6` of 1 to 7 MIST, at most three per page
+    #[derive(Debug)]
+    struct Coins;
+
+    impl transport::vcr::MockTransport for Coins {
+        fn replay(&self, key: &str) -> Option<Result<Value, String>> {
+            let (method, params) = key.split_once(' ')?;
+            let params: Value = serde_json::from_str(params).ok()?;
+            let (cursor, limit) = match method {
+                "suix_getCoins" => (&params[2], &params[3]),
+                "suix_getAllCoins" => (&params[1], &params[2]),
+                _ => return None,
+            };
+            let start = match cursor.as_str() {
+                Some(id) => usize::from_str_radix(id.strip_prefix("0xc")?, 16).ok()? + 1,
+                None => 0,
+            };
+            let end = (start + limit.as_u64()?.min(3) as usize).min(7);
+            let data: Vec<Value> = (start..end)
+                .map(|n| {
+                    json!({
+                        "coinType": "0x2::sui::SUI",
+                        "coinObjectId": format!("0xc{n:x}"),
+                        "version": "12",
+                        "digest": "CoinDigest",
+                        "balance": (n + 1).to_string(),
+                        "previousTransaction": "TxDigest",
+                    })
+                })
+                .collect();
+            Some(Ok(json!({ "result": {
+                "data": data,
+                "nextCursor": format!("0xc{:x}", end - 1),
+                "hasNextPage": end < 7,
+            } })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_coins() {
+        let client = RpcClient::new(RpcConfig {
+            mock: Some(Arc::new(Coins)),
+            ..RpcConfig::default()
+        });
+        let ids = |page: &CoinPage| -> Vec<String> {
+            page.data.iter().map(|c| c.coin_object_id.clone()).collect()
+        };
+        let first = client.get_coins("0xa11ce", None, None, 4).await.unwrap();
+        assert_eq!(ids(&first), ["0xc0", "0xc1", "0xc2", "0xc3"]);
+        assert_eq!(first.data[3].balance, 4);
+        assert_eq!(first.data[3].version, 12);
+        let rest = client
+            .get_all_coins("0xa11ce", first.next_cursor, 10)
+            .await
+            .unwrap();
+        assert_eq!(ids(&rest), ["0xc4", "0xc5", "0xc6"]);
+        assert!(rest.next_cursor.is_none());
+    }
+
+    /// Checkpoints 0 to 9, at most four per page
+    #[derive(Debug)]
+    struct Checkpoints;
+
+    impl transport::vcr::MockTransport for Checkpoints {
+        fn replay(&self, key: &str) -> Option<Result<Value, String>> {
+            let params: Value =
+                serde_json::from_str(key.strip_prefix("sui_getCheckpoints ")?).ok()?;
+            let limit = params[1].as_u64()?.min(4);
+            let descending = params[2] == true;
+            let cursor = json_u64(&params[0]);
+            let mut sequence: Vec<u64> = if descending {
+                (0..cursor.unwrap_or(10)).rev().collect()
+            } else {
+                (cursor.map_or(0, |c| c + 1)..10).collect()
+            };
+            let has_next = sequence.len() as u64 > limit;
+            sequence.truncate(limit as usize);
+            let data: Vec<Value> = sequence
+                .iter()
+                .map(|seq| json!({ "sequenceNumber": seq.to_string() }))
+                .collect();
+            Some(Ok(json!({ "result": {
+                "data": data,
+                "nextCursor": sequence.last().map(u64::to_string),
+                "hasNextPage": has_next,
+            } })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_checkpoints() {
+        let client = RpcClient::new(RpcConfig {
+            mock: Some(Arc::new(Checkpoints)),
+            ..RpcConfig::default()
+        });
+        let sequence = |checkpoints: Vec<Value>| -> Vec<u64> {
+            checkpoints
+                .iter()
+                .map(|c| json_u64(&c["sequenceNumber"]).unwrap())
+                .collect()
+        };
+        let forward = client.get_checkpoints(Some(3), 6, false).await.unwrap();
+        assert_eq!(sequence(forward), [3, 4, 5, 6, 7, 8]);
+        let from_genesis = client.get_checkpoints(None, 2, false).await.unwrap();
+        assert_eq!(sequence(from_genesis), [0, 1]);
+        let back = client.get_checkpoints(Some(2), 5, true).await.unwrap();
+        assert_eq!(sequence(back), [2, 1, 0]);
+        let latest = client.get_checkpoints(None, 1, true).await.unwrap();
+        assert_eq!(sequence(latest), [9]);
+    }
+
+    #[test]
+    fn test_balance_from_json() {
+        let balance = Balance::from_json(&json!({
+            "coinType": "0x2::sui::SUI",
+            "coinObjectCount": 1,
+            "totalBalance": "340282366920938463463374607431768211455",
+        }))
+        .unwrap();
+        assert_eq!(balance.total_balance, u128::MAX);
+        assert!(Balance::from_json(&json!({ "totalBalance": "1" })).is_err());
+        assert!(Balance::from_json(&json!({ "coinType": "0x2::sui::SUI" })).is_err());
+    }
+}
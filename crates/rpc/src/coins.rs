@@ -0,0 +1,321 @@
+//! Coin metadata registry and decimals-aware amount formatting
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{RpcConfig, rpc_request};
+
+/// Display metadata of a coin type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoinInfo {
+    pub coin_type: String,
+    pub symbol: String,
+    pub name: String,
+    pub decimals: u8,
+}
+
+impl CoinInfo {
+    /// Build from a `suix_getCoinMetadata` result, which is null for coins without metadata
+    fn from_metadata(coin_type: &str, metadata: &Value) -> Self {
+        if metadata.is_null() {
+            return Self::fallback(coin_type);
+        }
+        Self {
+            coin_type: coin_type.to_string(),
+            symbol: metadata["symbol"].as_str().unwrap_or_default().to_string(),
+            name: metadata["name"].as_str().unwrap_or_default().to_string(),
+            decimals: metadata["decimals"]
+                .as_u64()
+                .and_then(|d| u8::try_from(d).ok())
+                .unwrap_or(0),
+        }
+    }
+
+    /// Raw amounts labelled with the type's struct name
+    fn fallback(coin_type: &str) -> Self {
+        let symbol = coin_type.rsplit("::").next().unwrap_or(coin_type);
+        Self {
+            coin_type: coin_type.to_string(),
+            symbol: symbol.to_string(),
+            name: String::new(),
+            decimals: 0,
+        }
+    }
+
+    /// `raw` in whole units followed by the symbol, e.g. `1.5 SUI`
+    pub fn format(&self, raw: u128) -> String {
+        format!("{} {}", format_amount(raw, self.decimals), self.symbol)
+    }
+
+    /// Signed variant of [`CoinInfo::format`] for balance changes
+    pub fn format_signed(&self, raw: i128) -> String {
+        format!(
+            "{} {}",
+            format_signed_amount(raw, self.decimals),
+            self.symbol
+        )
+    }
+}
+
+/// Format a raw integer amount using the coin's decimals (e.g. MIST -> SUI)
+pub fn format_amount(raw: u128, decimals: u8) -> String {
+    if decimals == 0 {
+        return raw.to_string();
+    }
+    let scale = 10u128.pow(u32::from(decimals));
+    let whole = raw / scale;
+    let frac = raw % scale;
+    if frac == 0 {
+        return whole.to_string();
+    }
+    let frac = format!("{frac:0width$}", width = usize::from(decimals));
+    format!("{whole}.{}", frac.trim_end_matches('0'))
+}
+
+/// Parse a whole-unit amount such as `1.5` into raw units (inverse of [`format_amount`])
+pub fn parse_amount(amount: &str, decimals: u8) -> Result<u128> {
+    let amount = amount.trim();
+    let (whole, frac) = amount.split_once('.').unwrap_or((amount, ""));
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if whole.len() + frac.len() == 0 || !all_digits(whole) || !all_digits(frac) {
+        eyre::bail!("Invalid amount `{}`", amount);
+    }
+    if frac.len() > usize::from(decimals) {
+        eyre::bail!("Amount `{}` has more than {} decimals", amount, decimals);
+    }
+    let raw = format!("{whole}{frac:0<width$}", width = usize::from(decimals));
+    raw.parse()
+        .map_err(|_| eyre::eyre!("Amount `{}` is too large", amount))
+}
+
+/// [`format_amount`] for signed amounts such as balance changes
+pub fn format_signed_amount(raw: i128, decimals: u8) -> String {
+    let formatted = format_amount(raw.unsigned_abs(), decimals);
+    if raw < 0 {
+        format!("-{formatted}")
+    } else {
+        formatted
+    }
+}
+
+/// Lazily populated `CoinMetadata` lookup for one network.
+///
+/// Entries are fetched on first use and, when backed by a file, persisted so
+/// later runs do not need to ask the node again. The file maps RPC URLs to
+/// coin types so several networks can share it.
+#[derive(Debug)]
+pub struct CoinRegistry {
+    config: RpcConfig,
+    path: Option<PathBuf>,
+    coins: Mutex<BTreeMap<String, CoinInfo>>,
+}
+
+impl CoinRegistry {
+    /// In-memory registry that forgets everything when dropped
+    pub fn new(config: RpcConfig) -> Self {
+        Self {
+            config,
+            path: None,
+            coins: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Registry persisted in the JSON file at `path` (created on first save)
+    pub fn open(config: RpcConfig, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let coins = read_registry_file(&path)?
+            .remove(&config.url)
+            .unwrap_or_default();
+        Ok(Self {
+            config,
+            path: Some(path),
+            coins: Mutex::new(coins),
+        })
+    }
+
+    /// Metadata for `coin_type`, fetched from the node if not yet known
+    pub async fn get(&self, coin_type: &str) -> Result<CoinInfo> {
+        self.get_with(coin_type, || {
+            rpc_request(&self.config, "suix_getCoinMetadata", json!([coin_type]))
+        })
+        .await
+    }
+
+    /// [`CoinRegistry::get`] with the `CoinMetadata` (or null) coming from `fetch`,
+    /// for callers that talk to the node over another API such as gRPC
+    pub async fn get_with<F, Fut>(&self, coin_type: &str, fetch: F) -> Result<CoinInfo>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Value>>,
+    {
+        if let Some(info) = self.lock().get(coin_type) {
+            return Ok(info.clone());
+        }
+        let metadata = fetch().await?;
+        let info = CoinInfo::from_metadata(coin_type, &metadata);
+        self.lock().insert(coin_type.to_string(), info.clone());
+        if let Err(e) = self.save() {
+            eprintln!("Warning: failed to save coin registry: {e}");
+        }
+        Ok(info)
+    }
+
+    /// Metadata for `coin_type`, falling back to raw amounts if it cannot be fetched
+    pub async fn get_or_raw(&self, coin_type: &str) -> CoinInfo {
+        or_raw(coin_type, self.get(coin_type).await)
+    }
+
+    /// [`CoinRegistry::get_with`] falling back to raw amounts if it fails
+    pub async fn get_or_raw_with<F, Fut>(&self, coin_type: &str, fetch: F) -> CoinInfo
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Value>>,
+    {
+        or_raw(coin_type, self.get_with(coin_type, fetch).await)
+    }
+
+    /// All known coin types, sorted by type
+    pub fn list(&self) -> Vec<CoinInfo> {
+        self.lock().values().cloned().collect()
+    }
+
+    /// Re-fetch metadata for every known coin type, returning how many were updated
+    pub async fn refresh(&self) -> Result<usize> {
+        let coin_types: Vec<String> = self.lock().keys().cloned().collect();
+        let mut updated = 0;
+        for coin_type in coin_types {
+            let metadata =
+                rpc_request(&self.config, "suix_getCoinMetadata", json!([coin_type])).await?;
+            let info = CoinInfo::from_metadata(&coin_type, &metadata);
+            if self.lock().insert(coin_type, info.clone()).as_ref() != Some(&info) {
+                updated += 1;
+            }
+        }
+        self.save()?;
+        Ok(updated)
+    }
+
+    /// Write this network's entries back to the registry file, if any
+    pub fn save(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        let mut all = read_registry_file(path)?;
+        all.insert(self.config.url.clone(), self.lock().clone());
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(&all)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, CoinInfo>> {
+        self.coins.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn or_raw(coin_type: &str, info: Result<CoinInfo>) -> CoinInfo {
+    info.unwrap_or_else(|e| {
+        eprintln!("Warning: no metadata for {coin_type}: {e}");
+        CoinInfo::fallback(coin_type)
+    })
+}
+
+type RegistryFile = BTreeMap<String, BTreeMap<String, CoinInfo>>;
+
+fn read_registry_file(path: &Path) -> Result<RegistryFile> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|e| eyre::eyre!("Invalid coin registry {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RegistryFile::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(1_000_000_000, 9), "1");
+        assert_eq!(format_amount(1_500_000_000, 9), "1.5");
+        assert_eq!(format_amount(1, 9), "0.000000001");
+        assert_eq!(format_amount(42, 0), "42");
+        assert_eq!(format_signed_amount(-2_500_000, 6), "-2.5");
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("1.5", 9).unwrap(), 1_500_000_000);
+        assert_eq!(parse_amount("42", 0).unwrap(), 42);
+        assert_eq!(parse_amount(".25", 2).unwrap(), 25);
+        assert!(parse_amount("0.001", 2).is_err());
+        assert!(parse_amount("1e9", 9).is_err());
+        assert!(parse_amount("", 9).is_err());
+    }
+
+    #[test]
+    fn test_coin_info_from_metadata() {
+        let usdc = CoinInfo::from_metadata(
+            "0xdba3::usdc::USDC",
+            &json!({ "decimals": 6, "symbol": "USDC", "name": "USD Coin" }),
+        );
+        assert_eq!(usdc.format(1_250_000), "1.25 USDC");
+
+        let unknown = CoinInfo::from_metadata("0xabc::meme::MEME", &Value::Null);
+        assert_eq!(unknown.format_signed(-7), "-7 MEME");
+    }
+
+    #[tokio::test]
+    async fn test_get_with_fetches_once() {
+        let registry = CoinRegistry::new(RpcConfig::default());
+        let usdc = registry
+            .get_with("0xdba3::usdc::USDC", || async {
+                Ok(json!({ "decimals": 6, "symbol": "USDC", "name": "USD Coin" }))
+            })
+            .await
+            .unwrap();
+        assert_eq!(usdc.decimals, 6);
+        let cached = registry
+            .get_or_raw_with("0xdba3::usdc::USDC", || async {
+                eyre::bail!("should not be fetched again")
+            })
+            .await;
+        assert_eq!(cached, usdc);
+        let raw = registry
+            .get_or_raw_with("0xabc::meme::MEME", || async { eyre::bail!("unavailable") })
+            .await;
+        assert_eq!(raw.format(7), "7 MEME");
+    }
+
+    #[test]
+    fn test_registry_persists_per_network() {
+        let path = std::env::temp_dir().join(format!("suix-coins-{}.json", std::process::id()));
+        let info = CoinInfo::fallback("0x2::sui::SUI");
+
+        let registry = CoinRegistry::open(RpcConfig::default(), &path).unwrap();
+        registry.lock().insert(info.coin_type.clone(), info.clone());
+        registry.save().unwrap();
+
+        let reopened = CoinRegistry::open(RpcConfig::default(), &path).unwrap();
+        assert_eq!(reopened.list(), [info]);
+
+        let other = RpcConfig {
+            url: "http://127.0.0.1:9000".to_string(),
+            ..RpcConfig::default()
+        };
+        assert!(CoinRegistry::open(other, &path).unwrap().list().is_empty());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,203 @@
+//! Dynamic fields of an object, such as the entries of a Move `Table` or `Bag`
+//!
+//! `suix_getDynamicFields` lists names and types only; the values live in separate
+//! objects, fetched on request with [`multi_get_objects`].
+
+use eyre::Result;
+use serde_json::{Value, json};
+
+use crate::{
+    RpcConfig,
+    objects::{ObjectOptions, multi_get_objects},
+    rpc_request,
+};
+
+/// Fields per `suix_getDynamicFields` call, the most fullnodes return
+const PAGE_SIZE: usize = 50;
+
+/// Dynamic fields found by [`get_dynamic_fields`]
+#[derive(Debug, Clone, Default)]
+pub struct DynamicFieldPage {
+    pub data: Vec<Value>,
+    /// Cursor to pass to the next call for the fields after these, `None` at the end
+    pub next_cursor: Option<Value>,
+}
+
+/// Up to `limit` dynamic fields of `parent`, starting after `cursor` (the
+/// `next_cursor` of a previous page) when given.
+///
+/// With `content`, each field also gets a `value`: the field's value for a dynamic
+/// field, or the content of the child object for a dynamic object field.
+pub async fn get_dynamic_fields(
+    config: &RpcConfig,
+    parent: &str,
+    cursor: Option<Value>,
+    limit: usize,
+    content: bool,
+) -> Result<DynamicFieldPage> {
+    let mut cursor = cursor.unwrap_or(Value::Null);
+    let mut data = Vec::new();
+    let next_cursor = loop {
+        let page_size = (limit - data.len()).min(PAGE_SIZE);
+        let mut page = rpc_request(
+            config,
+            "suix_getDynamicFields",
+            json!([parent, cursor, page_size]),
+        )
+        .await?;
+        match page["data"].take() {
+            Value::Array(fields) => data.extend(fields),
+            other => eyre::bail!("Invalid suix_getDynamicFields result: {}", other),
+        }
+        if page["hasNextPage"] != true {
+            break None;
+        }
+        cursor = page["nextCursor"].take();
+        if data.len() >= limit {
+            break Some(cursor);
+        }
+    };
+    if content {
+        add_values(config, &mut data).await?;
+    }
+    Ok(DynamicFieldPage { data, next_cursor })
+}
+
+/// The object holding the dynamic field of `parent` named `name`, e.g.
+/// `{"type": "address", "value": "0x..."}`, with its type, owner and content
+pub async fn get_dynamic_field_object(
+    config: &RpcConfig,
+    parent: &str,
+    name: &Value,
+) -> Result<Value> {
+    rpc_request(config, "suix_getDynamicFieldObject", json!([parent, name])).await
+}
+
+/// Set the `value` of every field from the objects holding them
+async fn add_values(config: &RpcConfig, fields: &mut [Value]) -> Result<()> {
+    let ids: Vec<&str> = fields
+        .iter()
+        .map(|field| field["objectId"].as_str().unwrap_or_default())
+        .collect();
+    let objects = multi_get_objects(config, &ids, &ObjectOptions::content()).await?;
+    for (field, object) in fields.iter_mut().zip(&objects) {
+        field["value"] = field_value(field, object);
+    }
+    Ok(())
+}
+
+/// Value of `field` given the object holding it; dynamic fields wrap theirs in a
+/// `Field<K, V>` object, dynamic object fields are the object itself
+fn field_value(field: &Value, object: &Value) -> Value {
+    let content = &object["data"]["content"];
+    if field["type"] == "DynamicObject" {
+        content.clone()
+    } else {
+        content["fields"]["value"].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use transport::vcr::MockTransport;
+
+    use super::*;
+
+    /// A table of 0xt with five u64 entries `k` -> `10 * k`, served two per page
+    #[derive(Debug)]
+    struct Table;
+
+    impl MockTransport for Table {
+        fn replay(&self, key: &str) -> Option<Result<Value, String>> {
+            let (method, params) = key.split_once(' ')?;
+            let params: Value = serde_json::from_str(params).ok()?;
+            let result = match method {
+                "suix_getDynamicFields" => {
+                    let start = crate::json_u64(&params[1]).map_or(0, |c| c + 1);
+                    let end = (start + params[2].as_u64()?.min(2)).min(5);
+                    let data: Vec<Value> = (start..end)
+                        .map(|k| {
+                            json!({
+                                "name": { "type": "u64", "value": k.to_string() },
+                                "type": "DynamicField",
+                                "objectId": format!("0xf{k}"),
+                            })
+                        })
+                        .collect();
+                    json!({
+                        "data": data,
+                        "nextCursor": (end - 1).to_string(),
+                        "hasNextPage": end < 5,
+                    })
+                }
+                "sui_multiGetObjects" => {
+                    let objects: Vec<Value> = params[0]
+                        .as_array()?
+                        .iter()
+                        .map(|id| {
+                            let k: u64 = id.as_str()?.strip_prefix("0xf")?.parse().ok()?;
+                            Some(json!({ "data": { "objectId": id, "content": {
+                                "fields": { "name": k.to_string(), "value": (10 * k).to_string() },
+                            } } }))
+                        })
+                        .collect::<Option<_>>()?;
+                    json!(objects)
+                }
+                "suix_getDynamicFieldObject" => {
+                    json!({ "data": { "objectId": format!("0xf{}", params[1]["value"].as_str()?) } })
+                }
+                _ => return None,
+            };
+            Some(Ok(json!({ "result": result })))
+        }
+    }
+
+    fn config() -> RpcConfig {
+        RpcConfig {
+            mock: Some(Arc::new(Table)),
+            ..RpcConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_dynamic_fields() {
+        let config = config();
+        let names = |page: &DynamicFieldPage| -> Vec<String> {
+            page.data
+                .iter()
+                .map(|f| f["name"]["value"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let first = get_dynamic_fields(&config, "0xt", None, 3, false)
+            .await
+            .unwrap();
+        assert_eq!(names(&first), ["0", "1", "2"]);
+        assert!(first.data[0].get("value").is_none());
+        let rest = get_dynamic_fields(&config, "0xt", first.next_cursor, 10, true)
+            .await
+            .unwrap();
+        assert_eq!(names(&rest), ["3", "4"]);
+        assert_eq!(rest.data[1]["value"], "40");
+        assert!(rest.next_cursor.is_none());
+
+        let field =
+            get_dynamic_field_object(&config, "0xt", &json!({ "type": "u64", "value": "4" }))
+                .await
+                .unwrap();
+        assert_eq!(field["data"]["objectId"], "0xf4");
+    }
+
+    #[test]
+    fn test_field_value() {
+        let object = json!({ "data": { "content": {
+            "type": "0x2::coin::Coin<0x2::sui::SUI>",
+            "fields": { "balance": "7" },
+        } } });
+        let field = json!({ "type": "DynamicObject" });
+        assert_eq!(field_value(&field, &object)["fields"]["balance"], "7");
+        let field = json!({ "type": "DynamicField" });
+        assert_eq!(field_value(&field, &object), Value::Null);
+    }
+}
@@ -0,0 +1,293 @@
+//! `suix_queryEvents` with the filter built from plain criteria
+//!
+//! Fullnodes index events by a single criterion, so the most selective one is
+//! sent with the query and every criterion is checked again on what comes back.
+
+use eyre::Result;
+use filter::{Field, Filter};
+use serde_json::{Value, json};
+
+use crate::{RpcConfig, json_u64, rpc_request};
+
+/// Events per `suix_queryEvents` call, the most fullnodes return
+const PAGE_SIZE: usize = 50;
+
+/// Stop after this many pages without filling the limit, returning a cursor to go on from
+const MAX_PAGES: usize = 100;
+
+/// What [`query_events`] looks for; every criterion given must hold
+#[derive(Debug, Clone, Default)]
+pub struct EventQuery {
+    /// Package of the module the transaction called to emit the event
+    pub package: Option<String>,
+    /// Module the transaction called to emit the event
+    pub module: Option<String>,
+    /// Move type of the event, e.g. `0x3::validator::StakingRequestEvent`
+    pub event_type: Option<String>,
+    /// Sender of the transaction that emitted the event
+    pub sender: Option<String>,
+    /// Only events at or after this time, in ms since the Unix epoch
+    pub start_time_ms: Option<u64>,
+    /// Only events before this time, in ms since the Unix epoch
+    pub end_time_ms: Option<u64>,
+}
+
+/// Events found by [`query_events`]
+#[derive(Debug, Clone, Default)]
+pub struct EventPage {
+    pub data: Vec<Value>,
+    /// Cursor to pass to the next query for the events after these, `None` at the end
+    pub next_cursor: Option<Value>,
+}
+
+impl EventQuery {
+    /// The criteria other than time as a filter expression, if there are any
+    fn expression(&self) -> Option<Filter> {
+        let mut conditions: Vec<Filter> = [
+            (Field::Package, &self.package),
+            (Field::Module, &self.module),
+            (Field::Type, &self.event_type),
+            (Field::Sender, &self.sender),
+        ]
+        .into_iter()
+        .filter_map(|(field, value)| Some(Filter::eq(field, value.as_deref()?)))
+        .collect();
+        match conditions.len() {
+            0 => None,
+            1 => conditions.pop(),
+            _ => Some(Filter::And(conditions)),
+        }
+    }
+
+    /// The `EventFilter` sent to the node: the most selective criterion, or the
+    /// time range when no other criterion narrows the query
+    pub fn filter(&self) -> Value {
+        if let Some(expression) = self.expression() {
+            let (filter, _) = expression.event_filter();
+            if filter.get("TimeRange").is_none() {
+                return filter;
+            }
+        }
+        json!({ "TimeRange": {
+            "startTime": self.start_time_ms.unwrap_or(0).to_string(),
+            "endTime": self.end_time_ms.unwrap_or(u64::MAX).to_string(),
+        } })
+    }
+
+    /// Whether an event meets every criterion
+    pub fn matches(&self, event: &Value) -> bool {
+        let timestamp = json_u64(&event["timestampMs"]);
+        let in_range = match timestamp {
+            Some(ms) => {
+                self.start_time_ms.is_none_or(|start| ms >= start)
+                    && self.end_time_ms.is_none_or(|end| ms < end)
+            }
+            None => self.start_time_ms.is_none() && self.end_time_ms.is_none(),
+        };
+        in_range
+            && self
+                .expression()
+                .is_none_or(|expression| expression.matches_event(event))
+    }
+
+    /// Whether an event lies beyond the time range in the direction of the query,
+    /// so that no later page can match
+    fn is_past_range(&self, event: &Value, descending: bool) -> bool {
+        let Some(ms) = json_u64(&event["timestampMs"]) else {
+            return false;
+        };
+        if descending {
+            self.start_time_ms.is_some_and(|start| ms < start)
+        } else {
+            self.end_time_ms.is_some_and(|end| ms >= end)
+        }
+    }
+}
+
+/// Up to `limit` events matching `query`, newest first when `descending`, starting
+/// after `cursor` (the `next_cursor` of a previous page) when given.
+///
+/// Pages are fetched until `limit` events match, the events run out or pass the
+/// time range, or [`MAX_PAGES`] pages were read; the page then ends early with a
+/// cursor to continue from.
+pub async fn query_events(
+    config: &RpcConfig,
+    query: &EventQuery,
+    cursor: Option<Value>,
+    limit: usize,
+    descending: bool,
+) -> Result<EventPage> {
+    let filter = query.filter();
+    let mut cursor = cursor.unwrap_or(Value::Null);
+    let mut data = Vec::new();
+    for _ in 0..MAX_PAGES {
+        let mut page = rpc_request(
+            config,
+            "suix_queryEvents",
+            json!([filter, cursor, PAGE_SIZE, descending]),
+        )
+        .await?;
+        let events = match page["data"].take() {
+            Value::Array(events) => events,
+            other => eyre::bail!("Invalid suix_queryEvents result: {}", other),
+        };
+        for event in events {
+            if query.is_past_range(&event, descending) {
+                return Ok(EventPage {
+                    data,
+                    next_cursor: None,
+                });
+            }
+            cursor = event["id"].clone();
+            if query.matches(&event) {
+                data.push(event);
+                if data.len() == limit {
+                    return Ok(EventPage {
+                        data,
+                        next_cursor: Some(cursor),
+                    });
+                }
+            }
+        }
+        if page["hasNextPage"] != true {
+            return Ok(EventPage {
+                data,
+                next_cursor: None,
+            });
+        }
+        cursor = page["nextCursor"].take();
+    }
+    Ok(EventPage {
+        data,
+        next_cursor: Some(cursor),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use transport::vcr::MockTransport;
+
+    use super::*;
+
+    fn event(seq: u64, sender: &str, timestamp_ms: u64) -> Value {
+        json!({
+            "id": { "txDigest": format!("D{seq}"), "eventSeq": "0" },
+            "packageId": "0x2",
+            "transactionModule": "pool",
+            "type": "0x2::pool::Swapped",
+            "sender": sender,
+            "timestampMs": timestamp_ms.to_string(),
+        })
+    }
+
+    /// Six events one second apart, by alternating senders, served three per page
+    #[derive(Debug)]
+    struct Events;
+
+    impl MockTransport for Events {
+        fn replay(&self, key: &str) -> Option<Result<Value, String>> {
+            let params: Value =
+                serde_json::from_str(key.strip_prefix("suix_queryEvents ")?).ok()?;
+            let mut events: Vec<Value> = (0..6)
+                .map(|seq| event(seq, ["0xa", "0xb"][seq as usize % 2], 1000 * seq))
+                .collect();
+            if params[3] == true {
+                events.reverse();
+            }
+            let start = match &params[1] {
+                Value::Null => 0,
+                cursor => events.iter().position(|e| e["id"] == *cursor)? + 1,
+            };
+            let page: Vec<Value> = events.iter().skip(start).take(3).cloned().collect();
+            let has_next = start + 3 < events.len();
+            Some(Ok(json!({
+                "result": {
+                    "data": page,
+                    "nextCursor": page.last().map(|e| e["id"].clone()),
+                    "hasNextPage": has_next,
+                },
+            })))
+        }
+    }
+
+    fn config() -> RpcConfig {
+        RpcConfig {
+            mock: Some(Arc::new(Events)),
+            ..RpcConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_filter() {
+        let query = EventQuery {
+            package: Some("0x2".to_string()),
+            module: Some("pool".to_string()),
+            sender: Some("0xa".to_string()),
+            ..EventQuery::default()
+        };
+        assert_eq!(query.filter()["MoveEventModule"]["module"], "pool");
+
+        let query = EventQuery {
+            event_type: Some("0x2::pool::Swapped".to_string()),
+            start_time_ms: Some(5),
+            ..EventQuery::default()
+        };
+        assert!(query.filter().get("MoveEventType").is_some());
+
+        let query = EventQuery {
+            module: Some("pool".to_string()),
+            start_time_ms: Some(5),
+            ..EventQuery::default()
+        };
+        assert_eq!(query.filter()["TimeRange"]["startTime"], "5");
+    }
+
+    #[test]
+    fn test_matches() {
+        let query = EventQuery {
+            sender: Some("0xa".to_string()),
+            start_time_ms: Some(1000),
+            end_time_ms: Some(3000),
+            ..EventQuery::default()
+        };
+        assert!(query.matches(&event(0, "0xa", 1000)));
+        assert!(!query.matches(&event(0, "0xb", 1000)));
+        assert!(!query.matches(&event(0, "0xa", 3000)));
+        assert!(EventQuery::default().matches(&event(0, "0xb", 0)));
+    }
+
+    #[tokio::test]
+    async fn test_query_events_pages() {
+        let config = config();
+        let query = EventQuery {
+            sender: Some("0xa".to_string()),
+            ..EventQuery::default()
+        };
+        let first = query_events(&config, &query, None, 2, false).await.unwrap();
+        let digests = |page: &EventPage| -> Vec<String> {
+            page.data
+                .iter()
+                .map(|e| e["id"]["txDigest"].as_str().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(digests(&first), ["D0", "D2"]);
+        let rest = query_events(&config, &query, first.next_cursor, 2, false)
+            .await
+            .unwrap();
+        assert_eq!(digests(&rest), ["D4"]);
+        assert!(rest.next_cursor.is_none());
+
+        // Newest first, stopping once events are older than the range
+        let recent = EventQuery {
+            start_time_ms: Some(3500),
+            ..EventQuery::default()
+        };
+        let page = query_events(&config, &recent, None, 10, true)
+            .await
+            .unwrap();
+        assert_eq!(digests(&page), ["D5", "D4"]);
+        assert!(page.next_cursor.is_none());
+    }
+}
@@ -0,0 +1,71 @@
+//! Client for the Sui GraphQL service
+
+use eyre::Result;
+use serde_json::{Value, json};
+
+/// Default mainnet GraphQL endpoint
+pub const MAINNET_URL: &str = "https://graphql.mainnet.sui.io/graphql";
+
+#[derive(Debug, Clone)]
+pub struct GraphqlClient {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl GraphqlClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Send a query and return the whole response, including any `errors`
+    pub async fn request(&self, query: &str, variables: Value) -> Result<Value> {
+        self.http
+            .post(&self.url)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .map_err(|e| eyre::eyre!("GraphQL request failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| eyre::eyre!("Invalid GraphQL response: {}", e))
+    }
+
+    /// Send a query and return its `data`; GraphQL errors are returned as `Err`
+    pub async fn query(&self, query: &str, variables: Value) -> Result<Value> {
+        let mut response = self.request(query, variables).await?;
+        if let Some(message) = error_message(&response) {
+            eyre::bail!("GraphQL error: {}", message);
+        }
+        Ok(response["data"].take())
+    }
+}
+
+/// Messages of a response's `errors`, joined, if there are any
+pub fn error_message(response: &Value) -> Option<String> {
+    let errors = response["errors"].as_array().filter(|e| !e.is_empty())?;
+    let messages: Vec<&str> = errors
+        .iter()
+        .filter_map(|e| e["message"].as_str())
+        .collect();
+    Some(messages.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_message() {
+        let ok = json!({ "data": { "epoch": null } });
+        assert_eq!(error_message(&ok), None);
+        let failed = json!({ "errors": [{ "message": "a" }, { "message": "b" }], "data": null });
+        assert_eq!(error_message(&failed).as_deref(), Some("a; b"));
+    }
+}
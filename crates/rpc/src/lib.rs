@@ -1,11 +1,41 @@
+pub mod client;
+pub mod coins;
+pub mod dynamic_fields;
+pub mod events;
+pub mod graphql;
+pub mod objects;
+pub mod stream;
+pub mod ws;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use cache::{CacheKey, ResponseCache};
 use eyre::Result;
 use serde_json::{Value, json};
+use store::ChainStore;
+use transport::{Transport, vcr::MockTransport};
+
+/// `sui_multiGetTransactionBlocks` accepts at most 50 digests per call
+pub const MAX_DIGESTS_PER_CALL: usize = 50;
 
 /// Configuration for RPC client
 #[derive(Debug, Clone)]
 pub struct RpcConfig {
     pub url: String,
     pub pretty: bool,
+    /// On-disk cache consulted for responses that can never change
+    pub cache: Option<ResponseCache>,
+    /// Retry, failover and rate limiting policy; requests are sent once when unset
+    pub transport: Option<Arc<Transport>>,
+    /// Archival endpoint queried when `url` has pruned historical data
+    pub archive_url: Option<String>,
+    /// Local chain store that answers requests instead of the network
+    pub local: Option<ChainStore>,
+    /// Recorded responses replayed in place of the network, see [`transport::vcr`]
+    pub mock: Option<Arc<dyn MockTransport>>,
 }
 
 impl Default for RpcConfig {
@@ -13,42 +43,223 @@ impl Default for RpcConfig {
         Self {
             url: "https://fullnode.mainnet.sui.io:443".to_string(),
             pretty: false,
+            cache: None,
+            transport: None,
+            archive_url: None,
+            local: None,
+            mock: None,
         }
     }
 }
 
-/// Make a JSON-RPC call to the Sui node
-pub async fn make_rpc_call(config: &RpcConfig, method: &str, params: Option<&str>) -> Result<()> {
-    // Parse parameters if provided
-    let params_value: Value = if let Some(params_str) = params {
-        serde_json::from_str(params_str)
-            .map_err(|e| eyre::eyre!("Invalid JSON parameters: {}", e))?
-    } else {
-        json!([])
-    };
+/// Endpoint that answered a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataSource {
+    Primary,
+    Archive,
+    Local,
+}
 
-    // Construct JSON-RPC request
-    let request = json!({
+/// Methods whose successful responses are immutable once returned
+/// (finalized checkpoints, executed transactions, past object versions, published modules)
+const IMMUTABLE_METHODS: &[&str] = &[
+    "sui_getCheckpoint",
+    "sui_getTransactionBlock",
+    "sui_multiGetTransactionBlocks",
+    "sui_tryGetPastObject",
+    "sui_tryMultiGetPastObjects",
+    "sui_getNormalizedMoveModulesByPackage",
+    "sui_getNormalizedMoveModule",
+    "sui_getNormalizedMoveFunction",
+    "sui_getNormalizedMoveStruct",
+];
+
+/// Whether a response may be stored in the cache
+fn is_cacheable_response(method: &str, response: &Value) -> bool {
+    if response.get("error").is_some() {
+        return false;
+    }
+    let result = &response["result"];
+    match method {
+        "sui_tryGetPastObject" => result["status"] == "VersionFound",
+        "sui_tryMultiGetPastObjects" => result
+            .as_array()
+            .is_some_and(|objects| objects.iter().all(|o| o["status"] == "VersionFound")),
+        // Executed transactions only become final once checkpointed
+        "sui_getTransactionBlock" => result.get("checkpoint").is_some(),
+        "sui_multiGetTransactionBlocks" => result.as_array().is_some_and(|txs| {
+            txs.iter()
+                .all(|tx| tx.get("errors").is_none() && tx.get("checkpoint").is_some())
+        }),
+        _ => !result.is_null(),
+    }
+}
+
+/// Whether an error message indicates data the node no longer serves
+pub fn is_pruned_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    [
+        "pruned",
+        "not found",
+        "could not find",
+        "notexists",
+        "not available",
+    ]
+    .iter()
+    .any(|needle| message.contains(needle))
+}
+
+/// Whether a historical query came back empty, most likely because the node pruned it
+fn is_pruned_response(method: &str, response: &Value) -> bool {
+    if !IMMUTABLE_METHODS.contains(&method) {
+        return false;
+    }
+    if let Some(error) = response.get("error") {
+        return is_pruned_error(&error.to_string());
+    }
+    let missing = |status: &Value| status == "VersionNotFound" || status == "ObjectNotExists";
+    let result = &response["result"];
+    match method {
+        "sui_tryGetPastObject" => missing(&result["status"]),
+        "sui_tryMultiGetPastObjects" => result
+            .as_array()
+            .is_some_and(|objects| objects.iter().any(|o| missing(&o["status"]))),
+        "sui_multiGetTransactionBlocks" => result
+            .as_array()
+            .is_some_and(|txs| txs.iter().any(|tx| tx.get("errors").is_some())),
+        _ => false,
+    }
+}
+
+/// Cache key for a request, if its method is cacheable
+fn cache_key(config: &RpcConfig, request: &Value) -> Option<CacheKey> {
+    let method = request["method"].as_str()?;
+    if !IMMUTABLE_METHODS.contains(&method) {
+        return None;
+    }
+    Some(CacheKey::new(
+        &config.url,
+        method,
+        &request["params"].to_string(),
+    ))
+}
+
+/// Build a JSON-RPC 2.0 request envelope
+fn build_request(method: &str, params: Value) -> Value {
+    json!({
         "jsonrpc": "2.0",
         "id": 1,
         "method": method,
-        "params": params_value
-    });
+        "params": params
+    })
+}
 
-    if config.pretty {
-        println!("Making RPC call to: {}", config.url);
-        println!("Method: {method}");
-        println!("Request:");
-        println!("{}", serde_json::to_string_pretty(&request)?);
-        println!();
+/// Send a JSON-RPC request and return the full response envelope and who answered it.
+///
+/// Immutable responses are served from and stored to `config.cache` when set.
+/// Historical queries the primary node has pruned are retried against
+/// `config.archive_url` when set. With `config.local` set the network is never used.
+async fn send_request(config: &RpcConfig, request: &Value) -> Result<(Value, DataSource)> {
+    let method = request["method"].as_str().unwrap_or_default();
+    if let Some(ref local) = config.local {
+        return Ok((local_response(local, request)?, DataSource::Local));
+    }
+    let cached = config
+        .cache
+        .as_ref()
+        .and_then(|cache| Some((cache, cache_key(config, request)?)));
+    if let Some((cache, key)) = &cached
+        && let Some(response) = cache.get_json(key)?
+    {
+        return Ok((response, DataSource::Primary));
     }
 
-    // Make the HTTP request
+    let primary = send_to(config, &config.url, request).await;
+    let (response, source) = match (primary, config.archive_url.as_deref()) {
+        (Ok(response), Some(archive)) if is_pruned_response(method, &response) => {
+            match send_to(config, archive, request).await {
+                Ok(archived) if !is_pruned_response(method, &archived) => {
+                    (archived, DataSource::Archive)
+                }
+                _ => (response, DataSource::Primary),
+            }
+        }
+        (Err(e), Some(archive)) if is_pruned_error(&e.to_string()) => (
+            send_to(config, archive, request).await?,
+            DataSource::Archive,
+        ),
+        (primary, _) => (primary?, DataSource::Primary),
+    };
+
+    if let Some((cache, key)) = &cached
+        && is_cacheable_response(method, &response)
+        && let Err(e) = cache.put_json(key, &response)
+    {
+        eprintln!("Warning: failed to cache response: {e}");
+    }
+    Ok((response, source))
+}
+
+/// Answer a request from the local store, wrapped in a JSON-RPC envelope
+fn local_response(store: &ChainStore, request: &Value) -> Result<Value> {
+    let method = request["method"].as_str().unwrap_or_default();
+    match store.answer(method, &request["params"])? {
+        Some(result) => Ok(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })),
+        None => eyre::bail!(
+            "{} {} is not in the local store; run `suix store sync` to fetch it",
+            method,
+            request["params"]
+        ),
+    }
+}
+
+/// Send a request to `url` through the configured transport, if any.
+///
+/// With `config.mock` set, recorded responses are used instead and network
+/// responses are handed to it for recording.
+async fn send_to(config: &RpcConfig, url: &str, request: &Value) -> Result<Value> {
+    let Some(ref mock) = config.mock else {
+        return send_via_transport(config, url, request).await;
+    };
+    let key = mock_key(request);
+    if let Some(replayed) = mock.replay(&key) {
+        return replayed.map_err(|e| eyre::eyre!(e));
+    }
+    let response = send_via_transport(config, url, request).await?;
+    mock.record(&key, &response);
+    Ok(response)
+}
+
+/// Fixture key of a request: its method and params, independent of endpoint and id
+fn mock_key(request: &Value) -> String {
+    format!(
+        "{} {}",
+        request["method"].as_str().unwrap_or_default(),
+        request["params"]
+    )
+}
+
+async fn send_via_transport(config: &RpcConfig, url: &str, request: &Value) -> Result<Value> {
+    match config.transport {
+        Some(ref transport) => {
+            transport
+                .execute(
+                    url,
+                    |url| async move { fetch_response(&url, request).await },
+                )
+                .await
+        }
+        None => fetch_response(url, request).await,
+    }
+}
+
+/// Perform the HTTP round trip for a JSON-RPC request
+async fn fetch_response(url: &str, request: &Value) -> Result<Value> {
     let client = reqwest::Client::new();
     let response = client
-        .post(&config.url)
+        .post(url)
         .header("Content-Type", "application/json")
-        .json(&request)
+        .json(request)
         .send()
         .await
         .map_err(|e| eyre::eyre!("HTTP request failed: {}", e))?;
@@ -67,15 +278,98 @@ pub async fn make_rpc_call(config: &RpcConfig, method: &str, params: Option<&str
         );
     }
 
-    // Parse and display the response
-    let response_json: Value = serde_json::from_str(&response_text)
-        .map_err(|e| eyre::eyre!("Invalid JSON response: {}", e))?;
+    serde_json::from_str(&response_text).map_err(|e| eyre::eyre!("Invalid JSON response: {}", e))
+}
+
+/// Make a JSON-RPC call and return its `result` field.
+///
+/// Unlike [`make_rpc_call`] nothing is printed; JSON-RPC errors are returned as `Err`.
+pub async fn rpc_request(config: &RpcConfig, method: &str, params: Value) -> Result<Value> {
+    let request = build_request(method, params);
+    let (mut response, source) = send_request(config, &request).await?;
+    // Noted once per process so bulk commands do not flood stderr
+    static ARCHIVE_NOTED: AtomicBool = AtomicBool::new(false);
+    if source == DataSource::Archive && !ARCHIVE_NOTED.swap(true, Ordering::Relaxed) {
+        eprintln!(
+            "Note: some data (first: {}) served by archival endpoint {}",
+            method,
+            config.archive_url.as_deref().unwrap_or_default()
+        );
+    }
+
+    if let Some(error) = response.get("error") {
+        eyre::bail!("RPC error from {}: {}", method, error);
+    }
+
+    response
+        .get_mut("result")
+        .map(Value::take)
+        .ok_or_else(|| eyre::eyre!("RPC response for {} has no result", method))
+}
+
+/// Sequence number of the newest checkpoint
+pub async fn latest_checkpoint(config: &RpcConfig) -> Result<u64> {
+    let latest = rpc_request(config, "sui_getLatestCheckpointSequenceNumber", json!([])).await?;
+    json_u64(&latest).ok_or_else(|| eyre::eyre!("Invalid checkpoint sequence number: {}", latest))
+}
+
+/// Read a u64 from a JSON-RPC value; Sui encodes 64-bit integers as decimal strings
+pub fn json_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_u64(),
+        _ => None,
+    }
+}
+
+/// Read a u128 from a JSON-RPC value (e.g. coin balances), see [`json_u64`]
+pub fn json_u128(value: &Value) -> Option<u128> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_u64().map(u128::from),
+        _ => None,
+    }
+}
+
+/// Make a JSON-RPC call to the Sui node and print the response envelope.
+///
+/// Programs that want the result rather than output should use [`rpc_request`] or
+/// [`client::RpcClient`].
+pub async fn make_rpc_call(config: &RpcConfig, method: &str, params: Option<&str>) -> Result<()> {
+    // Parse parameters if provided
+    let params_value: Value = if let Some(params_str) = params {
+        serde_json::from_str(params_str)
+            .map_err(|e| eyre::eyre!("Invalid JSON parameters: {}", e))?
+    } else {
+        json!([])
+    };
+
+    // Construct JSON-RPC request
+    let request = build_request(method, params_value);
+
+    if config.pretty {
+        println!("Making RPC call to: {}", config.url);
+        println!("Method: {method}");
+        println!("Request:");
+        println!("{}", serde_json::to_string_pretty(&request)?);
+        println!();
+    }
+
+    // Make the HTTP request and display the response
+    let (response_json, source) = send_request(config, &request).await?;
+    let archive_url = config.archive_url.as_deref().unwrap_or_default();
 
     if config.pretty {
+        if source == DataSource::Archive {
+            println!("Source: archival endpoint {archive_url}");
+        }
         println!("Response:");
         println!("{}", serde_json::to_string_pretty(&response_json)?);
     } else {
         println!("{}", serde_json::to_string(&response_json)?);
+        if source == DataSource::Archive {
+            eprintln!("Note: served by archival endpoint {archive_url}");
+        }
     }
 
     // Check for JSON-RPC errors
@@ -86,7 +380,8 @@ pub async fn make_rpc_call(config: &RpcConfig, method: &str, params: Option<&str
     Ok(())
 }
 
-/// Common Sui RPC methods with helper functions
+/// Common Sui RPC methods that print their response, see [`make_rpc_call`]; the same
+/// calls returning their result are on [`client::RpcClient`]
 pub mod methods {
     use super::*;
 
@@ -129,6 +424,71 @@ pub mod methods {
         };
         make_rpc_call(config, "suix_getBalance", Some(&params)).await
     }
+
+    /// Query events matching `query`, see [`events::query_events`], and print the page
+    /// with the cursor to continue from
+    pub async fn query_events(
+        config: &RpcConfig,
+        query: &events::EventQuery,
+        cursor: Option<Value>,
+        limit: usize,
+        descending: bool,
+    ) -> Result<()> {
+        let page = events::query_events(config, query, cursor, limit, descending).await?;
+        print_result(
+            config,
+            &json!({ "data": page.data, "nextCursor": page.next_cursor }),
+        )
+    }
+
+    /// Print up to `limit` checkpoints from `start`, see [`client::RpcClient::get_checkpoints`]
+    pub async fn get_checkpoints(
+        config: &RpcConfig,
+        start: Option<u64>,
+        limit: usize,
+        descending: bool,
+    ) -> Result<()> {
+        let checkpoints = client::RpcClient::new(config.clone())
+            .get_checkpoints(start, limit, descending)
+            .await?;
+        print_result(config, &json!(checkpoints))
+    }
+
+    /// Print up to `limit` dynamic fields of `parent` with the cursor to continue from,
+    /// see [`dynamic_fields::get_dynamic_fields`]
+    pub async fn get_dynamic_fields(
+        config: &RpcConfig,
+        parent: &str,
+        cursor: Option<Value>,
+        limit: usize,
+        content: bool,
+    ) -> Result<()> {
+        let page =
+            dynamic_fields::get_dynamic_fields(config, parent, cursor, limit, content).await?;
+        print_result(
+            config,
+            &json!({ "data": page.data, "nextCursor": page.next_cursor }),
+        )
+    }
+
+    /// Get the object holding the dynamic field of `parent` named `name`
+    pub async fn get_dynamic_field_object(
+        config: &RpcConfig,
+        parent: &str,
+        name: &Value,
+    ) -> Result<()> {
+        let object = dynamic_fields::get_dynamic_field_object(config, parent, name).await?;
+        print_result(config, &object)
+    }
+
+    fn print_result(config: &RpcConfig, result: &Value) -> Result<()> {
+        if config.pretty {
+            println!("{}", serde_json::to_string_pretty(result)?);
+        } else {
+            println!("{result}");
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -140,5 +500,132 @@ mod tests {
         let config = RpcConfig::default();
         assert_eq!(config.url, "https://fullnode.mainnet.sui.io:443");
         assert!(!config.pretty);
+        assert!(config.cache.is_none());
+        assert!(config.transport.is_none());
+        assert!(config.archive_url.is_none());
+        assert!(config.local.is_none());
+        assert!(config.mock.is_none());
+    }
+
+    /// Config replaying `tests/fixtures/<name>.json`; record it with `SUIX_RECORD=1`
+    fn fixture_config(name: &str) -> RpcConfig {
+        let path = format!("{}/tests/fixtures/{name}.json", env!("CARGO_MANIFEST_DIR"));
+        let cassette = transport::vcr::Cassette::from_env(&path).unwrap();
+        RpcConfig {
+            mock: Some(Arc::new(cassette)),
+            ..RpcConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rpc_request_replayed() {
+        let config = fixture_config("mainnet");
+        let chain = rpc_request(&config, "sui_getChainIdentifier", json!([]))
+            .await
+            .unwrap();
+        assert_eq!(chain, "35834a8a");
+
+        let error = rpc_request(&config, "sui_getCheckpoint", json!(["not-a-number"]))
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("RPC error from sui_getCheckpoint")
+        );
+    }
+
+    #[test]
+    fn test_local_response() {
+        let store = ChainStore::temporary().unwrap();
+        store
+            .put_transaction("D1", &json!({ "digest": "D1" }))
+            .unwrap();
+        let request = build_request("sui_getTransactionBlock", json!(["D1", {}]));
+        let response = local_response(&store, &request).unwrap();
+        assert_eq!(response["result"]["digest"], "D1");
+
+        let missing = build_request("sui_getTransactionBlock", json!(["D2", {}]));
+        assert!(local_response(&store, &missing).is_err());
+    }
+
+    #[test]
+    fn test_is_pruned_error() {
+        assert!(is_pruned_error(
+            "RPC error from sui_getCheckpoint: Could not find the referenced checkpoint"
+        ));
+        assert!(is_pruned_error("data has been pruned"));
+        assert!(!is_pruned_error("HTTP request failed with status 429"));
+    }
+
+    #[test]
+    fn test_pruned_responses() {
+        let not_found = json!({"result": {"status": "VersionNotFound"}});
+        assert!(is_pruned_response("sui_tryGetPastObject", &not_found));
+        assert!(!is_pruned_response(
+            "sui_tryGetPastObject",
+            &json!({"result": {"status": "VersionFound"}})
+        ));
+        assert!(is_pruned_response(
+            "sui_getTransactionBlock",
+            &json!({"error": {"message": "Could not find the referenced transaction"}})
+        ));
+        // Only historical queries are redirected
+        assert!(!is_pruned_response(
+            "suix_getBalance",
+            &json!({"error": {"message": "not found"}})
+        ));
+    }
+
+    #[test]
+    fn test_cacheable_responses() {
+        let found = json!({"result": {"status": "VersionFound"}});
+        let deleted = json!({"result": {"status": "ObjectDeleted"}});
+        assert!(is_cacheable_response("sui_tryGetPastObject", &found));
+        assert!(!is_cacheable_response("sui_tryGetPastObject", &deleted));
+        assert!(!is_cacheable_response(
+            "sui_getCheckpoint",
+            &json!({"error": {"code": -32602}})
+        ));
+        assert!(!is_cacheable_response(
+            "sui_getCheckpoint",
+            &json!({"result": null})
+        ));
+        assert!(is_cacheable_response(
+            "sui_getTransactionBlock",
+            &json!({"result": {"digest": "D", "checkpoint": "7"}})
+        ));
+        assert!(!is_cacheable_response(
+            "sui_getTransactionBlock",
+            &json!({"result": {"digest": "D"}})
+        ));
+    }
+
+    #[test]
+    fn test_cache_key_only_for_immutable_methods() {
+        let config = RpcConfig::default();
+        let checkpoint = build_request("sui_getCheckpoint", json!(["1"]));
+        let latest = build_request("sui_getLatestCheckpointSequenceNumber", json!([]));
+        assert!(cache_key(&config, &checkpoint).is_some());
+        assert!(cache_key(&config, &latest).is_none());
+    }
+
+    #[test]
+    fn test_build_request() {
+        let request = build_request("sui_getChainIdentifier", json!([]));
+        assert_eq!(request["jsonrpc"], "2.0");
+        assert_eq!(request["method"], "sui_getChainIdentifier");
+        assert_eq!(request["params"], json!([]));
+    }
+
+    #[test]
+    fn test_json_integers() {
+        assert_eq!(json_u64(&json!("42")), Some(42));
+        assert_eq!(json_u64(&json!(7)), Some(7));
+        assert_eq!(json_u64(&json!(null)), None);
+        assert_eq!(
+            json_u128(&json!("340282366920938463463374607431768211455")),
+            Some(u128::MAX)
+        );
     }
 }
@@ -0,0 +1,168 @@
+//! `sui_multiGetObjects` for any number of objects
+//!
+//! Fullnodes take at most [`MAX_OBJECTS_PER_CALL`] IDs per call, so longer lists are
+//! split into batches fetched a few at a time and put back together in order.
+
+use eyre::Result;
+use futures::{StreamExt, TryStreamExt, stream};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::{RpcConfig, rpc_request};
+
+/// `sui_multiGetObjects` accepts at most 50 object IDs per call
+pub const MAX_OBJECTS_PER_CALL: usize = 50;
+
+/// Batches fetched at the same time
+const CONCURRENT_CALLS: usize = 4;
+
+/// What `sui_multiGetObjects` returns for each object, the `SuiObjectDataOptions`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectOptions {
+    pub show_type: bool,
+    pub show_owner: bool,
+    pub show_previous_transaction: bool,
+    pub show_display: bool,
+    pub show_content: bool,
+    /// BCS of the Move object or package
+    pub show_bcs: bool,
+    pub show_storage_rebate: bool,
+}
+
+impl ObjectOptions {
+    /// Type and content, enough to decode Move values
+    pub fn content() -> Self {
+        Self {
+            show_type: true,
+            show_content: true,
+            ..Self::default()
+        }
+    }
+
+    /// Everything except the display metadata and BCS
+    pub fn full() -> Self {
+        Self {
+            show_type: true,
+            show_owner: true,
+            show_previous_transaction: true,
+            show_content: true,
+            show_storage_rebate: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// One `sui_multiGetObjects` entry per ID, in the order of `ids`: `{"data": ...}` for
+/// an object that exists, `{"error": ...}` for one that does not.
+///
+/// Any number of IDs can be given; they are sent [`MAX_OBJECTS_PER_CALL`] at a time.
+pub async fn multi_get_objects<S: AsRef<str>>(
+    config: &RpcConfig,
+    ids: &[S],
+    options: &ObjectOptions,
+) -> Result<Vec<Value>> {
+    let batches: Vec<Vec<Value>> = stream::iter(ids.chunks(MAX_OBJECTS_PER_CALL))
+        .map(|chunk| async move {
+            let ids: Vec<&str> = chunk.iter().map(AsRef::as_ref).collect();
+            let objects = rpc_request(config, "sui_multiGetObjects", json!([ids, options])).await?;
+            match objects {
+                Value::Array(objects) if objects.len() == chunk.len() => Ok(objects),
+                other => eyre::bail!(
+                    "Invalid sui_multiGetObjects result for {} IDs: {}",
+                    chunk.len(),
+                    other
+                ),
+            }
+        })
+        .buffered(CONCURRENT_CALLS)
+        .try_collect()
+        .await?;
+    Ok(batches.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use transport::vcr::MockTransport;
+
+    use super::*;
+
+    /// Objects `0x0` to `0x7f` exist; counts the calls and the largest batch
+    #[derive(Debug, Default)]
+    struct Objects {
+        calls: AtomicUsize,
+        largest: AtomicUsize,
+    }
+
+    impl MockTransport for Objects {
+        fn replay(&self, key: &str) -> Option<Result<Value, String>> {
+            let params: Value =
+                serde_json::from_str(key.strip_prefix("sui_multiGetObjects ")?).ok()?;
+            assert_eq!(params[1], json!(ObjectOptions::content()));
+            let ids = params[0].as_array()?;
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.largest.fetch_max(ids.len(), Ordering::SeqCst);
+            let objects: Vec<Value> = ids
+                .iter()
+                .map(|id| {
+                    let n = u64::from_str_radix(id.as_str()?.strip_prefix("0x")?, 16).ok()?;
+                    Some(if n < 0x80 {
+                        json!({ "data": { "objectId": id } })
+                    } else {
+                        json!({ "error": { "code": "notExists", "object_id": id } })
+                    })
+                })
+                .collect::<Option<_>>()?;
+            Some(Ok(json!({ "result": objects })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multi_get_objects_batches() {
+        let mock = Arc::new(Objects::default());
+        let config = RpcConfig {
+            mock: Some(mock.clone()),
+            ..RpcConfig::default()
+        };
+        let ids: Vec<String> = (0..130).map(|n| format!("{n:#x}")).collect();
+        let objects = multi_get_objects(&config, &ids, &ObjectOptions::content())
+            .await
+            .unwrap();
+
+        assert_eq!(objects.len(), 130);
+        assert_eq!(objects[0]["data"]["objectId"], "0x0");
+        assert_eq!(objects[127]["data"]["objectId"], "0x7f");
+        assert!(objects[128]["data"].is_null());
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(mock.largest.load(Ordering::SeqCst), MAX_OBJECTS_PER_CALL);
+
+        let none: [&str; 0] = [];
+        assert!(
+            multi_get_objects(&config, &none, &ObjectOptions::content())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_options_json() {
+        assert_eq!(
+            json!(ObjectOptions::content()),
+            json!({
+                "showType": true,
+                "showOwner": false,
+                "showPreviousTransaction": false,
+                "showDisplay": false,
+                "showContent": true,
+                "showBcs": false,
+                "showStorageRebate": false,
+            })
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! Following the chain tip over JSON-RPC
+
+use std::time::Duration;
+
+use eyre::Result;
+use futures::{Stream, stream};
+use serde_json::{Value, json};
+
+use crate::{RpcConfig, latest_checkpoint, rpc_request};
+
+struct Follow {
+    config: RpcConfig,
+    next: u64,
+    latest: u64,
+    polled: bool,
+    interval: Duration,
+}
+
+/// Every checkpoint from `from` on, in order, waiting `interval` between
+/// polls once the tip is reached.
+///
+/// The stream never ends. Failures are yielded as `Err` items and the same
+/// checkpoint is retried after `interval`, so no checkpoint is skipped.
+pub fn follow_checkpoints(
+    config: RpcConfig,
+    from: u64,
+    interval: Duration,
+) -> impl Stream<Item = Result<Value>> {
+    let state = Follow {
+        config,
+        next: from,
+        latest: 0,
+        polled: false,
+        interval,
+    };
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if state.polled && state.next <= state.latest {
+                let seq = state.next;
+                let checkpoint =
+                    rpc_request(&state.config, "sui_getCheckpoint", json!([seq.to_string()])).await;
+                return match checkpoint {
+                    Ok(checkpoint) => {
+                        state.next += 1;
+                        Some((Ok(checkpoint), state))
+                    }
+                    Err(e) => {
+                        tokio::time::sleep(state.interval).await;
+                        Some((Err(eyre::eyre!("Checkpoint {}: {}", seq, e)), state))
+                    }
+                };
+            }
+            if state.polled {
+                tokio::time::sleep(state.interval).await;
+            }
+            state.polled = true;
+            match latest_checkpoint(&state.config).await {
+                Ok(latest) => state.latest = latest,
+                Err(e) => return Some((Err(e), state)),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_follow_checkpoints() {
+        let path = format!("{}/tests/fixtures/mainnet.json", env!("CARGO_MANIFEST_DIR"));
+        let config = RpcConfig {
+            mock: Some(Arc::new(transport::vcr::Cassette::replay(&path).unwrap())),
+            ..RpcConfig::default()
+        };
+        let checkpoints: Vec<Value> = follow_checkpoints(config, 5, Duration::ZERO)
+            .take(2)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(checkpoints[0]["sequenceNumber"], "5");
+        assert_eq!(checkpoints[1]["sequenceNumber"], "6");
+    }
+}
@@ -0,0 +1,247 @@
+//! JSON-RPC subscriptions over WebSocket
+//!
+//! The node pushes a notification for every matching event or transaction on the
+//! connection the subscription was made on. When that connection drops it is
+//! reopened and the subscription made again; notifications sent in between are lost.
+
+use std::time::Duration;
+
+use eyre::Result;
+use futures::{SinkExt, Stream, StreamExt, stream};
+use serde_json::{Value, json};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+use crate::build_request;
+
+/// Wait after the first failed reconnect, doubled on each further failure
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+/// Longest wait between reconnects
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// What to subscribe to, with the node's filter for it
+#[derive(Debug, Clone)]
+pub enum Subscription {
+    /// `suix_subscribeEvent` with an `EventFilter`, e.g. `{"Sender": "0x..."}`
+    Events(Value),
+    /// `suix_subscribeTransaction` with a `TransactionFilter`, e.g. `{"FromAddress": "0x..."}`
+    Transactions(Value),
+}
+
+impl Subscription {
+    /// Every event
+    pub fn all_events() -> Self {
+        Self::Events(json!({ "All": [] }))
+    }
+
+    fn method(&self) -> &'static str {
+        match self {
+            Self::Events(_) => "suix_subscribeEvent",
+            Self::Transactions(_) => "suix_subscribeTransaction",
+        }
+    }
+
+    fn filter(&self) -> &Value {
+        match self {
+            Self::Events(filter) | Self::Transactions(filter) => filter,
+        }
+    }
+}
+
+/// WebSocket URL of a JSON-RPC endpoint: `https://` becomes `wss://` and `http://` `ws://`
+pub fn websocket_url(url: &str) -> String {
+    if let Some(rest) = url.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        url.to_string()
+    }
+}
+
+struct Subscriber {
+    url: String,
+    subscription: Subscription,
+    /// Open connection and the id the node gave the subscription on it
+    socket: Option<(Socket, Value)>,
+    backoff: Duration,
+}
+
+/// Notifications of `subscription` on the node at `url` (HTTP or WebSocket), as the
+/// `result` of each: the event or transaction effects.
+///
+/// The stream never ends. A dropped connection or failed reconnect is yielded as an
+/// `Err` item before reconnecting, waiting longer after each failure in a row.
+pub fn subscribe(url: &str, subscription: Subscription) -> impl Stream<Item = Result<Value>> {
+    let state = Subscriber {
+        url: websocket_url(url),
+        subscription,
+        socket: None,
+        backoff: MIN_BACKOFF,
+    };
+    stream::unfold(state, |mut state| async move {
+        loop {
+            let Some((socket, id)) = &mut state.socket else {
+                match connect(&state.url, &state.subscription).await {
+                    Ok(connected) => {
+                        state.socket = Some(connected);
+                        state.backoff = MIN_BACKOFF;
+                        continue;
+                    }
+                    Err(e) => {
+                        let delay = state.backoff;
+                        state.backoff = (delay * 2).min(MAX_BACKOFF);
+                        tokio::time::sleep(delay).await;
+                        return Some((Err(e), state));
+                    }
+                }
+            };
+            let error = match socket.next().await {
+                Some(Ok(Message::Text(text))) => match notification(&text, id) {
+                    Ok(Some(result)) => return Some((Ok(result), state)),
+                    Ok(None) => continue,
+                    Err(e) => return Some((Err(e), state)),
+                },
+                // Pings are answered by the socket itself
+                Some(Ok(Message::Close(_))) | None => {
+                    eyre::eyre!("WebSocket {} closed, resubscribing", state.url)
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => eyre::eyre!("WebSocket {} failed, resubscribing: {}", state.url, e),
+            };
+            state.socket = None;
+            return Some((Err(error), state));
+        }
+    })
+}
+
+/// Open a connection to `url` and make `subscription` on it
+async fn connect(url: &str, subscription: &Subscription) -> Result<(Socket, Value)> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .map_err(|e| eyre::eyre!("Failed to connect to {}: {}", url, e))?;
+    let method = subscription.method();
+    let request = build_request(method, json!([subscription.filter()]));
+    socket
+        .send(Message::Text(request.to_string().into()))
+        .await
+        .map_err(|e| eyre::eyre!("Failed to send {}: {}", method, e))?;
+
+    while let Some(message) = socket.next().await {
+        let message = message.map_err(|e| eyre::eyre!("WebSocket {} failed: {}", url, e))?;
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let response: Value = serde_json::from_str(&text)
+            .map_err(|e| eyre::eyre!("Invalid JSON from {}: {}", url, e))?;
+        if response["id"] != request["id"] {
+            continue;
+        }
+        if let Some(error) = response.get("error") {
+            eyre::bail!("RPC error from {}: {}", method, error);
+        }
+        return Ok((socket, response["result"].clone()));
+    }
+    eyre::bail!("WebSocket {} closed before {} was answered", url, method)
+}
+
+/// `result` of a notification for subscription `id`, `None` for any other message
+fn notification(text: &str, id: &Value) -> Result<Option<Value>> {
+    let mut message: Value =
+        serde_json::from_str(text).map_err(|e| eyre::eyre!("Invalid notification: {}", e))?;
+    let Some(params) = message.get_mut("params") else {
+        return Ok(None);
+    };
+    if params["subscription"] != *id {
+        return Ok(None);
+    }
+    Ok(params.get_mut("result").map(Value::take))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(
+            websocket_url("https://fullnode.mainnet.sui.io:443"),
+            "wss://fullnode.mainnet.sui.io:443"
+        );
+        assert_eq!(
+            websocket_url("http://127.0.0.1:9000"),
+            "ws://127.0.0.1:9000"
+        );
+        assert_eq!(websocket_url("ws://127.0.0.1:9000"), "ws://127.0.0.1:9000");
+    }
+
+    #[test]
+    fn test_notification() {
+        let id = json!(7);
+        let event = r#"{"jsonrpc":"2.0","method":"suix_subscribeEvent",
+            "params":{"subscription":7,"result":{"type":"0x2::coin::Mint"}}}"#;
+        assert_eq!(
+            notification(event, &id).unwrap(),
+            Some(json!({ "type": "0x2::coin::Mint" }))
+        );
+        let other = r#"{"method":"suix_subscribeEvent","params":{"subscription":8,"result":{}}}"#;
+        assert_eq!(notification(other, &id).unwrap(), None);
+        assert_eq!(notification("[1]", &id).unwrap(), None);
+        assert!(notification("not json", &id).is_err());
+    }
+
+    /// Serves two connections, each confirming the subscription and sending one event
+    /// before closing
+    async fn serve(listener: TcpListener) {
+        for n in 0..2 {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let Some(Ok(Message::Text(text))) = socket.next().await else {
+                panic!("no subscribe request");
+            };
+            let request: Value = serde_json::from_str(&text).unwrap();
+            assert_eq!(request["method"], "suix_subscribeEvent");
+            assert_eq!(request["params"], json!([{ "All": [] }]));
+            let replies = [
+                json!({ "jsonrpc": "2.0", "id": request["id"], "result": 40 + n }),
+                json!({
+                    "jsonrpc": "2.0",
+                    "method": "suix_subscribeEvent",
+                    "params": { "subscription": 40 + n, "result": { "n": n } },
+                }),
+            ];
+            for reply in replies {
+                socket
+                    .send(Message::Text(reply.to_string().into()))
+                    .await
+                    .unwrap();
+            }
+            socket.close(None).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_resubscribes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener));
+
+        let items: Vec<Result<Value>> = subscribe(&url, Subscription::all_events())
+            .take(3)
+            .collect()
+            .await;
+        assert_eq!(items[0].as_ref().unwrap(), &json!({ "n": 0 }));
+        assert!(
+            items[1]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("resubscribing")
+        );
+        assert_eq!(items[2].as_ref().unwrap(), &json!({ "n": 1 }));
+    }
+}
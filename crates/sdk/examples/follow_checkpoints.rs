@@ -0,0 +1,35 @@
+//! Print a line for every new checkpoint.
+//!
+//! ```text
+//! cargo run -p suix-sdk --example follow_checkpoints -- https://fullnode.testnet.sui.io:443
+//! ```
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use suix_sdk::{RpcConfig, follow_checkpoints, json_u64, latest_checkpoint};
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let config = RpcConfig {
+        url: std::env::args()
+            .nth(1)
+            .unwrap_or_else(|| RpcConfig::default().url),
+        ..RpcConfig::default()
+    };
+    let from = latest_checkpoint(&config).await?;
+    let checkpoints = follow_checkpoints(config, from, Duration::from_secs(1));
+    let mut checkpoints = std::pin::pin!(checkpoints);
+    while let Some(checkpoint) = checkpoints.next().await {
+        match checkpoint {
+            Ok(checkpoint) => println!(
+                "checkpoint {} (epoch {}): {} transactions",
+                json_u64(&checkpoint["sequenceNumber"]).unwrap_or_default(),
+                json_u64(&checkpoint["epoch"]).unwrap_or_default(),
+                checkpoint["transactions"].as_array().map_or(0, Vec::len),
+            ),
+            Err(e) => eprintln!("Failed to follow checkpoints: {e}"),
+        }
+    }
+    Ok(())
+}
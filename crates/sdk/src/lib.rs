@@ -0,0 +1,155 @@
+//! Rust API for the clients behind the `suix` CLI.
+//!
+//! The workspace crates are re-exported as modules, and the types most
+//! programs need are available at the crate root:
+//!
+//! - [`RpcConfig`] and [`rpc_request`] for JSON-RPC, with the caching, retry
+//!   and archive fallback configured on the config, and [`RpcClient`] for its
+//!   common methods with typed results
+//! - [`GraphqlClient`] for the GraphQL service
+//! - [`SuiGrpcClient`] for the native gRPC API
+//! - [`ProgrammableTransactionBuilder`] and [`TransactionData`] to build
+//!   transactions, and [`Keystore`] to sign them
+//! - [`Filter`] for the filter expressions accepted by `--filter`
+//! - [`follow_checkpoints`] to stream checkpoints as they are produced
+//! - [`ChainStore`] for the local chain store
+//!
+//! # Examples
+//!
+//! Query the latest checkpoint over JSON-RPC:
+//!
+//! ```no_run
+//! use suix_sdk::{RpcConfig, json_u64, rpc_request};
+//!
+//! # async fn example() -> eyre::Result<()> {
+//! let config = RpcConfig::default();
+//! let latest = rpc_request(
+//!     &config,
+//!     "sui_getLatestCheckpointSequenceNumber",
+//!     serde_json::json!([]),
+//! )
+//! .await?;
+//! println!("latest checkpoint: {:?}", json_u64(&latest));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Print new transactions that call a package:
+//!
+//! ```no_run
+//! use futures::StreamExt;
+//! use suix_sdk::{Filter, RpcConfig, follow_checkpoints, latest_checkpoint, rpc_request};
+//!
+//! # async fn example() -> eyre::Result<()> {
+//! let config = RpcConfig::default();
+//! let filter = Filter::parse("package == 0xdee9")?;
+//! let from = latest_checkpoint(&config).await?;
+//! let mut checkpoints = std::pin::pin!(follow_checkpoints(config.clone(), from, std::time::Duration::from_secs(1)));
+//! while let Some(checkpoint) = checkpoints.next().await {
+//!     let checkpoint = checkpoint?;
+//!     let txs = rpc_request(
+//!         &config,
+//!         "sui_multiGetTransactionBlocks",
+//!         serde_json::json!([checkpoint["transactions"], { "showInput": true, "showEvents": true }]),
+//!     )
+//!     .await?;
+//!     for tx in txs.as_array().into_iter().flatten() {
+//!         if filter.matches_transaction(tx) {
+//!             println!("{}", tx["digest"]);
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Build and sign a SUI transfer:
+//!
+//! ```no_run
+//! use suix_sdk::{
+//!     Keystore, ProgrammableTransactionBuilder, TransactionData,
+//!     sui_types::base_types::{ObjectRef, SuiAddress},
+//! };
+//!
+//! # fn example(gas: ObjectRef, recipient: SuiAddress) -> eyre::Result<()> {
+//! let keystore = Keystore::load(Keystore::default_path())?;
+//! let sender = keystore.get("alice")?.address;
+//! let mut builder = ProgrammableTransactionBuilder::new();
+//! builder.transfer_sui(recipient, Some(1_000_000_000));
+//! let data =
+//!     TransactionData::new_programmable(sender, vec![gas], builder.finish(), 5_000_000, 750);
+//! let signature = keystore.sign_transaction("alice", &data)?;
+//! # let _ = signature;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Add an API key to every gRPC call and count failed calls:
+//!
+//! ```no_run
+//! use std::{
+//!     sync::{
+//!         Arc,
+//!         atomic::{AtomicU64, Ordering},
+//!     },
+//!     time::Duration,
+//! };
+//!
+//! use suix_sdk::{
+//!     GrpcConfig, SuiGrpcClient,
+//!     grpc::middleware::{CallInfo, Middleware},
+//! };
+//!
+//! #[derive(Debug, Default)]
+//! struct ApiKey {
+//!     failures: AtomicU64,
+//! }
+//!
+//! impl Middleware for ApiKey {
+//!     fn before_call(&self, call: &mut CallInfo) -> anyhow::Result<()> {
+//!         call.metadata.insert("x-api-key", "SECRET".parse()?);
+//!         Ok(())
+//!     }
+//!
+//!     fn after_call(&self, _call: &CallInfo, _elapsed: Duration, error: Option<&anyhow::Error>) {
+//!         if error.is_some() {
+//!             self.failures.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let api_key = Arc::new(ApiKey::default());
+//! let config = GrpcConfig {
+//!     middleware: vec![api_key.clone()],
+//!     ..GrpcConfig::default()
+//! };
+//! let client = SuiGrpcClient::new(config).await?;
+//! client.latest_checkpoint_sequence().await?;
+//! println!("failed calls: {}", api_key.failures.load(Ordering::Relaxed));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! See `crates/sdk/examples` for complete programs.
+
+pub use cache::{self, ResponseCache};
+pub use filter::{self, Filter};
+pub use grpc::{self, GrpcConfig, SuiGrpcClient};
+pub use keystore::{self, KeyEntry, Keystore};
+pub use rpc::{
+    self, RpcConfig,
+    client::RpcClient,
+    coins::{CoinInfo, CoinRegistry},
+    graphql::GraphqlClient,
+    json_u64, json_u128, latest_checkpoint,
+    objects::ObjectOptions,
+    rpc_request,
+    stream::follow_checkpoints,
+};
+pub use store::{self, ChainStore};
+pub use sui_types::{
+    self, programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::TransactionData,
+};
+pub use transport::{self, RetryPolicy, Transport};
@@ -0,0 +1,332 @@
+use std::path::Path;
+
+use eyre::{Result, bail};
+use serde_json::{Value, json};
+
+/// Key in the `meta` tree holding the next checkpoint to sync
+const NEXT_CHECKPOINT: &[u8] = b"next_checkpoint";
+
+/// Local copy of chain data for offline queries.
+///
+/// Checkpoints, transactions (with input, effects, events, object and balance
+/// changes) and object versions are kept in separate trees of an embedded
+/// database, in the same shape the JSON-RPC API returns them.
+#[derive(Debug, Clone)]
+pub struct ChainStore {
+    db: sled::Db,
+    checkpoints: sled::Tree,
+    transactions: sled::Tree,
+    objects: sled::Tree,
+    meta: sled::Tree,
+}
+
+/// Number of entries per kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreStats {
+    pub checkpoints: usize,
+    pub transactions: usize,
+    pub objects: usize,
+}
+
+impl ChainStore {
+    /// Open (or create) a store in the given directory
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let db = sled::open(path)
+            .map_err(|e| eyre::eyre!("Failed to open store at {}: {}", path.display(), e))?;
+        Self::from_db(db)
+    }
+
+    /// Open a throwaway store that is deleted when dropped
+    pub fn temporary() -> Result<Self> {
+        let db = sled::Config::new()
+            .temporary(true)
+            .open()
+            .map_err(|e| eyre::eyre!("Failed to open temporary store: {}", e))?;
+        Self::from_db(db)
+    }
+
+    fn from_db(db: sled::Db) -> Result<Self> {
+        let tree = |name: &str| {
+            db.open_tree(name)
+                .map_err(|e| eyre::eyre!("Failed to open store tree {}: {}", name, e))
+        };
+        Ok(Self {
+            checkpoints: tree("checkpoints")?,
+            transactions: tree("transactions")?,
+            objects: tree("objects")?,
+            meta: tree("meta")?,
+            db,
+        })
+    }
+
+    pub fn put_checkpoint(&self, sequence_number: u64, checkpoint: &Value) -> Result<()> {
+        insert(
+            &self.checkpoints,
+            &sequence_number.to_be_bytes(),
+            checkpoint,
+        )
+    }
+
+    pub fn checkpoint(&self, sequence_number: u64) -> Result<Option<Value>> {
+        get(&self.checkpoints, &sequence_number.to_be_bytes())
+    }
+
+    /// Lowest stored checkpoint sequence number
+    pub fn first_checkpoint(&self) -> Result<Option<u64>> {
+        let first = self
+            .checkpoints
+            .first()
+            .map_err(|e| eyre::eyre!("Store read failed: {}", e))?;
+        Ok(first.and_then(|(key, _)| decode_u64(&key)))
+    }
+
+    /// Highest stored checkpoint sequence number
+    pub fn latest_checkpoint(&self) -> Result<Option<u64>> {
+        let last = self
+            .checkpoints
+            .last()
+            .map_err(|e| eyre::eyre!("Store read failed: {}", e))?;
+        Ok(last.and_then(|(key, _)| decode_u64(&key)))
+    }
+
+    pub fn put_transaction(&self, digest: &str, tx: &Value) -> Result<()> {
+        insert(&self.transactions, digest.as_bytes(), tx)
+    }
+
+    pub fn transaction(&self, digest: &str) -> Result<Option<Value>> {
+        get(&self.transactions, digest.as_bytes())
+    }
+
+    /// Store an object version (the `data`/`details` part of an object response)
+    pub fn put_object(&self, object_id: &str, version: u64, object: &Value) -> Result<()> {
+        insert(&self.objects, &object_key(object_id, version), object)
+    }
+
+    /// An exact object version, or the newest stored version when `version` is `None`
+    pub fn object(&self, object_id: &str, version: Option<u64>) -> Result<Option<Value>> {
+        match version {
+            Some(version) => get(&self.objects, &object_key(object_id, version)),
+            None => {
+                let newest = self
+                    .objects
+                    .scan_prefix(object_prefix(object_id))
+                    .next_back()
+                    .transpose()
+                    .map_err(|e| eyre::eyre!("Store read failed: {}", e))?;
+                newest
+                    .map(|(_, bytes)| serde_json::from_slice(&bytes).map_err(Into::into))
+                    .transpose()
+            }
+        }
+    }
+
+    /// The newest stored version of every object, in object ID order
+    pub fn latest_objects(&self) -> impl Iterator<Item = Result<Value>> + '_ {
+        let mut entries = self.objects.iter().peekable();
+        std::iter::from_fn(move || {
+            loop {
+                let (key, bytes) = match entries.next()? {
+                    Ok(entry) => entry,
+                    Err(e) => return Some(Err(eyre::eyre!("Store read failed: {}", e))),
+                };
+                // Keys sort by ID, then version: skip all but the last of each ID
+                let id = key.split(|b| *b == 0).next().unwrap_or_default();
+                let superseded = entries.peek().is_some_and(|next| {
+                    next.as_ref()
+                        .is_ok_and(|(next, _)| next.split(|b| *b == 0).next() == Some(id))
+                });
+                if !superseded {
+                    return Some(serde_json::from_slice(&bytes).map_err(Into::into));
+                }
+            }
+        })
+    }
+
+    /// Next checkpoint `sync` should fetch
+    pub fn next_checkpoint(&self) -> Result<Option<u64>> {
+        let next = self
+            .meta
+            .get(NEXT_CHECKPOINT)
+            .map_err(|e| eyre::eyre!("Store read failed: {}", e))?;
+        Ok(next.and_then(|bytes| decode_u64(&bytes)))
+    }
+
+    pub fn set_next_checkpoint(&self, next: u64) -> Result<()> {
+        self.meta
+            .insert(NEXT_CHECKPOINT, &next.to_be_bytes())
+            .map_err(|e| eyre::eyre!("Store write failed: {}", e))?;
+        self.db
+            .flush()
+            .map_err(|e| eyre::eyre!("Store flush failed: {}", e))?;
+        Ok(())
+    }
+
+    pub fn stats(&self) -> StoreStats {
+        StoreStats {
+            checkpoints: self.checkpoints.len(),
+            transactions: self.transactions.len(),
+            objects: self.objects.len(),
+        }
+    }
+
+    /// Answer a JSON-RPC request from stored data.
+    ///
+    /// Returns the `result` the node would have returned, `Ok(None)` when the
+    /// data is not in the store, and an error for methods the store cannot serve.
+    pub fn answer(&self, method: &str, params: &Value) -> Result<Option<Value>> {
+        let param = |i: usize| &params[i];
+        match method {
+            "sui_getLatestCheckpointSequenceNumber" => {
+                Ok(self.latest_checkpoint()?.map(|seq| json!(seq.to_string())))
+            }
+            "sui_getCheckpoint" => match parse_u64(param(0)) {
+                Some(seq) => self.checkpoint(seq),
+                None => bail!("Checkpoints can only be looked up by sequence number offline"),
+            },
+            "sui_getTransactionBlock" => self.transaction(param(0).as_str().unwrap_or_default()),
+            "sui_multiGetTransactionBlocks" => {
+                let mut txs = Vec::new();
+                for digest in param(0).as_array().into_iter().flatten() {
+                    match self.transaction(digest.as_str().unwrap_or_default())? {
+                        Some(tx) => txs.push(tx),
+                        None => return Ok(None),
+                    }
+                }
+                Ok(Some(Value::Array(txs)))
+            }
+            "sui_getObject" => {
+                let id = param(0).as_str().unwrap_or_default();
+                Ok(self.object(id, None)?.map(|data| json!({ "data": data })))
+            }
+            "sui_multiGetObjects" => {
+                let mut objects = Vec::new();
+                for id in param(0).as_array().into_iter().flatten() {
+                    match self.object(id.as_str().unwrap_or_default(), None)? {
+                        Some(data) => objects.push(json!({ "data": data })),
+                        None => return Ok(None),
+                    }
+                }
+                Ok(Some(Value::Array(objects)))
+            }
+            "sui_tryGetPastObject" => {
+                let id = param(0).as_str().unwrap_or_default();
+                let Some(version) = parse_u64(param(1)) else {
+                    bail!("Invalid object version: {}", param(1));
+                };
+                Ok(self
+                    .object(id, Some(version))?
+                    .map(|details| json!({ "status": "VersionFound", "details": details })))
+            }
+            _ => bail!("{} cannot be answered from the local store", method),
+        }
+    }
+}
+
+/// Object IDs are normalized so short and long forms share entries
+fn object_prefix(object_id: &str) -> Vec<u8> {
+    let hex = object_id.trim_start_matches("0x").to_lowercase();
+    let mut prefix = format!("{hex:0>64}").into_bytes();
+    prefix.push(0);
+    prefix
+}
+
+/// `<id>\0<version BE>`, so versions of one object sort numerically
+fn object_key(object_id: &str, version: u64) -> Vec<u8> {
+    let mut key = object_prefix(object_id);
+    key.extend_from_slice(&version.to_be_bytes());
+    key
+}
+
+fn decode_u64(bytes: &[u8]) -> Option<u64> {
+    Some(u64::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn parse_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_u64(),
+        _ => None,
+    }
+}
+
+fn insert(tree: &sled::Tree, key: &[u8], value: &Value) -> Result<()> {
+    tree.insert(key, serde_json::to_vec(value)?)
+        .map_err(|e| eyre::eyre!("Store write failed: {}", e))?;
+    Ok(())
+}
+
+fn get(tree: &sled::Tree, key: &[u8]) -> Result<Option<Value>> {
+    let bytes = tree
+        .get(key)
+        .map_err(|e| eyre::eyre!("Store read failed: {}", e))?;
+    bytes
+        .map(|bytes| serde_json::from_slice(&bytes).map_err(Into::into))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoints_and_transactions() {
+        let store = ChainStore::temporary().unwrap();
+        assert_eq!(store.latest_checkpoint().unwrap(), None);
+        store
+            .put_checkpoint(9, &json!({ "sequenceNumber": "9" }))
+            .unwrap();
+        store
+            .put_checkpoint(300, &json!({ "sequenceNumber": "300" }))
+            .unwrap();
+        store
+            .put_transaction("D1", &json!({ "digest": "D1" }))
+            .unwrap();
+
+        assert_eq!(store.first_checkpoint().unwrap(), Some(9));
+        assert_eq!(store.latest_checkpoint().unwrap(), Some(300));
+        assert_eq!(
+            store.answer("sui_getCheckpoint", &json!(["9"])).unwrap(),
+            Some(json!({ "sequenceNumber": "9" }))
+        );
+        assert_eq!(
+            store
+                .answer("sui_multiGetTransactionBlocks", &json!([["D1", "D2"]]))
+                .unwrap(),
+            None
+        );
+        assert!(store.answer("suix_getBalance", &json!(["0x1"])).is_err());
+    }
+
+    #[test]
+    fn test_object_versions() {
+        let store = ChainStore::temporary().unwrap();
+        store
+            .put_object("0x5", 2, &json!({ "version": "2" }))
+            .unwrap();
+        store
+            .put_object("0x5", 10, &json!({ "version": "10" }))
+            .unwrap();
+        store
+            .put_object("0x6", 1, &json!({ "version": "1" }))
+            .unwrap();
+
+        let long = format!("0x{:0>64}", "5");
+        assert_eq!(
+            store.object(&long, None).unwrap(),
+            Some(json!({ "version": "10" }))
+        );
+        assert_eq!(
+            store
+                .answer("sui_tryGetPastObject", &json!(["0x5", 2]))
+                .unwrap(),
+            Some(json!({ "status": "VersionFound", "details": { "version": "2" } }))
+        );
+        assert_eq!(store.stats().objects, 3);
+        let latest: Vec<Value> = store.latest_objects().map(Result::unwrap).collect();
+        assert_eq!(
+            latest,
+            vec![json!({ "version": "10" }), json!({ "version": "1" })]
+        );
+    }
+}
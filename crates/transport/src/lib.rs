@@ -0,0 +1,509 @@
+//! Retry, backoff, failover and rate limiting shared by the suix clients.
+//!
+//! Clients hand each request to [`Transport::execute`] as a closure taking
+//! the endpoint to use; the transport decides when to wait, retry or move on
+//! to a failover endpoint based on [`classify`]ing the error message.
+//! [`vcr`] records and replays responses so tests need no network.
+
+pub mod vcr;
+
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+/// Starting rate for adaptive mode when no explicit limit is configured
+const ADAPTIVE_START_RPS: f64 = 20.0;
+
+/// Adaptive mode never slows below this rate
+const ADAPTIVE_MIN_RPS: f64 = 0.5;
+
+/// Adaptive mode stops limiting once it recovers past this rate without a ceiling
+const ADAPTIVE_RELEASE_RPS: f64 = 1000.0;
+
+/// How a failed attempt should be handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The server asked us to slow down (HTTP 429, gRPC RESOURCE_EXHAUSTED)
+    Throttled,
+    /// Network or server hiccup worth retrying
+    Transient,
+    /// Retrying will not help (bad request, missing data, ...)
+    Permanent,
+}
+
+/// Classify an error from its message, covering reqwest, HTTP status and tonic status texts
+pub fn classify(message: &str) -> ErrorClass {
+    let normalized: String = message
+        .to_lowercase()
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .collect();
+    let contains_any = |needles: &[&str]| needles.iter().any(|n| normalized.contains(n));
+
+    if contains_any(&[
+        "status429",
+        "toomanyrequests",
+        "resourceexhausted",
+        "ratelimit",
+    ]) {
+        ErrorClass::Throttled
+    } else if contains_any(&[
+        "status500",
+        "status502",
+        "status503",
+        "status504",
+        "timedout",
+        "timeout",
+        "deadlineexceeded",
+        "unavailable",
+        "errorsendingrequest",
+        "connection",
+        "resetbypeer",
+    ]) {
+        ErrorClass::Transient
+    } else {
+        ErrorClass::Permanent
+    }
+}
+
+/// Exponential backoff settings
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (starting at 1)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Counters describing how requests went
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub requests: u64,
+    pub attempts: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub retries: u64,
+    pub throttled: u64,
+    pub failovers: u64,
+    pub total_latency_ms: u64,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let avg = self
+            .total_latency_ms
+            .checked_div(self.attempts)
+            .unwrap_or_default();
+        write!(
+            f,
+            "{} requests ({} ok, {} failed), {} attempts, {} retries, {} throttled, {} failovers, avg latency {avg} ms",
+            self.requests,
+            self.successes,
+            self.failures,
+            self.attempts,
+            self.retries,
+            self.throttled,
+            self.failovers,
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    requests: AtomicU64,
+    attempts: AtomicU64,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    retries: AtomicU64,
+    throttled: AtomicU64,
+    failovers: AtomicU64,
+    total_latency_ms: AtomicU64,
+}
+
+fn bump(counter: &AtomicU64, by: u64) {
+    counter.fetch_add(by, Ordering::Relaxed);
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    next: Instant,
+    rps: Option<f64>,
+    ceiling: Option<f64>,
+    adaptive: bool,
+}
+
+/// Shared transport policy: one instance is used by every client in a process
+#[derive(Debug)]
+pub struct Transport {
+    policy: RetryPolicy,
+    failover: Vec<String>,
+    /// Index of the endpoint in use per primary endpoint: 0 for the primary
+    /// itself, `i` for `failover[i - 1]`
+    active: Mutex<HashMap<String, usize>>,
+    limiter: Mutex<LimiterState>,
+    counters: Counters,
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self::new(RetryPolicy::default())
+    }
+}
+
+impl Transport {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            failover: Vec::new(),
+            active: Mutex::new(HashMap::new()),
+            limiter: Mutex::new(LimiterState {
+                next: Instant::now(),
+                rps: None,
+                ceiling: None,
+                adaptive: false,
+            }),
+            counters: Counters::default(),
+        }
+    }
+
+    /// Cap the request rate at `rps` requests per second
+    pub fn with_rate_limit(self, rps: Option<f64>) -> Self {
+        {
+            let mut state = self.lock();
+            state.rps = rps.filter(|r| *r > 0.0);
+            state.ceiling = state.rps;
+        }
+        self
+    }
+
+    /// Halve the rate on throttling and recover gradually on success
+    pub fn with_adaptive(self, adaptive: bool) -> Self {
+        self.lock().adaptive = adaptive;
+        self
+    }
+
+    /// Endpoints tried, in order, when the primary keeps failing
+    pub fn with_failover(mut self, endpoints: Vec<String>) -> Self {
+        self.failover = endpoints;
+        self
+    }
+
+    pub fn stats(&self) -> Stats {
+        let c = &self.counters;
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        Stats {
+            requests: load(&c.requests),
+            attempts: load(&c.attempts),
+            successes: load(&c.successes),
+            failures: load(&c.failures),
+            retries: load(&c.retries),
+            throttled: load(&c.throttled),
+            failovers: load(&c.failovers),
+            total_latency_ms: load(&c.total_latency_ms),
+        }
+    }
+
+    /// Current requests-per-second limit, if any
+    pub fn current_rps(&self) -> Option<f64> {
+        self.lock().rps
+    }
+
+    /// Index of the endpoint in use for requests whose primary endpoint is `primary`
+    fn active(&self, primary: &str) -> usize {
+        self.lock_active().get(primary).copied().unwrap_or(0)
+    }
+
+    /// Endpoint at index `active` for `primary`
+    fn endpoint(&self, primary: &str, active: usize) -> String {
+        match active {
+            0 => primary.to_string(),
+            i => self.failover[(i - 1) % self.failover.len()].clone(),
+        }
+    }
+
+    /// Switch `primary`'s requests to the next endpoint after the one that just failed
+    fn fail_over(&self, primary: &str, failed: usize) {
+        if self.failover.is_empty() {
+            return;
+        }
+        let mut active = self.lock_active();
+        let current = active.entry(primary.to_string()).or_insert(0);
+        // Another request may have failed over already
+        if *current == failed {
+            *current = (failed + 1) % (self.failover.len() + 1);
+            bump(&self.counters.failovers, 1);
+        }
+    }
+
+    /// Run `op` against the active endpoint, retrying and failing over as needed
+    pub async fn execute<T, E, F, Fut>(&self, primary: &str, op: F) -> Result<T, E>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: fmt::Display,
+    {
+        self.run(primary, true, op).await
+    }
+
+    /// Run `op` against `primary` only, retrying as needed; for protocols the
+    /// failover endpoints do not speak, such as gRPC
+    pub async fn execute_pinned<T, E, F, Fut>(&self, primary: &str, mut op: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: fmt::Display,
+    {
+        self.run(primary, false, |_| op()).await
+    }
+
+    async fn run<T, E, F, Fut>(&self, primary: &str, failover: bool, mut op: F) -> Result<T, E>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: fmt::Display,
+    {
+        bump(&self.counters.requests, 1);
+        let mut attempt = 0;
+        loop {
+            self.acquire().await;
+            let active = if failover { self.active(primary) } else { 0 };
+            let started = Instant::now();
+            bump(&self.counters.attempts, 1);
+            let result = op(self.endpoint(primary, active)).await;
+            bump(
+                &self.counters.total_latency_ms,
+                started.elapsed().as_millis() as u64,
+            );
+
+            let error = match result {
+                Ok(value) => {
+                    bump(&self.counters.successes, 1);
+                    self.on_success();
+                    return Ok(value);
+                }
+                Err(e) => e,
+            };
+
+            let class = classify(&error.to_string());
+            if class == ErrorClass::Throttled {
+                bump(&self.counters.throttled, 1);
+                self.on_throttled();
+            }
+            if class == ErrorClass::Permanent || attempt >= self.policy.max_retries {
+                bump(&self.counters.failures, 1);
+                return Err(error);
+            }
+
+            attempt += 1;
+            bump(&self.counters.retries, 1);
+            if class == ErrorClass::Transient && failover {
+                self.fail_over(primary, active);
+            }
+            tokio::time::sleep(self.policy.delay(attempt)).await;
+        }
+    }
+
+    /// Wait for the next request slot under the current rate limit
+    async fn acquire(&self) {
+        let slot = {
+            let mut state = self.lock();
+            let Some(rps) = state.rps else {
+                return;
+            };
+            let now = Instant::now();
+            let slot = state.next.max(now);
+            state.next = slot + Duration::from_secs_f64(1.0 / rps);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+
+    fn on_throttled(&self) {
+        let mut state = self.lock();
+        if state.adaptive {
+            let current = state.rps.unwrap_or(ADAPTIVE_START_RPS);
+            state.rps = Some((current / 2.0).max(ADAPTIVE_MIN_RPS));
+        }
+    }
+
+    fn on_success(&self) {
+        let mut state = self.lock();
+        if !state.adaptive {
+            return;
+        }
+        let Some(rps) = state.rps else {
+            return;
+        };
+        // Additive increase of roughly one request per second, per second
+        let increased = rps + 1.0 / rps;
+        state.rps = match state.ceiling {
+            Some(ceiling) => Some(increased.min(ceiling)),
+            None if increased > ADAPTIVE_RELEASE_RPS => None,
+            None => Some(increased),
+        };
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LimiterState> {
+        self.limiter.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_active(&self) -> std::sync::MutexGuard<'_, HashMap<String, usize>> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU32;
+
+    use super::*;
+
+    fn fast_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            classify("HTTP request failed with status 429 Too Many Requests: slow down"),
+            ErrorClass::Throttled
+        );
+        assert_eq!(
+            classify("status: ResourceExhausted, message: \"quota\""),
+            ErrorClass::Throttled
+        );
+        assert_eq!(
+            classify("HTTP request failed: error sending request for url"),
+            ErrorClass::Transient
+        );
+        assert_eq!(
+            classify("HTTP request failed with status 400 Bad Request: {}"),
+            ErrorClass::Permanent
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(1), Duration::from_millis(250));
+        assert_eq!(policy.delay(3), Duration::from_secs(1));
+        assert_eq!(policy.delay(20), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_execute_retries_transient_errors() {
+        let transport = Transport::new(fast_policy(3));
+        let calls = AtomicU32::new(0);
+        let result: Result<u32, String> = transport
+            .execute("http://primary", |_| async {
+                match calls.fetch_add(1, Ordering::Relaxed) {
+                    0 | 1 => Err("connection refused".to_string()),
+                    n => Ok(n),
+                }
+            })
+            .await;
+        assert_eq!(result, Ok(2));
+        let stats = transport.stats();
+        assert_eq!((stats.attempts, stats.retries, stats.successes), (3, 2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_execute_does_not_retry_permanent_errors() {
+        let transport = Transport::new(fast_policy(3));
+        let result: Result<(), String> = transport
+            .execute("http://primary", |_| async {
+                Err("invalid params".to_string())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(transport.stats().attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failover_switches_endpoint() {
+        let transport =
+            Transport::new(fast_policy(1)).with_failover(vec!["http://backup".to_string()]);
+        let result: Result<String, String> = transport
+            .execute("http://primary", |endpoint| async move {
+                if endpoint == "http://primary" {
+                    Err("status 503 Service Unavailable".to_string())
+                } else {
+                    Ok(endpoint)
+                }
+            })
+            .await;
+        assert_eq!(result.as_deref(), Ok("http://backup"));
+        assert_eq!(transport.stats().failovers, 1);
+
+        // Other primaries keep their own endpoint
+        let other: Result<String, String> = transport
+            .execute("http://other", |endpoint| async move { Ok(endpoint) })
+            .await;
+        assert_eq!(other.as_deref(), Ok("http://other"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_pinned_never_fails_over() {
+        let transport =
+            Transport::new(fast_policy(2)).with_failover(vec!["http://backup".to_string()]);
+        let calls = AtomicU32::new(0);
+        let result: Result<u32, String> = transport
+            .execute_pinned("grpc://primary", || async {
+                match calls.fetch_add(1, Ordering::Relaxed) {
+                    0 => Err("status 503 Service Unavailable".to_string()),
+                    n => Ok(n),
+                }
+            })
+            .await;
+        assert_eq!(result, Ok(1));
+        assert_eq!(transport.stats().failovers, 0);
+        let primary: Result<String, String> = transport
+            .execute("grpc://primary", |endpoint| async move { Ok(endpoint) })
+            .await;
+        assert_eq!(primary.as_deref(), Ok("grpc://primary"));
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_backs_off_when_throttled() {
+        let transport = Transport::new(fast_policy(0))
+            .with_rate_limit(Some(8.0))
+            .with_adaptive(true);
+        let _: Result<(), String> = transport
+            .execute("http://primary", |_| async {
+                Err("status 429".to_string())
+            })
+            .await;
+        assert_eq!(transport.current_rps(), Some(4.0));
+        assert_eq!(transport.stats().throttled, 1);
+    }
+}
@@ -0,0 +1,146 @@
+//! Record/replay of responses for hermetic tests.
+//!
+//! A [`Cassette`] is a JSON fixture file mapping request keys to responses.
+//! In record mode requests go to the network and their responses are written
+//! to the file; in replay mode responses come only from the file and unknown
+//! requests fail instead of touching the network.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde_json::Value;
+
+/// Set to `1` to re-record cassettes opened with [`Cassette::from_env`]
+pub const RECORD_ENV: &str = "SUIX_RECORD";
+
+/// Answers requests in place of the network
+pub trait MockTransport: fmt::Debug + Send + Sync {
+    /// Response for `key`: `None` sends the request to the network
+    fn replay(&self, key: &str) -> Option<Result<Value, String>>;
+
+    /// Called with every response that came from the network
+    fn record(&self, _key: &str, _response: &Value) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Record,
+    Replay,
+}
+
+/// Responses keyed by request, stored as a pretty-printed JSON object
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mode,
+    responses: Mutex<BTreeMap<String, Value>>,
+}
+
+impl Cassette {
+    /// Serve responses from an existing fixture file
+    pub fn replay(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let responses = serde_json::from_str(&content)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            mode: Mode::Replay,
+            responses: Mutex::new(responses),
+        })
+    }
+
+    /// Record network responses into `path`, replacing its contents
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            mode: Mode::Record,
+            responses: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Record when [`RECORD_ENV`] is `1`, replay otherwise
+    pub fn from_env(path: impl AsRef<Path>) -> io::Result<Self> {
+        match std::env::var(RECORD_ENV).as_deref() {
+            Ok("1") => Ok(Self::record(path)),
+            _ => Self::replay(path),
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn save(&self, responses: &BTreeMap<String, Value>) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(responses).map_err(io::Error::other)?;
+        fs::write(&self.path, content + "\n")
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Value>> {
+        self.responses.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl MockTransport for Cassette {
+    fn replay(&self, key: &str) -> Option<Result<Value, String>> {
+        match self.mode {
+            Mode::Record => None,
+            Mode::Replay => Some(self.lock().get(key).cloned().ok_or_else(|| {
+                format!(
+                    "No recorded response for {key} in {}; re-record with {RECORD_ENV}=1",
+                    self.path.display()
+                )
+            })),
+        }
+    }
+
+    fn record(&self, key: &str, response: &Value) {
+        if self.mode != Mode::Record {
+            return;
+        }
+        let mut responses = self.lock();
+        responses.insert(key.to_string(), response.clone());
+        // Saved on every response so a failing test still leaves a usable fixture
+        if let Err(e) = self.save(&responses) {
+            eprintln!("Warning: failed to write {}: {e}", self.path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_record_then_replay() {
+        let path = std::env::temp_dir()
+            .join(format!("suix-vcr-{}", std::process::id()))
+            .join("cassette.json");
+
+        let recorder = Cassette::record(&path);
+        assert_eq!(MockTransport::replay(&recorder, "a"), None);
+        recorder.record("a", &json!({ "result": 1 }));
+
+        let player = Cassette::replay(&path).unwrap();
+        assert_eq!(player.mode(), Mode::Replay);
+        assert_eq!(
+            MockTransport::replay(&player, "a"),
+            Some(Ok(json!({ "result": 1 })))
+        );
+        // Unknown requests fail instead of reaching the network
+        assert!(matches!(MockTransport::replay(&player, "b"), Some(Err(_))));
+        player.record("b", &json!(2));
+        assert!(matches!(MockTransport::replay(&player, "b"), Some(Err(_))));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}